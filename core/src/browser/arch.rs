@@ -0,0 +1,121 @@
+//! CPU architecture of browser executables, read from their Mach-O headers on macOS.
+//!
+//! Apple Silicon Macs run arm64 and universal builds natively and x86_64 builds through
+//! Rosetta, so the same browser can be installed in builds that differ noticeably in speed.
+
+use serde::Serialize;
+
+const FAT_MAGIC: u32 = 0xcafe_babe;
+const FAT_MAGIC_64: u32 = 0xcafe_babf;
+/// `MH_MAGIC_64` as it appears when a little-endian header is read big-endian.
+const MH_CIGAM_64: u32 = 0xcffa_edfe;
+
+const CPU_TYPE_X86_64: u32 = 0x0100_0007;
+const CPU_TYPE_ARM64: u32 = 0x0100_000c;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Arch {
+    Arm64,
+    X86_64,
+    /// Contains both arm64 and x86_64 code
+    Universal,
+}
+
+impl Arch {
+    pub fn canonical_name(self) -> &'static str {
+        match self {
+            Arch::Arm64 => "arm64",
+            Arch::X86_64 => "x86_64",
+            Arch::Universal => "universal",
+        }
+    }
+
+    /// Architecture this build of pathway was compiled for.
+    pub fn host() -> Option<Self> {
+        if cfg!(target_arch = "aarch64") {
+            Some(Arch::Arm64)
+        } else if cfg!(target_arch = "x86_64") {
+            Some(Arch::X86_64)
+        } else {
+            None
+        }
+    }
+
+    /// Whether an executable of this architecture runs on `host` without translation.
+    pub fn runs_natively_on(self, host: Arch) -> bool {
+        self == Arch::Universal || self == host
+    }
+}
+
+/// Reads the architecture from the start of a thin or universal Mach-O file.
+pub fn parse_mach_o_arch(header: &[u8]) -> Option<Arch> {
+    let word =
+        |offset: usize| -> Option<[u8; 4]> { header.get(offset..offset + 4)?.try_into().ok() };
+
+    let (has_arm64, has_x86_64) = match u32::from_be_bytes(word(0)?) {
+        magic @ (FAT_MAGIC | FAT_MAGIC_64) => {
+            let entry_size = if magic == FAT_MAGIC { 20 } else { 32 };
+            let count = u32::from_be_bytes(word(4)?) as usize;
+            let cpu_types: Vec<u32> = (0..count)
+                .map(|index| word(8 + index * entry_size).map(u32::from_be_bytes))
+                .collect::<Option<_>>()?;
+            (
+                cpu_types.contains(&CPU_TYPE_ARM64),
+                cpu_types.contains(&CPU_TYPE_X86_64),
+            )
+        }
+        MH_CIGAM_64 => {
+            let cpu_type = u32::from_le_bytes(word(4)?);
+            (cpu_type == CPU_TYPE_ARM64, cpu_type == CPU_TYPE_X86_64)
+        }
+        _ => return None,
+    };
+
+    match (has_arm64, has_x86_64) {
+        (true, true) => Some(Arch::Universal),
+        (true, false) => Some(Arch::Arm64),
+        (false, true) => Some(Arch::X86_64),
+        (false, false) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fat_header(cpu_types: &[u32]) -> Vec<u8> {
+        let mut header = FAT_MAGIC.to_be_bytes().to_vec();
+        header.extend((cpu_types.len() as u32).to_be_bytes());
+        for cpu_type in cpu_types {
+            header.extend(cpu_type.to_be_bytes());
+            header.extend([0; 16]);
+        }
+        header
+    }
+
+    #[test]
+    fn mach_o_headers_yield_architecture() {
+        let thin = |cpu_type: u32| {
+            let mut header = 0xfeed_facf_u32.to_le_bytes().to_vec();
+            header.extend(cpu_type.to_le_bytes());
+            header
+        };
+
+        assert_eq!(parse_mach_o_arch(&thin(CPU_TYPE_ARM64)), Some(Arch::Arm64));
+        assert_eq!(
+            parse_mach_o_arch(&thin(CPU_TYPE_X86_64)),
+            Some(Arch::X86_64)
+        );
+        assert_eq!(
+            parse_mach_o_arch(&fat_header(&[CPU_TYPE_X86_64, CPU_TYPE_ARM64])),
+            Some(Arch::Universal)
+        );
+        assert_eq!(
+            parse_mach_o_arch(&fat_header(&[CPU_TYPE_ARM64])),
+            Some(Arch::Arm64)
+        );
+        assert_eq!(parse_mach_o_arch(b"#!/bin/sh\n"), None);
+        assert_eq!(parse_mach_o_arch(&FAT_MAGIC.to_be_bytes()), None);
+    }
+}
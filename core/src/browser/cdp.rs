@@ -0,0 +1,404 @@
+//! Opening tabs in a running Chromium instance through the DevTools protocol.
+//!
+//! Chromium writes the remote debugging port to `DevToolsActivePort` in its user data
+//! directory when started with `--remote-debugging-port`. When that port answers, URLs are
+//! opened with `Target.createTarget` in the existing process instead of spawning a new one.
+
+use super::{BrowserInfo, BrowserKind, LaunchCommand, LaunchOutcome};
+use crate::profile::{ProfileManager, ProfileOptions, ProfileType, WindowOptions};
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+use tracing::debug;
+use tungstenite::Message;
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Error)]
+pub enum CdpError {
+    #[error("DevTools endpoint is not reachable: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid DevTools response: {0}")]
+    InvalidResponse(String),
+    #[error("DevTools WebSocket error: {0}")]
+    WebSocket(#[from] Box<tungstenite::Error>),
+    #[error("DevTools protocol error: {0}")]
+    Protocol(String),
+}
+
+/// Reads the debugging port from `DevToolsActivePort` in a Chromium user data directory.
+pub fn devtools_active_port(user_data_dir: &Path) -> Option<u16> {
+    let content = std::fs::read_to_string(user_data_dir.join("DevToolsActivePort")).ok()?;
+    content.lines().next()?.trim().parse().ok()
+}
+
+/// Opens each URL as a new tab through `Target.createTarget` and returns the target ids.
+///
+/// With `new_window`, the first URL opens in a new window and the rest follow it there.
+pub fn open_tabs(port: u16, urls: &[String], new_window: bool) -> Result<Vec<String>, CdpError> {
+    let ws_url = browser_websocket_url(port)?;
+
+    let stream = connect(port)?;
+    let (mut socket, _) = tungstenite::client(ws_url.as_str(), stream).map_err(|e| match e {
+        tungstenite::HandshakeError::Failure(e) => CdpError::WebSocket(Box::new(e)),
+        tungstenite::HandshakeError::Interrupted(_) => {
+            CdpError::InvalidResponse("WebSocket handshake interrupted".to_string())
+        }
+    })?;
+
+    let mut target_ids = Vec::new();
+    for (index, url) in urls.iter().enumerate() {
+        let id = index as u64 + 1;
+        let request = json!({
+            "id": id,
+            "method": "Target.createTarget",
+            "params": { "url": url, "newWindow": new_window && index == 0 },
+        });
+        socket
+            .send(Message::text(request.to_string()))
+            .map_err(Box::new)?;
+
+        loop {
+            let Message::Text(text) = socket.read().map_err(Box::new)? else {
+                continue;
+            };
+            let response: Value = serde_json::from_str(&text)
+                .map_err(|e| CdpError::InvalidResponse(e.to_string()))?;
+            if response.get("id").and_then(Value::as_u64) != Some(id) {
+                continue;
+            }
+            if let Some(error) = response.get("error") {
+                return Err(CdpError::Protocol(error.to_string()));
+            }
+            let target_id = response
+                .pointer("/result/targetId")
+                .and_then(Value::as_str)
+                .ok_or_else(|| CdpError::InvalidResponse(text.to_string()))?;
+            target_ids.push(target_id.to_string());
+            break;
+        }
+    }
+
+    let _ = socket.close(None);
+    Ok(target_ids)
+}
+
+/// Opens the URLs in an already running instance of `browser`, if one exposes DevTools.
+///
+/// Returns `None` whenever the launch should fall back to spawning the browser: the browser
+/// is not Chromium-based, the requested window mode needs a fresh process, or no debugging
+/// endpoint answers.
+pub(crate) fn try_launch(
+    browser: &BrowserInfo,
+    urls: &[String],
+    profile_opts: Option<&ProfileOptions>,
+    window_opts: Option<&WindowOptions>,
+) -> Option<LaunchOutcome> {
+    if !matches!(
+        browser.kind,
+        BrowserKind::Chrome
+            | BrowserKind::Edge
+            | BrowserKind::Brave
+            | BrowserKind::Vivaldi
+            | BrowserKind::Arc
+            | BrowserKind::Helium
+            | BrowserKind::Opera
+            | BrowserKind::Thorium
+            | BrowserKind::UngoogledChromium
+            | BrowserKind::Yandex
+            | BrowserKind::Wavebox
+            | BrowserKind::Sidekick
+            | BrowserKind::Chromium
+    ) {
+        return None;
+    }
+
+    // Private, guest and kiosk windows are only available when starting the browser.
+    if window_opts.is_some_and(|opts| opts.incognito || opts.kiosk) {
+        return None;
+    }
+
+    let port = match window_opts.and_then(|opts| opts.cdp_port) {
+        Some(port) => port,
+        None => devtools_active_port(&user_data_dir(browser, profile_opts)?)?,
+    };
+    let new_window = window_opts.is_some_and(|opts| opts.new_window);
+
+    match open_tabs(port, urls, new_window) {
+        Ok(target_ids) => {
+            debug!(port, ?target_ids, "Opened tabs via DevTools protocol");
+            let endpoint = format!("127.0.0.1:{}", port);
+            Some(LaunchOutcome {
+                browser: Some(browser.clone()),
+                system_default: None,
+                command: LaunchCommand {
+                    program: PathBuf::from("Target.createTarget"),
+                    args: urls.to_vec(),
+                    display: format!("Target.createTarget via {} {}", endpoint, urls.join(" ")),
+                    is_system_default: false,
+                },
+                reused_existing: true,
+                pid: None,
+            })
+        }
+        Err(e) => {
+            debug!(
+                port,
+                "DevTools launch failed, spawning browser instead: {}", e
+            );
+            None
+        }
+    }
+}
+
+/// The user data directory whose DevTools endpoint opens tabs in the requested profile.
+///
+/// `Target.createTarget` opens tabs in whichever profile window the browser picks, so a named
+/// profile is only reached through the endpoint while it is the one profile open there.
+fn user_data_dir(browser: &BrowserInfo, profile_opts: Option<&ProfileOptions>) -> Option<PathBuf> {
+    let Some(profile_opts) = profile_opts else {
+        return ProfileManager::get_default_browser_dir(browser).ok();
+    };
+    let profile_directory = profile_opts
+        .custom_args
+        .iter()
+        .find_map(|arg| arg.strip_prefix("--profile-directory="));
+    match &profile_opts.profile_type {
+        ProfileType::CustomDirectory(path) => match profile_directory {
+            Some(profile_dir) => is_only_active_profile(path, profile_dir).then(|| path.clone()),
+            None => Some(path.clone()),
+        },
+        ProfileType::Named(name) => {
+            let profile = ProfileManager::find_profile(browser, name).ok()?;
+            if profile.arc_space.is_some() {
+                return None;
+            }
+            let user_data_dir = profile.path.parent()?;
+            let profile_dir = profile.path.file_name()?.to_str()?;
+            is_only_active_profile(user_data_dir, profile_dir).then(|| user_data_dir.to_path_buf())
+        }
+        // Temporary and guest profiles always belong to a new process.
+        ProfileType::Temporary(_) | ProfileType::Guest => None,
+        ProfileType::Default => ProfileManager::get_default_browser_dir(browser).ok(),
+    }
+}
+
+/// Whether `profile_dir` is the only profile open in the browser that uses `user_data_dir`,
+/// according to `last_active_profiles` in its `Local State`.
+fn is_only_active_profile(user_data_dir: &Path, profile_dir: &str) -> bool {
+    std::fs::read_to_string(user_data_dir.join("Local State"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .and_then(|local_state| {
+            let active = local_state
+                .pointer("/profile/last_active_profiles")?
+                .as_array()?
+                .iter()
+                .map(Value::as_str)
+                .collect::<Option<Vec<_>>>()?;
+            Some(active == [profile_dir])
+        })
+        .unwrap_or(false)
+}
+
+fn connect(port: u16) -> Result<TcpStream, CdpError> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let stream = TcpStream::connect_timeout(&address, TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    Ok(stream)
+}
+
+/// Fetches the browser-level WebSocket URL from `/json/version`.
+fn browser_websocket_url(port: u16) -> Result<String, CdpError> {
+    let mut stream = connect(port)?;
+    let request = format!(
+        "GET /json/version HTTP/1.1\r\nHost: 127.0.0.1:{}\r\nConnection: close\r\n\r\n",
+        port
+    );
+    stream.write_all(request.as_bytes())?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    let body = response
+        .split_once("\r\n\r\n")
+        .map(|(_, body)| body)
+        .ok_or_else(|| CdpError::InvalidResponse("missing HTTP body".to_string()))?;
+    let version: Value =
+        serde_json::from_str(body).map_err(|e| CdpError::InvalidResponse(e.to_string()))?;
+
+    version
+        .get("webSocketDebuggerUrl")
+        .and_then(Value::as_str)
+        .map(|url| url.to_string())
+        .ok_or_else(|| CdpError::InvalidResponse("missing webSocketDebuggerUrl".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use tempfile::TempDir;
+
+    #[test]
+    fn the_debugging_port_is_read_from_the_user_data_dir() {
+        let user_data = TempDir::new().unwrap();
+        assert_eq!(devtools_active_port(user_data.path()), None);
+
+        std::fs::write(
+            user_data.path().join("DevToolsActivePort"),
+            "9222\n/devtools/browser/1f2e3d\n",
+        )
+        .unwrap();
+        assert_eq!(devtools_active_port(user_data.path()), Some(9222));
+    }
+
+    #[test]
+    fn tabs_are_created_through_the_browser_endpoint() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (mut http, _) = listener.accept().unwrap();
+            // Read the whole request, so that closing the connection doesn't reset it
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = http.read(&mut buffer).unwrap();
+                assert!(read > 0, "request ended early");
+                request.extend_from_slice(&buffer[..read]);
+            }
+            let body = format!(
+                r#"{{"webSocketDebuggerUrl":"ws://127.0.0.1:{}/devtools/browser/1"}}"#,
+                port
+            );
+            write!(
+                http,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            drop(http);
+
+            let (stream, _) = listener.accept().unwrap();
+            let mut socket = tungstenite::accept(stream).unwrap();
+            let mut requests = Vec::new();
+            while let Ok(Message::Text(text)) = socket.read() {
+                let request: Value = serde_json::from_str(&text).unwrap();
+                let id = request["id"].as_u64().unwrap();
+                let reply = json!({ "id": id, "result": { "targetId": format!("T{}", id) } });
+                socket.send(Message::text(reply.to_string())).unwrap();
+                requests.push(request["params"].clone());
+            }
+            requests
+        });
+
+        let urls = vec![
+            "https://example.com/".to_string(),
+            "https://example.org/".to_string(),
+        ];
+        assert_eq!(open_tabs(port, &urls, true).unwrap(), vec!["T1", "T2"]);
+        assert_eq!(
+            server.join().unwrap(),
+            vec![
+                json!({ "url": "https://example.com/", "newWindow": true }),
+                json!({ "url": "https://example.org/", "newWindow": false }),
+            ]
+        );
+    }
+
+    #[test]
+    fn fresh_processes_are_not_reused() {
+        let browser = BrowserInfo {
+            kind: BrowserKind::Firefox,
+            channel: crate::browser::channels::BrowserChannel::Single,
+            display_name: "Firefox".to_string(),
+            executable_path: PathBuf::from("/usr/bin/firefox"),
+            version: None,
+            unique_id: "firefox.desktop".to_string(),
+            exec_command: None,
+            source: crate::browser::InstallationSource::Unknown,
+            scope: crate::browser::InstallScope::System,
+            arch: None,
+            is_default: false,
+        };
+        let urls = vec!["https://example.com/".to_string()];
+        assert!(try_launch(&browser, &urls, None, None).is_none());
+
+        let chromium = BrowserInfo {
+            kind: BrowserKind::Chromium,
+            ..browser
+        };
+        let incognito = WindowOptions {
+            incognito: true,
+            cdp_port: Some(9),
+            ..Default::default()
+        };
+        assert!(try_launch(&chromium, &urls, None, Some(&incognito)).is_none());
+
+        let temporary = ProfileOptions {
+            profile_type: ProfileType::Temporary(PathBuf::from("/tmp/pathway-temp")),
+            custom_args: Vec::new(),
+        };
+        assert_eq!(user_data_dir(&chromium, Some(&temporary)), None);
+    }
+
+    #[test]
+    fn endpoints_are_only_reused_for_the_profile_open_there() {
+        let user_data = TempDir::new().unwrap();
+        let browser = BrowserInfo {
+            kind: BrowserKind::Chromium,
+            channel: crate::browser::channels::BrowserChannel::Single,
+            display_name: "Chromium".to_string(),
+            executable_path: PathBuf::from("/usr/bin/chromium"),
+            version: None,
+            unique_id: "chromium.desktop".to_string(),
+            exec_command: None,
+            source: crate::browser::InstallationSource::Unknown,
+            scope: crate::browser::InstallScope::System,
+            arch: None,
+            is_default: false,
+        };
+        let in_profile = |profile_dir: &str| ProfileOptions {
+            profile_type: ProfileType::CustomDirectory(user_data.path().to_path_buf()),
+            custom_args: vec![format!("--profile-directory={}", profile_dir)],
+        };
+        let local_state = |active: &str| {
+            std::fs::write(
+                user_data.path().join("Local State"),
+                format!(r#"{{"profile":{{"last_active_profiles":{}}}}}"#, active),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            user_data_dir(&browser, Some(&in_profile("Profile 3"))),
+            None
+        );
+        local_state(r#"["Profile 3"]"#);
+        assert_eq!(
+            user_data_dir(&browser, Some(&in_profile("Profile 3"))),
+            Some(user_data.path().to_path_buf())
+        );
+        assert_eq!(user_data_dir(&browser, Some(&in_profile("Default"))), None);
+        local_state(r#"["Default","Profile 3"]"#);
+        assert_eq!(
+            user_data_dir(&browser, Some(&in_profile("Profile 3"))),
+            None
+        );
+
+        // Without a profile the browser picks the window, as it would when spawned
+        let whole_dir = ProfileOptions {
+            profile_type: ProfileType::CustomDirectory(user_data.path().to_path_buf()),
+            custom_args: Vec::new(),
+        };
+        assert_eq!(
+            user_data_dir(&browser, Some(&whole_dir)),
+            Some(user_data.path().to_path_buf())
+        );
+    }
+}
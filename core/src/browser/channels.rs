@@ -19,6 +19,25 @@ impl ChromiumChannel {
     }
 }
 
+/// Reads the channel of a Brave, Vivaldi or Opera build from its desktop file name, bundle id,
+/// package or registry name.
+///
+/// The vendors name their pre-release builds differently, so every platform maps them the
+/// same way: Brave Beta, Dev and Nightly become beta, dev and canary, Vivaldi Snapshot
+/// becomes dev, and Opera Beta (`OperaNext`) and Opera Developer become beta and dev.
+pub fn vendor_chromium_channel(token: &str) -> ChromiumChannel {
+    let token = token.to_ascii_lowercase();
+    if token.contains("beta") || token.contains("next") {
+        ChromiumChannel::Beta
+    } else if token.contains("nightly") || token.contains("canary") {
+        ChromiumChannel::Canary
+    } else if token.contains("dev") || token.contains("snapshot") {
+        ChromiumChannel::Dev
+    } else {
+        ChromiumChannel::Stable
+    }
+}
+
 #[derive(Debug, Serialize, PartialEq, Eq, Clone, Copy)]
 pub enum FirefoxChannel {
     Stable,
@@ -26,6 +45,8 @@ pub enum FirefoxChannel {
     Dev,
     Nightly,
     Esr,
+    /// Zen's pre-release builds
+    Twilight,
 }
 
 impl FirefoxChannel {
@@ -36,6 +57,7 @@ impl FirefoxChannel {
             FirefoxChannel::Dev => "dev",
             FirefoxChannel::Nightly => "nightly",
             FirefoxChannel::Esr => "esr",
+            FirefoxChannel::Twilight => "twilight",
         }
     }
 }
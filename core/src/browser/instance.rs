@@ -0,0 +1,276 @@
+//! Detection of browser processes that already run with a given profile.
+//!
+//! Chromium and Firefox lock their profile directory while running: `SingletonLock` and
+//! `lock` are symlinks to `<host>-<pid>` / `<address>:+<pid>` on Unix, while `lockfile` and
+//! `parent.lock` are held open exclusively on Windows. Other browsers are matched by
+//! executable in the process list; on Linux by the canonical path of a process's executable
+//! or of the program in its command line.
+
+use super::{BrowserInfo, BrowserKind};
+use crate::profile::{ProfileManager, ProfileOptions, ProfileType};
+use std::path::{Path, PathBuf};
+
+/// Returns true when `browser` already runs with the profile selected by `profile_opts`.
+pub fn is_running(browser: &BrowserInfo, profile_opts: Option<&ProfileOptions>) -> bool {
+    let profile_type = profile_opts.map(|opts| &opts.profile_type);
+
+    // Temporary profiles are created right before launch, so nothing can be using them.
+    if matches!(profile_type, Some(ProfileType::Temporary(_))) {
+        return false;
+    }
+
+    match browser.kind {
+        BrowserKind::Chrome
+        | BrowserKind::Edge
+        | BrowserKind::Brave
+        | BrowserKind::Vivaldi
+        | BrowserKind::Arc
+        | BrowserKind::Helium
+        | BrowserKind::Opera
+        | BrowserKind::Thorium
+        | BrowserKind::UngoogledChromium
+        | BrowserKind::Yandex
+        | BrowserKind::Wavebox
+        | BrowserKind::Sidekick
+        | BrowserKind::Chromium => {
+            let user_data_dir = match profile_type {
+                Some(ProfileType::CustomDirectory(path)) => Some(path.clone()),
+                _ => ProfileManager::get_default_browser_dir(browser).ok(),
+            };
+            match user_data_dir {
+                Some(dir) => is_profile_locked(&dir, "SingletonLock", "lockfile"),
+                None => is_executable_running(browser.launch_path()),
+            }
+        }
+        BrowserKind::Firefox
+        | BrowserKind::Waterfox
+        | BrowserKind::LibreWolf
+        | BrowserKind::Zen
+        | BrowserKind::Floorp
+        | BrowserKind::PaleMoon
+        | BrowserKind::Basilisk
+        | BrowserKind::TorBrowser => match firefox_profile_dir(browser, profile_type) {
+            Some(dir) => is_profile_locked(&dir, "lock", "parent.lock"),
+            None => is_executable_running(browser.launch_path()),
+        },
+        BrowserKind::Safari
+        | BrowserKind::Qutebrowser
+        | BrowserKind::Epiphany
+        | BrowserKind::Falkon
+        | BrowserKind::Konqueror
+        | BrowserKind::DuckDuckGo
+        | BrowserKind::Other => is_executable_running(browser.launch_path()),
+    }
+}
+
+/// Returns true when a running Chromium or Firefox holds the profile lock in `dir`.
+pub fn is_directory_locked(dir: &Path) -> bool {
+    is_profile_locked(dir, "SingletonLock", "lockfile")
+        || is_profile_locked(dir, "lock", "parent.lock")
+}
+
+fn firefox_profile_dir(
+    browser: &BrowserInfo,
+    profile_type: Option<&ProfileType>,
+) -> Option<PathBuf> {
+    match profile_type {
+        Some(ProfileType::CustomDirectory(path)) => Some(path.clone()),
+        Some(ProfileType::Named(name)) => ProfileManager::find_profile(browser, name)
+            .ok()
+            .map(|profile| profile.path),
+        _ => ProfileManager::discover_profiles(browser)
+            .ok()?
+            .into_iter()
+            .find(|profile| profile.is_default)
+            .map(|profile| profile.path),
+    }
+}
+
+#[cfg(unix)]
+fn is_profile_locked(dir: &Path, unix_lock: &str, _windows_lock: &str) -> bool {
+    let Ok(target) = std::fs::read_link(dir.join(unix_lock)) else {
+        return false;
+    };
+    lock_owner_pid(&target.to_string_lossy()).is_some_and(is_pid_alive)
+}
+
+#[cfg(windows)]
+fn is_profile_locked(dir: &Path, _unix_lock: &str, windows_lock: &str) -> bool {
+    let lock = dir.join(windows_lock);
+    // The running browser keeps the file open without sharing, so opening it fails.
+    lock.exists() && std::fs::OpenOptions::new().write(true).open(&lock).is_err()
+}
+
+#[cfg(not(any(unix, windows)))]
+fn is_profile_locked(_dir: &Path, _unix_lock: &str, _windows_lock: &str) -> bool {
+    false
+}
+
+/// Extracts the pid from a Chromium (`host-1234`) or Firefox (`127.0.1.1:+1234`) lock target.
+#[cfg(unix)]
+fn lock_owner_pid(target: &str) -> Option<u32> {
+    target
+        .rsplit(['-', '+'])
+        .next()
+        .and_then(|pid| pid.parse().ok())
+}
+
+#[cfg(target_os = "linux")]
+fn is_pid_alive(pid: u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn is_pid_alive(pid: u32) -> bool {
+    std::process::Command::new("kill")
+        .arg("-0")
+        .arg(pid.to_string())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(target_os = "linux")]
+fn is_executable_running(executable: &Path) -> bool {
+    is_executable_running_in(Path::new("/proc"), executable)
+}
+
+/// Looks through the processes under `proc_dir` for one running `executable`, comparing
+/// canonical paths since launch paths are often symlinks, e.g. `/usr/bin/google-chrome`.
+///
+/// A process matches by its `exe` link, or by the program named in its `cmdline`: wrapper
+/// scripts such as Chrome's `exec -a "$0"` the real binary, which leaves the launch path only
+/// in the command line.
+#[cfg(target_os = "linux")]
+fn is_executable_running_in(proc_dir: &Path, executable: &Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(proc_dir) else {
+        return false;
+    };
+    let canonical = std::fs::canonicalize(executable).unwrap_or_else(|_| executable.to_path_buf());
+    let is_launch_path = |path: &Path| {
+        path == executable
+            || path == canonical
+            || std::fs::canonicalize(path).is_ok_and(|path| path == canonical)
+    };
+
+    entries.flatten().any(|entry| {
+        let process = entry.path();
+        // The link of a binary replaced by an update reads "<path> (deleted)"
+        let exe = std::fs::read_link(process.join("exe")).map(|exe| {
+            let exe = exe.to_string_lossy();
+            PathBuf::from(exe.strip_suffix(" (deleted)").unwrap_or(&exe))
+        });
+        if exe.is_ok_and(|exe| is_launch_path(&exe)) {
+            return true;
+        }
+        std::fs::read(process.join("cmdline")).is_ok_and(|cmdline| {
+            let program = cmdline.split(|&byte| byte == 0).next().unwrap_or_default();
+            let program = Path::new(std::str::from_utf8(program).unwrap_or_default());
+            program.is_absolute() && is_launch_path(program)
+        })
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn is_executable_running(executable: &Path) -> bool {
+    std::process::Command::new("pgrep")
+        .arg("-f")
+        .arg(executable)
+        .stdout(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(target_os = "windows")]
+fn is_executable_running(executable: &Path) -> bool {
+    let Some(image) = executable.file_name().and_then(|s| s.to_str()) else {
+        return false;
+    };
+    std::process::Command::new("tasklist")
+        .args(["/FI", &format!("IMAGENAME eq {}", image), "/NH"])
+        .output()
+        .is_ok_and(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .to_ascii_lowercase()
+                .contains(&image.to_ascii_lowercase())
+        })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn is_executable_running(_executable: &Path) -> bool {
+    false
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    #[cfg(target_os = "linux")]
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn lock_targets_yield_owner_pid() {
+        assert_eq!(lock_owner_pid("my-laptop-4242"), Some(4242));
+        assert_eq!(lock_owner_pid("127.0.1.1:+31337"), Some(31337));
+        assert_eq!(lock_owner_pid("garbage"), None);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn processes_match_the_launch_path_behind_symlinks_and_wrappers() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let opt = dir.path().join("opt");
+        let bin = dir.path().join("bin");
+        let proc_dir = dir.path().join("proc");
+        for path in [&opt, &bin, &proc_dir] {
+            std::fs::create_dir_all(path).unwrap();
+        }
+        let binary = opt.join("chrome");
+        let wrapper = opt.join("google-chrome");
+        std::fs::write(&binary, "").unwrap();
+        std::fs::write(&wrapper, "").unwrap();
+        symlink(&wrapper, bin.join("google-chrome")).unwrap();
+        symlink(&binary, bin.join("chromium")).unwrap();
+        let other = opt.join("other");
+        std::fs::write(&other, "").unwrap();
+
+        let add_process = |pid: &str, exe: &Path, cmdline: &str| {
+            let process = proc_dir.join(pid);
+            std::fs::create_dir(&process).unwrap();
+            symlink(exe, process.join("exe")).unwrap();
+            std::fs::write(process.join("cmdline"), cmdline).unwrap();
+        };
+        add_process("1", &other, "other\0");
+        assert!(!is_executable_running_in(&proc_dir, &bin.join("chromium")));
+
+        // The exe link resolves to the binary behind the symlinked launch path
+        add_process("2", &binary, "chrome\0--type=renderer\0");
+        assert!(is_executable_running_in(&proc_dir, &bin.join("chromium")));
+        // Behind a wrapper script only the command line names the launch path
+        assert!(!is_executable_running_in(
+            &proc_dir,
+            &bin.join("google-chrome")
+        ));
+        add_process(
+            "3",
+            &binary,
+            &format!(
+                "{}\0--profile-directory=Default\0",
+                bin.join("google-chrome").display()
+            ),
+        );
+        assert!(is_executable_running_in(
+            &proc_dir,
+            &bin.join("google-chrome")
+        ));
+
+        // Binaries replaced by an update are still running
+        let replaced = opt.join("firefox");
+        assert!(!is_executable_running_in(&proc_dir, &replaced));
+        add_process(
+            "4",
+            Path::new(&format!("{} (deleted)", replaced.display())),
+            "",
+        );
+        assert!(is_executable_running_in(&proc_dir, &replaced));
+    }
+}
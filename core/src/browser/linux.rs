@@ -1,5 +1,9 @@
-use super::{BrowserInfo, BrowserKind};
-use crate::browser::channels::{BrowserChannel, ChromiumChannel, FirefoxChannel};
+use super::mail::{MailClient, MailClientKind};
+use super::{BrowserInfo, BrowserKind, InstallScope};
+use crate::browser::channels::{self, BrowserChannel, ChromiumChannel, FirefoxChannel};
+use crate::browser::sources::{
+    self, parse_flatpak_run, InstallationSource, LinuxInstallationSource,
+};
 use crate::filesystem::FileSystem;
 use std::collections::HashSet;
 use std::env;
@@ -7,8 +11,14 @@ use std::path::{Path, PathBuf};
 
 use super::{LaunchCommand, LaunchOutcome, LaunchTarget, SystemDefaultBrowser};
 use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 use thiserror::Error;
-use tracing::debug;
+use tracing::{debug, warn};
+
+mod appimage;
+mod version;
+mod wsl;
 
 fn fs_is_file<F: FileSystem>(fs: &F, path: &Path) -> bool {
     fs.metadata(path)
@@ -22,6 +32,10 @@ pub enum LaunchError {
     MissingExecutable(String),
     #[error("No URLs provided to launch")]
     NoUrls,
+    #[error("{0} is already running with this profile; use --temp-profile or --user-dir for a separate instance")]
+    AlreadyRunning(String),
+    #[error("Path is not accessible to the confined snap '{snap}': {path}")]
+    SnapConfinement { snap: String, path: PathBuf },
     #[error("Failed to launch browser: {source}")]
     Spawn {
         #[from]
@@ -38,15 +52,17 @@ pub fn launch_with_profile(
     profile_opts: Option<&crate::profile::ProfileOptions>,
     window_opts: Option<&crate::profile::WindowOptions>,
 ) -> Result<LaunchOutcome, LaunchError> {
-    if urls.is_empty() {
-        return Err(LaunchError::NoUrls);
-    }
-
     match target {
         LaunchTarget::Browser(info) => {
-            let (program, resolved_args, urls_consumed) = prepare_launch_command(info, urls)?;
-
-            let mut command = Command::new(&program);
+            let (program, resolved_args, urls_consumed) = match info.source {
+                InstallationSource::Linux(LinuxInstallationSource::Flatpak) => {
+                    prepare_flatpak_command(info, profile_opts)?
+                }
+                InstallationSource::Linux(LinuxInstallationSource::Snap) => {
+                    prepare_snap_command(info, profile_opts)?
+                }
+                _ => prepare_launch_command(info, urls)?,
+            };
 
             let mut profile_args = Vec::new();
             let mut has_profile_args = false;
@@ -59,14 +75,28 @@ pub fn launch_with_profile(
                 has_profile_args = !profile_args.is_empty();
             }
 
-            command.args(&resolved_args);
+            let mut launch_args = resolved_args;
             if has_profile_args {
-                command.args(&profile_args);
+                launch_args.extend(profile_args);
             }
             if !urls_consumed {
-                command.args(urls);
+                launch_args.extend(urls.iter().cloned());
             }
 
+            if info.source == InstallationSource::Linux(LinuxInstallationSource::Wsl) {
+                launch_args = wsl::translate_args(&crate::filesystem::RealFileSystem, launch_args);
+            }
+
+            let (program, launch_args) = match window_opts.and_then(|opts| opts.sandbox) {
+                Some(tool) if is_sandboxable(info) => {
+                    let bind_dirs = sandbox_bind_dirs(info, profile_opts);
+                    wrap_in_sandbox(tool, program, launch_args, &bind_dirs)
+                }
+                _ => (program, launch_args),
+            };
+
+            let mut command = Command::new(&program);
+            command.args(&launch_args);
             command.stdin(Stdio::null());
             command.stdout(Stdio::null());
             command.stderr(Stdio::null());
@@ -82,7 +112,7 @@ pub fn launch_with_profile(
                 "Launching browser"
             };
             debug!(program = %program.display(), args = ?all_args, "{}", log_message);
-            command.spawn()?;
+            let child = command.spawn()?;
 
             let cmd = LaunchCommand {
                 program: program.clone(),
@@ -95,10 +125,23 @@ pub fn launch_with_profile(
                 browser: Some(info.clone()),
                 system_default: None,
                 command: cmd,
+                reused_existing: false,
+                pid: Some(child.id()),
             })
         }
         LaunchTarget::SystemDefault => {
-            let mut command = Command::new("xdg-open");
+            if urls.is_empty() {
+                return Err(LaunchError::NoUrls);
+            }
+
+            // Inside WSL, wslview hands URLs to the Windows default browser.
+            let (program, mut command) = match wsl::is_wsl(&crate::filesystem::RealFileSystem)
+                .then(wsl::wslview_command)
+                .flatten()
+            {
+                Some(command) => ("wslview", command),
+                None => ("xdg-open", Command::new("xdg-open")),
+            };
             command.args(urls);
             command.stdin(Stdio::null());
             command.stdout(Stdio::null());
@@ -108,13 +151,13 @@ pub fn launch_with_profile(
                 .get_args()
                 .map(|s| s.to_string_lossy().to_string())
                 .collect();
-            debug!(program = "xdg-open", args = ?all_args, "Launching system default browser");
-            command.spawn()?;
+            debug!(program, args = ?all_args, "Launching system default browser");
+            let child = command.spawn()?;
 
             let cmd = LaunchCommand {
-                program: PathBuf::from("xdg-open"),
+                program: PathBuf::from(program),
                 args: all_args.clone(),
-                display: format!("xdg-open {}", all_args.join(" ")),
+                display: format!("{} {}", program, all_args.join(" ")),
                 is_system_default: true,
             };
 
@@ -122,16 +165,78 @@ pub fn launch_with_profile(
                 browser: None,
                 system_default: system_default_browser_with_fs(&crate::filesystem::RealFileSystem),
                 command: cmd,
+                reused_existing: false,
+                pid: Some(child.id()),
             })
         }
     }
 }
 pub fn system_default_browser_with_fs<F: FileSystem>(fs: &F) -> Option<SystemDefaultBrowser> {
-    let desktop_id = detect_default_desktop_entry(fs)?;
+    // KDE keeps its own browser setting which takes precedence over mimeapps.list there.
+    if is_kde_session() {
+        if let Some(default) = kde_default_browser(fs) {
+            return Some(default);
+        }
+    }
+
+    if let Some(desktop_id) = detect_default_desktop_entry(fs) {
+        return system_default_from_desktop_id(fs, desktop_id);
+    }
+
+    if wsl::is_wsl(fs) {
+        return wsl::system_default_browser(&wsl::detect_browsers(fs));
+    }
+
+    None
+}
+
+/// Installations under the home directory or a per-user Nix profile are per-user. Flatpaks are
+/// judged by their exported desktop entry, which tells `--user` installations apart.
+pub fn install_scope(browser: &BrowserInfo) -> InstallScope {
+    install_scope_in(browser, dirs_next::home_dir().as_deref())
+}
+
+fn install_scope_in(browser: &BrowserInfo, home: Option<&Path>) -> InstallScope {
+    let path = match browser.source {
+        InstallationSource::Linux(LinuxInstallationSource::Flatpak) => {
+            Path::new(&browser.unique_id)
+        }
+        _ => browser.launch_path(),
+    };
+    let per_user = home.is_some_and(|home| path.starts_with(home))
+        || path.starts_with("/etc/profiles/per-user")
+        || (browser.source == InstallationSource::Linux(LinuxInstallationSource::Wsl)
+            && path.components().any(|c| c.as_os_str() == "AppData"));
+    if per_user {
+        InstallScope::User
+    } else {
+        InstallScope::System
+    }
+}
+
+/// Desktop entries are matched by desktop id; WSL browsers, which have none, by executable.
+pub fn is_system_default(browser: &BrowserInfo, system_default: &SystemDefaultBrowser) -> bool {
+    let desktop_id = Path::new(&browser.unique_id)
+        .file_name()
+        .and_then(|name| name.to_str());
+    match desktop_id {
+        Some(id) if id.ends_with(".desktop") => id == system_default.identifier,
+        _ => system_default.path.as_deref() == Some(browser.launch_path()),
+    }
+}
+
+fn system_default_from_desktop_id<F: FileSystem>(
+    fs: &F,
+    desktop_id: String,
+) -> Option<SystemDefaultBrowser> {
     let desktop_path = resolve_desktop_entry_path(fs, &desktop_id)?;
     let content = fs.read_to_string(&desktop_path).ok()?;
 
-    if let Some(info) = create_browser_info(&desktop_path, &content) {
+    // Terminal entries are left to xdg-open, which knows how to start a terminal emulator.
+    if let Some(info) = (!requires_terminal(&content))
+        .then(|| create_browser_info(&desktop_path, &content))
+        .flatten()
+    {
         let path = info.launch_path().to_path_buf();
         return Some(SystemDefaultBrowser {
             identifier: desktop_id,
@@ -141,7 +246,7 @@ pub fn system_default_browser_with_fs<F: FileSystem>(fs: &F) -> Option<SystemDef
         });
     }
 
-    let display_name = get_desktop_entry_value(&content, "Name")
+    let display_name = get_localized_desktop_entry_value(&content, "Name")
         .map(|s| s.to_string())
         .unwrap_or_else(|| {
             desktop_path
@@ -160,6 +265,16 @@ pub fn system_default_browser_with_fs<F: FileSystem>(fs: &F) -> Option<SystemDef
 }
 
 pub fn detect_browsers<F: FileSystem>(fs: &F) -> Vec<BrowserInfo> {
+    detect_browsers_filtered(fs, None)
+}
+
+/// Like [`detect_browsers`], but only probes versions of browsers of `kind`.
+pub fn detect_browsers_of_kind<F: FileSystem>(fs: &F, kind: BrowserKind) -> Vec<BrowserInfo> {
+    detect_browsers_filtered(fs, Some(kind))
+}
+
+fn detect_browsers_filtered<F: FileSystem>(fs: &F, kind: Option<BrowserKind>) -> Vec<BrowserInfo> {
+    let wanted = |browser: &BrowserInfo| kind.is_none_or(|kind| browser.kind == kind);
     let mut browsers = Vec::new();
     let mut processed_files = HashSet::new();
 
@@ -179,9 +294,20 @@ pub fn detect_browsers<F: FileSystem>(fs: &F) -> Vec<BrowserInfo> {
                         }
 
                         if let Ok(content) = fs.read_to_string(&path) {
+                            if is_web_browser(&content) && requires_terminal(&content) {
+                                // Launched detached with null stdio, text-mode browsers would exit immediately.
+                                warn!(
+                                    entry = %path.display(),
+                                    "Skipping terminal browser entry (Terminal=true); text-mode browsers are not supported"
+                                );
+                                processed_files.insert(canonical_path);
+                                continue;
+                            }
                             if is_web_browser(&content) {
                                 if let Some(browser_info) = create_browser_info(&path, &content) {
-                                    browsers.push(browser_info);
+                                    if wanted(&browser_info) {
+                                        browsers.push(browser_info);
+                                    }
                                     processed_files.insert(canonical_path);
                                 }
                             }
@@ -192,9 +318,479 @@ pub fn detect_browsers<F: FileSystem>(fs: &F) -> Vec<BrowserInfo> {
         }
     }
 
+    // AppImages integrated through desktop entries are already covered above.
+    let known_executables: HashSet<PathBuf> = browsers
+        .iter()
+        .map(|browser| browser.executable_path.clone())
+        .collect();
+    browsers.extend(detect_appimages(fs).into_iter().filter(|browser| {
+        wanted(browser) && !known_executables.contains(&browser.executable_path)
+    }));
+
+    // Scanning `PATH` is opt-in: machines without desktop entries are rare and probing adds
+    // noise (wrapper scripts, duplicates under other names) to the inventory.
+    if super::detection_config().scan_path {
+        let path_browsers = detect_path_browsers(fs, &browsers);
+        browsers.extend(path_browsers.into_iter().filter(wanted));
+    }
+
+    if wsl::is_wsl(fs) {
+        browsers.extend(wsl::detect_browsers(fs).into_iter().filter(wanted));
+    }
+
+    version::resolve_versions(fs, &mut browsers);
+
     browsers
 }
 
+/// Whether a desktop entry registers `x-scheme-handler/<scheme>`.
+pub fn has_scheme_handler<F: FileSystem>(fs: &F, scheme: &str) -> bool {
+    desktop_file_dirs()
+        .into_iter()
+        .filter(|dir| fs.is_dir(dir))
+        .filter_map(|dir| fs.read_dir(&dir).ok())
+        .flatten()
+        .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("desktop"))
+        .any(|path| {
+            fs.read_to_string(&path)
+                .is_ok_and(|content| handles_scheme(&content, scheme))
+        })
+}
+
+pub fn detect_mail_clients<F: FileSystem>(fs: &F) -> Vec<MailClient> {
+    let mut clients = Vec::new();
+
+    for dir in desktop_file_dirs() {
+        if !fs.is_dir(&dir) {
+            continue;
+        }
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("desktop") {
+                continue;
+            }
+            let Ok(content) = fs.read_to_string(&path) else {
+                continue;
+            };
+            // Browsers that also register mailto stay browsers; terminal clients like mutt
+            // cannot run detached.
+            if !handles_scheme(&content, "mailto")
+                || is_web_browser(&content)
+                || requires_terminal(&content)
+            {
+                continue;
+            }
+            if let Some(client) = create_mail_client(&path, &content) {
+                clients.push(client);
+            }
+        }
+    }
+
+    clients
+}
+
+pub(super) fn create_mail_client(path: &Path, content: &str) -> Option<MailClient> {
+    let exec_value = get_desktop_entry_value(content, "Exec")?;
+    let executable_path = parse_exec_path(exec_value)?;
+    let display_name = get_localized_desktop_entry_value(content, "Name")?.to_string();
+
+    let kind = match MailClientKind::from_token(path.file_stem()?.to_str()?) {
+        MailClientKind::Other => MailClientKind::from_token(&display_name),
+        kind => kind,
+    };
+    let source = entry_source(path, content, exec_value, &executable_path);
+
+    Some(MailClient {
+        kind,
+        display_name,
+        executable_path,
+        unique_id: path.to_str()?.to_string(),
+        exec_command: Some(exec_value.to_string()),
+        source: InstallationSource::Linux(source),
+    })
+}
+
+pub fn launch_mail(client: &MailClient, urls: &[String]) -> Result<LaunchOutcome, LaunchError> {
+    if urls.is_empty() {
+        return Err(LaunchError::NoUrls);
+    }
+
+    let (program, args) = client
+        .exec_command
+        .as_deref()
+        .and_then(|exec| mail_command_from_exec(exec, urls))
+        .unwrap_or_else(|| (client.executable_path.clone(), urls.to_vec()));
+
+    let mut command = Command::new(&program);
+    command.args(&args);
+    command.stdin(Stdio::null());
+    command.stdout(Stdio::null());
+    command.stderr(Stdio::null());
+
+    debug!(program = %program.display(), args = ?args, "Launching mail client");
+    let child = command.spawn()?;
+
+    Ok(LaunchOutcome {
+        browser: None,
+        system_default: None,
+        command: LaunchCommand {
+            display: format!("{} {}", program.display(), args.join(" ")),
+            program,
+            args,
+            is_system_default: false,
+        },
+        reused_existing: false,
+        pid: Some(child.id()),
+    })
+}
+
+/// Expands a desktop entry `Exec` line for mail URLs, dropping field codes other than `%u`.
+fn mail_command_from_exec(exec: &str, urls: &[String]) -> Option<(PathBuf, Vec<String>)> {
+    let tokens = shell_words::split(exec).ok()?;
+    let (program, rest) = tokens.split_first()?;
+
+    let mut args = Vec::new();
+    let mut consumed_urls = false;
+    for token in rest {
+        match token.as_str() {
+            "%u" | "%U" | "%f" | "%F" => {
+                args.extend(urls.iter().cloned());
+                consumed_urls = true;
+            }
+            code if code.len() == 2 && code.starts_with('%') => {}
+            _ => args.push(token.clone()),
+        }
+    }
+    if !consumed_urls {
+        args.extend(urls.iter().cloned());
+    }
+
+    Some((PathBuf::from(program), args))
+}
+
+fn is_sandboxable(info: &BrowserInfo) -> bool {
+    !matches!(
+        info.source,
+        InstallationSource::Linux(LinuxInstallationSource::Flatpak)
+            | InstallationSource::Linux(LinuxInstallationSource::Snap)
+            | InstallationSource::Linux(LinuxInstallationSource::Wsl)
+    )
+}
+
+/// Directories the sandboxed browser needs write access to: its profile and downloads.
+fn sandbox_bind_dirs(
+    info: &BrowserInfo,
+    profile_opts: Option<&crate::profile::ProfileOptions>,
+) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    match profile_opts.map(|opts| &opts.profile_type) {
+        Some(crate::profile::ProfileType::CustomDirectory(path))
+        | Some(crate::profile::ProfileType::Temporary(path)) => dirs.push(path.clone()),
+        _ => {
+            if let Ok(profile_dir) = crate::profile::ProfileManager::get_default_browser_dir(info) {
+                dirs.push(profile_dir);
+            }
+        }
+    }
+
+    if let Some(downloads) = dirs_next::download_dir() {
+        dirs.push(downloads);
+    }
+
+    dirs
+}
+
+/// Wraps a browser command line with firejail or bubblewrap.
+///
+/// firejail whitelists only the bind directories inside `$HOME`. bubblewrap mounts the
+/// host read-only, hides `$HOME` and `/tmp` behind tmpfs, and re-binds the display sockets
+/// and the bind directories read-write.
+fn wrap_in_sandbox(
+    tool: crate::profile::SandboxTool,
+    program: PathBuf,
+    args: Vec<String>,
+    bind_dirs: &[PathBuf],
+) -> (PathBuf, Vec<String>) {
+    let mut wrapped = Vec::new();
+
+    let wrapper = match tool {
+        crate::profile::SandboxTool::Firejail => {
+            wrapped.push("--quiet".to_string());
+            for dir in bind_dirs {
+                wrapped.push(format!("--whitelist={}", dir.display()));
+            }
+            "firejail"
+        }
+        crate::profile::SandboxTool::Bwrap => {
+            let mut push = |items: &[&str]| wrapped.extend(items.iter().map(|s| s.to_string()));
+            push(&["--ro-bind", "/", "/"]);
+            push(&["--dev", "/dev"]);
+            push(&["--proc", "/proc"]);
+            push(&["--tmpfs", "/tmp"]);
+            push(&["--bind-try", "/tmp/.X11-unix", "/tmp/.X11-unix"]);
+
+            if let Ok(home) = env::var("HOME") {
+                push(&["--tmpfs", &home]);
+                let xauthority = Path::new(&home).join(".Xauthority");
+                let xauthority = xauthority.to_string_lossy();
+                push(&["--ro-bind-try", &xauthority, &xauthority]);
+            }
+            if let Ok(runtime_dir) = env::var("XDG_RUNTIME_DIR") {
+                push(&["--bind-try", &runtime_dir, &runtime_dir]);
+            }
+            for dir in bind_dirs {
+                let dir = dir.to_string_lossy();
+                push(&["--bind-try", &dir, &dir]);
+            }
+            push(&["--new-session"]);
+            "bwrap"
+        }
+    };
+
+    wrapped.push(program.to_string_lossy().to_string());
+    wrapped.extend(args);
+
+    (PathBuf::from(wrapper), wrapped)
+}
+
+/// Executable names probed on `PATH` when `detection.scan_path` is set.
+const PATH_EXECUTABLES: &[&str] = &[
+    "google-chrome-stable",
+    "google-chrome",
+    "google-chrome-beta",
+    "google-chrome-unstable",
+    "chromium",
+    "chromium-browser",
+    "ungoogled-chromium",
+    "microsoft-edge-stable",
+    "microsoft-edge",
+    "microsoft-edge-beta",
+    "microsoft-edge-dev",
+    "brave-browser",
+    "brave",
+    "vivaldi-stable",
+    "vivaldi-snapshot",
+    "vivaldi",
+    "opera",
+    "opera-beta",
+    "opera-developer",
+    "thorium-browser",
+    "yandex-browser-stable",
+    "yandex-browser-beta",
+    "firefox",
+    "firefox-esr",
+    "firefox-developer-edition",
+    "firefox-nightly",
+    "librewolf",
+    "waterfox",
+    "floorp",
+    "zen-browser",
+    "palemoon",
+    "basilisk",
+    "qutebrowser",
+    "epiphany",
+    "falkon",
+    "konqueror",
+];
+
+/// Finds known browser executables on `PATH` that no desktop entry or AppImage accounts for.
+fn detect_path_browsers<F: FileSystem>(fs: &F, known: &[BrowserInfo]) -> Vec<BrowserInfo> {
+    let Some(path_var) = env::var_os("PATH") else {
+        return Vec::new();
+    };
+    let path_dirs: Vec<PathBuf> = env::split_paths(&path_var)
+        .filter(|dir| dir.is_absolute())
+        .collect();
+
+    // Desktop entries often name the executable without a directory.
+    let resolve = |executable: &Path| -> Option<PathBuf> {
+        if executable.is_absolute() {
+            return fs.canonicalize(executable).ok();
+        }
+        path_dirs
+            .iter()
+            .map(|dir| dir.join(executable))
+            .find(|candidate| fs_is_file(fs, candidate))
+            .and_then(|candidate| fs.canonicalize(&candidate).ok())
+    };
+    // Symlinks like `google-chrome` -> `google-chrome-stable` resolve to the same binary.
+    let mut seen: HashSet<PathBuf> = known
+        .iter()
+        .filter_map(|browser| resolve(&browser.executable_path))
+        .collect();
+
+    let mut browsers = Vec::new();
+    for name in PATH_EXECUTABLES {
+        let Some(executable_path) = path_dirs
+            .iter()
+            .map(|dir| dir.join(name))
+            .find(|candidate| fs_is_file(fs, candidate))
+        else {
+            continue;
+        };
+        let Some(canonical) = fs.canonicalize(&executable_path).ok() else {
+            continue;
+        };
+        let Some((kind, channel)) = classify_browser_from_token(name) else {
+            continue;
+        };
+        if !seen.insert(canonical) {
+            continue;
+        }
+
+        let source = if sources::snap_name(&executable_path).is_some() {
+            LinuxInstallationSource::Snap
+        } else if sources::is_nix_path(&executable_path) {
+            LinuxInstallationSource::Nix
+        } else {
+            LinuxInstallationSource::System
+        };
+        browsers.push(BrowserInfo {
+            kind,
+            channel,
+            display_name: name.to_string(),
+            unique_id: executable_path.to_string_lossy().to_string(),
+            executable_path,
+            version: None,
+            exec_command: None,
+            source: InstallationSource::Linux(source),
+            scope: InstallScope::System,
+            arch: None,
+            is_default: false,
+        });
+    }
+    browsers
+}
+
+fn appimage_dirs() -> Vec<PathBuf> {
+    appimage_dirs_with(&super::detection_config().application_dirs)
+}
+
+/// The directories searched for AppImages, with `application_dirs` from the config last.
+fn appimage_dirs_with(application_dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(home) = env::var("HOME") {
+        dirs.push(Path::new(&home).join("Applications"));
+        dirs.push(Path::new(&home).join(".local/bin"));
+        dirs.push(Path::new(&home).join("bin"));
+    }
+    for dir in application_dirs {
+        if !dirs.contains(dir) {
+            dirs.push(dir.clone());
+        }
+    }
+    dirs
+}
+
+fn is_appimage(path: &Path) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("appimage"))
+}
+
+fn detect_appimages<F: FileSystem>(fs: &F) -> Vec<BrowserInfo> {
+    let mut browsers = Vec::new();
+    let mut seen = HashSet::new();
+
+    for dir in appimage_dirs() {
+        if !fs.is_dir(&dir) {
+            continue;
+        }
+        let Ok(entries) = fs.read_dir(&dir) else {
+            continue;
+        };
+
+        for path in entries {
+            if !is_appimage(&path) || !fs_is_file(fs, &path) {
+                continue;
+            }
+            let Ok(canonical_path) = fs.canonicalize(&path) else {
+                continue;
+            };
+            if !seen.insert(canonical_path) {
+                continue;
+            }
+
+            if let Some(browser_info) = create_appimage_browser_info(fs, &path) {
+                browsers.push(browser_info);
+            }
+        }
+    }
+
+    browsers
+}
+
+fn create_appimage_browser_info<F: FileSystem>(fs: &F, path: &Path) -> Option<BrowserInfo> {
+    let file_stem = path.file_stem()?.to_str()?;
+    let content = appimage::desktop_entry(fs, path);
+
+    let (kind, channel, display_name) =
+        match classify_browser_from_token(&file_stem.to_ascii_lowercase()) {
+            Some((kind, channel)) => (kind, channel, file_stem.to_string()),
+            None => {
+                let content = content.as_deref()?;
+                if !is_web_browser(content) {
+                    return None;
+                }
+                let (kind, channel) = infer_kind_from_entry(path, content)?;
+                let display_name = get_localized_desktop_entry_value(content, "Name")
+                    .unwrap_or(file_stem)
+                    .to_string();
+                (kind, channel, display_name)
+            }
+        };
+    // AppImages are never run, so their version comes from the embedded entry
+    let version = content
+        .as_deref()
+        .and_then(|content| get_desktop_entry_value(content, "X-AppImage-Version"))
+        .map(str::to_string);
+
+    Some(BrowserInfo {
+        kind,
+        channel,
+        display_name,
+        executable_path: path.to_path_buf(),
+        version,
+        unique_id: path.to_str()?.to_string(),
+        exec_command: None,
+        source: InstallationSource::Linux(LinuxInstallationSource::AppImage),
+        scope: InstallScope::System,
+        arch: None,
+        is_default: false,
+    })
+}
+
+/// Runs a command and collects its output, killing it if it does not exit within `timeout`.
+fn output_with_timeout(mut command: Command, timeout: Duration) -> Option<std::process::Output> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => return child.wait_with_output().ok(),
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(20)),
+            _ => {
+                debug!(?command, "Command timed out");
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+}
+
+/// Directories searched for desktop entries.
+///
+/// Besides the well-known system, Flatpak, snap and Nix locations, every directory in
+/// `$XDG_DATA_DIRS` is searched, which is how other profile layouts are picked up.
 fn desktop_file_dirs() -> Vec<PathBuf> {
     let mut dirs = vec![
         PathBuf::from("/usr/share/applications"),
@@ -204,6 +800,34 @@ fn desktop_file_dirs() -> Vec<PathBuf> {
     if let Ok(home) = env::var("HOME") {
         dirs.push(Path::new(&home).join(".local/share/applications"));
         dirs.push(Path::new(&home).join(".local/share/flatpak/exports/share/applications"));
+        dirs.push(Path::new(&home).join(".nix-profile/share/applications"));
+        dirs.push(Path::new(&home).join(".local/state/nix/profile/share/applications"));
+    }
+    dirs.push(PathBuf::from("/run/current-system/sw/share/applications"));
+    if let Ok(user) = env::var("USER") {
+        dirs.push(
+            Path::new("/etc/profiles/per-user")
+                .join(user)
+                .join("share/applications"),
+        );
+    }
+    dirs.push(PathBuf::from(
+        "/nix/var/nix/profiles/default/share/applications",
+    ));
+
+    if let Some(data_dirs) = env::var_os("XDG_DATA_DIRS") {
+        for dir in env::split_paths(&data_dirs).filter(|dir| dir.is_absolute()) {
+            let dir = dir.join("applications");
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+    }
+
+    for dir in &super::detection_config().desktop_file_dirs {
+        if !dirs.contains(dir) {
+            dirs.push(dir.clone());
+        }
     }
     dirs
 }
@@ -219,25 +843,86 @@ fn get_desktop_entry_value<'a>(content: &'a str, key: &str) -> Option<&'a str> {
     })
 }
 
-fn is_web_browser(content: &str) -> bool {
-    if let Some(mime_type) = get_desktop_entry_value(content, "MimeType") {
-        return mime_type.split(';').any(|t| t == "x-scheme-handler/https");
+/// Looks up `key` for the user's messages locale, e.g. `Name[de]` on a German system.
+fn get_localized_desktop_entry_value<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .find_map(|var| env::var(var).ok().filter(|value| !value.is_empty()));
+    localized_desktop_entry_value(content, key, locale.as_deref())
+}
+
+/// Follows the desktop entry spec: for `lang_COUNTRY.ENCODING@MODIFIER` the keys
+/// `lang_COUNTRY@MODIFIER`, `lang_COUNTRY`, `lang@MODIFIER` and `lang` are tried in turn
+/// before the unlocalized key.
+fn localized_desktop_entry_value<'a>(
+    content: &'a str,
+    key: &str,
+    locale: Option<&str>,
+) -> Option<&'a str> {
+    let locales = locale.map(desktop_entry_locales).unwrap_or_default();
+    locales
+        .iter()
+        .find_map(|locale| get_desktop_entry_value(content, &format!("{}[{}]", key, locale)))
+        .or_else(|| get_desktop_entry_value(content, key))
+}
+
+fn desktop_entry_locales(locale: &str) -> Vec<String> {
+    let (locale, modifier) = match locale.split_once('@') {
+        Some((locale, modifier)) => (locale, Some(modifier)),
+        None => (locale, None),
+    };
+    let locale = locale.split('.').next().unwrap_or(locale);
+    if locale.is_empty() || locale == "C" || locale == "POSIX" {
+        return Vec::new();
+    }
+    let (lang, country) = match locale.split_once('_') {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (locale, None),
+    };
+
+    let mut locales = Vec::new();
+    if let (Some(country), Some(modifier)) = (country, modifier) {
+        locales.push(format!("{}_{}@{}", lang, country, modifier));
+    }
+    if let Some(country) = country {
+        locales.push(format!("{}_{}", lang, country));
     }
-    false
+    if let Some(modifier) = modifier {
+        locales.push(format!("{}@{}", lang, modifier));
+    }
+    locales.push(lang.to_string());
+    locales
+}
+
+fn is_web_browser(content: &str) -> bool {
+    handles_scheme(content, "https")
+}
+
+fn handles_scheme(content: &str, scheme: &str) -> bool {
+    let handler = format!("x-scheme-handler/{}", scheme);
+    get_desktop_entry_value(content, "MimeType")
+        .is_some_and(|mime_type| mime_type.split(';').any(|t| t == handler))
+}
+
+fn requires_terminal(content: &str) -> bool {
+    get_desktop_entry_value(content, "Terminal").is_some_and(|value| value == "true")
 }
 
 fn create_browser_info(path: &Path, content: &str) -> Option<BrowserInfo> {
     let (kind, channel) =
         parse_desktop_file_name(path.to_str()?).or_else(|| infer_kind_from_entry(path, content))?;
 
-    let display_name = get_desktop_entry_value(content, "Name")
+    let display_name = get_localized_desktop_entry_value(content, "Name")
         .map(|s| s.to_string())
         .unwrap_or_else(|| kind.canonical_name().to_string());
 
     let exec_value = get_desktop_entry_value(content, "Exec")?;
     let executable_path = parse_exec_path(exec_value)?;
 
-    let version = None; // Version detection is out of scope.
+    // Resolved for all detected browsers at once, see `version::resolve_versions`.
+    let version = None;
+
+    let source = entry_source(path, content, exec_value, &executable_path);
 
     Some(BrowserInfo {
         kind,
@@ -247,13 +932,153 @@ fn create_browser_info(path: &Path, content: &str) -> Option<BrowserInfo> {
         version,
         unique_id: path.to_str()?.to_string(),
         exec_command: Some(exec_value.to_string()),
+        source: InstallationSource::Linux(source),
+        scope: InstallScope::System,
+        arch: None,
+        is_default: false,
     })
 }
 
+fn entry_source(
+    desktop_path: &Path,
+    content: &str,
+    exec: &str,
+    executable_path: &Path,
+) -> LinuxInstallationSource {
+    if is_flatpak_entry(content, exec) {
+        LinuxInstallationSource::Flatpak
+    } else if is_snap_entry(content, executable_path) {
+        LinuxInstallationSource::Snap
+    } else if sources::is_nix_path(desktop_path) || sources::is_nix_path(executable_path) {
+        LinuxInstallationSource::Nix
+    } else if is_appimage(executable_path) {
+        LinuxInstallationSource::AppImage
+    } else {
+        LinuxInstallationSource::System
+    }
+}
+
+fn is_flatpak_entry(content: &str, exec: &str) -> bool {
+    if get_desktop_entry_value(content, "X-Flatpak").is_some() {
+        return true;
+    }
+
+    parse_flatpak_run(exec).is_some()
+}
+
+fn is_snap_entry(content: &str, executable_path: &Path) -> bool {
+    get_desktop_entry_value(content, "X-SnapInstanceName").is_some()
+        || sources::snap_name(executable_path).is_some()
+}
+
+/// Builds a `snap run` invocation for a confined snap browser.
+///
+/// Strictly confined snaps only see non-hidden directories in `$HOME` and their own
+/// `~/snap/<name>` data, so custom or temporary profile directories elsewhere are rejected.
+fn prepare_snap_command(
+    info: &BrowserInfo,
+    profile_opts: Option<&crate::profile::ProfileOptions>,
+) -> Result<(PathBuf, Vec<String>, bool), LaunchError> {
+    let command = info
+        .executable_path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| LaunchError::MissingExecutable(info.unique_id.clone()))?;
+    let snap = sources::snap_name(&info.executable_path).unwrap_or(command);
+
+    if let Some(profile_opts) = profile_opts {
+        match &profile_opts.profile_type {
+            crate::profile::ProfileType::CustomDirectory(path)
+            | crate::profile::ProfileType::Temporary(path) => {
+                let home = dirs_next::home_dir().unwrap_or_default();
+                if !is_snap_accessible(path, &home, snap) {
+                    return Err(LaunchError::SnapConfinement {
+                        snap: snap.to_string(),
+                        path: path.clone(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok((
+        PathBuf::from("snap"),
+        vec!["run".to_string(), command.to_string()],
+        false,
+    ))
+}
+
+fn is_snap_accessible(path: &Path, home: &Path, snap: &str) -> bool {
+    if path.starts_with(home.join("snap").join(snap)) {
+        return true;
+    }
+
+    // The snapd `home` interface excludes hidden files and directories at the top of $HOME.
+    match path
+        .strip_prefix(home)
+        .ok()
+        .and_then(|rest| rest.iter().next())
+    {
+        Some(first) => !first.to_string_lossy().starts_with('.'),
+        None => false,
+    }
+}
+
+/// Builds a `flatpak run` invocation so the browser stays inside its sandbox.
+///
+/// Custom and temporary profile directories live outside the sandbox, so they are exposed
+/// with `--filesystem`; URLs and profile arguments are appended by the caller after the app id.
+fn prepare_flatpak_command(
+    info: &BrowserInfo,
+    profile_opts: Option<&crate::profile::ProfileOptions>,
+) -> Result<(PathBuf, Vec<String>, bool), LaunchError> {
+    let (run_options, app_id) = info
+        .exec_command
+        .as_deref()
+        .and_then(parse_flatpak_run)
+        .or_else(|| {
+            let app_id = Path::new(&info.unique_id).file_stem()?.to_str()?;
+            Some((Vec::new(), app_id.to_string()))
+        })
+        .ok_or_else(|| LaunchError::MissingExecutable(info.unique_id.clone()))?;
+
+    let mut args = vec!["run".to_string()];
+    // File forwarding only applies to the @@ markers of the desktop entry, which are not used here.
+    args.extend(
+        run_options
+            .into_iter()
+            .filter(|option| option != "--file-forwarding"),
+    );
+
+    if let Some(profile_opts) = profile_opts {
+        match &profile_opts.profile_type {
+            crate::profile::ProfileType::CustomDirectory(path)
+            | crate::profile::ProfileType::Temporary(path) => {
+                args.push(format!("--filesystem={}", path.display()));
+            }
+            _ => {}
+        }
+    }
+
+    args.push(app_id);
+
+    Ok((PathBuf::from("flatpak"), args, false))
+}
+
 fn parse_exec_path(exec: &str) -> Option<PathBuf> {
     let parts = shell_words::split(exec).ok()?;
-    let first = parts.first()?.clone();
-    Some(PathBuf::from(first))
+    let mut iter = parts.into_iter().peekable();
+
+    // Snap entries wrap the command as `env VAR=value ... /snap/bin/<name>`.
+    if iter.peek().map(String::as_str) == Some("env") {
+        iter.next();
+        while iter.peek().is_some_and(|token| token.contains('=')) {
+            iter.next();
+        }
+    }
+
+    iter.next().map(PathBuf::from)
 }
 
 fn parse_desktop_file_name(path_str: &str) -> Option<(BrowserKind, BrowserChannel)> {
@@ -397,6 +1222,9 @@ fn infer_kind_from_entry(path: &Path, content: &str) -> Option<(BrowserKind, Bro
     if let Some(name) = get_desktop_entry_value(content, "Name") {
         candidates.push(name.to_string());
     }
+    if let Some(name) = get_localized_desktop_entry_value(content, "Name") {
+        candidates.push(name.to_string());
+    }
 
     if let Some(exec) = get_desktop_entry_value(content, "Exec") {
         candidates.push(exec.to_string());
@@ -425,12 +1253,42 @@ fn classify_browser_from_token(token: &str) -> Option<(BrowserKind, BrowserChann
         return Some((BrowserKind::Helium, BrowserChannel::Single));
     }
 
+    if token.contains("wavebox") {
+        return Some((BrowserKind::Wavebox, BrowserChannel::Single));
+    }
+
+    // Also matches the `sidekick-browser` desktop entry.
+    if token.contains("sidekick") {
+        return Some((BrowserKind::Sidekick, BrowserChannel::Single));
+    }
+
+    if token.contains("yandex") {
+        let channel = if token.contains("beta") {
+            ChromiumChannel::Beta
+        } else {
+            ChromiumChannel::Stable
+        };
+        return Some((BrowserKind::Yandex, BrowserChannel::Chromium(channel)));
+    }
+
+    // Also matches the `io.github.ungoogled_software.ungoogled_chromium` Flatpak id. Distro
+    // packages that install plain `chromium` entries cannot be told apart from Chromium.
+    if token.contains("ungoogled") {
+        return Some((BrowserKind::UngoogledChromium, BrowserChannel::Single));
+    }
+
+    // Every CPU variant (AVX, AVX2, SSE3, SSE4) installs `thorium-browser`; AppImages carry
+    // the variant in their file name.
+    if token.contains("thorium") {
+        return Some((BrowserKind::Thorium, BrowserChannel::Single));
+    }
+
     if token.contains("google-chrome") || token.contains("chrome") {
         let channel = if token.contains("canary") {
             ChromiumChannel::Canary
         } else if token.contains("beta") {
             ChromiumChannel::Beta
-        } else if token.contains("dev") {
+        } else if token.contains("dev") || token.contains("unstable") {
             ChromiumChannel::Dev
         } else {
             ChromiumChannel::Stable
@@ -469,18 +1327,19 @@ fn classify_browser_from_token(token: &str) -> Option<(BrowserKind, BrowserChann
     }
 
     if token.contains("brave") {
-        let channel = if token.contains("beta") {
-            ChromiumChannel::Beta
-        } else if token.contains("nightly") {
-            ChromiumChannel::Dev
-        } else {
-            ChromiumChannel::Stable
-        };
+        let channel = channels::vendor_chromium_channel(token);
         return Some((BrowserKind::Brave, BrowserChannel::Chromium(channel)));
     }
 
     if token.contains("vivaldi") {
-        return Some((BrowserKind::Vivaldi, BrowserChannel::Single));
+        let channel = channels::vendor_chromium_channel(token);
+        return Some((BrowserKind::Vivaldi, BrowserChannel::Chromium(channel)));
+    }
+
+    // Also matches the `com.opera.Opera` Flatpak id.
+    if token.contains("opera") {
+        let channel = channels::vendor_chromium_channel(token);
+        return Some((BrowserKind::Opera, BrowserChannel::Chromium(channel)));
     }
 
     if token.contains("tor") {
@@ -491,6 +1350,62 @@ fn classify_browser_from_token(token: &str) -> Option<(BrowserKind, BrowserChann
         return Some((BrowserKind::Waterfox, BrowserChannel::Single));
     }
 
+    // Also matches the `io.gitlab.librewolf-community` Flatpak id.
+    if token.contains("librewolf") {
+        return Some((BrowserKind::LibreWolf, BrowserChannel::Single));
+    }
+
+    if token.contains("palemoon") || token.contains("pale moon") {
+        return Some((BrowserKind::PaleMoon, BrowserChannel::Single));
+    }
+
+    if token.contains("basilisk") {
+        return Some((BrowserKind::Basilisk, BrowserChannel::Single));
+    }
+
+    // Also matches the `one.ablaze.floorp` Flatpak id.
+    if token.contains("floorp") {
+        let channel = if token.contains("beta") {
+            FirefoxChannel::Beta
+        } else {
+            FirefoxChannel::Stable
+        };
+        return Some((BrowserKind::Floorp, BrowserChannel::Firefox(channel)));
+    }
+
+    // Also matches the `org.kde.falkon` and `org.kde.konqueror` desktop entries.
+    if token.contains("falkon") {
+        return Some((BrowserKind::Falkon, BrowserChannel::Single));
+    }
+
+    if token.contains("konqueror") {
+        return Some((BrowserKind::Konqueror, BrowserChannel::Single));
+    }
+
+    // Also matches the `org.gnome.Epiphany` desktop entry of distro packages and the Flatpak.
+    if token.contains("epiphany") {
+        return Some((BrowserKind::Epiphany, BrowserChannel::Single));
+    }
+
+    // Also matches the `org.qutebrowser.qutebrowser` desktop entry.
+    if token.contains("qutebrowser") {
+        return Some((BrowserKind::Qutebrowser, BrowserChannel::Single));
+    }
+
+    // `zen` alone is too short to match as a substring, so look for it as a separate word.
+    if token.contains("zen_browser")
+        || token
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .any(|word| word == "zen")
+    {
+        let channel = if token.contains("twilight") {
+            FirefoxChannel::Twilight
+        } else {
+            FirefoxChannel::Stable
+        };
+        return Some((BrowserKind::Zen, BrowserChannel::Firefox(channel)));
+    }
+
     if token.contains("arc") {
         return Some((BrowserKind::Arc, BrowserChannel::Single));
     }
@@ -514,6 +1429,127 @@ fn detect_default_desktop_entry<F: FileSystem>(fs: &F) -> Option<String> {
     None
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum KdeBrowserSetting {
+    DesktopEntry(String),
+    Command(String),
+}
+
+fn is_kde_session() -> bool {
+    let current_desktop = env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+    if current_desktop
+        .split(':')
+        .any(|desktop| desktop.eq_ignore_ascii_case("kde"))
+    {
+        return true;
+    }
+
+    env::var("KDE_FULL_SESSION")
+        .map(|value| value.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+fn kde_default_browser<F: FileSystem>(fs: &F) -> Option<SystemDefaultBrowser> {
+    for path in candidate_kdeglobals_files() {
+        if !fs_is_file(fs, &path) {
+            continue;
+        }
+
+        let Ok(content) = fs.read_to_string(&path) else {
+            continue;
+        };
+
+        match parse_kdeglobals_browser(&content) {
+            Some(KdeBrowserSetting::DesktopEntry(desktop_id)) => {
+                debug!(desktop_id = %desktop_id, "Using KDE BrowserApplication setting");
+                if let Some(default) = system_default_from_desktop_id(fs, desktop_id) {
+                    return Some(default);
+                }
+            }
+            Some(KdeBrowserSetting::Command(command)) => {
+                debug!(command = %command, "Using KDE BrowserApplication command");
+                let Some(path) = parse_exec_path(&command) else {
+                    continue;
+                };
+                let display_name = path
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("system-default")
+                    .to_string();
+                let kind = classify_browser_from_token(&display_name.to_ascii_lowercase())
+                    .map(|(kind, _)| kind);
+
+                return Some(SystemDefaultBrowser {
+                    identifier: command,
+                    display_name,
+                    kind,
+                    path: Some(path),
+                });
+            }
+            None => {}
+        }
+    }
+
+    None
+}
+
+fn candidate_kdeglobals_files() -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    if let Some(config_home) = xdg_config_home() {
+        files.push(config_home.join("kdeglobals"));
+    }
+
+    files.push(PathBuf::from("/etc/xdg/kdeglobals"));
+    files
+}
+
+/// Reads `BrowserApplication` from the `[General]` group of kdeglobals.
+///
+/// KDE stores either a desktop entry id or, when prefixed with `!`, a raw command.
+fn parse_kdeglobals_browser(content: &str) -> Option<KdeBrowserSetting> {
+    let mut in_general = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            in_general = trimmed == "[General]";
+            continue;
+        }
+
+        if !in_general {
+            continue;
+        }
+
+        let Some((key, value)) = trimmed.split_once('=') else {
+            continue;
+        };
+
+        // `[$e]` marks values that KDE expands shell-style; the value format is the same.
+        let key = key.trim();
+        if key != "BrowserApplication" && key != "BrowserApplication[$e]" {
+            continue;
+        }
+
+        let value = value.trim();
+        if value.is_empty() {
+            return None;
+        }
+
+        return Some(match value.strip_prefix('!') {
+            Some(command) => KdeBrowserSetting::Command(command.trim().to_string()),
+            None => KdeBrowserSetting::DesktopEntry(value.to_string()),
+        });
+    }
+
+    None
+}
+
 fn candidate_mimeapps_files() -> Vec<PathBuf> {
     let mut files = Vec::new();
 
@@ -617,3 +1653,409 @@ fn resolve_desktop_entry_path<F: FileSystem>(fs: &F, desktop_id: &str) -> Option
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn system_default_is_matched_by_desktop_id() {
+        let browser = BrowserInfo {
+            kind: BrowserKind::Firefox,
+            channel: BrowserChannel::Firefox(FirefoxChannel::Stable),
+            display_name: "Firefox".to_string(),
+            executable_path: PathBuf::from("/usr/bin/firefox"),
+            version: None,
+            unique_id: "/usr/share/applications/firefox.desktop".to_string(),
+            exec_command: Some("firefox %u".to_string()),
+            source: InstallationSource::Linux(LinuxInstallationSource::System),
+            scope: InstallScope::System,
+            arch: None,
+            is_default: false,
+        };
+        let system_default = |identifier: &str| SystemDefaultBrowser {
+            identifier: identifier.to_string(),
+            display_name: "Firefox".to_string(),
+            kind: Some(BrowserKind::Firefox),
+            path: Some(PathBuf::from("/usr/bin/firefox")),
+        };
+
+        assert!(is_system_default(
+            &browser,
+            &system_default("firefox.desktop")
+        ));
+        assert!(!is_system_default(
+            &browser,
+            &system_default("firefox-esr.desktop")
+        ));
+    }
+
+    #[test]
+    fn desktop_entry_names_follow_the_locale() {
+        let content = "[Desktop Entry]\nName=Web Browser\nName[de]=Webbrowser\n\
+                       Name[de_CH]=Webbrowser (Schweiz)\nName[sr@latin]=Veb pregledač\n";
+
+        let name = |locale| localized_desktop_entry_value(content, "Name", locale);
+        assert_eq!(name(Some("de_CH.UTF-8")), Some("Webbrowser (Schweiz)"));
+        assert_eq!(name(Some("de_AT.UTF-8")), Some("Webbrowser"));
+        assert_eq!(name(Some("sr_RS.UTF-8@latin")), Some("Veb pregledač"));
+        assert_eq!(name(Some("fr_FR.UTF-8")), Some("Web Browser"));
+        assert_eq!(name(Some("C.UTF-8")), Some("Web Browser"));
+        assert_eq!(name(None), Some("Web Browser"));
+    }
+
+    #[test]
+    fn install_scope_follows_home_directory() {
+        let home = Path::new("/home/me");
+        let browser = |source, executable: &str, unique_id: &str| BrowserInfo {
+            kind: BrowserKind::Firefox,
+            channel: BrowserChannel::Firefox(FirefoxChannel::Stable),
+            display_name: "Firefox".to_string(),
+            executable_path: PathBuf::from(executable),
+            version: None,
+            unique_id: unique_id.to_string(),
+            exec_command: None,
+            source: InstallationSource::Linux(source),
+            scope: InstallScope::System,
+            arch: None,
+            is_default: false,
+        };
+        let scope = |browser: BrowserInfo| install_scope_in(&browser, Some(home));
+
+        assert_eq!(
+            scope(browser(
+                LinuxInstallationSource::System,
+                "/usr/bin/firefox",
+                "/home/me/.local/share/applications/firefox.desktop",
+            )),
+            InstallScope::System
+        );
+        assert_eq!(
+            scope(browser(
+                LinuxInstallationSource::AppImage,
+                "/home/me/Applications/firefox.AppImage",
+                "/home/me/Applications/firefox.AppImage",
+            )),
+            InstallScope::User
+        );
+        assert_eq!(
+            scope(browser(
+                LinuxInstallationSource::Flatpak,
+                "/usr/bin/flatpak",
+                "/home/me/.local/share/flatpak/exports/share/applications/org.mozilla.firefox.desktop",
+            )),
+            InstallScope::User
+        );
+        assert_eq!(
+            scope(browser(
+                LinuxInstallationSource::Flatpak,
+                "/usr/bin/flatpak",
+                "/var/lib/flatpak/exports/share/applications/org.mozilla.firefox.desktop",
+            )),
+            InstallScope::System
+        );
+    }
+
+    #[test]
+    fn appimages_are_searched_in_configured_application_dirs() {
+        let dirs = appimage_dirs_with(&[PathBuf::from("/opt/apps"), PathBuf::from("/opt/apps")]);
+        assert_eq!(dirs.last(), Some(&PathBuf::from("/opt/apps")));
+        assert_eq!(
+            dirs.iter()
+                .filter(|dir| *dir == Path::new("/opt/apps"))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn path_executables_are_classified() {
+        for name in PATH_EXECUTABLES {
+            assert!(classify_browser_from_token(name).is_some(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn vendor_channels_are_classified() {
+        for (token, kind, channel) in [
+            (
+                "brave-browser-beta.desktop",
+                BrowserKind::Brave,
+                ChromiumChannel::Beta,
+            ),
+            (
+                "brave-browser-nightly.desktop",
+                BrowserKind::Brave,
+                ChromiumChannel::Canary,
+            ),
+            (
+                "vivaldi-stable.desktop",
+                BrowserKind::Vivaldi,
+                ChromiumChannel::Stable,
+            ),
+            (
+                "vivaldi-snapshot.desktop",
+                BrowserKind::Vivaldi,
+                ChromiumChannel::Dev,
+            ),
+            (
+                "opera-beta.desktop",
+                BrowserKind::Opera,
+                ChromiumChannel::Beta,
+            ),
+            (
+                "opera-developer.desktop",
+                BrowserKind::Opera,
+                ChromiumChannel::Dev,
+            ),
+        ] {
+            assert_eq!(
+                classify_browser_from_token(token),
+                Some((kind, BrowserChannel::Chromium(channel))),
+                "{}",
+                token
+            );
+        }
+    }
+
+    #[test]
+    fn chromium_forks_are_classified() {
+        for (token, kind) in [
+            ("thorium-browser.desktop", BrowserKind::Thorium),
+            ("yandex-browser-beta.desktop", BrowserKind::Yandex),
+            ("wavebox.desktop", BrowserKind::Wavebox),
+            ("sidekick-browser.desktop", BrowserKind::Sidekick),
+            (
+                "thorium_browser_130.0.6723.174_avx2.appimage",
+                BrowserKind::Thorium,
+            ),
+            ("chromium-browser.desktop", BrowserKind::Chromium),
+            (
+                "io.github.ungoogled_software.ungoogled_chromium.desktop",
+                BrowserKind::UngoogledChromium,
+            ),
+        ] {
+            assert_eq!(
+                classify_browser_from_token(token).map(|(kind, _)| kind),
+                Some(kind),
+                "{}",
+                token
+            );
+        }
+        assert_eq!(
+            classify_browser_from_token("yandex-browser-beta.desktop"),
+            Some((
+                BrowserKind::Yandex,
+                BrowserChannel::Chromium(ChromiumChannel::Beta)
+            ))
+        );
+    }
+
+    #[test]
+    fn firefox_forks_are_classified() {
+        for (token, kind) in [
+            ("librewolf.desktop", BrowserKind::LibreWolf),
+            (
+                "io.gitlab.librewolf-community.desktop",
+                BrowserKind::LibreWolf,
+            ),
+            ("waterfox.desktop", BrowserKind::Waterfox),
+            ("zen.desktop", BrowserKind::Zen),
+            ("app.zen_browser.zen.desktop", BrowserKind::Zen),
+            ("zen browser", BrowserKind::Zen),
+            ("one.ablaze.floorp.desktop", BrowserKind::Floorp),
+            ("palemoon.desktop", BrowserKind::PaleMoon),
+            ("basilisk.desktop", BrowserKind::Basilisk),
+        ] {
+            assert_eq!(
+                classify_browser_from_token(token).map(|(kind, _)| kind),
+                Some(kind),
+                "{}",
+                token
+            );
+        }
+        assert_eq!(
+            classify_browser_from_token("floorp-beta.desktop"),
+            Some((
+                BrowserKind::Floorp,
+                BrowserChannel::Firefox(FirefoxChannel::Beta)
+            ))
+        );
+    }
+
+    #[test]
+    fn browsers_with_other_engines_are_classified() {
+        for (token, kind) in [
+            (
+                "org.qutebrowser.qutebrowser.desktop",
+                BrowserKind::Qutebrowser,
+            ),
+            ("qutebrowser", BrowserKind::Qutebrowser),
+            ("org.gnome.epiphany.desktop", BrowserKind::Epiphany),
+            ("epiphany-browser.desktop", BrowserKind::Epiphany),
+            ("org.kde.falkon.desktop", BrowserKind::Falkon),
+            ("org.kde.konqueror.desktop", BrowserKind::Konqueror),
+        ] {
+            assert_eq!(
+                classify_browser_from_token(token).map(|(kind, _)| kind),
+                Some(kind),
+                "{}",
+                token
+            );
+        }
+    }
+
+    #[test]
+    fn kdeglobals_desktop_entry_is_parsed() {
+        let content = "[General]\nBrowserApplication=org.kde.falkon.desktop\n";
+        assert_eq!(
+            parse_kdeglobals_browser(content),
+            Some(KdeBrowserSetting::DesktopEntry(
+                "org.kde.falkon.desktop".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn kdeglobals_command_is_parsed() {
+        let content = "[KDE]\nBrowserApplication=ignored.desktop\n\n[General]\nBrowserApplication[$e]=!/usr/bin/firefox --new-window\n";
+        assert_eq!(
+            parse_kdeglobals_browser(content),
+            Some(KdeBrowserSetting::Command(
+                "/usr/bin/firefox --new-window".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn snap_exec_resolves_wrapped_executable() {
+        let exec = "env BAMF_DESKTOP_FILE_HINT=/var/lib/snapd/desktop/applications/firefox_firefox.desktop /snap/bin/firefox %u";
+        let path = parse_exec_path(exec).expect("executable path");
+
+        assert_eq!(path, PathBuf::from("/snap/bin/firefox"));
+        assert_eq!(sources::snap_name(&path), Some("firefox"));
+    }
+
+    #[test]
+    fn snap_confinement_rules() {
+        let home = Path::new("/home/user");
+
+        assert!(is_snap_accessible(
+            Path::new("/home/user/snap/chromium/common/pathway_profile_1"),
+            home,
+            "chromium"
+        ));
+        assert!(is_snap_accessible(
+            Path::new("/home/user/profiles/work"),
+            home,
+            "chromium"
+        ));
+        assert!(!is_snap_accessible(
+            Path::new("/home/user/.profiles/work"),
+            home,
+            "chromium"
+        ));
+        assert!(!is_snap_accessible(
+            Path::new("/tmp/pathway_profile_1"),
+            home,
+            "chromium"
+        ));
+    }
+
+    #[test]
+    fn sandbox_wraps_command_with_bind_dirs() {
+        let (program, args) = wrap_in_sandbox(
+            crate::profile::SandboxTool::Firejail,
+            PathBuf::from("/usr/bin/firefox"),
+            vec!["https://example.com".to_string()],
+            &[PathBuf::from("/home/user/.mozilla/firefox")],
+        );
+
+        assert_eq!(program, PathBuf::from("firejail"));
+        assert_eq!(
+            args,
+            vec![
+                "--quiet",
+                "--whitelist=/home/user/.mozilla/firefox",
+                "/usr/bin/firefox",
+                "https://example.com"
+            ]
+        );
+
+        let (program, args) = wrap_in_sandbox(
+            crate::profile::SandboxTool::Bwrap,
+            PathBuf::from("/usr/bin/chromium"),
+            vec!["https://example.com".to_string()],
+            &[PathBuf::from("/tmp/pathway_profile_1")],
+        );
+
+        assert_eq!(program, PathBuf::from("bwrap"));
+        let tail: Vec<&str> = args
+            .iter()
+            .rev()
+            .take(6)
+            .rev()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(
+            tail,
+            vec![
+                "--bind-try",
+                "/tmp/pathway_profile_1",
+                "/tmp/pathway_profile_1",
+                "--new-session",
+                "/usr/bin/chromium",
+                "https://example.com"
+            ]
+        );
+    }
+
+    #[test]
+    fn terminal_entries_are_recognized() {
+        let w3m = "[Desktop Entry]\nName=w3m\nExec=w3m %u\nTerminal=true\n\
+                   MimeType=text/html;x-scheme-handler/http;x-scheme-handler/https;\n";
+        assert!(requires_terminal(w3m));
+
+        let firefox = "[Desktop Entry]\nName=Firefox\nExec=firefox %u\nTerminal=false\n";
+        assert!(!requires_terminal(firefox));
+        assert!(!requires_terminal("[Desktop Entry]\nName=Chromium\n"));
+    }
+
+    #[test]
+    fn mail_exec_lines_expand_urls() {
+        let url = vec!["mailto:someone@example.com".to_string()];
+
+        let (program, args) =
+            mail_command_from_exec("thunderbird %u", &url).expect("exec should parse");
+        assert_eq!(program, PathBuf::from("thunderbird"));
+        assert_eq!(args, vec!["mailto:someone@example.com"]);
+
+        let (program, args) = mail_command_from_exec(
+            "/usr/bin/flatpak run --branch=stable --command=geary org.gnome.Geary %U",
+            &url,
+        )
+        .expect("exec should parse");
+        assert_eq!(program, PathBuf::from("/usr/bin/flatpak"));
+        assert_eq!(args.last().unwrap(), "mailto:someone@example.com");
+
+        let (_, args) = mail_command_from_exec("evolution --component=mail %i", &url).unwrap();
+        assert_eq!(args, vec!["--component=mail", "mailto:someone@example.com"]);
+
+        let mail_entry = "[Desktop Entry]\nName=Thunderbird\nExec=thunderbird %u\n\
+                          MimeType=message/rfc822;x-scheme-handler/mailto;\n";
+        assert!(handles_scheme(mail_entry, "mailto"));
+        assert!(!is_web_browser(mail_entry));
+    }
+
+    #[test]
+    fn kdeglobals_without_browser_setting() {
+        assert_eq!(
+            parse_kdeglobals_browser("[General]\nColorScheme=Breeze\n"),
+            None
+        );
+        assert_eq!(
+            parse_kdeglobals_browser("[General]\nBrowserApplication=\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,589 @@
+//! Reads the desktop entry embedded in an AppImage without running it.
+//!
+//! A type 2 AppImage is an ELF runtime followed by a SquashFS image, which starts where the
+//! runtime's section headers end. The top-level `*.desktop` file of the image, often a symlink
+//! into `usr/share/applications`, is looked up and read from there. Images compressed with
+//! anything but gzip or zstd, and type 1 AppImages (ISO 9660), are not read.
+
+use crate::filesystem::FileSystem;
+use std::io::Read;
+use std::path::Path;
+
+const SQUASHFS_MAGIC: &[u8; 4] = b"hsqs";
+const SUPERBLOCK_SIZE: usize = 96;
+
+const GZIP: u16 = 1;
+const ZSTD: u16 = 6;
+
+/// Largest uncompressed metadata block.
+const METADATA_BLOCK_SIZE: usize = 8192;
+const METADATA_UNCOMPRESSED: u16 = 0x8000;
+const DATA_UNCOMPRESSED: u32 = 1 << 24;
+const NO_FRAGMENT: u32 = 0xFFFF_FFFF;
+
+/// Inode header and the largest fixed part of an inode, that of an extended file.
+const INODE_FIXED_SIZE: usize = 16 + 40;
+
+const BASIC_DIRECTORY: u16 = 1;
+const BASIC_FILE: u16 = 2;
+const BASIC_SYMLINK: u16 = 3;
+const EXTENDED_DIRECTORY: u16 = 8;
+const EXTENDED_FILE: u16 = 9;
+const EXTENDED_SYMLINK: u16 = 10;
+
+/// Larger desktop entries and directory listings are not read.
+const MAX_FILE_SIZE: u64 = 64 * 1024;
+const MAX_LISTING_SIZE: usize = 1024 * 1024;
+const MAX_SYMLINKS: usize = 8;
+
+/// The top-level desktop entry of the AppImage at `path`.
+pub(super) fn desktop_entry<F: FileSystem>(fs: &F, path: &Path) -> Option<String> {
+    let image = Image::open(fs, path)?;
+    let root = image.inode(image.root)?;
+    let (_, reference) = image
+        .entries(&root)?
+        .into_iter()
+        .find(|(name, _)| name.ends_with(".desktop"))?;
+    let file = image.follow(image.inode(reference)?)?;
+    String::from_utf8(image.read_file(&file)?).ok()
+}
+
+/// Where a type 2 AppImage's SquashFS image starts: right after the ELF section header table.
+fn squashfs_offset(header: &[u8]) -> Option<u64> {
+    let mut reader = Reader::new(header);
+    if reader.bytes(4)? != b"\x7fELF" {
+        return None;
+    }
+    let class = reader.bytes(1)?[0];
+    // Big-endian runtimes do not exist for the architectures AppImages are built for
+    if reader.bytes(1)?[0] != 1 {
+        return None;
+    }
+    let (shoff, shentsize, shnum) = match class {
+        1 => (
+            Reader::at(header, 0x20)?.u32()? as u64,
+            Reader::at(header, 0x2E)?.u16()?,
+            Reader::at(header, 0x30)?.u16()?,
+        ),
+        2 => (
+            Reader::at(header, 0x28)?.u64()?,
+            Reader::at(header, 0x3A)?.u16()?,
+            Reader::at(header, 0x3C)?.u16()?,
+        ),
+        _ => return None,
+    };
+    shoff.checked_add(shentsize as u64 * shnum as u64)
+}
+
+#[derive(Debug)]
+enum Inode {
+    Directory {
+        block: u32,
+        offset: u16,
+        size: u32,
+    },
+    File {
+        blocks_start: u64,
+        size: u64,
+        fragment: Option<(u32, u32)>,
+        block_sizes: Vec<u32>,
+    },
+    Symlink(String),
+    Other,
+}
+
+struct Image<'a, F: FileSystem> {
+    fs: &'a F,
+    path: &'a Path,
+    /// Offset of the SquashFS image in the file
+    start: u64,
+    compressor: u16,
+    block_size: u32,
+    root: u64,
+    inode_table: u64,
+    directory_table: u64,
+    fragment_table: u64,
+}
+
+impl<'a, F: FileSystem> Image<'a, F> {
+    fn open(fs: &'a F, path: &'a Path) -> Option<Self> {
+        let start = squashfs_offset(&fs.read_range(path, 0, 64).ok()?)?;
+        let superblock = fs.read_range(path, start, SUPERBLOCK_SIZE).ok()?;
+        let mut reader = Reader::new(&superblock);
+        if reader.bytes(4)? != SQUASHFS_MAGIC {
+            return None;
+        }
+        reader.skip(8)?;
+        let block_size = reader.u32()?;
+        reader.skip(4)?;
+        let compressor = reader.u16()?;
+        reader.skip(6)?;
+        if reader.u16()? != 4 {
+            return None;
+        }
+        reader.skip(2)?;
+        let root = reader.u64()?;
+        reader.skip(24)?;
+        let inode_table = reader.u64()?;
+        let directory_table = reader.u64()?;
+        let fragment_table = reader.u64()?;
+        Some(Image {
+            fs,
+            path,
+            start,
+            compressor,
+            block_size,
+            root,
+            inode_table,
+            directory_table,
+            fragment_table,
+        })
+    }
+
+    fn read(&self, offset: u64, len: usize) -> Option<Vec<u8>> {
+        let data = self
+            .fs
+            .read_range(self.path, self.start.checked_add(offset)?, len)
+            .ok()?;
+        (data.len() == len).then_some(data)
+    }
+
+    fn decompress(&self, data: Vec<u8>, compressed: bool, limit: usize) -> Option<Vec<u8>> {
+        if !compressed {
+            return Some(data);
+        }
+        match self.compressor {
+            GZIP => {
+                let mut output = Vec::new();
+                flate2::read::ZlibDecoder::new(&data[..])
+                    .take(limit as u64)
+                    .read_to_end(&mut output)
+                    .ok()?;
+                Some(output)
+            }
+            ZSTD => zstd::bulk::decompress(&data, limit).ok(),
+            _ => None,
+        }
+    }
+
+    /// `len` bytes from `offset` in the uncompressed metadata blocks starting at `block`; fewer
+    /// when the blocks end first.
+    fn metadata(&self, block: u64, offset: usize, len: usize) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut position = block;
+        while data.len() < offset + len {
+            let Some(header) = self.read(position, 2) else {
+                break;
+            };
+            let header = u16::from_le_bytes([header[0], header[1]]);
+            let size = (header & !METADATA_UNCOMPRESSED) as usize;
+            let block = self.read(position + 2, size).and_then(|block| {
+                self.decompress(
+                    block,
+                    header & METADATA_UNCOMPRESSED == 0,
+                    METADATA_BLOCK_SIZE,
+                )
+            });
+            match block {
+                Some(block) if size > 0 => data.extend(block),
+                _ => break,
+            }
+            position += 2 + size as u64;
+        }
+        data.get(offset..).unwrap_or_default().to_vec()
+    }
+
+    fn inode(&self, reference: u64) -> Option<Inode> {
+        let block = self.inode_table + (reference >> 16);
+        let offset = (reference & 0xFFFF) as usize;
+        let data = self.metadata(block, offset, INODE_FIXED_SIZE);
+        let mut reader = Reader::new(&data);
+        let kind = reader.u16()?;
+        reader.skip(14)?;
+        let inode = match kind {
+            BASIC_DIRECTORY => {
+                let block = reader.u32()?;
+                reader.skip(4)?;
+                let size = reader.u16()? as u32;
+                let offset = reader.u16()?;
+                Inode::Directory {
+                    block,
+                    offset,
+                    size,
+                }
+            }
+            EXTENDED_DIRECTORY => {
+                reader.skip(4)?;
+                let size = reader.u32()?;
+                let block = reader.u32()?;
+                reader.skip(6)?;
+                let offset = reader.u16()?;
+                Inode::Directory {
+                    block,
+                    offset,
+                    size,
+                }
+            }
+            BASIC_FILE | EXTENDED_FILE => {
+                let (blocks_start, fragment, fragment_offset, size) = if kind == BASIC_FILE {
+                    let blocks_start = reader.u32()? as u64;
+                    let fragment = reader.u32()?;
+                    let fragment_offset = reader.u32()?;
+                    (
+                        blocks_start,
+                        fragment,
+                        fragment_offset,
+                        reader.u32()? as u64,
+                    )
+                } else {
+                    let blocks_start = reader.u64()?;
+                    let size = reader.u64()?;
+                    reader.skip(12)?;
+                    let fragment = reader.u32()?;
+                    let fragment_offset = reader.u32()?;
+                    reader.skip(4)?;
+                    (blocks_start, fragment, fragment_offset, size)
+                };
+                if size > MAX_FILE_SIZE || self.block_size == 0 {
+                    return None;
+                }
+                let fragment = (fragment != NO_FRAGMENT).then_some((fragment, fragment_offset));
+                let block_size = self.block_size as u64;
+                let blocks = match fragment {
+                    Some(_) => size / block_size,
+                    None => size.div_ceil(block_size),
+                } as usize;
+                let fixed = reader.position;
+                let data = self.metadata(block, offset, fixed + 4 * blocks);
+                let mut reader = Reader::at(&data, fixed)?;
+                let block_sizes = (0..blocks)
+                    .map(|_| reader.u32())
+                    .collect::<Option<Vec<_>>>()?;
+                Inode::File {
+                    blocks_start,
+                    size,
+                    fragment,
+                    block_sizes,
+                }
+            }
+            BASIC_SYMLINK | EXTENDED_SYMLINK => {
+                reader.skip(4)?;
+                let target_size = reader.u32()? as usize;
+                let data = self.metadata(block, offset, 24 + target_size);
+                let target = Reader::at(&data, 24)?.bytes(target_size)?;
+                Inode::Symlink(String::from_utf8(target.to_vec()).ok()?)
+            }
+            _ => Inode::Other,
+        };
+        Some(inode)
+    }
+
+    /// The names and inode references of a directory's entries.
+    fn entries(&self, directory: &Inode) -> Option<Vec<(String, u64)>> {
+        let Inode::Directory {
+            block,
+            offset,
+            size,
+        } = directory
+        else {
+            return None;
+        };
+        // The size counts the implicit `.` and `..` entries as 3 bytes
+        let len = (*size as usize).checked_sub(3)?;
+        if len > MAX_LISTING_SIZE {
+            return None;
+        }
+        let data = self.metadata(self.directory_table + *block as u64, *offset as usize, len);
+        let mut reader = Reader::new(data.get(..len)?);
+        let mut entries = Vec::new();
+        while reader.remaining() > 0 {
+            let count = reader.u32()? as usize + 1;
+            let start = reader.u32()? as u64;
+            reader.skip(4)?;
+            for _ in 0..count {
+                let offset = reader.u16()? as u64;
+                reader.skip(4)?;
+                let name_size = reader.u16()? as usize + 1;
+                let name = String::from_utf8_lossy(reader.bytes(name_size)?).into_owned();
+                entries.push((name, (start << 16) | offset));
+            }
+        }
+        Some(entries)
+    }
+
+    /// Resolves symlinks, which are relative to the image root or to the top-level directory.
+    fn follow(&self, mut inode: Inode) -> Option<Inode> {
+        for _ in 0..MAX_SYMLINKS {
+            let Inode::Symlink(target) = &inode else {
+                return Some(inode);
+            };
+            let mut current = self.inode(self.root)?;
+            for name in target.split('/').filter(|name| !matches!(*name, "" | ".")) {
+                let (_, reference) = self
+                    .entries(&current)?
+                    .into_iter()
+                    .find(|(entry, _)| entry == name)?;
+                current = self.inode(reference)?;
+            }
+            inode = current;
+        }
+        None
+    }
+
+    fn read_file(&self, file: &Inode) -> Option<Vec<u8>> {
+        let Inode::File {
+            blocks_start,
+            size,
+            fragment,
+            block_sizes,
+        } = file
+        else {
+            return None;
+        };
+        let block_size = self.block_size as usize;
+        let mut contents = Vec::new();
+        let mut position = *blocks_start;
+        for &stored in block_sizes {
+            let on_disk = (stored & !DATA_UNCOMPRESSED) as usize;
+            if on_disk == 0 {
+                contents.resize(contents.len() + block_size, 0);
+                continue;
+            }
+            let block = self.read(position, on_disk)?;
+            contents.extend(self.decompress(block, stored & DATA_UNCOMPRESSED == 0, block_size)?);
+            position += on_disk as u64;
+        }
+
+        if let Some((index, offset)) = *fragment {
+            let location = self.read(self.fragment_table + (index as u64 / 512) * 8, 8)?;
+            let location = Reader::new(&location).u64()?;
+            let entry = self.metadata(location, (index as usize % 512) * 16, 16);
+            let mut reader = Reader::new(&entry);
+            let start = reader.u64()?;
+            let stored = reader.u32()?;
+            let on_disk = (stored & !DATA_UNCOMPRESSED) as usize;
+            let block = self.read(start, on_disk)?;
+            let block = self.decompress(block, stored & DATA_UNCOMPRESSED == 0, block_size)?;
+            let tail = (*size as usize).checked_sub(contents.len())?;
+            let offset = offset as usize;
+            contents.extend_from_slice(block.get(offset..offset + tail)?);
+        }
+        contents.truncate(*size as usize);
+        Some(contents)
+    }
+}
+
+/// Little-endian reads from a byte slice.
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Reader { data, position: 0 }
+    }
+
+    fn at(data: &'a [u8], position: usize) -> Option<Self> {
+        (position <= data.len()).then_some(Reader { data, position })
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.position
+    }
+
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self
+            .data
+            .get(self.position..self.position.checked_add(len)?)?;
+        self.position += len;
+        Some(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.bytes(len).map(|_| ())
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.bytes(2)?.try_into().ok()?))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.bytes(8)?.try_into().ok()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filesystem::MockFileSystem;
+    use std::path::PathBuf;
+
+    fn inode_header(kind: u16, number: u32) -> Vec<u8> {
+        let mut header = kind.to_le_bytes().to_vec();
+        header.extend([0u8; 10]);
+        header.extend(number.to_le_bytes());
+        header
+    }
+
+    fn directory_inode(number: u32, listing_offset: u16, listing_len: usize) -> Vec<u8> {
+        let mut inode = inode_header(BASIC_DIRECTORY, number);
+        inode.extend(0u32.to_le_bytes());
+        inode.extend(2u32.to_le_bytes());
+        inode.extend((listing_len as u16 + 3).to_le_bytes());
+        inode.extend(listing_offset.to_le_bytes());
+        inode.extend(1u32.to_le_bytes());
+        inode
+    }
+
+    fn listing(entries: &[(&str, u16, u16)]) -> Vec<u8> {
+        let mut listing = (entries.len() as u32 - 1).to_le_bytes().to_vec();
+        listing.extend(0u32.to_le_bytes());
+        listing.extend(1u32.to_le_bytes());
+        for (name, inode_offset, kind) in entries {
+            listing.extend(inode_offset.to_le_bytes());
+            listing.extend(0i16.to_le_bytes());
+            listing.extend(kind.to_le_bytes());
+            listing.extend((name.len() as u16 - 1).to_le_bytes());
+            listing.extend(name.as_bytes());
+        }
+        listing
+    }
+
+    fn metadata_block(contents: &[u8]) -> Vec<u8> {
+        let mut block = (contents.len() as u16 | METADATA_UNCOMPRESSED)
+            .to_le_bytes()
+            .to_vec();
+        block.extend(contents);
+        block
+    }
+
+    /// An AppImage whose top-level `browser.desktop` links to `share/browser.desktop`.
+    fn appimage(desktop: &str, compress: bool) -> Vec<u8> {
+        let mut elf = b"\x7fELF\x02\x01".to_vec();
+        elf.resize(64, 0);
+        elf[0x28..0x30].copy_from_slice(&64u64.to_le_bytes());
+
+        let (data, stored_size) = if compress {
+            let data = zstd::bulk::compress(desktop.as_bytes(), 3).unwrap();
+            let size = data.len() as u32;
+            (data, size)
+        } else {
+            let size = desktop.len() as u32 | DATA_UNCOMPRESSED;
+            (desktop.as_bytes().to_vec(), size)
+        };
+
+        let target = "share/browser.desktop";
+        let root_listing = listing(&[("browser.desktop", 100, 3), ("share", 32, 1)]);
+        let share_listing = listing(&[("browser.desktop", 64, 2)]);
+        let mut inodes = directory_inode(1, 0, root_listing.len());
+        inodes.extend(directory_inode(
+            2,
+            root_listing.len() as u16,
+            share_listing.len(),
+        ));
+        inodes.extend(inode_header(BASIC_FILE, 3));
+        inodes.extend((SUPERBLOCK_SIZE as u32).to_le_bytes());
+        inodes.extend(NO_FRAGMENT.to_le_bytes());
+        inodes.extend(0u32.to_le_bytes());
+        inodes.extend((desktop.len() as u32).to_le_bytes());
+        inodes.extend(stored_size.to_le_bytes());
+        assert_eq!(inodes.len(), 100);
+        inodes.extend(inode_header(BASIC_SYMLINK, 4));
+        inodes.extend(1u32.to_le_bytes());
+        inodes.extend((target.len() as u32).to_le_bytes());
+        inodes.extend(target.as_bytes());
+
+        let inode_table = (SUPERBLOCK_SIZE + data.len()) as u64;
+        let inode_block = metadata_block(&inodes);
+        let directory_table = inode_table + inode_block.len() as u64;
+        let directory_block = metadata_block(&[root_listing, share_listing].concat());
+
+        let mut superblock = SQUASHFS_MAGIC.to_vec();
+        superblock.extend(4u32.to_le_bytes());
+        superblock.extend(0u32.to_le_bytes());
+        superblock.extend(131072u32.to_le_bytes());
+        superblock.extend(0u32.to_le_bytes());
+        superblock.extend(ZSTD.to_le_bytes());
+        superblock.extend(17u16.to_le_bytes());
+        superblock.extend(0u16.to_le_bytes());
+        superblock.extend(1u16.to_le_bytes());
+        superblock.extend(4u16.to_le_bytes());
+        superblock.extend(0u16.to_le_bytes());
+        superblock.extend(0u64.to_le_bytes());
+        superblock.extend([0u8; 24]);
+        superblock.extend(inode_table.to_le_bytes());
+        superblock.extend(directory_table.to_le_bytes());
+        superblock.extend(u64::MAX.to_le_bytes());
+        superblock.extend(u64::MAX.to_le_bytes());
+        assert_eq!(superblock.len(), SUPERBLOCK_SIZE);
+
+        [elf, superblock, data, inode_block, directory_block].concat()
+    }
+
+    fn filesystem(image: Vec<u8>) -> MockFileSystem {
+        let mut fs = MockFileSystem::new();
+        fs.expect_read_range().returning(move |_, offset, len| {
+            let start = (offset as usize).min(image.len());
+            let end = start.saturating_add(len).min(image.len());
+            Ok(image[start..end].to_vec())
+        });
+        fs
+    }
+
+    #[test]
+    fn desktop_entry_is_read_from_the_image() {
+        let desktop = "[Desktop Entry]\nName=Thorium\nExec=thorium-browser %U\n";
+        let path = PathBuf::from("/home/me/Applications/Browser-x86_64.AppImage");
+        for compress in [false, true] {
+            let fs = filesystem(appimage(desktop, compress));
+            assert_eq!(desktop_entry(&fs, &path).as_deref(), Some(desktop));
+        }
+    }
+
+    #[test]
+    fn appimages_are_detected_without_running_them() {
+        use crate::browser::linux::create_appimage_browser_info;
+        use crate::browser::BrowserKind;
+
+        // Named after the browser, the image does not need to be readable
+        let named = create_appimage_browser_info(
+            &filesystem(b"\x7fELF".to_vec()),
+            Path::new("/home/me/Applications/firefox-nightly.AppImage"),
+        )
+        .unwrap();
+        assert_eq!(named.kind, BrowserKind::Firefox);
+        assert_eq!(named.version, None);
+
+        let desktop = "[Desktop Entry]\nName=Thorium\nExec=thorium-browser %U\n\
+                       MimeType=text/html;x-scheme-handler/http;x-scheme-handler/https;\n\
+                       X-AppImage-Version=130.0.6723.174\n";
+        let path = Path::new("/home/me/Applications/Web-x86_64.AppImage");
+        let browser =
+            create_appimage_browser_info(&filesystem(appimage(desktop, true)), path).unwrap();
+        assert_eq!(browser.kind, BrowserKind::Thorium);
+        assert_eq!(browser.display_name, "Thorium");
+        assert_eq!(browser.executable_path, path);
+        assert_eq!(browser.version.as_deref(), Some("130.0.6723.174"));
+
+        let editor = "[Desktop Entry]\nName=Editor\nExec=editor %F\nMimeType=text/plain;\n";
+        assert!(create_appimage_browser_info(&filesystem(appimage(editor, false)), path).is_none());
+    }
+
+    #[test]
+    fn other_files_are_not_read() {
+        let path = PathBuf::from("/home/me/Applications/Browser.AppImage");
+        assert_eq!(
+            desktop_entry(&filesystem(b"#!/bin/sh\n".to_vec()), &path),
+            None
+        );
+
+        let mut image = appimage("[Desktop Entry]\n", false);
+        image[64..68].copy_from_slice(b"CD00");
+        assert_eq!(desktop_entry(&filesystem(image), &path), None);
+    }
+}
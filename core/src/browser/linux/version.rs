@@ -0,0 +1,215 @@
+//! Version resolution for Linux browsers.
+//!
+//! Flatpak and snap packages carry their version in package metadata. Other installations
+//! are asked with `<exe> --version`, whose answers are cached per executable and
+//! modification time in `<cache dir>/pathway/versions.json` because starting a browser
+//! binary takes noticeable time.
+
+use super::{output_with_timeout, parse_flatpak_run};
+use crate::browser::sources::{self, InstallationSource, LinuxInstallationSource};
+use crate::browser::{BrowserInfo, BrowserKind};
+use crate::filesystem::FileSystem;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, UNIX_EPOCH};
+use tracing::debug;
+
+const VERSION_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct VersionCache {
+    entries: BTreeMap<PathBuf, CachedVersion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedVersion {
+    modified: u64,
+    version: String,
+}
+
+/// Fills in `version` for every browser that does not have one yet.
+pub(super) fn resolve_versions<F: FileSystem>(fs: &F, browsers: &mut [BrowserInfo]) {
+    let cache_path = cache_path();
+    let mut cache: VersionCache = cache_path
+        .as_deref()
+        .and_then(|path| fs.read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    let mut cache_changed = false;
+
+    for browser in browsers.iter_mut().filter(|b| b.version.is_none()) {
+        browser.version = match browser.source {
+            InstallationSource::Linux(LinuxInstallationSource::Flatpak) => browser
+                .exec_command
+                .as_deref()
+                .and_then(parse_flatpak_run)
+                .and_then(|(_, app_id)| flatpak_version(fs, &app_id)),
+            InstallationSource::Linux(LinuxInstallationSource::Snap) => {
+                sources::snap_name(&browser.executable_path).and_then(|snap| snap_version(fs, snap))
+            }
+            // Windows binaries are not worth starting just for their version.
+            InstallationSource::Linux(LinuxInstallationSource::Wsl) => None,
+            // Downloaded images are not run; see `create_appimage_browser_info`.
+            InstallationSource::Linux(LinuxInstallationSource::AppImage) => None,
+            // Unknown executables might open a window instead of printing a version.
+            _ if browser.kind == BrowserKind::Other => None,
+            _ => {
+                let (version, changed) =
+                    executable_version(fs, &browser.executable_path, &mut cache);
+                cache_changed |= changed;
+                version
+            }
+        };
+    }
+
+    if let (true, Some(path)) = (cache_changed, cache_path) {
+        if let Some(parent) = path.parent() {
+            let _ = fs.create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_vec_pretty(&cache) {
+            if let Err(e) = fs.write(&path, &content) {
+                debug!(path = %path.display(), "Failed to write version cache: {}", e);
+            }
+        }
+    }
+}
+
+fn cache_path() -> Option<PathBuf> {
+    dirs_next::cache_dir().map(|dir| dir.join("pathway").join("versions.json"))
+}
+
+/// Returns the version and whether the cache was updated.
+fn executable_version<F: FileSystem>(
+    fs: &F,
+    executable: &Path,
+    cache: &mut VersionCache,
+) -> (Option<String>, bool) {
+    let Some(modified) = fs
+        .metadata(executable)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+    else {
+        return (None, false);
+    };
+
+    if let Some(cached) = cache.entries.get(executable) {
+        if cached.modified == modified {
+            return (Some(cached.version.clone()), false);
+        }
+    }
+
+    let mut command = Command::new(executable);
+    command.arg("--version");
+    let version = output_with_timeout(command, VERSION_TIMEOUT)
+        .filter(|output| output.status.success())
+        .and_then(|output| parse_version_output(&String::from_utf8_lossy(&output.stdout)));
+
+    match version {
+        Some(version) => {
+            cache.entries.insert(
+                executable.to_path_buf(),
+                CachedVersion {
+                    modified,
+                    version: version.clone(),
+                },
+            );
+            (Some(version), true)
+        }
+        None => (None, false),
+    }
+}
+
+/// Extracts the first dotted version number, e.g. from `Google Chrome 120.0.6099.109`.
+fn parse_version_output(output: &str) -> Option<String> {
+    output.lines().next()?.split_whitespace().find_map(|token| {
+        let token = token.trim_end_matches([',', ';']);
+        let is_version = token.starts_with(|c: char| c.is_ascii_digit())
+            && token.contains('.')
+            && token
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+        is_version.then(|| token.to_string())
+    })
+}
+
+/// Reads the newest `<release version="...">` from the app's AppStream metadata.
+fn flatpak_version<F: FileSystem>(fs: &F, app_id: &str) -> Option<String> {
+    let mut installations = vec![PathBuf::from("/var/lib/flatpak")];
+    if let Some(data) = dirs_next::data_dir() {
+        installations.insert(0, data.join("flatpak"));
+    }
+
+    installations.iter().find_map(|installation| {
+        let files = installation
+            .join("app")
+            .join(app_id)
+            .join("current/active/files/share");
+        [
+            files
+                .join("metainfo")
+                .join(format!("{}.metainfo.xml", app_id)),
+            files
+                .join("appdata")
+                .join(format!("{}.appdata.xml", app_id)),
+        ]
+        .iter()
+        .find_map(|path| fs.read_to_string(path).ok())
+        .and_then(|content| parse_metainfo_release(&content))
+    })
+}
+
+fn parse_metainfo_release(content: &str) -> Option<String> {
+    let release = &content[content.find("<release ")?..];
+    let tag = &release[..release.find('>')?];
+    let value = &tag[tag.find("version=")? + "version=".len()..];
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value = &value[1..];
+    Some(value[..value.find(quote)?].to_string())
+}
+
+fn snap_version<F: FileSystem>(fs: &F, snap: &str) -> Option<String> {
+    let manifest = Path::new("/snap").join(snap).join("current/meta/snap.yaml");
+    let content = fs.read_to_string(&manifest).ok()?;
+    content.lines().find_map(|line| {
+        let value = line.strip_prefix("version:")?.trim();
+        Some(value.trim_matches(['\'', '"']).to_string()).filter(|v| !v.is_empty())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_are_parsed_from_command_output() {
+        assert_eq!(
+            parse_version_output("Google Chrome 120.0.6099.109 \n"),
+            Some("120.0.6099.109".to_string())
+        );
+        assert_eq!(
+            parse_version_output("Mozilla Firefox 121.0.1"),
+            Some("121.0.1".to_string())
+        );
+        assert_eq!(
+            parse_version_output(
+                "Chromium 119.0.6045.159 built on Debian 12.2, running on Debian 12.4"
+            ),
+            Some("119.0.6045.159".to_string())
+        );
+        assert_eq!(parse_version_output("no version here"), None);
+    }
+
+    #[test]
+    fn versions_are_parsed_from_package_metadata() {
+        let metainfo = r#"<component><releases>
+            <release version="121.0" date="2023-12-19"/>
+            <release version="120.0.1" date="2023-12-01"/>
+        </releases></component>"#;
+        assert_eq!(parse_metainfo_release(metainfo), Some("121.0".to_string()));
+        assert_eq!(parse_metainfo_release("<component/>"), None);
+    }
+}
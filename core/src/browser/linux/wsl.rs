@@ -0,0 +1,385 @@
+//! Windows interop for pathway running inside WSL.
+//!
+//! Windows browsers are enumerated through `reg.exe` and reached through the drive mounts
+//! (`/mnt/c/...`). Their command lines are translated so that any Linux paths, such as
+//! profile directories or `file://` URLs, point back into the distribution.
+
+use super::{classify_browser_from_token, output_with_timeout, BrowserInfo};
+use crate::browser::sources::{InstallScope, InstallationSource, LinuxInstallationSource};
+use crate::browser::SystemDefaultBrowser;
+use crate::filesystem::FileSystem;
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+const START_MENU_INTERNET: &str = "SOFTWARE\\Clients\\StartMenuInternet";
+const USER_CHOICE: &str =
+    "HKCU\\Software\\Microsoft\\Windows\\Shell\\Associations\\UrlAssociations\\https\\UserChoice";
+const DEFAULT_MOUNT_ROOT: &str = "/mnt/";
+
+/// Returns true when running inside WSL with Windows interop enabled.
+///
+/// Setting `PATHWAY_WSL_INTEROP=0` disables Windows browser discovery.
+pub(super) fn is_wsl<F: FileSystem>(fs: &F) -> bool {
+    if env::var("PATHWAY_WSL_INTEROP").is_ok_and(|value| value == "0") {
+        return false;
+    }
+
+    if env::var_os("WSL_DISTRO_NAME").is_some() || env::var_os("WSL_INTEROP").is_some() {
+        return true;
+    }
+
+    fs.read_to_string(Path::new("/proc/sys/kernel/osrelease"))
+        .is_ok_and(|release| release.to_ascii_lowercase().contains("microsoft"))
+}
+
+pub(super) fn detect_browsers<F: FileSystem>(fs: &F) -> Vec<BrowserInfo> {
+    let mount_root = mount_root(fs);
+    let mut browsers: Vec<BrowserInfo> = Vec::new();
+
+    for hive in ["HKCU", "HKLM"] {
+        let key = format!("{}\\{}", hive, START_MENU_INTERNET);
+        let Some(output) = reg_query(&[key.as_str(), "/s"]) else {
+            continue;
+        };
+
+        for (client_key, display_name, command) in parse_start_menu_clients(&output) {
+            let Some(windows_path) = parse_windows_command_path(&command) else {
+                continue;
+            };
+            let Some(executable_path) = to_linux_path(&windows_path, &mount_root) else {
+                continue;
+            };
+            if !fs.exists(&executable_path)
+                || browsers
+                    .iter()
+                    .any(|known| known.executable_path == executable_path)
+            {
+                continue;
+            }
+
+            let Some((kind, channel)) = classify_browser_from_token(&display_name.to_lowercase())
+            else {
+                continue;
+            };
+
+            browsers.push(BrowserInfo {
+                kind,
+                channel,
+                display_name,
+                executable_path,
+                version: None,
+                unique_id: client_key,
+                exec_command: None,
+                source: InstallationSource::Linux(LinuxInstallationSource::Wsl),
+                scope: InstallScope::System,
+                arch: None,
+                is_default: false,
+            });
+        }
+    }
+
+    browsers
+}
+
+/// Reads the Windows default browser from the `https` UserChoice association.
+pub(super) fn system_default_browser(browsers: &[BrowserInfo]) -> Option<SystemDefaultBrowser> {
+    let output = reg_query(&[USER_CHOICE, "/v", "ProgId"])?;
+    let prog_id = parse_reg_values(&output)
+        .into_iter()
+        .find(|(_, name, _)| name == "ProgId")
+        .map(|(_, _, value)| value)?;
+
+    let kind = classify_browser_from_token(&prog_id.to_lowercase()).map(|(kind, _)| kind);
+    let browser = kind.and_then(|kind| browsers.iter().find(|b| b.kind == kind));
+
+    Some(SystemDefaultBrowser {
+        display_name: browser
+            .map(|b| b.display_name.clone())
+            .unwrap_or_else(|| prog_id.clone()),
+        identifier: prog_id,
+        kind,
+        path: browser.map(|b| b.executable_path.clone()),
+    })
+}
+
+/// Rewrites Linux paths in a command line so a Windows executable can resolve them.
+///
+/// Handles bare absolute paths, `--flag=/path` arguments and `file://` URLs.
+pub(super) fn translate_args<F: FileSystem>(fs: &F, args: Vec<String>) -> Vec<String> {
+    let mount_root = mount_root(fs);
+    let distro = env::var("WSL_DISTRO_NAME").ok();
+    let translate = |path: &str| to_windows_path(Path::new(path), &mount_root, distro.as_deref());
+
+    args.into_iter()
+        .map(|arg| {
+            if let Some(path) = arg.strip_prefix("file://") {
+                if path.starts_with('/') {
+                    if let Some(windows) = translate(path) {
+                        let windows = windows.replace('\\', "/");
+                        return match windows.strip_prefix("//") {
+                            Some(unc) => format!("file://{}", unc),
+                            None => format!("file:///{}", windows),
+                        };
+                    }
+                }
+                return arg;
+            }
+
+            if arg.starts_with('/') {
+                return translate(&arg).unwrap_or(arg);
+            }
+
+            if let Some((flag, value)) = arg.split_once('=') {
+                if flag.starts_with('-') && value.starts_with('/') {
+                    if let Some(windows) = translate(value) {
+                        return format!("{}={}", flag, windows);
+                    }
+                }
+            }
+
+            arg
+        })
+        .collect()
+}
+
+/// Opens URLs with the Windows default browser through `wslview`, when installed.
+pub(super) fn wslview_command() -> Option<Command> {
+    env::var_os("PATH")?
+        .to_str()
+        .into_iter()
+        .flat_map(env::split_paths)
+        .map(|dir| dir.join("wslview"))
+        .find(|candidate| candidate.is_file())
+        .map(Command::new)
+}
+
+fn reg_query(args: &[&str]) -> Option<String> {
+    let mut command = Command::new(reg_exe());
+    command.arg("query").args(args).stdin(Stdio::null());
+
+    let output = output_with_timeout(command, Duration::from_secs(5))?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn reg_exe() -> PathBuf {
+    // Interop normally appends the Windows directories to PATH, but this can be turned off.
+    let system32 = PathBuf::from("/mnt/c/Windows/System32/reg.exe");
+    if system32.is_file() {
+        system32
+    } else {
+        PathBuf::from("reg.exe")
+    }
+}
+
+/// Reads the drive mount root from `/etc/wsl.conf`, defaulting to `/mnt/`.
+fn mount_root<F: FileSystem>(fs: &F) -> PathBuf {
+    fs.read_to_string(Path::new("/etc/wsl.conf"))
+        .ok()
+        .and_then(|content| parse_automount_root(&content))
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_MOUNT_ROOT))
+}
+
+fn parse_automount_root(content: &str) -> Option<PathBuf> {
+    let mut in_automount = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') && line.ends_with(']') {
+            in_automount = line.eq_ignore_ascii_case("[automount]");
+            continue;
+        }
+        if !in_automount {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            if key.trim() == "root" {
+                let value = value.trim().trim_matches('"');
+                if !value.is_empty() {
+                    return Some(PathBuf::from(value));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Parses `reg query` output into `(key, value name, value data)` triples.
+fn parse_reg_values(output: &str) -> Vec<(String, String, String)> {
+    let mut values = Vec::new();
+    let mut current_key = None;
+
+    for line in output.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.starts_with("HKEY_") {
+            current_key = Some(line.trim().to_string());
+            continue;
+        }
+
+        let Some(key) = current_key.as_ref() else {
+            continue;
+        };
+        let mut parts = line.trim().splitn(3, "    ");
+        let (Some(name), Some(kind)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        if !kind.starts_with("REG_") {
+            continue;
+        }
+        let data = parts.next().unwrap_or("").trim().to_string();
+        values.push((key.clone(), name.to_string(), data));
+    }
+
+    values
+}
+
+/// Collects `(client key, display name, open command)` for each StartMenuInternet client.
+fn parse_start_menu_clients(output: &str) -> Vec<(String, String, String)> {
+    let mut names = BTreeMap::new();
+    let mut commands = BTreeMap::new();
+
+    for (key, name, data) in parse_reg_values(output) {
+        if name != "(Default)" || data.is_empty() {
+            continue;
+        }
+        let Some(position) = key.find(START_MENU_INTERNET) else {
+            continue;
+        };
+        let relative = &key[position + START_MENU_INTERNET.len()..];
+        let mut components = relative.trim_start_matches('\\').splitn(2, '\\');
+        let Some(client) = components.next().filter(|client| !client.is_empty()) else {
+            continue;
+        };
+        let client_key = key[..position + START_MENU_INTERNET.len() + 1 + client.len()].to_string();
+
+        match components.next() {
+            None => {
+                names.insert(client_key, data);
+            }
+            Some(rest) if rest.eq_ignore_ascii_case("shell\\open\\command") => {
+                commands.insert(client_key, data);
+            }
+            Some(_) => {}
+        }
+    }
+
+    names
+        .into_iter()
+        .filter_map(|(key, name)| {
+            let command = commands.remove(&key)?;
+            Some((key, name, command))
+        })
+        .collect()
+}
+
+fn parse_windows_command_path(command: &str) -> Option<String> {
+    let trimmed = command.trim();
+    if let Some(rest) = trimmed.strip_prefix('"') {
+        return rest.split('"').next().map(|s| s.to_string());
+    }
+
+    let exe_end = trimmed.to_ascii_lowercase().find(".exe")? + 4;
+    Some(trimmed[..exe_end].to_string())
+}
+
+/// Maps `C:\Program Files\...` to `<mount root>/c/Program Files/...`.
+fn to_linux_path(windows_path: &str, mount_root: &Path) -> Option<PathBuf> {
+    let mut chars = windows_path.chars();
+    let drive = chars.next().filter(|c| c.is_ascii_alphabetic())?;
+    if chars.next() != Some(':') {
+        return None;
+    }
+
+    let mut path = mount_root.join(drive.to_ascii_lowercase().to_string());
+    for component in chars.as_str().split(['\\', '/']).filter(|c| !c.is_empty()) {
+        path.push(component);
+    }
+    Some(path)
+}
+
+/// Maps a Linux path to its Windows form: a drive path for mounted drives, otherwise a
+/// `\\wsl.localhost\<distro>` UNC path.
+fn to_windows_path(path: &Path, mount_root: &Path, distro: Option<&str>) -> Option<String> {
+    if let Ok(relative) = path.strip_prefix(mount_root) {
+        let mut components = relative.iter();
+        let drive = components.next()?.to_str()?;
+        if drive.len() == 1 && drive.chars().all(|c| c.is_ascii_alphabetic()) {
+            let rest: Vec<_> = components.map(|c| c.to_string_lossy()).collect();
+            return Some(format!(
+                "{}:\\{}",
+                drive.to_ascii_uppercase(),
+                rest.join("\\")
+            ));
+        }
+    }
+
+    let distro = distro?;
+    let rest: Vec<_> = path
+        .strip_prefix("/")
+        .ok()?
+        .iter()
+        .map(|c| c.to_string_lossy())
+        .collect();
+    Some(format!(
+        "\\\\wsl.localhost\\{}\\{}",
+        distro,
+        rest.join("\\")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REG_OUTPUT: &str = "\r\n\
+HKEY_LOCAL_MACHINE\\SOFTWARE\\Clients\\StartMenuInternet\\Google Chrome\r\n    (Default)    REG_SZ    Google Chrome\r\n\r\n\
+HKEY_LOCAL_MACHINE\\SOFTWARE\\Clients\\StartMenuInternet\\Google Chrome\\Capabilities\r\n    ApplicationName    REG_SZ    Google Chrome\r\n\r\n\
+HKEY_LOCAL_MACHINE\\SOFTWARE\\Clients\\StartMenuInternet\\Google Chrome\\shell\\open\\command\r\n    (Default)    REG_SZ    \"C:\\Program Files\\Google\\Chrome\\Application\\chrome.exe\"\r\n";
+
+    #[test]
+    fn start_menu_clients_are_parsed() {
+        let clients = parse_start_menu_clients(REG_OUTPUT);
+        assert_eq!(
+            clients,
+            vec![(
+                "HKEY_LOCAL_MACHINE\\SOFTWARE\\Clients\\StartMenuInternet\\Google Chrome"
+                    .to_string(),
+                "Google Chrome".to_string(),
+                "\"C:\\Program Files\\Google\\Chrome\\Application\\chrome.exe\"".to_string(),
+            )]
+        );
+
+        let path = parse_windows_command_path(&clients[0].2).unwrap();
+        assert_eq!(
+            to_linux_path(&path, Path::new("/mnt/")),
+            Some(PathBuf::from(
+                "/mnt/c/Program Files/Google/Chrome/Application/chrome.exe"
+            ))
+        );
+    }
+
+    #[test]
+    fn linux_paths_are_translated_for_windows() {
+        let root = Path::new("/mnt/");
+        assert_eq!(
+            to_windows_path(Path::new("/mnt/d/profiles/work"), root, Some("Ubuntu")),
+            Some("D:\\profiles\\work".to_string())
+        );
+        assert_eq!(
+            to_windows_path(Path::new("/tmp/pathway_profile_1"), root, Some("Ubuntu")),
+            Some("\\\\wsl.localhost\\Ubuntu\\tmp\\pathway_profile_1".to_string())
+        );
+        assert_eq!(to_windows_path(Path::new("/tmp/x"), root, None), None);
+    }
+
+    #[test]
+    fn automount_root_is_read_from_wsl_conf() {
+        let conf = "[boot]\nsystemd=true\n\n[automount]\nroot = /\noptions = \"metadata\"\n";
+        assert_eq!(parse_automount_root(conf), Some(PathBuf::from("/")));
+        assert_eq!(parse_automount_root("[network]\nroot = /x\n"), None);
+    }
+}
@@ -1,14 +1,22 @@
 use super::{BrowserInfo, LaunchOutcome, LaunchTarget, SystemDefaultBrowser};
-use crate::browser::channels::{BrowserChannel, ChromiumChannel, FirefoxChannel, SafariChannel};
-use crate::browser::BrowserKind;
+use crate::browser::arch::{self, Arch};
+use crate::browser::channels::{
+    self, BrowserChannel, ChromiumChannel, FirefoxChannel, SafariChannel,
+};
+use crate::browser::mail::{MailClient, MailClientKind};
+use crate::browser::sources;
+use crate::browser::{BrowserKind, InstallScope, InstallationSource};
 use crate::filesystem::FileSystem;
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 // Core Foundation and Services imports
 use core_foundation::array::{CFArray, CFArrayRef};
 use core_foundation::base::TCFType;
 use core_foundation::bundle::CFBundle;
+use core_foundation::error::CFErrorRef;
 use core_foundation::string::{CFString, CFStringRef};
 use core_foundation::url::CFURL;
 
@@ -16,6 +24,10 @@ use core_foundation::url::CFURL;
 extern "C" {
     fn LSCopyAllHandlersForURLScheme(inURLScheme: CFStringRef) -> CFArrayRef;
     fn LSCopyDefaultHandlerForURLScheme(inURLScheme: CFStringRef) -> CFStringRef;
+    fn LSCopyApplicationURLsForBundleIdentifier(
+        inBundleIdentifier: CFStringRef,
+        outError: *mut CFErrorRef,
+    ) -> CFArrayRef;
 }
 
 #[derive(Debug, Error)]
@@ -24,6 +36,10 @@ pub enum LaunchError {
     MissingExecutable(String),
     #[error("No URLs provided to launch")]
     NoUrls,
+    #[error("{0} is already running with this profile; use --temp-profile or --user-dir for a separate instance")]
+    AlreadyRunning(String),
+    #[error("Failed to automate {app}: {reason}")]
+    Automation { app: String, reason: String },
     #[error("Failed to launch browser: {source}")]
     Spawn {
         #[from]
@@ -32,20 +48,260 @@ pub enum LaunchError {
 }
 
 pub fn detect_browsers<F: FileSystem>(_fs: &F) -> Vec<BrowserInfo> {
+    detect_browsers_filtered(_fs, None)
+}
+
+/// Like [`detect_browsers`], but only inspects bundles of `kind` and skips the application
+/// folder scan once Launch Services knows one.
+pub fn detect_browsers_of_kind<F: FileSystem>(fs: &F, kind: BrowserKind) -> Vec<BrowserInfo> {
+    detect_browsers_filtered(fs, Some(kind))
+}
+
+fn detect_browsers_filtered<F: FileSystem>(_fs: &F, kind: Option<BrowserKind>) -> Vec<BrowserInfo> {
+    let wanted = |id: &String| {
+        kind.is_none_or(|kind| parse_bundle_id(id).is_some_and(|(found, _)| found == kind))
+    };
     let mut browsers = Vec::new();
-    let bundle_ids = get_https_handlers();
+    let mut casks = homebrew_cask_apps(_fs);
+    casks.retain(|id, _| wanted(id));
+    let bundle_ids = handlers_for_scheme("https");
+
+    for id in bundle_ids.into_iter().filter(wanted) {
+        // The copy Homebrew manages is recognized as such even when Launch Services lists it.
+        let cask_path = casks.remove(&id);
+        if let Some(app_path) = &cask_path {
+            browsers.extend(browser_info_for_app(&id, app_path.clone(), true, _fs));
+        }
+        // Every other copy is listed too, e.g. one in /Applications and one in ~/Applications.
+        for app_path in get_app_paths_from_bundle_id(&id) {
+            if cask_path.as_ref() != Some(&app_path) {
+                browsers.extend(browser_info_for_app(&id, app_path, false, _fs));
+            }
+        }
+    }
 
-    for id in bundle_ids {
-        if let Some(info) = create_browser_info(&id, _fs) {
+    // Casks installed outside the usual folders may not be registered with Launch Services yet.
+    for (id, app_path) in casks {
+        if let Some(info) = browser_info_for_app(&id, app_path, true, _fs) {
+            browsers.push(info);
+        }
+    }
+
+    if kind.is_some() && !browsers.is_empty() {
+        return browsers;
+    }
+
+    // A stale or reset Launch Services database can miss installed browsers entirely.
+    for app_path in application_folder_apps(_fs, application_folders()) {
+        let Some(id) = bundle_identifier(&app_path).filter(wanted) else {
+            continue;
+        };
+        if browsers
+            .iter()
+            .any(|browser| app_bundle_path(browser.launch_path()) == Some(app_path.as_path()))
+        {
+            continue;
+        }
+        if let Some(info) = browser_info_for_app(&id, app_path, false, _fs) {
             browsers.push(info);
         }
     }
     browsers
 }
 
-fn get_https_handlers() -> Vec<String> {
+/// The standard and configured application folders.
+fn application_folders() -> Vec<PathBuf> {
+    let mut roots = vec![
+        PathBuf::from("/Applications"),
+        PathBuf::from("/System/Applications"),
+    ];
+    if let Some(home) = dirs_next::home_dir() {
+        roots.push(home.join("Applications"));
+    }
+    roots.extend(super::detection_config().application_dirs.iter().cloned());
+    roots
+}
+
+/// Bundles in `roots` and their subfolders (e.g. `Setapp`).
+fn application_folder_apps<F: FileSystem>(fs: &F, roots: Vec<PathBuf>) -> Vec<PathBuf> {
+    let is_app = |path: &Path| path.extension().and_then(|s| s.to_str()) == Some("app");
+    let mut apps = Vec::new();
+    for root in roots {
+        let Ok(entries) = fs.read_dir(&root) else {
+            continue;
+        };
+        for path in entries {
+            if is_app(&path) {
+                apps.push(path);
+            } else if let Ok(nested) = fs.read_dir(&path) {
+                apps.extend(nested.into_iter().filter(|path| is_app(path)));
+            }
+        }
+    }
+    apps
+}
+
+/// Application bundles installed by Homebrew casks, keyed by bundle id.
+fn homebrew_cask_apps<F: FileSystem>(fs: &F) -> BTreeMap<String, PathBuf> {
+    let home = dirs_next::home_dir();
+    let mut apps = BTreeMap::new();
+
+    for caskroom in sources::HOMEBREW_CASKROOMS {
+        let Ok(casks) = std::fs::read_dir(caskroom) else {
+            continue;
+        };
+        for cask in casks.flatten() {
+            for app_path in cask_app_paths(fs, &cask.path(), home.as_deref()) {
+                if let Some(id) = bundle_identifier(&app_path) {
+                    apps.entry(id).or_insert(app_path);
+                }
+            }
+        }
+    }
+    apps
+}
+
+/// Existing application bundles installed by the cask in `cask_dir`.
+fn cask_app_paths<F: FileSystem>(fs: &F, cask_dir: &Path, home: Option<&Path>) -> Vec<PathBuf> {
+    let metadata = cask_dir.join(".metadata");
+    let appdir = fs
+        .read_to_string(&metadata.join("config.json"))
+        .ok()
+        .and_then(|config| sources::cask_appdir(&config, home))
+        .unwrap_or_else(|| PathBuf::from("/Applications"));
+    let artifacts = fs
+        .read_to_string(&metadata.join("INSTALL_RECEIPT.json"))
+        .map(|receipt| sources::cask_app_artifacts(&receipt))
+        .unwrap_or_default();
+
+    artifacts
+        .iter()
+        .map(|name| appdir.join(name))
+        .chain(staged_cask_apps(cask_dir))
+        .filter(|path| fs.exists(path))
+        .collect()
+}
+
+/// Ungoogled-Chromium keeps Chromium's `org.chromium.Chromium` bundle id, so only its
+/// Homebrew cask tells the two apart.
+fn is_ungoogled_chromium<F: FileSystem>(fs: &F, app_path: &Path) -> bool {
+    let home = dirs_next::home_dir();
+    sources::HOMEBREW_CASKROOMS.iter().any(|caskroom| {
+        let cask_dir = Path::new(caskroom).join("ungoogled-chromium");
+        fs.exists(&cask_dir)
+            && cask_app_paths(fs, &cask_dir, home.as_deref())
+                .iter()
+                .any(|path| path == app_path)
+    })
+}
+
+/// Bundles kept inside `Caskroom/<token>/<version>/`, which older casks symlinked from
+/// `/Applications`.
+fn staged_cask_apps(cask_dir: &Path) -> Vec<PathBuf> {
+    let Ok(versions) = std::fs::read_dir(cask_dir) else {
+        return Vec::new();
+    };
+    versions
+        .flatten()
+        .filter(|version| !version.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|version| std::fs::read_dir(version.path()).ok())
+        .flat_map(|entries| entries.flatten().map(|entry| entry.path()))
+        .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("app"))
+        .collect()
+}
+
+fn bundle_identifier(app_path: &Path) -> Option<String> {
+    let bundle_url = CFURL::from_path(app_path, true)?;
+    let bundle = CFBundle::new(bundle_url)?;
+    let info_dict = bundle.info_dictionary();
+
+    if info_dict.contains_key(&CFString::new("CFBundleIdentifier")) {
+        info_dict
+            .get(CFString::new("CFBundleIdentifier"))
+            .downcast::<CFString>()
+            .map(|s| s.to_string())
+    } else {
+        None
+    }
+}
+
+pub fn has_scheme_handler<F: FileSystem>(_fs: &F, scheme: &str) -> bool {
+    !handlers_for_scheme(scheme).is_empty()
+}
+
+pub fn detect_mail_clients<F: FileSystem>(_fs: &F) -> Vec<MailClient> {
+    // Browsers can register for mailto as well; they are not mail clients.
+    let browsers = handlers_for_scheme("https");
+    handlers_for_scheme("mailto")
+        .into_iter()
+        .filter(|id| !browsers.contains(id))
+        .filter_map(|id| create_mail_client(&id, _fs))
+        .collect()
+}
+
+fn detect_source_for_browser<F: FileSystem>(
+    fs: &F,
+    app_path: &Path,
+    from_cask: bool,
+) -> InstallationSource {
+    let home = dirs_next::home_dir();
+    InstallationSource::MacOs(sources::macos_source_for_app(
+        fs,
+        app_path,
+        home.as_deref(),
+        from_cask,
+    ))
+}
+
+fn create_mail_client<F: FileSystem>(bundle_id: &str, fs: &F) -> Option<MailClient> {
+    let app_path = get_app_path_from_bundle_id(bundle_id)?;
+    let display_name = app_path.file_stem()?.to_str()?.to_string();
+
+    Some(MailClient {
+        kind: MailClientKind::from_token(bundle_id),
+        display_name,
+        unique_id: bundle_id.to_string(),
+        exec_command: None,
+        source: detect_source_for_browser(fs, &app_path, false),
+        executable_path: app_path,
+    })
+}
+
+pub fn launch_mail(client: &MailClient, urls: &[String]) -> Result<LaunchOutcome, LaunchError> {
+    if urls.is_empty() {
+        return Err(LaunchError::NoUrls);
+    }
+
+    let mut command = Command::new("open");
+    command.arg("-a").arg(&client.executable_path).args(urls);
+    command.stdin(Stdio::null());
+    command.stdout(Stdio::null());
+    command.stderr(Stdio::null());
+
+    let all_args: Vec<String> = command
+        .get_args()
+        .map(|s| s.to_string_lossy().to_string())
+        .collect();
+    debug!(program = "open", args = ?all_args, "Launching mail client");
+    let child = command.spawn()?;
+
+    Ok(LaunchOutcome {
+        browser: None,
+        system_default: None,
+        command: LaunchCommand {
+            program: PathBuf::from("open"),
+            display: format!("open {}", all_args.join(" ")),
+            args: all_args,
+            is_system_default: false,
+        },
+        reused_existing: false,
+        pid: Some(child.id()),
+    })
+}
+
+fn handlers_for_scheme(scheme: &str) -> Vec<String> {
     unsafe {
-        let scheme = CFString::new("https");
+        let scheme = CFString::new(scheme);
         let handlers_ref = LSCopyAllHandlersForURLScheme(scheme.as_concrete_TypeRef());
         if handlers_ref.is_null() {
             return Vec::new();
@@ -55,10 +311,33 @@ fn get_https_handlers() -> Vec<String> {
     }
 }
 
-fn create_browser_info<F: FileSystem>(bundle_id: &str, _fs: &F) -> Option<BrowserInfo> {
-    let (kind, channel) = parse_bundle_id(bundle_id)?;
+/// Reads the Mach-O header of `executable`; universal headers list every slice up front.
+fn executable_arch(executable: &Path) -> Option<Arch> {
+    let mut header = Vec::with_capacity(4096);
+    std::fs::File::open(executable)
+        .ok()?
+        .take(4096)
+        .read_to_end(&mut header)
+        .ok()?;
+    arch::parse_mach_o_arch(&header)
+}
 
+fn create_browser_info<F: FileSystem>(bundle_id: &str, fs: &F) -> Option<BrowserInfo> {
     let app_path = get_app_path_from_bundle_id(bundle_id)?;
+    browser_info_for_app(bundle_id, app_path, false, fs)
+}
+
+fn browser_info_for_app<F: FileSystem>(
+    bundle_id: &str,
+    app_path: PathBuf,
+    from_cask: bool,
+    fs: &F,
+) -> Option<BrowserInfo> {
+    let (mut kind, channel) = parse_bundle_id(bundle_id)?;
+    if kind == BrowserKind::Chromium && is_ungoogled_chromium(fs, &app_path) {
+        kind = BrowserKind::UngoogledChromium;
+    }
+
     let bundle_url = CFURL::from_path(&app_path, true)?;
     let bundle = CFBundle::new(bundle_url)?;
 
@@ -102,6 +381,7 @@ fn create_browser_info<F: FileSystem>(bundle_id: &str, _fs: &F) -> Option<Browse
     });
 
     let executable_path = app_path.join("Contents/MacOS").join(executable_name);
+    let arch = executable_arch(&executable_path);
 
     Some(BrowserInfo {
         kind,
@@ -111,26 +391,32 @@ fn create_browser_info<F: FileSystem>(bundle_id: &str, _fs: &F) -> Option<Browse
         version,
         unique_id: bundle_id.to_string(),
         exec_command: None,
+        source: detect_source_for_browser(fs, &app_path, from_cask),
+        scope: InstallScope::System,
+        arch,
+        is_default: false,
     })
 }
 
+/// Asks Launch Services for the bundle's preferred location, which works without Spotlight.
 fn get_app_path_from_bundle_id(bundle_id: &str) -> Option<PathBuf> {
-    use std::process::Command;
-    let output = Command::new("mdfind")
-        .arg(format!("kMDItemCFBundleIdentifier == '{}'", bundle_id))
-        .output()
-        .ok()?;
-
-    if !output.status.success() {
-        return None;
-    }
+    get_app_paths_from_bundle_id(bundle_id).into_iter().next()
+}
 
-    let path_str = String::from_utf8(output.stdout).ok()?;
-    let first_path = path_str.lines().next()?.trim();
-    if first_path.is_empty() {
-        None
-    } else {
-        Some(PathBuf::from(first_path))
+/// Every copy of the bundle Launch Services knows, the preferred one (usually the one in
+/// /Applications) first.
+fn get_app_paths_from_bundle_id(bundle_id: &str) -> Vec<PathBuf> {
+    unsafe {
+        let bundle_id = CFString::new(bundle_id);
+        let urls_ref = LSCopyApplicationURLsForBundleIdentifier(
+            bundle_id.as_concrete_TypeRef(),
+            std::ptr::null_mut(),
+        );
+        if urls_ref.is_null() {
+            return Vec::new();
+        }
+        let urls: CFArray<CFURL> = CFArray::wrap_under_create_rule(urls_ref as *const _);
+        urls.iter().filter_map(|url| url.to_path()).collect()
     }
 }
 
@@ -186,11 +472,59 @@ fn parse_bundle_id(bundle_id: &str) -> Option<(BrowserKind, BrowserChannel)> {
         return Some((BrowserKind::Waterfox, BrowserChannel::Single));
     }
 
+    // LibreWolf
+    if lower_id == "io.gitlab.librewolf-community" {
+        return Some((BrowserKind::LibreWolf, BrowserChannel::Single));
+    }
+
+    // Zen
+    if lower_id == "app.zen-browser.zen" {
+        return Some((
+            BrowserKind::Zen,
+            BrowserChannel::Firefox(FirefoxChannel::Stable),
+        ));
+    }
+    if lower_id == "app.zen-browser.zen-twilight" {
+        return Some((
+            BrowserKind::Zen,
+            BrowserChannel::Firefox(FirefoxChannel::Twilight),
+        ));
+    }
+
+    // Floorp
+    if lower_id == "one.ablaze.floorp" {
+        return Some((
+            BrowserKind::Floorp,
+            BrowserChannel::Firefox(FirefoxChannel::Stable),
+        ));
+    }
+    if lower_id == "one.ablaze.floorp.beta" {
+        return Some((
+            BrowserKind::Floorp,
+            BrowserChannel::Firefox(FirefoxChannel::Beta),
+        ));
+    }
+
+    // qutebrowser
+    if lower_id == "org.qutebrowser.qutebrowser" {
+        return Some((BrowserKind::Qutebrowser, BrowserChannel::Single));
+    }
+
     // Helium
     if lower_id == "net.imput.helium" {
         return Some((BrowserKind::Helium, BrowserChannel::Single));
     }
 
+    // Thorium reuses the Chromium bundle id prefix.
+    if lower_id == "org.chromium.thorium" {
+        return Some((BrowserKind::Thorium, BrowserChannel::Single));
+    }
+
+    // DuckDuckGo
+    if lower_id == "com.duckduckgo.macos.browser" {
+        return Some((BrowserKind::DuckDuckGo, BrowserChannel::Single));
+    }
+
     // Chromium-based browsers
     let parts: Vec<&str> = lower_id.split('.').collect();
     let company = parts.get(1).copied()?;
@@ -202,13 +536,19 @@ fn parse_bundle_id(bundle_id: &str) -> Option<(BrowserKind, BrowserChannel)> {
         "operasoftware" => BrowserKind::Opera,
         "vivaldi" => BrowserKind::Vivaldi,
         "chromium" => BrowserKind::Chromium,
+        "yandex" => BrowserKind::Yandex,
+        "wavebox" => BrowserKind::Wavebox,
+        "pushplaylabs" => BrowserKind::Sidekick,
         _ => return None,
     };
 
     let channel_str = parts.last().copied().unwrap_or("stable");
 
     let channel = match kind {
-        BrowserKind::Chrome | BrowserKind::Brave | BrowserKind::Edge => {
+        BrowserKind::Brave | BrowserKind::Vivaldi | BrowserKind::Opera => {
+            BrowserChannel::Chromium(channels::vendor_chromium_channel(&lower_id))
+        }
+        BrowserKind::Chrome | BrowserKind::Edge | BrowserKind::Yandex => {
             let ch = match channel_str {
                 "beta" => ChromiumChannel::Beta,
                 "dev" => ChromiumChannel::Dev,
@@ -223,6 +563,26 @@ fn parse_bundle_id(bundle_id: &str) -> Option<(BrowserKind, BrowserChannel)> {
     Some((kind, channel))
 }
 
+/// Apps in `~/Applications` (or another folder in the home directory) are per-user.
+pub fn install_scope(browser: &BrowserInfo) -> InstallScope {
+    if dirs_next::home_dir().is_some_and(|home| browser.launch_path().starts_with(home)) {
+        InstallScope::User
+    } else {
+        InstallScope::System
+    }
+}
+
+/// Copies of an app share its bundle id, so the one Launch Services resolves to is matched by path.
+pub fn is_system_default(browser: &BrowserInfo, system_default: &SystemDefaultBrowser) -> bool {
+    browser
+        .unique_id
+        .eq_ignore_ascii_case(&system_default.identifier)
+        && system_default
+            .path
+            .as_deref()
+            .is_none_or(|path| path == browser.launch_path())
+}
+
 // Stubbed out functions
 pub fn system_default_browser_with_fs<F: FileSystem>(fs: &F) -> Option<SystemDefaultBrowser> {
     let bundle_id = default_handler_for_https()?;
@@ -250,6 +610,186 @@ use super::LaunchCommand;
 use std::process::{Command, Stdio};
 use tracing::debug;
 
+const OSASCRIPT: &str = "/usr/bin/osascript";
+
+/// Safari has no scripting command for private windows, so the script triggers the
+/// File > New Private Window shortcut through System Events and then loads the URLs.
+const SAFARI_PRIVATE_WINDOW_SCRIPT: &str = r#"on run argv
+    tell application "Safari" to activate
+    tell application "System Events" to keystroke "n" using {command down, shift down}
+    delay 0.5
+    tell application "Safari"
+        if (count of argv) > 0 then set URL of current tab of front window to item 1 of argv
+        repeat with i from 2 to count of argv
+            tell front window to set current tab to (make new tab with properties {URL:item i of argv})
+        end repeat
+    end tell
+end run"#;
+
+/// Opens a window of the Safari profile passed as the first argument through the
+/// File > New Window > New <profile> Window menu item.
+const SAFARI_PROFILE_WINDOW_SCRIPT: &str = r#"on run argv
+    tell application "Safari" to activate
+    tell application "System Events" to tell process "Safari"
+        click menu item ("New " & item 1 of argv & " Window") of menu 1 of menu item "New Window" of menu "File" of menu bar 1
+    end tell
+    delay 0.5
+    tell application "Safari"
+        if (count of argv) > 1 then set URL of current tab of front window to item 2 of argv
+        repeat with i from 3 to count of argv
+            tell front window to set current tab to (make new tab with properties {URL:item i of argv})
+        end repeat
+    end tell
+end run"#;
+
+/// Resolves `--profile <name>` for Safari to the display name of a non-default profile.
+fn safari_named_profile(
+    info: &BrowserInfo,
+    profile_opts: Option<&crate::profile::ProfileOptions>,
+) -> Option<String> {
+    if info.kind != crate::browser::BrowserKind::Safari {
+        return None;
+    }
+    let crate::profile::ProfileType::Named(name) = &profile_opts?.profile_type else {
+        return None;
+    };
+
+    match crate::profile::ProfileManager::find_profile(info, name) {
+        Ok(profile) if profile.is_default => None,
+        Ok(profile) => Some(profile.display_name),
+        Err(_) => Some(name.clone()),
+    }
+}
+
+fn launch_safari_private_window(
+    info: &BrowserInfo,
+    urls: &[String],
+) -> Result<LaunchOutcome, LaunchError> {
+    run_osascript(
+        info,
+        SAFARI_PRIVATE_WINDOW_SCRIPT,
+        urls.to_vec(),
+        "Opening Safari private window",
+    )
+}
+
+fn launch_safari_profile_window(
+    info: &BrowserInfo,
+    profile: &str,
+    urls: &[String],
+) -> Result<LaunchOutcome, LaunchError> {
+    let mut script_args = vec![profile.to_string()];
+    script_args.extend(urls.iter().cloned());
+    run_osascript(
+        info,
+        SAFARI_PROFILE_WINDOW_SCRIPT,
+        script_args,
+        "Opening Safari profile window",
+    )
+}
+
+/// Focuses the Arc Space named by the first argument and opens the remaining URLs in it.
+const ARC_SPACE_SCRIPT: &str = r#"on run argv
+    tell application "Arc"
+        activate
+        tell front window
+            tell space (item 1 of argv) to focus
+            repeat with i from 2 to count of argv
+                make new tab with properties {URL:item i of argv}
+            end repeat
+        end tell
+    end tell
+end run"#;
+
+fn launch_arc_space(
+    info: &BrowserInfo,
+    space: &str,
+    urls: &[String],
+) -> Result<LaunchOutcome, LaunchError> {
+    let mut script_args = vec![space.to_string()];
+    script_args.extend(urls.iter().cloned());
+    run_osascript(
+        info,
+        ARC_SPACE_SCRIPT,
+        script_args,
+        "Opening URLs in Arc Space",
+    )
+}
+
+fn run_osascript(
+    info: &BrowserInfo,
+    script: &str,
+    script_args: Vec<String>,
+    log_message: &str,
+) -> Result<LaunchOutcome, LaunchError> {
+    let automation_error = |reason: String| LaunchError::Automation {
+        app: info.display_name.clone(),
+        reason,
+    };
+
+    if !Path::new(OSASCRIPT).exists() {
+        return Err(automation_error("osascript is not available".to_string()));
+    }
+
+    let mut command = Command::new(OSASCRIPT);
+    command.arg("-e").arg(script).args(&script_args);
+    command.stdin(Stdio::null());
+
+    let all_args: Vec<String> = command
+        .get_args()
+        .map(|s| s.to_string_lossy().to_string())
+        .collect();
+    debug!(program = OSASCRIPT, args = ?all_args, "{}", log_message);
+
+    // Wait for the script so that missing automation permissions surface as an error.
+    let output = command.output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = if stderr.contains("-1728") {
+            match info.kind {
+                BrowserKind::Safari => {
+                    "the requested Safari menu item was not found (profiles require Safari 17 or later)"
+                        .to_string()
+                }
+                _ => "the requested window or space was not found".to_string(),
+            }
+        } else if stderr.contains("-1719") || stderr.contains("-25211") {
+            "grant Accessibility access to your terminal in System Settings > Privacy & Security"
+                .to_string()
+        } else if stderr.contains("-1743") {
+            format!(
+                "allow your terminal to control {} and System Events in System Settings > Privacy & Security > Automation",
+                info.display_name
+            )
+        } else {
+            stderr.trim().to_string()
+        };
+        return Err(automation_error(reason));
+    }
+
+    let cmd = LaunchCommand {
+        program: PathBuf::from(OSASCRIPT),
+        args: all_args.clone(),
+        display: format!("{} {}", OSASCRIPT, all_args.join(" ")),
+        is_system_default: false,
+    };
+
+    Ok(LaunchOutcome {
+        browser: Some(info.clone()),
+        system_default: None,
+        command: cmd,
+        reused_existing: false,
+        pid: None,
+    })
+}
+
+/// Returns the enclosing `.app` bundle of an executable inside `Contents/MacOS`.
+fn app_bundle_path(executable: &Path) -> Option<&Path> {
+    executable
+        .ancestors()
+        .find(|path| path.extension().and_then(|s| s.to_str()) == Some("app"))
+}
+
 pub fn launch(target: LaunchTarget<'_>, urls: &[String]) -> Result<LaunchOutcome, LaunchError> {
     launch_with_profile(target, urls, None, None)
 }
@@ -260,13 +800,20 @@ pub fn launch_with_profile(
     profile_opts: Option<&crate::profile::ProfileOptions>,
     window_opts: Option<&crate::profile::WindowOptions>,
 ) -> Result<LaunchOutcome, LaunchError> {
-    if urls.is_empty() {
-        return Err(LaunchError::NoUrls);
-    }
-
     match target {
         LaunchTarget::Browser(info) => {
-            if info.kind == crate::browser::BrowserKind::Safari {
+            if info.kind == crate::browser::BrowserKind::Safari
+                && window_opts.is_some_and(|opts| opts.incognito)
+            {
+                launch_safari_private_window(info, urls)
+            } else if let Some(profile) = safari_named_profile(info, profile_opts) {
+                launch_safari_profile_window(info, &profile, urls)
+            } else if let Some(space) = window_opts
+                .and_then(|opts| opts.arc_space.as_deref())
+                .filter(|_| info.kind == BrowserKind::Arc)
+            {
+                launch_arc_space(info, space, urls)
+            } else if info.kind == crate::browser::BrowserKind::Safari {
                 let mut command = Command::new("open");
                 command.arg("-b").arg("com.apple.Safari");
 
@@ -286,7 +833,50 @@ pub fn launch_with_profile(
                     .map(|s| s.to_string_lossy().to_string())
                     .collect();
                 debug!(program = "open", args = ?all_args, "Launching Safari via open command");
-                command.spawn()?;
+                let child = command.spawn()?;
+
+                let cmd = LaunchCommand {
+                    program: PathBuf::from("open"),
+                    args: all_args.clone(),
+                    display: format!("open {}", all_args.join(" ")),
+                    is_system_default: false,
+                };
+
+                Ok(LaunchOutcome {
+                    browser: Some(info.clone()),
+                    system_default: None,
+                    command: cmd,
+                    reused_existing: false,
+                    pid: Some(child.id()),
+                })
+            } else if let Some(app_path) = window_opts
+                .filter(|opts| opts.new_instance)
+                .and_then(|_| app_bundle_path(info.launch_path()))
+            {
+                // `open -n` starts another copy of the app even if one is already running,
+                // which Chromium needs to honour a different --user-data-dir.
+                let mut command = Command::new("open");
+                command.arg("-n").arg("-a").arg(app_path).arg("--args");
+
+                if let (Some(profile_opts), Some(window_opts)) = (profile_opts, window_opts) {
+                    command.args(crate::profile::ProfileManager::generate_profile_args(
+                        info,
+                        profile_opts,
+                        window_opts,
+                    ));
+                }
+
+                command.args(urls);
+                command.stdin(Stdio::null());
+                command.stdout(Stdio::null());
+                command.stderr(Stdio::null());
+
+                let all_args: Vec<String> = command
+                    .get_args()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .collect();
+                debug!(program = "open", args = ?all_args, "Launching new browser instance");
+                let child = command.spawn()?;
 
                 let cmd = LaunchCommand {
                     program: PathBuf::from("open"),
@@ -299,6 +889,8 @@ pub fn launch_with_profile(
                     browser: Some(info.clone()),
                     system_default: None,
                     command: cmd,
+                    reused_existing: false,
+                    pid: Some(child.id()),
                 })
             } else {
                 let exec = info.launch_path();
@@ -334,7 +926,7 @@ pub fn launch_with_profile(
                     "Launching browser"
                 };
                 debug!(program = %exec.display(), args = ?all_args, "{}", log_message);
-                command.spawn()?;
+                let child = command.spawn()?;
 
                 let cmd = LaunchCommand {
                     program: exec.to_path_buf(),
@@ -347,10 +939,15 @@ pub fn launch_with_profile(
                     browser: Some(info.clone()),
                     system_default: None,
                     command: cmd,
+                    reused_existing: false,
+                    pid: Some(child.id()),
                 })
             }
         }
         LaunchTarget::SystemDefault => {
+            if urls.is_empty() {
+                return Err(LaunchError::NoUrls);
+            }
             let mut command = Command::new("open");
 
             if let Some(window_opts) = window_opts {
@@ -369,7 +966,7 @@ pub fn launch_with_profile(
                 .map(|s| s.to_string_lossy().to_string())
                 .collect();
             debug!(program = "open", args = ?all_args, "Launching system default browser");
-            command.spawn()?;
+            let child = command.spawn()?;
 
             let cmd = LaunchCommand {
                 program: PathBuf::from("open"),
@@ -382,6 +979,8 @@ pub fn launch_with_profile(
                 browser: None,
                 system_default: system_default_browser_with_fs(&crate::filesystem::RealFileSystem),
                 command: cmd,
+                reused_existing: false,
+                pid: Some(child.id()),
             })
         }
     }
@@ -404,3 +1003,56 @@ fn default_handler_for_https() -> Option<String> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filesystem::MockFileSystem;
+
+    #[test]
+    fn bundles_are_located_through_launch_services() {
+        let safari = get_app_paths_from_bundle_id("com.apple.Safari");
+        assert!(!safari.is_empty());
+        assert!(safari
+            .iter()
+            .all(|path| path.extension().and_then(|s| s.to_str()) == Some("app")));
+        assert!(get_app_paths_from_bundle_id("invalid.pathway.no-such-app").is_empty());
+    }
+
+    #[test]
+    fn application_folders_are_scanned_one_level_deep() {
+        let mut fs = MockFileSystem::new();
+        fs.expect_read_dir().returning(|path| {
+            let entries: &[&str] = match path.to_str().unwrap() {
+                "/Applications" => &[
+                    "/Applications/Firefox.app",
+                    "/Applications/Setapp",
+                    "/Applications/notes.txt",
+                ],
+                "/Applications/Setapp" => {
+                    &["/Applications/Setapp/Arc.app", "/Applications/Setapp/x"]
+                }
+                "/Volumes/Tools" => &["/Volumes/Tools/Brave Browser.app"],
+                _ => return Err(std::io::Error::from(std::io::ErrorKind::NotFound)),
+            };
+            Ok(entries.iter().map(PathBuf::from).collect())
+        });
+
+        let apps = application_folder_apps(
+            &fs,
+            vec![
+                PathBuf::from("/Applications"),
+                PathBuf::from("/Missing"),
+                PathBuf::from("/Volumes/Tools"),
+            ],
+        );
+        assert_eq!(
+            apps,
+            vec![
+                PathBuf::from("/Applications/Firefox.app"),
+                PathBuf::from("/Applications/Setapp/Arc.app"),
+                PathBuf::from("/Volumes/Tools/Brave Browser.app"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! Mail clients that handle `mailto:` URLs.
+//!
+//! Detection mirrors browser detection: desktop entries registered for
+//! `x-scheme-handler/mailto` on Linux, Launch Services handlers on macOS and
+//! `Clients\Mail` registrations on Windows.
+
+use super::{platform, InstallationSource, LaunchError, LaunchOutcome, LaunchTarget};
+use crate::filesystem::FileSystem;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MailClientKind {
+    Thunderbird,
+    Betterbird,
+    Evolution,
+    Geary,
+    KMail,
+    Mailspring,
+    AppleMail,
+    Outlook,
+    Spark,
+    Airmail,
+    Mimestream,
+    Other,
+}
+
+impl MailClientKind {
+    pub fn canonical_name(self) -> &'static str {
+        match self {
+            MailClientKind::Thunderbird => "thunderbird",
+            MailClientKind::Betterbird => "betterbird",
+            MailClientKind::Evolution => "evolution",
+            MailClientKind::Geary => "geary",
+            MailClientKind::KMail => "kmail",
+            MailClientKind::Mailspring => "mailspring",
+            MailClientKind::AppleMail => "apple-mail",
+            MailClientKind::Outlook => "outlook",
+            MailClientKind::Spark => "spark",
+            MailClientKind::Airmail => "airmail",
+            MailClientKind::Mimestream => "mimestream",
+            MailClientKind::Other => "mail",
+        }
+    }
+
+    /// Classifies a desktop file name, bundle id or registry key name.
+    pub fn from_token(token: &str) -> Self {
+        let token = token.to_ascii_lowercase();
+        if token.contains("betterbird") {
+            MailClientKind::Betterbird
+        } else if token.contains("thunderbird") {
+            MailClientKind::Thunderbird
+        } else if token.contains("evolution") {
+            MailClientKind::Evolution
+        } else if token.contains("geary") {
+            MailClientKind::Geary
+        } else if token.contains("kmail") {
+            MailClientKind::KMail
+        } else if token.contains("mailspring") {
+            MailClientKind::Mailspring
+        } else if token == "com.apple.mail" {
+            MailClientKind::AppleMail
+        } else if token.contains("outlook") {
+            MailClientKind::Outlook
+        } else if token.contains("readdle.smartemail") || token.contains("spark") {
+            MailClientKind::Spark
+        } else if token.contains("airmail") {
+            MailClientKind::Airmail
+        } else if token.contains("mimestream") {
+            MailClientKind::Mimestream
+        } else {
+            MailClientKind::Other
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MailClient {
+    pub kind: MailClientKind,
+    pub display_name: String,
+    /// Executable on Linux and Windows, application bundle on macOS
+    pub executable_path: PathBuf,
+    // Desktop file path, bundle id or registry path of this installation.
+    pub unique_id: String,
+    /// Command registered for `mailto:` URLs, when the platform provides one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exec_command: Option<String>,
+    pub source: InstallationSource,
+}
+
+impl MailClient {
+    pub fn alias(&self) -> &'static str {
+        self.kind.canonical_name()
+    }
+
+    pub fn matches_token(&self, token: &str) -> bool {
+        let normalized = super::normalize_token(token);
+        !normalized.is_empty()
+            && (normalized == self.kind.canonical_name()
+                || normalized == super::normalize_token(&self.display_name))
+    }
+}
+
+pub fn detect_mail_clients_with_fs<F: FileSystem>(fs: &F) -> Vec<MailClient> {
+    unique_clients(platform::detect_mail_clients(fs))
+}
+
+/// Drops handlers that start the same command as an earlier one, e.g. a desktop entry
+/// installed both system-wide and per user.
+fn unique_clients(clients: Vec<MailClient>) -> Vec<MailClient> {
+    let mut seen = HashSet::new();
+    clients
+        .into_iter()
+        .filter(|client| {
+            let signature = client
+                .exec_command
+                .clone()
+                .unwrap_or_else(|| client.executable_path.to_string_lossy().to_string());
+            seen.insert(signature)
+        })
+        .collect()
+}
+
+pub fn detect_mail_clients() -> Vec<MailClient> {
+    detect_mail_clients_with_fs(&crate::filesystem::RealFileSystem)
+}
+
+pub fn find_mail_client<'a>(clients: &'a [MailClient], token: &str) -> Option<&'a MailClient> {
+    clients.iter().find(|client| client.matches_token(token))
+}
+
+/// Opens `mailto:` URLs in `client`, or with the OS default mail handler when `None`.
+pub fn launch_mail(
+    client: Option<&MailClient>,
+    urls: &[String],
+) -> Result<LaunchOutcome, LaunchError> {
+    match client {
+        Some(client) => platform::launch_mail(client, urls),
+        None => platform::launch(LaunchTarget::SystemDefault, urls),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(kind: MailClientKind, display_name: &str, exec: Option<&str>) -> MailClient {
+        MailClient {
+            kind,
+            display_name: display_name.to_string(),
+            executable_path: PathBuf::from(format!("/usr/bin/{}", kind.canonical_name())),
+            unique_id: format!("{}.desktop", kind.canonical_name()),
+            exec_command: exec.map(str::to_string),
+            source: InstallationSource::Unknown,
+        }
+    }
+
+    #[test]
+    fn handler_names_identify_mail_clients() {
+        let kind = MailClientKind::from_token;
+        // Desktop file names
+        assert_eq!(kind("org.mozilla.Thunderbird"), MailClientKind::Thunderbird);
+        assert_eq!(kind("eu.betterbird.Betterbird"), MailClientKind::Betterbird);
+        assert_eq!(kind("org.gnome.Evolution"), MailClientKind::Evolution);
+        assert_eq!(kind("org.kde.kmail2"), MailClientKind::KMail);
+        // Bundle ids
+        assert_eq!(kind("com.apple.mail"), MailClientKind::AppleMail);
+        assert_eq!(kind("com.apple.mailcompose"), MailClientKind::Other);
+        assert_eq!(kind("com.readdle.smartemail-Mac"), MailClientKind::Spark);
+        assert_eq!(kind("com.microsoft.Outlook"), MailClientKind::Outlook);
+        // Registry key names
+        assert_eq!(kind("Microsoft Outlook"), MailClientKind::Outlook);
+        assert_eq!(kind("Mutt"), MailClientKind::Other);
+    }
+
+    #[test]
+    fn mail_clients_are_found_by_alias_or_name() {
+        let clients = vec![
+            client(MailClientKind::Thunderbird, "Mozilla Thunderbird", None),
+            client(MailClientKind::AppleMail, "Mail", None),
+        ];
+        let found = |token| find_mail_client(&clients, token).map(|client| client.kind);
+
+        assert_eq!(found("Thunderbird"), Some(MailClientKind::Thunderbird));
+        assert_eq!(
+            found("mozilla_thunderbird"),
+            Some(MailClientKind::Thunderbird)
+        );
+        assert_eq!(found("apple-mail"), Some(MailClientKind::AppleMail));
+        assert_eq!(found("mail"), Some(MailClientKind::AppleMail));
+        assert_eq!(found("  "), None);
+        assert_eq!(found("outlook"), None);
+    }
+
+    #[test]
+    fn handlers_with_the_same_command_are_listed_once() {
+        let clients = unique_clients(vec![
+            client(
+                MailClientKind::Thunderbird,
+                "Thunderbird",
+                Some("thunderbird %u"),
+            ),
+            client(
+                MailClientKind::Thunderbird,
+                "Thunderbird",
+                Some("thunderbird %u"),
+            ),
+            client(MailClientKind::Geary, "Geary", None),
+            client(MailClientKind::Geary, "Geary", None),
+            client(MailClientKind::Evolution, "Evolution", Some("evolution %U")),
+        ]);
+
+        let kinds: Vec<_> = clients.iter().map(|client| client.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                MailClientKind::Thunderbird,
+                MailClientKind::Geary,
+                MailClientKind::Evolution
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn mailto_desktop_entries_become_clients() {
+        let entry = "[Desktop Entry]\nName=Mozilla Thunderbird\nExec=/usr/bin/thunderbird %u\n\
+                     MimeType=x-scheme-handler/mailto;\n";
+        let thunderbird = platform::create_mail_client(
+            std::path::Path::new("/usr/share/applications/org.mozilla.Thunderbird.desktop"),
+            entry,
+        )
+        .unwrap();
+        assert_eq!(thunderbird.kind, MailClientKind::Thunderbird);
+        assert_eq!(thunderbird.display_name, "Mozilla Thunderbird");
+        assert_eq!(
+            thunderbird.executable_path,
+            PathBuf::from("/usr/bin/thunderbird")
+        );
+        assert_eq!(
+            thunderbird.exec_command.as_deref(),
+            Some("/usr/bin/thunderbird %u")
+        );
+
+        // The name identifies clients whose desktop file name says nothing
+        let entry = "[Desktop Entry]\nName=KMail\nExec=/usr/bin/kmail %u\n";
+        let kmail = platform::create_mail_client(
+            std::path::Path::new("/usr/share/applications/mail-client.desktop"),
+            entry,
+        )
+        .unwrap();
+        assert_eq!(kmail.kind, MailClientKind::KMail);
+
+        let no_exec = "[Desktop Entry]\nName=Broken\n";
+        assert!(platform::create_mail_client(
+            std::path::Path::new("/usr/share/applications/broken.desktop"),
+            no_exec
+        )
+        .is_none());
+    }
+}
@@ -1,6 +1,9 @@
-use serde::Serialize;
-use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::{mpsc, OnceLock};
+use std::time::{Duration, Instant};
 
 #[cfg(target_os = "macos")]
 mod macos;
@@ -22,14 +25,25 @@ mod unknown;
 #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
 use unknown as platform;
 
+pub mod arch;
+#[cfg(feature = "cdp")]
+pub mod cdp;
 pub mod channels;
+pub mod instance;
+pub mod mail;
+pub mod sources;
+pub mod watch;
 
+use self::arch::Arch;
 pub use self::channels::BrowserChannel;
 use self::channels::{ChromiumChannel, FirefoxChannel, SafariChannel};
+pub use self::sources::{InstallScope, InstallationSource};
+use crate::config::DetectionConfig;
 
 pub use platform::LaunchError;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+// Declaration order is the inventory sort order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum BrowserKind {
     Chrome,
@@ -44,6 +58,21 @@ pub enum BrowserKind {
     TorBrowser,
     Chromium,
     Waterfox,
+    LibreWolf,
+    Zen,
+    Floorp,
+    PaleMoon,
+    Basilisk,
+    Thorium,
+    UngoogledChromium,
+    Yandex,
+    Wavebox,
+    Sidekick,
+    Qutebrowser,
+    Epiphany,
+    Falkon,
+    Konqueror,
+    DuckDuckGo,
     Other,
 }
 
@@ -62,9 +91,60 @@ impl BrowserKind {
             BrowserKind::TorBrowser => "tor",
             BrowserKind::Chromium => "chromium",
             BrowserKind::Waterfox => "waterfox",
+            BrowserKind::LibreWolf => "librewolf",
+            BrowserKind::Zen => "zen",
+            BrowserKind::Floorp => "floorp",
+            BrowserKind::PaleMoon => "palemoon",
+            BrowserKind::Basilisk => "basilisk",
+            BrowserKind::Thorium => "thorium",
+            BrowserKind::UngoogledChromium => "ungoogled-chromium",
+            BrowserKind::Yandex => "yandex",
+            BrowserKind::Wavebox => "wavebox",
+            BrowserKind::Sidekick => "sidekick",
+            BrowserKind::Qutebrowser => "qutebrowser",
+            BrowserKind::Epiphany => "epiphany",
+            BrowserKind::Falkon => "falkon",
+            BrowserKind::Konqueror => "konqueror",
+            BrowserKind::DuckDuckGo => "duckduckgo",
             BrowserKind::Other => "browser",
         }
     }
+
+    /// Inverse of [`BrowserKind::canonical_name`].
+    pub fn from_canonical_name(name: &str) -> Option<Self> {
+        let kind = match name {
+            "chrome" => BrowserKind::Chrome,
+            "firefox" => BrowserKind::Firefox,
+            "safari" => BrowserKind::Safari,
+            "edge" => BrowserKind::Edge,
+            "brave" => BrowserKind::Brave,
+            "arc" => BrowserKind::Arc,
+            "helium" => BrowserKind::Helium,
+            "vivaldi" => BrowserKind::Vivaldi,
+            "opera" => BrowserKind::Opera,
+            "tor" => BrowserKind::TorBrowser,
+            "chromium" => BrowserKind::Chromium,
+            "waterfox" => BrowserKind::Waterfox,
+            "librewolf" => BrowserKind::LibreWolf,
+            "zen" => BrowserKind::Zen,
+            "floorp" => BrowserKind::Floorp,
+            "palemoon" => BrowserKind::PaleMoon,
+            "basilisk" => BrowserKind::Basilisk,
+            "thorium" => BrowserKind::Thorium,
+            "ungoogled-chromium" => BrowserKind::UngoogledChromium,
+            "yandex" => BrowserKind::Yandex,
+            "wavebox" => BrowserKind::Wavebox,
+            "sidekick" => BrowserKind::Sidekick,
+            "qutebrowser" => BrowserKind::Qutebrowser,
+            "epiphany" => BrowserKind::Epiphany,
+            "falkon" => BrowserKind::Falkon,
+            "konqueror" => BrowserKind::Konqueror,
+            "duckduckgo" => BrowserKind::DuckDuckGo,
+            "browser" => BrowserKind::Other,
+            _ => return None,
+        };
+        Some(kind)
+    }
 }
 
 // Basic browser info (used for inventory operations)
@@ -80,6 +160,7 @@ pub struct BasicBrowserInfo {
     pub unique_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub exec_command: Option<String>,
+    pub source: InstallationSource,
 }
 
 // Full browser info used at runtime
@@ -95,6 +176,14 @@ pub struct BrowserInfo {
     pub unique_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub exec_command: Option<String>,
+    pub source: InstallationSource,
+    /// Per-user or machine-wide installation
+    pub scope: InstallScope,
+    /// CPU architecture of the executable, where detected (macOS)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arch: Option<Arch>,
+    /// This installation is the one the OS opens web links with
+    pub is_default: bool,
 }
 
 impl From<BrowserInfo> for BasicBrowserInfo {
@@ -107,6 +196,7 @@ impl From<BrowserInfo> for BasicBrowserInfo {
             version: info.version,
             unique_id: info.unique_id,
             exec_command: info.exec_command,
+            source: info.source,
         }
     }
 }
@@ -116,6 +206,40 @@ impl BrowserInfo {
         &self.executable_path
     }
 
+    /// False for builds that need translation on this machine, e.g. x86_64 under Rosetta.
+    pub fn runs_natively(&self) -> bool {
+        match (self.arch, Arch::host()) {
+            (Some(arch), Some(host)) => arch.runs_natively_on(host),
+            _ => true,
+        }
+    }
+
+    /// Major component of `version`, for version-gated features.
+    pub fn major_version(&self) -> Option<u32> {
+        self.version
+            .as_deref()?
+            .split('.')
+            .next()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    /// Opera GX, which is detected as Opera but keeps its own user data directory.
+    pub fn is_opera_gx(&self) -> bool {
+        self.kind == BrowserKind::Opera
+            && [
+                self.display_name.as_str(),
+                self.unique_id.as_str(),
+                &self.executable_path.to_string_lossy(),
+            ]
+            .iter()
+            .any(|field| {
+                let field = field.to_ascii_lowercase();
+                field.contains("opera gx") || field.contains("operagx")
+            })
+    }
+
     pub fn alias(&self) -> String {
         let channel_name = self.channel.canonical_name();
         if channel_name == "stable" {
@@ -198,20 +322,156 @@ impl SystemDefaultBrowser {
 pub struct BrowserInventory {
     pub browsers: Vec<BrowserInfo>,
     pub system_default: SystemDefaultBrowser,
+    /// Detection ran out of its time budget, so browsers or the system default may be missing.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub detection_incomplete: bool,
+}
+
+/// Default inventory order: browser kind, then channel priority, then builds that run natively
+/// before emulated ones (see [`BrowserInfo::runs_natively`]), then install scope (per-user
+/// before system-wide), then installation source.
+///
+/// The unique id and executable path break remaining ties so the order never depends on
+/// enumeration order.
+pub fn compare_browsers(a: &BrowserInfo, b: &BrowserInfo) -> Ordering {
+    a.kind
+        .cmp(&b.kind)
+        .then_with(|| {
+            default_channel_priority(&a.channel).cmp(&default_channel_priority(&b.channel))
+        })
+        .then_with(|| b.runs_natively().cmp(&a.runs_natively()))
+        .then_with(|| a.scope.cmp(&b.scope))
+        .then_with(|| a.source.cmp(&b.source))
+        .then_with(|| a.unique_id.cmp(&b.unique_id))
+        .then_with(|| a.executable_path.cmp(&b.executable_path))
+}
+
+static DETECTION_CONFIG: OnceLock<DetectionConfig> = OnceLock::new();
+
+/// Adds the extra scan roots from the user config to every later detection.
+///
+/// Only the first call has an effect, and it has to happen before the first detection.
+pub fn configure_detection(config: DetectionConfig) {
+    let _ = DETECTION_CONFIG.set(config);
+}
+
+/// Detection settings from [`configure_detection`], or the defaults.
+fn detection_config() -> &'static DetectionConfig {
+    DETECTION_CONFIG.get_or_init(DetectionConfig::default)
 }
 
 pub fn detect_inventory_with_fs<F: crate::filesystem::FileSystem>(fs: &F) -> BrowserInventory {
-    let browsers = dedupe_browsers(platform::detect_browsers(fs));
-    // TODO: sort
+    detect_inventory_sorted_by(fs, compare_browsers)
+}
+
+/// Detects browsers and orders them with a custom comparator instead of [`compare_browsers`].
+pub fn detect_inventory_sorted_by<F, C>(fs: &F, compare: C) -> BrowserInventory
+where
+    F: crate::filesystem::FileSystem,
+    C: FnMut(&BrowserInfo, &BrowserInfo) -> Ordering,
+{
+    inventory_from(fs, platform::detect_browsers(fs), compare)
+}
+
+pub fn detect_inventory() -> BrowserInventory {
+    detect_inventory_with_fs(&crate::filesystem::RealFileSystem)
+}
+
+/// Detects only browsers of `kind`.
+///
+/// Cheaper than a full scan when the browser to launch is already known: other browsers are
+/// not inspected and secondary detection sources are skipped once one is found.
+pub fn detect_inventory_of_kind_with_fs<F: crate::filesystem::FileSystem>(
+    fs: &F,
+    kind: BrowserKind,
+) -> BrowserInventory {
+    inventory_from(
+        fs,
+        platform::detect_browsers_of_kind(fs, kind),
+        compare_browsers,
+    )
+}
+
+pub fn detect_inventory_of_kind(kind: BrowserKind) -> BrowserInventory {
+    detect_inventory_of_kind_with_fs(&crate::filesystem::RealFileSystem, kind)
+}
+
+fn inventory_from<F, C>(fs: &F, browsers: Vec<BrowserInfo>, compare: C) -> BrowserInventory
+where
+    F: crate::filesystem::FileSystem,
+    C: FnMut(&BrowserInfo, &BrowserInfo) -> Ordering,
+{
+    let mut browsers = dedupe_browsers(browsers);
+    browsers.sort_by(compare);
+    let system_default =
+        platform::system_default_browser_with_fs(fs).unwrap_or_else(SystemDefaultBrowser::fallback);
+    mark_system_default(&mut browsers, &system_default);
     BrowserInventory {
         browsers,
-        system_default: platform::system_default_browser_with_fs(fs)
-            .unwrap_or_else(SystemDefaultBrowser::fallback),
+        system_default,
+        detection_incomplete: false,
     }
 }
 
-pub fn detect_inventory() -> BrowserInventory {
-    detect_inventory_with_fs(&crate::filesystem::RealFileSystem)
+/// Sets `is_default` on the inventory entries the system default resolves to.
+fn mark_system_default(browsers: &mut [BrowserInfo], system_default: &SystemDefaultBrowser) {
+    for browser in browsers {
+        browser.is_default = platform::is_system_default(browser, system_default);
+    }
+}
+
+/// Detects browsers (only those of `kind`, when given) within a time budget.
+///
+/// Browser detection and the system default lookup run on worker threads. Whatever has not
+/// finished when `budget` runs out is left out and `detection_incomplete` is set; the workers
+/// are abandoned rather than waited for.
+pub fn detect_inventory_within(budget: Duration, kind: Option<BrowserKind>) -> BrowserInventory {
+    let deadline = Instant::now() + budget;
+    let browsers = spawn_detection(move || {
+        let fs = crate::filesystem::RealFileSystem;
+        match kind {
+            Some(kind) => platform::detect_browsers_of_kind(&fs, kind),
+            None => platform::detect_browsers(&fs),
+        }
+    });
+    let system_default = spawn_detection(|| {
+        platform::system_default_browser_with_fs(&crate::filesystem::RealFileSystem)
+    });
+
+    let browsers = receive_by(&browsers, deadline);
+    let system_default = receive_by(&system_default, deadline);
+    let detection_incomplete = browsers.is_none() || system_default.is_none();
+
+    let mut browsers = dedupe_browsers(browsers.unwrap_or_default());
+    browsers.sort_by(compare_browsers);
+    let system_default = system_default
+        .flatten()
+        .unwrap_or_else(SystemDefaultBrowser::fallback);
+    mark_system_default(&mut browsers, &system_default);
+    BrowserInventory {
+        browsers,
+        system_default,
+        detection_incomplete,
+    }
+}
+
+fn spawn_detection<T, D>(detect: D) -> mpsc::Receiver<T>
+where
+    T: Send + 'static,
+    D: FnOnce() -> T + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        // The receiver is gone when the budget ran out first.
+        let _ = sender.send(detect());
+    });
+    receiver
+}
+
+fn receive_by<T>(receiver: &mpsc::Receiver<T>, deadline: Instant) -> Option<T> {
+    receiver
+        .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+        .ok()
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -227,6 +487,11 @@ pub struct LaunchOutcome {
     pub browser: Option<BrowserInfo>,
     pub system_default: Option<SystemDefaultBrowser>,
     pub command: LaunchCommand,
+    /// Whether the URLs went to an instance that was already running with the profile
+    pub reused_existing: bool,
+    /// Process id of the spawned launch command, if a process was spawned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
 }
 
 #[derive(Debug, Clone)]
@@ -241,13 +506,117 @@ pub fn launch(target: LaunchTarget<'_>, urls: &[String]) -> Result<LaunchOutcome
 }
 
 /// Launches a browser target with the given URLs, optionally specifying profile and window options.
+///
+/// A browser opens its start page when `urls` is empty; the system default needs at least one URL.
+///
+/// With the `cdp` feature, URLs for a Chromium browser that is already running with a
+/// debugging port are opened as tabs in that instance instead.
 pub fn launch_with_profile(
     target: LaunchTarget<'_>,
     urls: &[String],
     profile_opts: Option<&crate::profile::ProfileOptions>,
     window_opts: Option<&crate::profile::WindowOptions>,
 ) -> Result<LaunchOutcome, LaunchError> {
-    platform::launch_with_profile(target, urls, profile_opts, window_opts)
+    let running = match &target {
+        LaunchTarget::Browser(info) => instance::is_running(info, profile_opts),
+        LaunchTarget::SystemDefault => false,
+    };
+
+    if let LaunchTarget::Browser(info) = &target {
+        if running && window_opts.is_some_and(|opts| opts.require_new_instance) {
+            return Err(LaunchError::AlreadyRunning(info.display_name.clone()));
+        }
+
+        #[cfg(feature = "cdp")]
+        if !urls.is_empty() && !window_opts.is_some_and(|opts| opts.require_new_instance) {
+            if let Some(outcome) = cdp::try_launch(info, urls, profile_opts, window_opts) {
+                return Ok(outcome);
+            }
+        }
+    }
+
+    let mut outcome = platform::launch_with_profile(target, urls, profile_opts, window_opts)?;
+    outcome.reused_existing = running;
+    Ok(outcome)
+}
+
+/// A set of URLs to open together in one launch target.
+#[derive(Debug, Clone)]
+pub struct LaunchJob<'a> {
+    pub target: LaunchTarget<'a>,
+    pub urls: Vec<String>,
+    pub profile_opts: Option<crate::profile::ProfileOptions>,
+    pub window_opts: Option<crate::profile::WindowOptions>,
+}
+
+impl LaunchJob<'_> {
+    /// Whether both jobs start the same browser with the same profile, which would race.
+    fn shares_profile_with(&self, other: &LaunchJob<'_>) -> bool {
+        let same_target = match (&self.target, &other.target) {
+            (LaunchTarget::Browser(a), LaunchTarget::Browser(b)) => {
+                a.executable_path == b.executable_path
+            }
+            (LaunchTarget::SystemDefault, LaunchTarget::SystemDefault) => true,
+            _ => false,
+        };
+        same_target && self.profile_opts == other.profile_opts
+    }
+}
+
+/// Runs the jobs concurrently and returns the results in job order.
+///
+/// Jobs that share a browser and profile run one after another on the same thread. A failing
+/// job does not stop the others, so callers can report each outcome.
+pub fn launch_all(jobs: &[LaunchJob<'_>]) -> Vec<Result<LaunchOutcome, LaunchError>> {
+    let mut lanes: Vec<Vec<usize>> = Vec::new();
+    for (index, job) in jobs.iter().enumerate() {
+        match lanes
+            .iter_mut()
+            .find(|lane| jobs[lane[0]].shares_profile_with(job))
+        {
+            Some(lane) => lane.push(index),
+            None => lanes.push(vec![index]),
+        }
+    }
+
+    let mut results: Vec<Option<Result<LaunchOutcome, LaunchError>>> =
+        jobs.iter().map(|_| None).collect();
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = lanes
+            .iter()
+            .map(|lane| {
+                scope.spawn(move || {
+                    lane.iter()
+                        .map(|&index| {
+                            let job = &jobs[index];
+                            let result = launch_with_profile(
+                                job.target.clone(),
+                                &job.urls,
+                                job.profile_opts.as_ref(),
+                                job.window_opts.as_ref(),
+                            );
+                            (index, result)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            for (index, result) in handle.join().expect("launch thread panicked") {
+                results[index] = Some(result);
+            }
+        }
+    });
+    results
+        .into_iter()
+        .map(|result| result.expect("every job is launched"))
+        .collect()
+}
+
+/// Whether an application is registered to open `scheme` URLs.
+pub fn has_scheme_handler(scheme: &str) -> bool {
+    platform::has_scheme_handler(&crate::filesystem::RealFileSystem, scheme)
 }
 
 pub fn find_browser<'a>(
@@ -284,11 +653,14 @@ pub fn available_tokens(browsers: &[BrowserInfo]) -> Vec<String> {
     tokens
 }
 
+/// Drops installations detected twice, keeping the per-user registration of the two.
 fn dedupe_browsers(browsers: Vec<BrowserInfo>) -> Vec<BrowserInfo> {
-    let mut seen = HashSet::new();
-    let mut unique = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut unique: Vec<BrowserInfo> = Vec::new();
+
+    for mut browser in browsers {
+        browser.scope = platform::install_scope(&browser);
 
-    for browser in browsers {
         let signature = browser
             .exec_command
             .clone()
@@ -301,8 +673,16 @@ fn dedupe_browsers(browsers: Vec<BrowserInfo>) -> Vec<BrowserInfo> {
             signature
         );
 
-        if seen.insert(key) {
-            unique.push(browser);
+        match seen.get(&key) {
+            Some(&index) => {
+                if browser.scope < unique[index].scope {
+                    unique[index] = browser;
+                }
+            }
+            None => {
+                seen.insert(key, unique.len());
+                unique.push(browser);
+            }
         }
     }
 
@@ -323,6 +703,7 @@ pub fn default_channel_priority(channel: &BrowserChannel) -> u8 {
             FirefoxChannel::Beta => 2,
             FirefoxChannel::Dev => 3,
             FirefoxChannel::Nightly => 4,
+            FirefoxChannel::Twilight => 5,
         },
         BrowserChannel::Safari(ch) => match ch {
             SafariChannel::Stable => 0,
@@ -331,3 +712,213 @@ pub fn default_channel_priority(channel: &BrowserChannel) -> u8 {
         BrowserChannel::Single => 0,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn browser(
+        kind: BrowserKind,
+        channel: BrowserChannel,
+        source: InstallationSource,
+    ) -> BrowserInfo {
+        BrowserInfo {
+            kind,
+            channel,
+            display_name: kind.canonical_name().to_string(),
+            executable_path: PathBuf::from(kind.canonical_name()),
+            version: None,
+            unique_id: format!("{}-{}", kind.canonical_name(), channel.canonical_name()),
+            exec_command: None,
+            source,
+            scope: InstallScope::System,
+            arch: None,
+            is_default: false,
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn parallel_launches_report_every_job_in_order() {
+        let mut missing = browser(
+            BrowserKind::Chromium,
+            BrowserChannel::Chromium(ChromiumChannel::Stable),
+            InstallationSource::Unknown,
+        );
+        missing.executable_path = PathBuf::from("/nonexistent/pathway-test-browser");
+        let working = BrowserInfo {
+            executable_path: PathBuf::from("/bin/true"),
+            ..missing.clone()
+        };
+        let jobs = [
+            LaunchJob {
+                target: LaunchTarget::Browser(&missing),
+                urls: vec!["https://example.com/".to_string()],
+                profile_opts: None,
+                window_opts: None,
+            },
+            LaunchJob {
+                target: LaunchTarget::Browser(&working),
+                urls: vec!["https://example.org/".to_string()],
+                profile_opts: None,
+                window_opts: None,
+            },
+            LaunchJob {
+                target: LaunchTarget::Browser(&working),
+                urls: vec!["https://example.net/".to_string()],
+                profile_opts: None,
+                window_opts: None,
+            },
+        ];
+        assert!(!jobs[0].shares_profile_with(&jobs[1]));
+        assert!(jobs[1].shares_profile_with(&jobs[2]));
+
+        let results = launch_all(&jobs);
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_err());
+        for (result, url) in results[1..]
+            .iter()
+            .zip(["https://example.org/", "https://example.net/"])
+        {
+            let launched = result.as_ref().unwrap();
+            assert!(launched.pid.is_some());
+            assert_eq!(launched.command.args.last().map(String::as_str), Some(url));
+        }
+    }
+
+    #[test]
+    fn detection_results_are_dropped_after_the_deadline() {
+        let fast = spawn_detection(|| 1);
+        assert_eq!(
+            receive_by(&fast, Instant::now() + Duration::from_secs(5)),
+            Some(1)
+        );
+
+        let slow = spawn_detection(|| {
+            std::thread::sleep(Duration::from_millis(500));
+            2
+        });
+        assert_eq!(
+            receive_by(&slow, Instant::now() + Duration::from_millis(10)),
+            None
+        );
+    }
+
+    #[test]
+    fn kinds_round_trip_through_canonical_names() {
+        for kind in [
+            BrowserKind::Chrome,
+            BrowserKind::TorBrowser,
+            BrowserKind::Waterfox,
+            BrowserKind::LibreWolf,
+            BrowserKind::Zen,
+            BrowserKind::Floorp,
+            BrowserKind::PaleMoon,
+            BrowserKind::Basilisk,
+            BrowserKind::Thorium,
+            BrowserKind::UngoogledChromium,
+            BrowserKind::Yandex,
+            BrowserKind::Wavebox,
+            BrowserKind::Sidekick,
+            BrowserKind::Qutebrowser,
+            BrowserKind::Epiphany,
+            BrowserKind::Falkon,
+            BrowserKind::Konqueror,
+            BrowserKind::DuckDuckGo,
+            BrowserKind::Other,
+        ] {
+            assert_eq!(
+                BrowserKind::from_canonical_name(kind.canonical_name()),
+                Some(kind)
+            );
+        }
+        assert_eq!(BrowserKind::from_canonical_name("netscape"), None);
+    }
+
+    #[test]
+    fn browsers_sort_by_kind_channel_and_source() {
+        use sources::LinuxInstallationSource::{Flatpak, System};
+
+        let mut browsers = [
+            browser(
+                BrowserKind::Firefox,
+                BrowserChannel::Firefox(FirefoxChannel::Nightly),
+                InstallationSource::Linux(System),
+            ),
+            browser(
+                BrowserKind::Chrome,
+                BrowserChannel::Chromium(ChromiumChannel::Beta),
+                InstallationSource::Linux(System),
+            ),
+            browser(
+                BrowserKind::Firefox,
+                BrowserChannel::Firefox(FirefoxChannel::Stable),
+                InstallationSource::Linux(Flatpak),
+            ),
+            browser(
+                BrowserKind::Firefox,
+                BrowserChannel::Firefox(FirefoxChannel::Stable),
+                InstallationSource::Linux(System),
+            ),
+            browser(
+                BrowserKind::Chrome,
+                BrowserChannel::Chromium(ChromiumChannel::Stable),
+                InstallationSource::Linux(System),
+            ),
+        ];
+        browsers.sort_by(compare_browsers);
+
+        let order: Vec<(String, &str)> = browsers
+            .iter()
+            .map(|b| (b.alias(), b.source.canonical_name()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("chrome".to_string(), "system"),
+                ("chrome-beta".to_string(), "system"),
+                ("firefox".to_string(), "system"),
+                ("firefox".to_string(), "flatpak"),
+                ("firefox-nightly".to_string(), "system"),
+            ]
+        );
+    }
+
+    #[test]
+    fn native_builds_sort_before_emulated_ones_then_user_installs() {
+        let Some(host) = Arch::host() else {
+            return;
+        };
+        let emulated = if host == Arch::Arm64 {
+            Arch::X86_64
+        } else {
+            Arch::Arm64
+        };
+        let chrome = |arch, scope, path: &str| BrowserInfo {
+            arch: Some(arch),
+            scope,
+            executable_path: PathBuf::from(path),
+            ..browser(
+                BrowserKind::Chrome,
+                BrowserChannel::Chromium(ChromiumChannel::Stable),
+                InstallationSource::Unknown,
+            )
+        };
+
+        let mut browsers = [
+            chrome(emulated, InstallScope::User, "/emulated-user"),
+            chrome(host, InstallScope::System, "/native-system"),
+            chrome(Arch::Universal, InstallScope::User, "/universal-user"),
+        ];
+        browsers.sort_by(compare_browsers);
+
+        let order: Vec<_> = browsers
+            .iter()
+            .map(|b| b.executable_path.to_str().unwrap())
+            .collect();
+        assert_eq!(
+            order,
+            ["/universal-user", "/native-system", "/emulated-user"]
+        );
+    }
+}
@@ -0,0 +1,448 @@
+use crate::filesystem::FileSystem;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum LinuxInstallationSource {
+    /// Installed by the distribution package manager or manually
+    System,
+    Flatpak,
+    Snap,
+    AppImage,
+    /// A Windows browser reached through WSL interop
+    Wsl,
+    /// Installed through a Nix profile, NixOS or home-manager
+    Nix,
+}
+
+impl LinuxInstallationSource {
+    pub fn canonical_name(self) -> &'static str {
+        match self {
+            LinuxInstallationSource::System => "system",
+            LinuxInstallationSource::Flatpak => "flatpak",
+            LinuxInstallationSource::Snap => "snap",
+            LinuxInstallationSource::AppImage => "appimage",
+            LinuxInstallationSource::Wsl => "wsl",
+            LinuxInstallationSource::Nix => "nix",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum MacOsInstallationSource {
+    /// Installed in `/Applications`
+    System,
+    /// Installed in `~/Applications`
+    User,
+    /// Installed from the Mac App Store
+    AppStore,
+    /// Installed through Setapp
+    Setapp,
+    /// Installed as a Homebrew cask
+    Homebrew,
+}
+
+impl MacOsInstallationSource {
+    pub fn canonical_name(self) -> &'static str {
+        match self {
+            MacOsInstallationSource::System => "system",
+            MacOsInstallationSource::User => "user",
+            MacOsInstallationSource::AppStore => "app-store",
+            MacOsInstallationSource::Setapp => "setapp",
+            MacOsInstallationSource::Homebrew => "homebrew",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum WindowsInstallationSource {
+    /// Installed for all users
+    System,
+    /// Installed for the current user only
+    User,
+    /// Packaged app from the Microsoft Store
+    Store,
+    /// Installed by the Scoop package manager
+    Scoop,
+    /// Installed by the Chocolatey package manager
+    Chocolatey,
+    /// Portable package installed by winget
+    Winget,
+    /// Unregistered copy found in a portable apps directory
+    Portable,
+}
+
+impl WindowsInstallationSource {
+    pub fn canonical_name(self) -> &'static str {
+        match self {
+            WindowsInstallationSource::System => "system",
+            WindowsInstallationSource::User => "user",
+            WindowsInstallationSource::Store => "store",
+            WindowsInstallationSource::Scoop => "scoop",
+            WindowsInstallationSource::Chocolatey => "chocolatey",
+            WindowsInstallationSource::Winget => "winget",
+            WindowsInstallationSource::Portable => "portable",
+        }
+    }
+}
+
+// General enum to hold the platform-specific installation source
+#[derive(Debug, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum InstallationSource {
+    Linux(LinuxInstallationSource),
+    MacOs(MacOsInstallationSource),
+    Windows(WindowsInstallationSource),
+    Unknown,
+}
+
+impl InstallationSource {
+    pub fn canonical_name(self) -> &'static str {
+        match self {
+            InstallationSource::Linux(s) => s.canonical_name(),
+            InstallationSource::MacOs(s) => s.canonical_name(),
+            InstallationSource::Windows(s) => s.canonical_name(),
+            InstallationSource::Unknown => "unknown",
+        }
+    }
+}
+
+/// Whether an installation is available to the current user only or to everyone.
+#[derive(Debug, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum InstallScope {
+    User,
+    System,
+}
+
+impl InstallScope {
+    pub fn canonical_name(self) -> &'static str {
+        match self {
+            InstallScope::User => "user",
+            InstallScope::System => "system",
+        }
+    }
+
+    pub fn from_canonical_name(name: &str) -> Option<Self> {
+        match name {
+            "user" => Some(InstallScope::User),
+            "system" => Some(InstallScope::System),
+            _ => None,
+        }
+    }
+}
+
+/// Homebrew keeps cask metadata here on Apple Silicon and Intel Macs respectively.
+pub const HOMEBREW_CASKROOMS: &[&str] = &["/opt/homebrew/Caskroom", "/usr/local/Caskroom"];
+
+/// Classifies a macOS application bundle by where and how it was installed.
+///
+/// `from_cask` is set when a Homebrew cask lists the bundle as one of its artifacts; bundles
+/// that are symlinks into a Caskroom are recognized without it.
+pub fn macos_source_for_app<F: FileSystem>(
+    fs: &F,
+    app_path: &Path,
+    home: Option<&Path>,
+    from_cask: bool,
+) -> MacOsInstallationSource {
+    let in_caskroom = || {
+        fs.canonicalize(app_path).is_ok_and(|resolved| {
+            HOMEBREW_CASKROOMS
+                .iter()
+                .any(|caskroom| resolved.starts_with(caskroom))
+        })
+    };
+
+    if fs.exists(&app_path.join("Contents/_MASReceipt")) {
+        MacOsInstallationSource::AppStore
+    } else if from_cask || in_caskroom() {
+        MacOsInstallationSource::Homebrew
+    } else if app_path.starts_with("/Applications/Setapp") {
+        MacOsInstallationSource::Setapp
+    } else if home.is_some_and(|home| app_path.starts_with(home.join("Applications"))) {
+        MacOsInstallationSource::User
+    } else {
+        MacOsInstallationSource::System
+    }
+}
+
+/// Application bundle names installed by a cask.
+///
+/// Accepts both `.metadata/INSTALL_RECEIPT.json` (`uninstall_artifacts`) and the cask JSON
+/// stored by older Homebrew versions (`artifacts`). Renamed apps yield their target name.
+pub fn cask_app_artifacts(json: &str) -> Vec<String> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(json) else {
+        return Vec::new();
+    };
+    let artifacts = value
+        .get("uninstall_artifacts")
+        .or_else(|| value.get("artifacts"))
+        .and_then(|a| a.as_array());
+
+    let mut apps = Vec::new();
+    for entry in artifacts.into_iter().flatten() {
+        let Some(items) = entry.get("app").and_then(|a| a.as_array()) else {
+            continue;
+        };
+        for item in items {
+            if let Some(name) = item.as_str() {
+                apps.push(name.to_string());
+            } else if let Some(target) = item.get("target").and_then(|t| t.as_str()) {
+                // `app "Foo.app", target: "Bar.app"` serializes as ["Foo.app", {"target": ...}].
+                apps.pop();
+                apps.push(target.to_string());
+            }
+        }
+    }
+    apps
+}
+
+/// The `appdir` a cask was installed into, from its `.metadata/config.json`.
+pub fn cask_appdir(json: &str, home: Option<&Path>) -> Option<PathBuf> {
+    let value = serde_json::from_str::<serde_json::Value>(json).ok()?;
+    let appdir = ["explicit", "default"]
+        .iter()
+        .find_map(|section| value.get(section)?.get("appdir")?.as_str())?;
+
+    match appdir.strip_prefix("~/") {
+        Some(rest) => Some(home?.join(rest)),
+        None => Some(PathBuf::from(appdir)),
+    }
+}
+
+/// Classifies a Windows executable from its path and the registry hive it was found in.
+pub fn windows_source_for_executable(
+    executable: &Path,
+    registered_per_user: bool,
+) -> WindowsInstallationSource {
+    let path = executable
+        .to_string_lossy()
+        .replace('/', "\\")
+        .to_lowercase();
+    if path.contains("\\windowsapps\\") {
+        WindowsInstallationSource::Store
+    } else if path.contains("\\scoop\\apps\\") {
+        WindowsInstallationSource::Scoop
+    } else if path.contains("\\chocolatey\\lib\\") {
+        WindowsInstallationSource::Chocolatey
+    } else if path.contains("\\winget\\packages\\") {
+        WindowsInstallationSource::Winget
+    } else if registered_per_user || path.contains("\\appdata\\") {
+        WindowsInstallationSource::User
+    } else {
+        WindowsInstallationSource::System
+    }
+}
+
+/// Returns true for paths inside the Nix store or a Nix profile.
+pub fn is_nix_path(path: &Path) -> bool {
+    ["/nix/", "/run/current-system/", "/etc/profiles/per-user/"]
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+        || path
+            .components()
+            .any(|component| component.as_os_str() == ".nix-profile")
+        || path
+            .to_str()
+            .is_some_and(|path| path.contains("/.local/state/nix/profile"))
+}
+
+/// Returns the snap name for an executable exposed under `/snap/bin`.
+///
+/// Commands of non-default snap apps are named `<snap>.<app>`.
+pub fn snap_name(executable: &Path) -> Option<&str> {
+    let command = executable.strip_prefix("/snap/bin").ok()?.to_str()?;
+    command.split('.').next().filter(|name| !name.is_empty())
+}
+
+/// Splits a `flatpak run [options] <app-id> ...` command line into its run options and app id.
+pub fn parse_flatpak_run(exec: &str) -> Option<(Vec<String>, String)> {
+    let tokens = shell_words::split(exec).ok()?;
+    let mut iter = tokens.into_iter();
+
+    let program = iter.next()?;
+    if Path::new(&program).file_name()?.to_str()? != "flatpak" {
+        return None;
+    }
+    if iter.next()? != "run" {
+        return None;
+    }
+
+    let mut options = Vec::new();
+    for token in iter {
+        if token.starts_with('-') {
+            options.push(token);
+        } else {
+            return Some((options, token));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filesystem::MockFileSystem;
+
+    #[test]
+    fn macos_sources_follow_bundle_location() {
+        let mut mock_fs = MockFileSystem::new();
+        mock_fs
+            .expect_exists()
+            .returning(|path| path.starts_with("/Applications/Safari.app"));
+        mock_fs.expect_canonicalize().returning(|path| {
+            if path.ends_with("Firefox.app") {
+                Ok(PathBuf::from(
+                    "/opt/homebrew/Caskroom/firefox/121.0/Firefox.app",
+                ))
+            } else {
+                Ok(path.to_path_buf())
+            }
+        });
+        let home = Path::new("/Users/me");
+
+        let source = |app: &str| macos_source_for_app(&mock_fs, Path::new(app), Some(home), false);
+        assert_eq!(
+            source("/Applications/Safari.app"),
+            MacOsInstallationSource::AppStore
+        );
+        assert_eq!(
+            source("/Applications/Google Chrome.app"),
+            MacOsInstallationSource::System
+        );
+        assert_eq!(
+            source("/Applications/Setapp/Sidekick.app"),
+            MacOsInstallationSource::Setapp
+        );
+        assert_eq!(
+            source("/Users/me/Applications/Arc.app"),
+            MacOsInstallationSource::User
+        );
+        assert_eq!(
+            source("/Applications/Firefox.app"),
+            MacOsInstallationSource::Homebrew
+        );
+        assert_eq!(
+            macos_source_for_app(
+                &mock_fs,
+                Path::new("/Users/me/Applications/Brave Browser.app"),
+                Some(home),
+                true
+            ),
+            MacOsInstallationSource::Homebrew
+        );
+    }
+
+    #[test]
+    fn cask_metadata_yields_app_locations() {
+        let receipt = r#"{
+            "homebrew_version": "4.2.0",
+            "uninstall_artifacts": [
+                {"app": ["Firefox.app"]},
+                {"app": ["Firefox Nightly.app", {"target": "Nightly.app"}]},
+                {"zap": [{"trash": ["~/Library/Caches/Firefox"]}]}
+            ]
+        }"#;
+        assert_eq!(
+            cask_app_artifacts(receipt),
+            vec!["Firefox.app".to_string(), "Nightly.app".to_string()]
+        );
+        assert!(cask_app_artifacts("not json").is_empty());
+
+        let home = Path::new("/Users/me");
+        let config =
+            r#"{"default": {"appdir": "/Applications"}, "explicit": {"appdir": "~/Applications"}}"#;
+        assert_eq!(
+            cask_appdir(config, Some(home)),
+            Some(PathBuf::from("/Users/me/Applications"))
+        );
+        assert_eq!(
+            cask_appdir(r#"{"default": {"appdir": "/Applications"}}"#, Some(home)),
+            Some(PathBuf::from("/Applications"))
+        );
+    }
+
+    #[test]
+    fn nix_paths_are_recognized() {
+        for path in [
+            "/home/me/.nix-profile/share/applications/firefox.desktop",
+            "/home/me/.local/state/nix/profile/share/applications/brave-browser.desktop",
+            "/run/current-system/sw/share/applications/chromium-browser.desktop",
+            "/etc/profiles/per-user/me/share/applications/firefox.desktop",
+            "/nix/store/abc123-firefox-121.0/bin/firefox",
+        ] {
+            assert!(is_nix_path(Path::new(path)), "{}", path);
+        }
+        assert!(!is_nix_path(Path::new(
+            "/usr/share/applications/firefox.desktop"
+        )));
+        assert!(!is_nix_path(Path::new("/home/me/nixos-notes/firefox")));
+    }
+
+    #[test]
+    fn flatpak_run_exec_is_split() {
+        let exec = "/usr/bin/flatpak run --branch=stable --arch=x86_64 --command=firefox --file-forwarding org.mozilla.firefox @@u %u @@";
+        let (options, app_id) = parse_flatpak_run(exec).expect("flatpak exec line");
+
+        assert_eq!(app_id, "org.mozilla.firefox");
+        assert_eq!(
+            options,
+            vec![
+                "--branch=stable",
+                "--arch=x86_64",
+                "--command=firefox",
+                "--file-forwarding"
+            ]
+        );
+        assert!(parse_flatpak_run("/usr/bin/firefox %u").is_none());
+    }
+
+    #[test]
+    fn windows_sources_follow_path_and_hive() {
+        let source =
+            |path: &str, per_user| windows_source_for_executable(Path::new(path), per_user);
+        assert_eq!(
+            source(
+                r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+                false
+            ),
+            WindowsInstallationSource::System
+        );
+        assert_eq!(
+            source(
+                r"C:\Users\me\AppData\Local\Vivaldi\Application\vivaldi.exe",
+                false
+            ),
+            WindowsInstallationSource::User
+        );
+        assert_eq!(
+            source(r"C:\Program Files\Mozilla Firefox\firefox.exe", true),
+            WindowsInstallationSource::User
+        );
+        assert_eq!(
+            source(
+                r"C:\Program Files\WindowsApps\Mozilla.Firefox_121.0.0.0_x64\VFS\firefox.exe",
+                false
+            ),
+            WindowsInstallationSource::Store
+        );
+        assert_eq!(
+            source(r"C:\Users\me\scoop\apps\firefox\current\firefox.exe", false),
+            WindowsInstallationSource::Scoop
+        );
+        assert_eq!(
+            source(
+                r"C:\ProgramData\chocolatey\lib\ungoogled-chromium\tools\chrome.exe",
+                false
+            ),
+            WindowsInstallationSource::Chocolatey
+        );
+        assert_eq!(
+            source(
+                r"C:\Users\me\AppData\Local\Microsoft\WinGet\Packages\Hibbiki.Chromium_Microsoft.Winget.Source_8wekyb3d8bbwe\chrome.exe",
+                true
+            ),
+            WindowsInstallationSource::Winget
+        );
+    }
+}
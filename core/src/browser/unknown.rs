@@ -1,4 +1,8 @@
-use super::{BrowserInfo, LaunchCommand, LaunchOutcome, LaunchTarget, SystemDefaultBrowser};
+use super::mail::MailClient;
+use super::{
+    BrowserInfo, BrowserKind, InstallScope, LaunchCommand, LaunchOutcome, LaunchTarget,
+    SystemDefaultBrowser,
+};
 use crate::filesystem::FileSystem;
 use std::io;
 use std::path::PathBuf;
@@ -10,6 +14,8 @@ use tracing::debug;
 pub enum LaunchError {
     #[error("No URLs provided to launch")]
     NoUrls,
+    #[error("{0} is already running with this profile; use --temp-profile or --user-dir for a separate instance")]
+    AlreadyRunning(String),
     #[error("Unable to launch system default browser on this platform")]
     Unsupported,
     #[error("Failed to launch browser: {source}")]
@@ -23,6 +29,52 @@ pub fn detect_browsers<F: FileSystem>(_fs: &F) -> Vec<BrowserInfo> {
     Vec::new()
 }
 
+pub fn detect_browsers_of_kind<F: FileSystem>(_fs: &F, _kind: BrowserKind) -> Vec<BrowserInfo> {
+    Vec::new()
+}
+
+pub fn install_scope(_browser: &BrowserInfo) -> InstallScope {
+    InstallScope::System
+}
+
+pub fn is_system_default(_browser: &BrowserInfo, _system_default: &SystemDefaultBrowser) -> bool {
+    false
+}
+
+pub fn detect_mail_clients<F: FileSystem>(_fs: &F) -> Vec<MailClient> {
+    Vec::new()
+}
+
+pub fn has_scheme_handler<F: FileSystem>(_fs: &F, _scheme: &str) -> bool {
+    false
+}
+
+pub fn launch_mail(client: &MailClient, urls: &[String]) -> Result<LaunchOutcome, LaunchError> {
+    if urls.is_empty() {
+        return Err(LaunchError::NoUrls);
+    }
+
+    let mut command = Command::new(&client.executable_path);
+    command.args(urls);
+    command.stdin(Stdio::null());
+    command.stdout(Stdio::null());
+    command.stderr(Stdio::null());
+    let child = command.spawn()?;
+
+    Ok(LaunchOutcome {
+        browser: None,
+        system_default: None,
+        command: LaunchCommand {
+            program: client.executable_path.clone(),
+            args: urls.to_vec(),
+            display: format!("{} {}", client.executable_path.display(), urls.join(" ")),
+            is_system_default: false,
+        },
+        reused_existing: false,
+        pid: Some(child.id()),
+    })
+}
+
 /// Returns the system's default browser metadata, if detectable on this platform.
 ///
 /// This is a platform-dependent stub that currently does not detect or return a system
@@ -70,7 +122,7 @@ pub fn launch(target: LaunchTarget<'_>, urls: &[String]) -> Result<LaunchOutcome
 /// # Errors
 ///
 /// Returns a `LaunchError` when:
-/// - `LaunchError::NoUrls` if `urls` is empty.
+/// - `LaunchError::NoUrls` if `target` is `LaunchTarget::SystemDefault` and `urls` is empty.
 /// - `LaunchError::Unsupported` if `target` is `LaunchTarget::SystemDefault`.
 /// - `LaunchError::Spawn` if spawning the browser process fails (propagated from `std::io::Error`).
 ///
@@ -90,10 +142,6 @@ pub fn launch_with_profile(
     _profile_opts: Option<&crate::profile::ProfileOptions>,
     _window_opts: Option<&crate::profile::WindowOptions>,
 ) -> Result<LaunchOutcome, LaunchError> {
-    if urls.is_empty() {
-        return Err(LaunchError::NoUrls);
-    }
-
     match target {
         LaunchTarget::Browser(info) => {
             let exec = info.launch_path();
@@ -104,7 +152,7 @@ pub fn launch_with_profile(
             command.stdout(Stdio::null());
             command.stderr(Stdio::null());
             debug!(program = %exec.display(), args = ?urls, "Launching browser");
-            command.spawn()?;
+            let child = command.spawn()?;
 
             let cmd = LaunchCommand {
                 program: exec.clone(),
@@ -117,9 +165,14 @@ pub fn launch_with_profile(
                 browser: Some(info.clone()),
                 system_default: None,
                 command: cmd,
+                reused_existing: false,
+                pid: Some(child.id()),
             })
         }
         LaunchTarget::SystemDefault => {
+            if urls.is_empty() {
+                return Err(LaunchError::NoUrls);
+            }
             debug!("System default browser launch is unsupported on this platform");
             Err(LaunchError::Unsupported)
         }
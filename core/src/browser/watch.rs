@@ -0,0 +1,130 @@
+//! Changes between two browser inventories, reported by the periodic rescans of
+//! `browser watch`.
+
+use super::BrowserInfo;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum InventoryEvent {
+    Installed {
+        browser: BrowserInfo,
+    },
+    Removed {
+        browser: BrowserInfo,
+    },
+    /// The installation is still there but reports a different version
+    Updated {
+        browser: BrowserInfo,
+        previous_version: Option<String>,
+    },
+}
+
+impl InventoryEvent {
+    pub fn browser(&self) -> &BrowserInfo {
+        match self {
+            InventoryEvent::Installed { browser }
+            | InventoryEvent::Removed { browser }
+            | InventoryEvent::Updated { browser, .. } => browser,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InventoryEvent::Installed { .. } => "installed",
+            InventoryEvent::Removed { .. } => "removed",
+            InventoryEvent::Updated { .. } => "updated",
+        }
+    }
+}
+
+/// Copies of a macOS app share their bundle id, so installations are told apart by path too.
+fn installation_key(browser: &BrowserInfo) -> (&str, &Path) {
+    (&browser.unique_id, &browser.executable_path)
+}
+
+/// Lists what was installed, removed or updated between `previous` and `current`.
+pub fn inventory_changes(previous: &[BrowserInfo], current: &[BrowserInfo]) -> Vec<InventoryEvent> {
+    let before: HashMap<_, _> = previous
+        .iter()
+        .map(|browser| (installation_key(browser), browser))
+        .collect();
+    let after: HashMap<_, _> = current
+        .iter()
+        .map(|browser| (installation_key(browser), browser))
+        .collect();
+
+    let mut events = Vec::new();
+    for browser in current {
+        match before.get(&installation_key(browser)) {
+            None => events.push(InventoryEvent::Installed {
+                browser: browser.clone(),
+            }),
+            Some(old) if old.version != browser.version => events.push(InventoryEvent::Updated {
+                browser: browser.clone(),
+                previous_version: old.version.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    for browser in previous {
+        if !after.contains_key(&installation_key(browser)) {
+            events.push(InventoryEvent::Removed {
+                browser: browser.clone(),
+            });
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::channels::{BrowserChannel, ChromiumChannel};
+    use crate::browser::{BrowserKind, InstallScope, InstallationSource};
+    use std::path::PathBuf;
+
+    fn chrome(unique_id: &str, version: &str) -> BrowserInfo {
+        BrowserInfo {
+            kind: BrowserKind::Chrome,
+            channel: BrowserChannel::Chromium(ChromiumChannel::Stable),
+            display_name: "Google Chrome".to_string(),
+            executable_path: PathBuf::from("/opt/google/chrome/chrome"),
+            version: Some(version.to_string()),
+            unique_id: unique_id.to_string(),
+            exec_command: None,
+            source: InstallationSource::Unknown,
+            scope: InstallScope::System,
+            arch: None,
+            is_default: false,
+        }
+    }
+
+    #[test]
+    fn changes_are_reported_per_installation() {
+        let previous = vec![
+            chrome("chrome.desktop", "120.0"),
+            chrome("old.desktop", "1.0"),
+        ];
+        let current = vec![
+            chrome("chrome.desktop", "121.0"),
+            chrome("new.desktop", "1.0"),
+        ];
+
+        let events: Vec<_> = inventory_changes(&previous, &current)
+            .iter()
+            .map(|event| (event.name(), event.browser().unique_id.clone()))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                ("updated", "chrome.desktop".to_string()),
+                ("installed", "new.desktop".to_string()),
+                ("removed", "old.desktop".to_string()),
+            ]
+        );
+        assert!(inventory_changes(&current, &current).is_empty());
+    }
+}
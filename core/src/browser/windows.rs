@@ -1,7 +1,14 @@
-use super::{BrowserInfo, BrowserKind};
-use crate::browser::channels::{BrowserChannel, ChromiumChannel, FirefoxChannel};
+use super::mail::{MailClient, MailClientKind};
+use super::sources::{self, WindowsInstallationSource};
+use super::{BrowserInfo, BrowserKind, InstallScope, InstallationSource};
+use crate::browser::channels::{self, BrowserChannel, ChromiumChannel, FirefoxChannel};
 use crate::filesystem::FileSystem;
-use std::path::PathBuf;
+use std::ffi::{c_void, OsStr};
+use std::os::windows::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use windows_sys::Win32::Storage::FileSystem::{
+    GetFileVersionInfoSizeW, GetFileVersionInfoW, VerQueryValueW, VS_FIXEDFILEINFO,
+};
 use winreg::enums::*;
 use winreg::RegKey;
 
@@ -16,6 +23,8 @@ pub enum LaunchError {
     MissingExecutable(String),
     #[error("No URLs provided to launch")]
     NoUrls,
+    #[error("{0} is already running with this profile; use --temp-profile or --user-dir for a separate instance")]
+    AlreadyRunning(String),
     #[error("Failed to launch browser: {source}")]
     Spawn {
         #[from]
@@ -32,10 +41,6 @@ pub fn launch_with_profile(
     profile_opts: Option<&crate::profile::ProfileOptions>,
     window_opts: Option<&crate::profile::WindowOptions>,
 ) -> Result<LaunchOutcome, LaunchError> {
-    if urls.is_empty() {
-        return Err(LaunchError::NoUrls);
-    }
-
     match target {
         LaunchTarget::Browser(info) => {
             let exec = info.launch_path();
@@ -71,7 +76,7 @@ pub fn launch_with_profile(
                 "Launching browser"
             };
             debug!(program = %exec.display(), args = ?all_args, "{}", log_message);
-            command.spawn()?;
+            let child = command.spawn()?;
 
             let cmd = LaunchCommand {
                 program: exec.to_path_buf(),
@@ -84,9 +89,14 @@ pub fn launch_with_profile(
                 browser: Some(info.clone()),
                 system_default: None,
                 command: cmd,
+                reused_existing: false,
+                pid: Some(child.id()),
             })
         }
         LaunchTarget::SystemDefault => {
+            if urls.is_empty() {
+                return Err(LaunchError::NoUrls);
+            }
             // Use cmd /c start to open with system default browser
             let mut command = Command::new("cmd");
             command.arg("/c").arg("start").arg("");
@@ -100,7 +110,7 @@ pub fn launch_with_profile(
                 .map(|s| s.to_string_lossy().to_string())
                 .collect();
             debug!(program = "cmd", args = ?all_args, "Launching system default browser");
-            command.spawn()?;
+            let child = command.spawn()?;
 
             let cmd = LaunchCommand {
                 program: PathBuf::from("cmd"),
@@ -113,10 +123,31 @@ pub fn launch_with_profile(
                 browser: None,
                 system_default: system_default_browser_with_fs(&crate::filesystem::RealFileSystem),
                 command: cmd,
+                reused_existing: false,
+                pid: Some(child.id()),
             })
         }
     }
 }
+/// Package managers other than Chocolatey and portable copies install for the current user.
+pub fn install_scope(browser: &BrowserInfo) -> InstallScope {
+    match browser.source {
+        InstallationSource::Windows(
+            WindowsInstallationSource::System | WindowsInstallationSource::Chocolatey,
+        ) => InstallScope::System,
+        InstallationSource::Windows(_) => InstallScope::User,
+        _ => InstallScope::System,
+    }
+}
+
+/// ProgIDs are not recorded with the inventory, so the default is matched by executable.
+pub fn is_system_default(browser: &BrowserInfo, system_default: &SystemDefaultBrowser) -> bool {
+    system_default
+        .path
+        .as_deref()
+        .is_some_and(|path| executable_key(path) == executable_key(browser.launch_path()))
+}
+
 pub fn system_default_browser_with_fs<F: FileSystem>(_fs: &F) -> Option<SystemDefaultBrowser> {
     let prog_id = default_prog_id()?;
 
@@ -135,19 +166,42 @@ pub fn system_default_browser_with_fs<F: FileSystem>(_fs: &F) -> Option<SystemDe
 // End stubs
 
 pub fn detect_browsers<F: FileSystem>(_fs: &F) -> Vec<BrowserInfo> {
+    detect_browsers_filtered(None)
+}
+
+/// Like [`detect_browsers`], but skips the package and portable scans once the registry
+/// yields a browser of `kind`.
+pub fn detect_browsers_of_kind<F: FileSystem>(_fs: &F, kind: BrowserKind) -> Vec<BrowserInfo> {
+    detect_browsers_filtered(Some(kind))
+}
+
+fn detect_browsers_filtered(kind: Option<BrowserKind>) -> Vec<BrowserInfo> {
+    let wanted = |browser: &BrowserInfo| kind.is_none_or(|kind| browser.kind == kind);
     let mut browsers = Vec::new();
-    let mut seen_paths = std::collections::HashSet::new();
+    let mut seen_executables = std::collections::HashSet::new();
     let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
     let hkcu = RegKey::predef(HKEY_CURRENT_USER);
 
     let search_path = "SOFTWARE\\Clients\\StartMenuInternet";
+    let mut roots = vec![(false, search_path), (true, search_path)];
+    roots.extend(
+        super::detection_config()
+            .registry_keys
+            .iter()
+            .filter_map(|key| split_registry_key(key)),
+    );
 
-    for key in [&hklm, &hkcu] {
+    for (per_user, search_path) in roots {
+        let key = if per_user { &hkcu } else { &hklm };
         if let Ok(internet_clients) = key.open_subkey(search_path) {
             for client_name in internet_clients.enum_keys().filter_map(Result::ok) {
-                if let Some(browser_info) = create_browser_info(key, search_path, &client_name) {
-                    // Deduplicate by executable path
-                    if seen_paths.insert(browser_info.executable_path.clone()) {
+                if let Some(browser_info) =
+                    create_browser_info(key, search_path, &client_name, per_user)
+                {
+                    // The same installation is often registered in both hives.
+                    if wanted(&browser_info)
+                        && seen_executables.insert(executable_key(&browser_info.executable_path))
+                    {
                         browsers.push(browser_info);
                     }
                 }
@@ -155,13 +209,714 @@ pub fn detect_browsers<F: FileSystem>(_fs: &F) -> Vec<BrowserInfo> {
         }
     }
 
+    if kind.is_some() && !browsers.is_empty() {
+        return browsers;
+    }
+
+    // Some installers (e.g. per-user Brave) only register an uninstaller, and package managers
+    // and USB sticks carry portable builds that never register with the shell.
+    for browser_info in detect_uninstall_browsers(&hklm, &hkcu)
+        .into_iter()
+        .chain(detect_packaged_browsers())
+        .chain(detect_portable_browsers())
+        .filter(wanted)
+    {
+        if seen_executables.insert(executable_key(&browser_info.executable_path)) {
+            browsers.push(browser_info);
+        }
+    }
+
+    browsers
+}
+
+/// Splits a configured key such as `HKLM\SOFTWARE\Corp\Browsers` into whether it lives in
+/// `HKEY_CURRENT_USER` and its path below the hive.
+fn split_registry_key(key: &str) -> Option<(bool, &str)> {
+    let (hive, path) = key.split_once('\\')?;
+    let per_user = match hive.to_ascii_uppercase().as_str() {
+        "HKLM" | "HKEY_LOCAL_MACHINE" => false,
+        "HKCU" | "HKEY_CURRENT_USER" => true,
+        _ => return None,
+    };
+    Some((per_user, path.trim_matches('\\')).filter(|(_, path)| !path.is_empty()))
+}
+
+/// `Uninstall` keys of 64-bit and 32-bit machine-wide installs and of per-user installs.
+const UNINSTALL_KEYS: &[(bool, &str)] = &[
+    (
+        false,
+        r"SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall",
+    ),
+    (
+        false,
+        r"SOFTWARE\WOW6432Node\Microsoft\Windows\CurrentVersion\Uninstall",
+    ),
+    (true, r"SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall"),
+];
+
+/// Finds browsers through their `Uninstall` entries.
+///
+/// The entry's `DisplayIcon` usually points at the browser executable; otherwise the executable
+/// is looked for under `InstallLocation`. Entries whose executable is not the browser's own
+/// (WebView2, Chrome Remote Desktop, updaters) are skipped.
+fn detect_uninstall_browsers(hklm: &RegKey, hkcu: &RegKey) -> Vec<BrowserInfo> {
+    let mut browsers = Vec::new();
+
+    for &(per_user, search_path) in UNINSTALL_KEYS {
+        let base_key = if per_user { hkcu } else { hklm };
+        let Ok(entries) = base_key.open_subkey(search_path) else {
+            continue;
+        };
+        for entry_name in entries.enum_keys().filter_map(Result::ok) {
+            let reg_path = format!("{}\\{}", search_path, entry_name);
+            let Ok(entry) = base_key.open_subkey(&reg_path) else {
+                continue;
+            };
+            let Ok(display_name) = entry.get_value::<String, _>("DisplayName") else {
+                continue;
+            };
+            let Some((kind, channel, _, executable)) = packaged_browser(&display_name) else {
+                continue;
+            };
+
+            let from_icon = entry
+                .get_value::<String, _>("DisplayIcon")
+                .ok()
+                .map(|icon| uninstall_icon_path(&icon))
+                .filter(|path| {
+                    path.file_name()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(executable))
+                        && path.is_file()
+                });
+            let Some(executable_path) = from_icon.or_else(|| {
+                let location: String = entry.get_value("InstallLocation").ok()?;
+                find_executable(Path::new(&expand_env_vars(&location)), executable, 3)
+            }) else {
+                continue;
+            };
+
+            let version = entry
+                .get_value::<String, _>("DisplayVersion")
+                .ok()
+                .or_else(|| file_version(&executable_path));
+            browsers.push(BrowserInfo {
+                kind,
+                channel,
+                display_name,
+                version,
+                unique_id: reg_path,
+                exec_command: None,
+                source: detect_source_for_browser(&executable_path, per_user),
+                executable_path,
+                scope: InstallScope::System,
+                arch: None,
+                is_default: false,
+            });
+        }
+    }
+    browsers
+}
+
+/// Extracts the file from a `DisplayIcon` value such as `"C:\...\brave.exe",0`.
+fn uninstall_icon_path(icon: &str) -> PathBuf {
+    let icon = icon.trim();
+    let path = match icon.rsplit_once(',') {
+        Some((path, index)) if index.trim().parse::<i32>().is_ok() => path,
+        _ => icon,
+    };
+    PathBuf::from(expand_env_vars(path.trim().trim_matches('"')))
+}
+
+/// Package directories of Scoop (`apps\<name>\current`), Chocolatey (`lib\<name>`) and
+/// winget portable installs (`Packages\<id>_<source>`), each named after its package.
+fn package_dirs() -> Vec<(String, PathBuf)> {
+    let env_dir = |name: &str| std::env::var_os(name).map(PathBuf::from);
+    let program_data = env_dir("ProgramData").unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"));
+
+    let scoop_apps = [
+        env_dir("SCOOP").or_else(|| dirs_next::home_dir().map(|home| home.join("scoop"))),
+        Some(env_dir("SCOOP_GLOBAL").unwrap_or_else(|| program_data.join("scoop"))),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|root| root.join("apps"));
+    let chocolatey_lib = env_dir("ChocolateyInstall")
+        .unwrap_or_else(|| program_data.join("chocolatey"))
+        .join("lib");
+    let winget_packages = [
+        dirs_next::data_local_dir().map(|dir| dir.join(r"Microsoft\WinGet\Packages")),
+        env_dir("ProgramFiles").map(|dir| dir.join(r"WinGet\Packages")),
+    ];
+
+    let mut dirs = Vec::new();
+    for (root, subdir) in scoop_apps
+        .map(|root| (root, "current"))
+        .chain(std::iter::once((chocolatey_lib, "")))
+        .chain(winget_packages.into_iter().flatten().map(|root| (root, "")))
+    {
+        let Ok(entries) = std::fs::read_dir(&root) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let dir = entry.path();
+            dirs.push((
+                name,
+                if subdir.is_empty() {
+                    dir
+                } else {
+                    dir.join(subdir)
+                },
+            ));
+        }
+    }
+    dirs
+}
+
+fn detect_packaged_browsers() -> Vec<BrowserInfo> {
+    package_dirs()
+        .into_iter()
+        .filter_map(|(package, dir)| {
+            let (kind, channel, display_name, executable) = packaged_browser(&package)?;
+            let executable_path = find_executable(&dir, executable, 3)?;
+            Some(BrowserInfo {
+                kind,
+                channel,
+                display_name: display_name.to_string(),
+                version: file_version(&executable_path),
+                unique_id: dir.to_string_lossy().to_string(),
+                exec_command: None,
+                source: detect_source_for_browser(&executable_path, false),
+                executable_path,
+                scope: InstallScope::System,
+                arch: None,
+                is_default: false,
+            })
+        })
+        .collect()
+}
+
+/// Directories scanned for portable browsers: the configured application directories (e.g.
+/// `D:\PortableApps`) and `%USERPROFILE%\PortableApps`.
+fn portable_dirs() -> Vec<PathBuf> {
+    portable_dirs_with(
+        &super::detection_config().application_dirs,
+        dirs_next::home_dir().as_deref(),
+    )
+}
+
+fn portable_dirs_with(application_dirs: &[PathBuf], home: Option<&Path>) -> Vec<PathBuf> {
+    let mut dirs = application_dirs.to_vec();
+    if let Some(home) = home {
+        let default = home.join("PortableApps");
+        if !dirs.contains(&default) {
+            dirs.push(default);
+        }
+    }
+    dirs
+}
+
+/// Finds browsers in the portable directories and their immediate subdirectories, so both
+/// `D:\PortableApps` and `D:\PortableApps\FirefoxPortable` can be configured.
+fn detect_portable_browsers() -> Vec<BrowserInfo> {
+    let mut browsers = Vec::new();
+
+    for root in portable_dirs() {
+        let Ok(entries) = std::fs::read_dir(&root) else {
+            continue;
+        };
+        let candidates = std::iter::once(root.clone()).chain(
+            entries
+                .flatten()
+                .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+                .map(|entry| entry.path()),
+        );
+
+        for dir in candidates {
+            let Some(name) = dir.file_name().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let Some((kind, channel, display_name, executable)) = packaged_browser(name) else {
+                continue;
+            };
+            // PortableApps.com launchers (`FirefoxPortable\FirefoxPortable.exe`) keep the
+            // profile on the stick, so they are preferred over the bundled executable.
+            let launcher = dir.join(format!("{}.exe", name));
+            let Some(executable_path) = Some(launcher)
+                .filter(|launcher| launcher.is_file())
+                .or_else(|| find_executable(&dir, executable, 3))
+            else {
+                continue;
+            };
+
+            browsers.push(BrowserInfo {
+                kind,
+                channel,
+                display_name: display_name.to_string(),
+                version: file_version(&executable_path),
+                unique_id: dir.to_string_lossy().to_string(),
+                exec_command: None,
+                source: InstallationSource::Windows(WindowsInstallationSource::Portable),
+                executable_path,
+                scope: InstallScope::System,
+                arch: None,
+                is_default: false,
+            });
+        }
+    }
     browsers
 }
 
+/// Classifies a Scoop, Chocolatey or winget package name or a portable app directory,
+/// returning the browser it ships, its display name and the executable to look for.
+fn packaged_browser(
+    package: &str,
+) -> Option<(BrowserKind, BrowserChannel, &'static str, &'static str)> {
+    let package = package.to_lowercase();
+    let chromium_channel = || {
+        BrowserChannel::Chromium(if package.contains("beta") {
+            ChromiumChannel::Beta
+        } else if package.contains("dev") {
+            ChromiumChannel::Dev
+        } else if package.contains("canary") || package.contains("nightly") {
+            ChromiumChannel::Canary
+        } else {
+            ChromiumChannel::Stable
+        })
+    };
+
+    // Tor Browser ships firefox.exe, so it has to be recognized before Firefox.
+    let browser = if package.contains("tor") && package.contains("browser") {
+        (
+            BrowserKind::TorBrowser,
+            BrowserChannel::Single,
+            "Tor Browser",
+            "firefox.exe",
+        )
+    } else if package.contains("firefox") {
+        let channel = if package.contains("dev") {
+            FirefoxChannel::Dev
+        } else if package.contains("nightly") {
+            FirefoxChannel::Nightly
+        } else if package.contains("esr") {
+            FirefoxChannel::Esr
+        } else {
+            FirefoxChannel::Stable
+        };
+        (
+            BrowserKind::Firefox,
+            BrowserChannel::Firefox(channel),
+            "Mozilla Firefox",
+            "firefox.exe",
+        )
+    } else if package.contains("chrome") {
+        (
+            BrowserKind::Chrome,
+            chromium_channel(),
+            "Google Chrome",
+            "chrome.exe",
+        )
+    } else if package.contains("wavebox") {
+        (
+            BrowserKind::Wavebox,
+            BrowserChannel::Single,
+            "Wavebox",
+            "wavebox.exe",
+        )
+    } else if package.contains("sidekick") {
+        (
+            BrowserKind::Sidekick,
+            BrowserChannel::Single,
+            "Sidekick",
+            "sidekick.exe",
+        )
+    } else if package.contains("yandex") {
+        // Yandex installs its executable as a generic `browser.exe`.
+        (
+            BrowserKind::Yandex,
+            chromium_channel(),
+            "Yandex Browser",
+            "browser.exe",
+        )
+    } else if package.contains("ungoogled") {
+        (
+            BrowserKind::UngoogledChromium,
+            BrowserChannel::Single,
+            "Ungoogled Chromium",
+            "chrome.exe",
+        )
+    } else if package.contains("chromium") {
+        (
+            BrowserKind::Chromium,
+            BrowserChannel::Single,
+            "Chromium",
+            "chrome.exe",
+        )
+    } else if package.contains("edge") {
+        (
+            BrowserKind::Edge,
+            chromium_channel(),
+            "Microsoft Edge",
+            "msedge.exe",
+        )
+    } else if package.contains("brave") {
+        (BrowserKind::Brave, chromium_channel(), "Brave", "brave.exe")
+    } else if package.contains("vivaldi") {
+        (
+            BrowserKind::Vivaldi,
+            BrowserChannel::Chromium(channels::vendor_chromium_channel(&package)),
+            "Vivaldi",
+            "vivaldi.exe",
+        )
+    } else if package.contains("opera") {
+        (
+            BrowserKind::Opera,
+            BrowserChannel::Chromium(channels::vendor_chromium_channel(&package)),
+            "Opera",
+            "opera.exe",
+        )
+    } else if package.contains("librewolf") {
+        (
+            BrowserKind::LibreWolf,
+            BrowserChannel::Single,
+            "LibreWolf",
+            "librewolf.exe",
+        )
+    } else if package.contains("thorium") {
+        // Thorium ships separate builds per CPU instruction set.
+        let display = if package.contains("avx2") {
+            "Thorium AVX2"
+        } else if package.contains("avx") {
+            "Thorium AVX"
+        } else if package.contains("sse4") {
+            "Thorium SSE4"
+        } else if package.contains("sse3") {
+            "Thorium SSE3"
+        } else {
+            "Thorium"
+        };
+        (
+            BrowserKind::Thorium,
+            BrowserChannel::Single,
+            display,
+            "thorium.exe",
+        )
+    } else if package.contains("palemoon") || package.contains("pale moon") {
+        (
+            BrowserKind::PaleMoon,
+            BrowserChannel::Single,
+            "Pale Moon",
+            "palemoon.exe",
+        )
+    } else if package.contains("basilisk") {
+        (
+            BrowserKind::Basilisk,
+            BrowserChannel::Single,
+            "Basilisk",
+            "basilisk.exe",
+        )
+    } else if package.contains("floorp") {
+        let (channel, display) = if package.contains("beta") {
+            (FirefoxChannel::Beta, "Floorp Beta")
+        } else {
+            (FirefoxChannel::Stable, "Floorp")
+        };
+        (
+            BrowserKind::Floorp,
+            BrowserChannel::Firefox(channel),
+            display,
+            "floorp.exe",
+        )
+    } else if package.contains("falkon") {
+        (
+            BrowserKind::Falkon,
+            BrowserChannel::Single,
+            "Falkon",
+            "falkon.exe",
+        )
+    } else if package.contains("qutebrowser") {
+        (
+            BrowserKind::Qutebrowser,
+            BrowserChannel::Single,
+            "qutebrowser",
+            "qutebrowser.exe",
+        )
+    } else if package.contains("zen-browser") || package.starts_with("zen") {
+        let (channel, display) = if package.contains("twilight") {
+            (FirefoxChannel::Twilight, "Zen Twilight")
+        } else {
+            (FirefoxChannel::Stable, "Zen Browser")
+        };
+        (
+            BrowserKind::Zen,
+            BrowserChannel::Firefox(channel),
+            display,
+            "zen.exe",
+        )
+    } else if package.contains("waterfox") {
+        (
+            BrowserKind::Waterfox,
+            BrowserChannel::Single,
+            "Waterfox",
+            "waterfox.exe",
+        )
+    } else {
+        return None;
+    };
+    Some(browser)
+}
+
+/// Looks for `name` in `dir` and up to `depth` levels of subdirectories.
+fn find_executable(dir: &Path, name: &str, depth: usize) -> Option<PathBuf> {
+    let candidate = dir.join(name);
+    if candidate.is_file() {
+        return Some(candidate);
+    }
+    if depth == 0 {
+        return None;
+    }
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .find_map(|entry| find_executable(&entry.path(), name, depth - 1))
+}
+
+/// Whether an application registered itself for `scheme` under `HKEY_CLASSES_ROOT`.
+pub fn has_scheme_handler<F: FileSystem>(_fs: &F, scheme: &str) -> bool {
+    RegKey::predef(HKEY_CLASSES_ROOT)
+        .open_subkey(scheme)
+        .and_then(|key| key.get_value::<String, _>("URL Protocol"))
+        .is_ok()
+}
+
+pub fn detect_mail_clients<F: FileSystem>(_fs: &F) -> Vec<MailClient> {
+    let mut clients = Vec::new();
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+
+    let search_path = "SOFTWARE\\Clients\\Mail";
+
+    for (key, per_user) in [(&hklm, false), (&hkcu, true)] {
+        if let Ok(mail_clients) = key.open_subkey(search_path) {
+            for client_name in mail_clients.enum_keys().filter_map(Result::ok) {
+                if let Some(client) = create_mail_client(key, search_path, &client_name, per_user) {
+                    clients.push(client);
+                }
+            }
+        }
+    }
+
+    clients
+}
+
+fn create_mail_client(
+    base_key: &RegKey,
+    search_path: &str,
+    client_name: &str,
+    per_user: bool,
+) -> Option<MailClient> {
+    let reg_path = format!("{}\\{}", search_path, client_name);
+    let client_key = base_key.open_subkey(&reg_path).ok()?;
+
+    let display_name: String = client_key
+        .get_value("")
+        .unwrap_or_else(|_| client_name.to_string());
+
+    // Prefer the mailto protocol command, which takes the URL as "%1".
+    let command: String = client_key
+        .open_subkey("Protocols\\mailto\\shell\\open\\command")
+        .or_else(|_| client_key.open_subkey("shell\\open\\command"))
+        .ok()?
+        .get_value("")
+        .ok()?;
+    let executable_path = parse_command_path(&command)?;
+    let source = detect_source_for_browser(&executable_path, per_user);
+
+    Some(MailClient {
+        kind: MailClientKind::from_token(client_name),
+        display_name,
+        executable_path,
+        unique_id: reg_path,
+        exec_command: Some(command),
+        source,
+    })
+}
+
+pub fn launch_mail(client: &MailClient, urls: &[String]) -> Result<LaunchOutcome, LaunchError> {
+    if urls.is_empty() {
+        return Err(LaunchError::NoUrls);
+    }
+
+    let exec = &client.executable_path;
+    let mut args: Vec<String> = client
+        .exec_command
+        .as_deref()
+        .map(registered_command_args)
+        .unwrap_or_default();
+    if let Some(position) = args.iter().position(|arg| arg == "%1") {
+        args.splice(position..=position, urls.iter().cloned());
+    } else {
+        args.extend(urls.iter().cloned());
+    }
+
+    let mut command = Command::new(exec);
+    command.args(&args);
+    command.stdin(Stdio::null());
+    command.stdout(Stdio::null());
+    command.stderr(Stdio::null());
+
+    debug!(program = %exec.display(), args = ?args, "Launching mail client");
+    let child = command.spawn()?;
+
+    Ok(LaunchOutcome {
+        browser: None,
+        system_default: None,
+        command: LaunchCommand {
+            program: exec.clone(),
+            display: format!("{} {}", exec.display(), args.join(" ")),
+            args,
+            is_system_default: false,
+        },
+        reused_existing: false,
+        pid: Some(child.id()),
+    })
+}
+
+/// Arguments of a registered shell command, without the executable.
+fn registered_command_args(command: &str) -> Vec<String> {
+    let trimmed = command.trim();
+    let rest = if let Some(quoted) = trimmed.strip_prefix('"') {
+        quoted.split_once('"').map(|(_, rest)| rest).unwrap_or("")
+    } else {
+        trimmed.split_once(' ').map(|(_, rest)| rest).unwrap_or("")
+    };
+    rest.split_whitespace()
+        .map(|arg| arg.trim_matches('"').to_string())
+        .collect()
+}
+
+fn wide(value: &OsStr) -> Vec<u16> {
+    value.encode_wide().chain(std::iter::once(0)).collect()
+}
+
+/// Reads the product version from the executable's version resource.
+///
+/// Prefers the `ProductVersion` string (e.g. Firefox reports `121.0.1` there) and falls back
+/// to the numeric product version of `VS_FIXEDFILEINFO`.
+fn file_version(path: &Path) -> Option<String> {
+    let file_name = wide(path.as_os_str());
+    unsafe {
+        let size = GetFileVersionInfoSizeW(file_name.as_ptr(), std::ptr::null_mut());
+        if size == 0 {
+            return None;
+        }
+        let mut data = vec![0u8; size as usize];
+        if GetFileVersionInfoW(file_name.as_ptr(), 0, size, data.as_mut_ptr().cast()) == 0 {
+            return None;
+        }
+        let block = data.as_ptr().cast::<c_void>();
+
+        let query = |sub_block: &str| -> Option<(*const c_void, u32)> {
+            let sub_block = wide(OsStr::new(sub_block));
+            let mut buffer = std::ptr::null_mut();
+            let mut len = 0u32;
+            (VerQueryValueW(block, sub_block.as_ptr(), &mut buffer, &mut len) != 0
+                && !buffer.is_null()
+                && len > 0)
+                .then_some((buffer.cast_const(), len))
+        };
+
+        // Each translation is a (language, code page) pair of u16 values.
+        let string_version = query("\\VarFileInfo\\Translation").and_then(|(buffer, _)| {
+            let translation = buffer.cast::<u16>();
+            let (language, code_page) = (*translation, *translation.add(1));
+            let (value, len) = query(&format!(
+                "\\StringFileInfo\\{:04x}{:04x}\\ProductVersion",
+                language, code_page
+            ))?;
+            let chars = std::slice::from_raw_parts(value.cast::<u16>(), len as usize);
+            let version = String::from_utf16_lossy(chars)
+                .trim_end_matches('\0')
+                .trim()
+                .to_string();
+            (!version.is_empty()).then_some(version)
+        });
+
+        string_version.or_else(|| {
+            let (buffer, len) = query("\\")?;
+            if (len as usize) < std::mem::size_of::<VS_FIXEDFILEINFO>() {
+                return None;
+            }
+            fixed_product_version(&buffer.cast::<VS_FIXEDFILEINFO>().read_unaligned())
+        })
+    }
+}
+
+const VS_FFI_SIGNATURE: u32 = 0xFEEF_04BD;
+
+/// Formats the numeric product version as `major.minor.build.revision`.
+fn fixed_product_version(info: &VS_FIXEDFILEINFO) -> Option<String> {
+    (info.dwSignature == VS_FFI_SIGNATURE).then(|| {
+        format!(
+            "{}.{}.{}.{}",
+            info.dwProductVersionMS >> 16,
+            info.dwProductVersionMS & 0xffff,
+            info.dwProductVersionLS >> 16,
+            info.dwProductVersionLS & 0xffff
+        )
+    })
+}
+
+/// Key that identifies one executable regardless of how the registry spells its path.
+///
+/// Commands may use environment variables, 8.3 short names (`C:\PROGRA~1\...`), forward
+/// slashes or different casing for the same file. Canonicalizing resolves short names to
+/// long ones when the file exists.
+fn executable_key(path: &Path) -> String {
+    let expanded = PathBuf::from(expand_env_vars(&path.to_string_lossy()));
+    let resolved = std::fs::canonicalize(&expanded).unwrap_or(expanded);
+    normalize_path_key(&resolved.to_string_lossy())
+}
+
+fn normalize_path_key(path: &str) -> String {
+    let path = path.strip_prefix(r"\\?\").unwrap_or(path);
+    path.replace('/', "\\")
+        .trim_end_matches('\\')
+        .to_lowercase()
+}
+
+/// Expands `%NAME%` references; unknown variables are left as they are.
+fn expand_env_vars(value: &str) -> String {
+    let mut result = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('%') {
+        let Some(len) = rest[start + 1..].find('%') else {
+            break;
+        };
+        let name = &rest[start + 1..start + 1 + len];
+        result.push_str(&rest[..start]);
+        match std::env::var(name) {
+            Ok(expanded) if !name.is_empty() => result.push_str(&expanded),
+            _ => result.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// `per_user` is set for registrations found under `HKEY_CURRENT_USER`.
+fn detect_source_for_browser(executable_path: &Path, per_user: bool) -> InstallationSource {
+    let expanded = PathBuf::from(expand_env_vars(&executable_path.to_string_lossy()));
+    InstallationSource::Windows(sources::windows_source_for_executable(&expanded, per_user))
+}
+
 fn create_browser_info(
     base_key: &RegKey,
     search_path: &str,
     client_name: &str,
+    per_user: bool,
 ) -> Option<BrowserInfo> {
     let reg_path = format!("{}\\{}", search_path, client_name);
     let client_key = base_key.open_subkey(&reg_path).ok()?;
@@ -177,8 +932,10 @@ fn create_browser_info(
 
     let executable_path = parse_command_path(&command_path)?;
 
-    // Version detection is complex, requires reading file properties.
-    let version = None;
+    let version = file_version(&PathBuf::from(expand_env_vars(
+        &executable_path.to_string_lossy(),
+    )));
+    let source = detect_source_for_browser(&executable_path, per_user);
 
     Some(BrowserInfo {
         kind,
@@ -188,6 +945,10 @@ fn create_browser_info(
         version,
         unique_id: reg_path,
         exec_command: Some(command_path),
+        source,
+        scope: InstallScope::System,
+        arch: None,
+        is_default: false,
     })
 }
 
@@ -264,18 +1025,55 @@ fn parse_client_name(
         };
         (BrowserKind::Edge, BrowserChannel::Chromium(channel))
     } else if name.contains("brave") || client.contains("brave") {
+        let channel = channels::vendor_chromium_channel(&format!("{} {}", name, client));
+        (BrowserKind::Brave, BrowserChannel::Chromium(channel))
+    } else if name.contains("vivaldi") || client.contains("vivaldi") {
+        let channel = channels::vendor_chromium_channel(&format!("{} {}", name, client));
+        (BrowserKind::Vivaldi, BrowserChannel::Chromium(channel))
+    } else if name.contains("opera") || client.contains("opera") {
+        let channel = channels::vendor_chromium_channel(&format!("{} {}", name, client));
+        (BrowserKind::Opera, BrowserChannel::Chromium(channel))
+    } else if name.contains("librewolf") || client.contains("librewolf") {
+        (BrowserKind::LibreWolf, BrowserChannel::Single)
+    } else if name.contains("duckduckgo") || client.contains("duckduckgo") {
+        (BrowserKind::DuckDuckGo, BrowserChannel::Single)
+    } else if name.contains("wavebox") || client.contains("wavebox") {
+        (BrowserKind::Wavebox, BrowserChannel::Single)
+    } else if name.contains("sidekick") || client.contains("sidekick") {
+        (BrowserKind::Sidekick, BrowserChannel::Single)
+    } else if name.contains("yandex") || client.contains("yandex") {
         let channel = if name.contains("beta") || client.contains("beta") {
             ChromiumChannel::Beta
-        } else if name.contains("dev") || client.contains("dev") {
-            ChromiumChannel::Dev
-        } else if name.contains("nightly") || client.contains("nightly") {
-            ChromiumChannel::Canary
         } else {
             ChromiumChannel::Stable
         };
-        (BrowserKind::Brave, BrowserChannel::Chromium(channel))
-    } else if name.contains("vivaldi") || client.contains("vivaldi") {
-        (BrowserKind::Vivaldi, BrowserChannel::Single)
+        (BrowserKind::Yandex, BrowserChannel::Chromium(channel))
+    } else if name.contains("ungoogled") || client.contains("ungoogled") {
+        (BrowserKind::UngoogledChromium, BrowserChannel::Single)
+    } else if name.contains("thorium") || client.contains("thorium") {
+        (BrowserKind::Thorium, BrowserChannel::Single)
+    } else if name.contains("pale moon") || client.contains("palemoon") {
+        (BrowserKind::PaleMoon, BrowserChannel::Single)
+    } else if name.contains("basilisk") || client.contains("basilisk") {
+        (BrowserKind::Basilisk, BrowserChannel::Single)
+    } else if name.contains("floorp") || client.contains("floorp") {
+        let channel = if name.contains("beta") || client.contains("beta") {
+            FirefoxChannel::Beta
+        } else {
+            FirefoxChannel::Stable
+        };
+        (BrowserKind::Floorp, BrowserChannel::Firefox(channel))
+    } else if name.contains("falkon") || client.contains("falkon") {
+        (BrowserKind::Falkon, BrowserChannel::Single)
+    } else if name.contains("qutebrowser") || client.contains("qutebrowser") {
+        (BrowserKind::Qutebrowser, BrowserChannel::Single)
+    } else if name.starts_with("zen") || client.starts_with("zen") {
+        let channel = if name.contains("twilight") || client.contains("twilight") {
+            FirefoxChannel::Twilight
+        } else {
+            FirefoxChannel::Stable
+        };
+        (BrowserKind::Zen, BrowserChannel::Firefox(channel))
     } else if client.contains("tor") || name.contains("tor") {
         (BrowserKind::TorBrowser, BrowserChannel::Single)
     } else {
@@ -313,7 +1111,10 @@ fn browser_info_for_prog_id(prog_id: &str) -> Option<BrowserInfo> {
                 let reg_path = format!("{}\\{}", SEARCH_PATH, client_name);
                 if let Ok(client_key) = base.open_subkey(&reg_path) {
                     if client_matches_prog_id(&client_key, prog_id) {
-                        if let Some(info) = create_browser_info(&base, SEARCH_PATH, &client_name) {
+                        let per_user = hive == HKEY_CURRENT_USER;
+                        if let Some(info) =
+                            create_browser_info(&base, SEARCH_PATH, &client_name, per_user)
+                        {
                             return Some(info);
                         }
                     }
@@ -433,6 +1234,30 @@ where
     for token in tokens {
         let lowered = token.to_ascii_lowercase();
 
+        if lowered.contains("thorium") {
+            return Some(BrowserKind::Thorium);
+        }
+
+        if lowered.contains("duckduckgo") {
+            return Some(BrowserKind::DuckDuckGo);
+        }
+
+        if lowered.contains("wavebox") {
+            return Some(BrowserKind::Wavebox);
+        }
+
+        if lowered.contains("sidekick") {
+            return Some(BrowserKind::Sidekick);
+        }
+
+        if lowered.contains("yandex") {
+            return Some(BrowserKind::Yandex);
+        }
+
+        if lowered.contains("ungoogled") {
+            return Some(BrowserKind::UngoogledChromium);
+        }
+
         if lowered.contains("chromium") {
             return Some(BrowserKind::Chromium);
         }
@@ -457,6 +1282,38 @@ where
             return Some(BrowserKind::Vivaldi);
         }
 
+        if lowered.contains("opera") {
+            return Some(BrowserKind::Opera);
+        }
+
+        if lowered.contains("librewolf") {
+            return Some(BrowserKind::LibreWolf);
+        }
+
+        if lowered.contains("palemoon") {
+            return Some(BrowserKind::PaleMoon);
+        }
+
+        if lowered.contains("basilisk") {
+            return Some(BrowserKind::Basilisk);
+        }
+
+        if lowered.contains("floorp") {
+            return Some(BrowserKind::Floorp);
+        }
+
+        if lowered.contains("falkon") {
+            return Some(BrowserKind::Falkon);
+        }
+
+        if lowered.contains("qutebrowser") {
+            return Some(BrowserKind::Qutebrowser);
+        }
+
+        if lowered.starts_with("zen") {
+            return Some(BrowserKind::Zen);
+        }
+
         if lowered.contains("tor") {
             return Some(BrowserKind::TorBrowser);
         }
@@ -464,3 +1321,165 @@ where
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_keys_ignore_spelling_differences() {
+        assert_eq!(
+            normalize_path_key(r"C:\Program Files\Google\Chrome\Application\chrome.exe"),
+            normalize_path_key(r"\\?\c:\program files\google\chrome\application\CHROME.EXE"),
+        );
+        assert_eq!(
+            normalize_path_key("C:/Program Files/Mozilla Firefox/firefox.exe"),
+            r"c:\program files\mozilla firefox\firefox.exe"
+        );
+    }
+
+    #[test]
+    fn uninstall_icons_yield_executable_paths() {
+        assert_eq!(
+            uninstall_icon_path(
+                r#""C:\Users\me\AppData\Local\BraveSoftware\Brave-Browser\Application\brave.exe",0"#
+            ),
+            PathBuf::from(
+                r"C:\Users\me\AppData\Local\BraveSoftware\Brave-Browser\Application\brave.exe"
+            )
+        );
+        assert_eq!(
+            uninstall_icon_path(r"C:\Program Files\Mozilla Firefox\firefox.exe,-1"),
+            PathBuf::from(r"C:\Program Files\Mozilla Firefox\firefox.exe")
+        );
+        assert_eq!(
+            uninstall_icon_path(r"C:\Program Files\Vivaldi\vivaldi.exe"),
+            PathBuf::from(r"C:\Program Files\Vivaldi\vivaldi.exe")
+        );
+    }
+
+    #[test]
+    fn env_vars_expand_in_registry_paths() {
+        std::env::set_var("PATHWAY_TEST_DIR", r"C:\Users\me\AppData\Local");
+        assert_eq!(
+            expand_env_vars(r"%PATHWAY_TEST_DIR%\Vivaldi\Application\vivaldi.exe"),
+            r"C:\Users\me\AppData\Local\Vivaldi\Application\vivaldi.exe"
+        );
+        assert_eq!(expand_env_vars("100% sure"), "100% sure");
+        assert_eq!(
+            expand_env_vars(r"%PATHWAY_UNSET_VAR%\x.exe"),
+            r"%PATHWAY_UNSET_VAR%\x.exe"
+        );
+    }
+
+    #[test]
+    fn configured_registry_keys_are_split() {
+        assert_eq!(
+            split_registry_key(r"HKLM\SOFTWARE\Corp\Browsers"),
+            Some((false, r"SOFTWARE\Corp\Browsers"))
+        );
+        assert_eq!(
+            split_registry_key(r"HKEY_CURRENT_USER\Software\Corp\"),
+            Some((true, r"Software\Corp"))
+        );
+        assert_eq!(split_registry_key(r"HKCR\http"), None);
+        assert_eq!(split_registry_key("HKLM"), None);
+    }
+
+    #[test]
+    fn package_names_identify_browsers() {
+        let kind = |package: &str| {
+            packaged_browser(package).map(|(kind, channel, _, exe)| (kind, channel, exe))
+        };
+        assert_eq!(
+            kind("tor-browser"),
+            Some((
+                BrowserKind::TorBrowser,
+                BrowserChannel::Single,
+                "firefox.exe"
+            ))
+        );
+        assert_eq!(
+            kind("Mozilla.Firefox.DeveloperEdition_Microsoft.Winget.Source_8wekyb3d8bbwe"),
+            Some((
+                BrowserKind::Firefox,
+                BrowserChannel::Firefox(FirefoxChannel::Dev),
+                "firefox.exe"
+            ))
+        );
+        assert_eq!(
+            kind("googlechrome-beta"),
+            Some((
+                BrowserKind::Chrome,
+                BrowserChannel::Chromium(ChromiumChannel::Beta),
+                "chrome.exe"
+            ))
+        );
+        assert_eq!(
+            kind("ungoogled-chromium"),
+            Some((
+                BrowserKind::UngoogledChromium,
+                BrowserChannel::Single,
+                "chrome.exe"
+            ))
+        );
+        assert_eq!(
+            kind("chromium"),
+            Some((BrowserKind::Chromium, BrowserChannel::Single, "chrome.exe"))
+        );
+        assert_eq!(
+            kind("GoogleChromePortable"),
+            Some((
+                BrowserKind::Chrome,
+                BrowserChannel::Chromium(ChromiumChannel::Stable),
+                "chrome.exe"
+            ))
+        );
+        assert_eq!(
+            kind("Zen-Team.Zen-Browser.Twilight_Microsoft.Winget.Source_8wekyb3d8bbwe"),
+            Some((
+                BrowserKind::Zen,
+                BrowserChannel::Firefox(FirefoxChannel::Twilight),
+                "zen.exe"
+            ))
+        );
+        assert_eq!(
+            kind("Alex313031.Thorium.AVX2_Microsoft.Winget.Source_8wekyb3d8bbwe"),
+            Some((BrowserKind::Thorium, BrowserChannel::Single, "thorium.exe"))
+        );
+        assert_eq!(kind("7zip"), None);
+    }
+
+    #[test]
+    fn fixed_product_versions_are_formatted() {
+        let mut info = VS_FIXEDFILEINFO {
+            dwProductVersionMS: (120 << 16) | 1,
+            dwProductVersionLS: (6099 << 16) | 129,
+            ..Default::default()
+        };
+        assert_eq!(fixed_product_version(&info), None);
+
+        info.dwSignature = VS_FFI_SIGNATURE;
+        assert_eq!(
+            fixed_product_version(&info).as_deref(),
+            Some("120.1.6099.129")
+        );
+    }
+
+    #[test]
+    fn portable_dirs_merge_configured_application_dirs() {
+        let home = Path::new(r"C:\Users\me");
+        assert_eq!(
+            portable_dirs_with(&[PathBuf::from(r"D:\PortableApps")], Some(home)),
+            [
+                PathBuf::from(r"D:\PortableApps"),
+                PathBuf::from(r"C:\Users\me\PortableApps")
+            ]
+        );
+        assert_eq!(
+            portable_dirs_with(&[home.join("PortableApps")], Some(home)),
+            [PathBuf::from(r"C:\Users\me\PortableApps")]
+        );
+        assert!(portable_dirs_with(&[], None).is_empty());
+    }
+}
@@ -0,0 +1,412 @@
+use crate::browser::BrowserInfo;
+use crate::filesystem::FileSystem;
+use crate::serve::DEFAULT_IDLE_TIMEOUT_SECS;
+use crate::url::{
+    CredentialHandling, DirectoryHandling, NativeApp, NormalizationOptions, RewriteRule, RouteRule,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Environment variable that overrides the config file location.
+pub const CONFIG_ENV: &str = "PATHWAY_CONFIG";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Failed to read config file {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid config file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+/// User configuration loaded from `config.toml`.
+///
+/// ```toml
+/// [urls]
+/// allowed_schemes = ["zoommtg", "slack", "spotify"]
+/// strip_tracking = true
+/// data_url_types = ["text/html", "image/*"]
+/// directories = "index"
+/// native_apps = ["zoom", "teams"]
+/// credentials = "block"
+///
+/// [urls.normalize]
+/// strip_fragment = true
+/// sort_query = true
+///
+/// [[rewrite]]
+/// host = "www.reddit.com"
+/// set_host = "old.reddit.com"
+///
+/// [[route]]
+/// host = "*.github.com"
+/// browser = "firefox"
+///
+/// [mail]
+/// client = "thunderbird"
+///
+/// [detection]
+/// timeout_ms = 5000
+/// desktop_file_dirs = ["/opt/corp/share/applications"]
+///
+/// [stats]
+/// launches = true
+///
+/// [backup]
+/// dir = "/mnt/backups/pathway"
+/// keep = 10
+///
+/// [serve]
+/// idle_timeout_secs = 600
+///
+/// [chrome]
+/// default_profile = "work"
+///
+/// [chrome.profiles]
+/// work = "Profile 3"
+/// ```
+// Unknown top-level keys are still rejected: they must parse as a `BrowserConfig` table.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub urls: UrlConfig,
+    pub mail: MailConfig,
+    pub detection: DetectionConfig,
+    pub stats: StatsConfig,
+    pub backup: BackupConfig,
+    pub serve: ServeConfig,
+    /// URL rewrite rules, from `[[rewrite]]` tables
+    pub rewrite: Vec<RewriteRule>,
+    /// Browsers for URLs on given hosts, from `[[route]]` tables
+    pub route: Vec<RouteRule>,
+    /// Per-browser settings, keyed by browser token such as `chrome` or `firefox-beta`
+    #[serde(flatten)]
+    pub browsers: BTreeMap<String, BrowserConfig>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UrlConfig {
+    /// Non-web schemes handed to the OS handler registered for them
+    pub allowed_schemes: Vec<String>,
+    /// Remove tracking parameters from web URLs before launching them
+    pub strip_tracking: bool,
+    /// Parameters removed by `strip_tracking` instead of the built-in list; a trailing `*`
+    /// matches a prefix (e.g. "utm_*")
+    pub tracking_params: Option<Vec<String>>,
+    /// Open the link inside Outlook Safe Links, Google `/url` and similar redirect wrappers
+    pub unwrap_redirects: bool,
+    /// Media types of `data:` URLs that are opened, sanitized (e.g. "text/html", "image/*")
+    pub data_url_types: Vec<String>,
+    /// Largest decoded `data:` URL payload in bytes (2 MiB by default)
+    pub max_data_url_bytes: Option<usize>,
+    /// Longest accepted URL in bytes (32767 by default); `data:` URLs are exempt
+    pub max_url_length: Option<usize>,
+    /// What `file://` URLs of directories open: "browser" (its listing), "index" (their
+    /// index.html), "file-manager" or "refuse"
+    pub directories: DirectoryHandling,
+    /// What URLs with a user name or password do: "warn", "strip" (the credentials) or "block"
+    pub credentials: CredentialHandling,
+    /// Rewrites that make equivalent URLs normalize alike
+    pub normalize: NormalizationOptions,
+    /// Apps ("zoom", "spotify", "teams", "figma") that open their web links when installed
+    pub native_apps: Vec<NativeApp>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MailConfig {
+    /// Mail client for `mailto:` URLs (e.g. "thunderbird"); the OS handler when unset
+    pub client: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatsConfig {
+    /// Count launches per browser and profile, shown by `profile list --stats`
+    pub launches: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackupConfig {
+    /// Where `profile backup` writes to (defaults to pathway/backups in the local data directory)
+    pub dir: Option<PathBuf>,
+    /// Backups kept per profile; older ones are removed
+    pub keep: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        BackupConfig {
+            dir: None,
+            keep: DEFAULT_BACKUP_KEEP,
+        }
+    }
+}
+
+pub const DEFAULT_BACKUP_KEEP: usize = 5;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServeConfig {
+    /// Seconds without requests after which a `launch --serve` server stops
+    pub idle_timeout_secs: u64,
+}
+
+impl Default for ServeConfig {
+    fn default() -> Self {
+        ServeConfig {
+            idle_timeout_secs: DEFAULT_IDLE_TIMEOUT_SECS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BrowserConfig {
+    /// Profile (or alias) used when a launch names no profile
+    pub default_profile: Option<String>,
+    /// Profile aliases: a stable name mapped to this machine's profile name or directory
+    pub profiles: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DetectionConfig {
+    /// Time budget for browser detection in milliseconds; 0 waits for detection to finish
+    pub timeout_ms: u64,
+    /// Extra directories with `.desktop` files (Linux)
+    pub desktop_file_dirs: Vec<PathBuf>,
+    /// Extra folders scanned for application bundles (macOS), AppImages (Linux) or portable browsers (Windows)
+    pub application_dirs: Vec<PathBuf>,
+    /// Extra registry keys laid out like `HKLM\SOFTWARE\Clients\StartMenuInternet` (Windows)
+    pub registry_keys: Vec<String>,
+    /// Also look for well-known browser executables on `PATH` (Linux)
+    pub scan_path: bool,
+}
+
+impl Default for DetectionConfig {
+    fn default() -> Self {
+        DetectionConfig {
+            timeout_ms: DEFAULT_DETECTION_TIMEOUT_MS,
+            desktop_file_dirs: Vec::new(),
+            application_dirs: Vec::new(),
+            registry_keys: Vec::new(),
+            scan_path: false,
+        }
+    }
+}
+
+pub const DEFAULT_DETECTION_TIMEOUT_MS: u64 = 10_000;
+
+impl Config {
+    /// Default location: `<config dir>/pathway/config.toml`.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs_next::config_dir().map(|dir| dir.join("pathway").join("config.toml"))
+    }
+
+    /// Loads the config from `explicit`, `$PATHWAY_CONFIG`, or the default location.
+    ///
+    /// A missing file at the default location yields the default config; a missing file that
+    /// was named explicitly is an error.
+    pub fn load<F: FileSystem>(explicit: Option<&Path>, fs: &F) -> Result<Self, ConfigError> {
+        let named = explicit
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::var_os(CONFIG_ENV).map(PathBuf::from));
+
+        match named {
+            Some(path) => Self::load_from(&path, fs),
+            None => match Self::default_path() {
+                Some(path) if fs.exists(&path) => Self::load_from(&path, fs),
+                _ => Ok(Self::default()),
+            },
+        }
+    }
+
+    /// The profile that the alias `name` stands for with `browser`, if any.
+    ///
+    /// Aliases under a channel-specific token (`[chrome-beta.profiles]`) win over those
+    /// under the browser name (`[chrome.profiles]`).
+    pub fn profile_alias(&self, browser: &BrowserInfo, name: &str) -> Option<&str> {
+        self.browser_configs(browser)
+            .find_map(|config| config.profiles.get(name))
+            .map(String::as_str)
+    }
+
+    /// The profile launches with `browser` use when they name none, with the same precedence
+    /// as aliases.
+    pub fn default_profile(&self, browser: &BrowserInfo) -> Option<&str> {
+        self.browser_configs(browser)
+            .find_map(|config| config.default_profile.as_deref())
+    }
+
+    /// Settings that apply to `browser`, the channel-specific ones first.
+    fn browser_configs(&self, browser: &BrowserInfo) -> impl Iterator<Item = &BrowserConfig> {
+        let alias = browser.alias();
+        let mut matching: Vec<(&String, &BrowserConfig)> = self
+            .browsers
+            .iter()
+            .filter(|(token, _)| browser.matches_token(token, None))
+            .collect();
+        matching.sort_by_key(|(token, _)| **token != alias);
+        matching.into_iter().map(|(_, config)| config)
+    }
+
+    pub fn load_from<F: FileSystem>(path: &Path, fs: &F) -> Result<Self, ConfigError> {
+        let content = fs
+            .read_to_string(path)
+            .map_err(|source| ConfigError::Read {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        toml::from_str(&content).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filesystem::MockFileSystem;
+
+    #[test]
+    fn parses_allowed_schemes() {
+        let mut mock_fs = MockFileSystem::new();
+        mock_fs
+            .expect_read_to_string()
+            .returning(|_| Ok("[urls]\nallowed_schemes = [\"zoommtg\", \"slack\"]\n".to_string()));
+
+        let config = Config::load_from(Path::new("/config.toml"), &mock_fs).unwrap();
+        assert_eq!(config.urls.allowed_schemes, vec!["zoommtg", "slack"]);
+        assert_eq!(config.mail.client, None);
+        assert!(!config.stats.launches);
+        assert_eq!(config.backup.keep, DEFAULT_BACKUP_KEEP);
+        assert_eq!(config.detection.timeout_ms, DEFAULT_DETECTION_TIMEOUT_MS);
+        assert_eq!(config.urls.directories, DirectoryHandling::Browser);
+        assert_eq!(config.urls.credentials, CredentialHandling::Warn);
+    }
+
+    #[test]
+    fn parses_extra_detection_roots() {
+        let mut mock_fs = MockFileSystem::new();
+        mock_fs.expect_read_to_string().returning(|_| {
+            Ok(r#"
+                [detection]
+                desktop_file_dirs = ["/opt/corp/applications"]
+                registry_keys = ['HKLM\SOFTWARE\Corp\Browsers']
+                scan_path = true
+            "#
+            .to_string())
+        });
+
+        let config = Config::load_from(Path::new("/config.toml"), &mock_fs).unwrap();
+        assert_eq!(
+            config.detection.desktop_file_dirs,
+            vec![PathBuf::from("/opt/corp/applications")]
+        );
+        assert!(config.detection.application_dirs.is_empty());
+        assert!(config.detection.scan_path);
+        assert_eq!(
+            config.detection.registry_keys,
+            vec![r"HKLM\SOFTWARE\Corp\Browsers"]
+        );
+        assert_eq!(config.detection.timeout_ms, DEFAULT_DETECTION_TIMEOUT_MS);
+    }
+
+    #[test]
+    fn resolves_profile_aliases_per_browser() {
+        use crate::browser::channels::{BrowserChannel, ChromiumChannel};
+        use crate::browser::{BrowserKind, InstallScope, InstallationSource};
+
+        let mut mock_fs = MockFileSystem::new();
+        mock_fs.expect_read_to_string().returning(|_| {
+            Ok(r#"
+                [chrome]
+                default_profile = "work"
+
+                [chrome.profiles]
+                work = "Profile 3"
+                personal = "Default"
+
+                [chrome-beta]
+                default_profile = "personal"
+
+                [chrome-beta.profiles]
+                work = "Profile 1"
+            "#
+            .to_string())
+        });
+        let config = Config::load_from(Path::new("/config.toml"), &mock_fs).unwrap();
+
+        let mut chrome = BrowserInfo {
+            kind: BrowserKind::Chrome,
+            channel: BrowserChannel::Chromium(ChromiumChannel::Stable),
+            display_name: "Google Chrome".to_string(),
+            executable_path: PathBuf::from("/usr/bin/google-chrome"),
+            version: None,
+            unique_id: "google-chrome.desktop".to_string(),
+            exec_command: None,
+            source: InstallationSource::Unknown,
+            scope: InstallScope::System,
+            arch: None,
+            is_default: false,
+        };
+        assert_eq!(config.profile_alias(&chrome, "work"), Some("Profile 3"));
+        assert_eq!(config.profile_alias(&chrome, "Work"), None);
+        assert_eq!(config.default_profile(&chrome), Some("work"));
+
+        chrome.channel = BrowserChannel::Chromium(ChromiumChannel::Beta);
+        chrome.display_name = "Google Chrome Beta".to_string();
+        assert_eq!(config.profile_alias(&chrome, "work"), Some("Profile 1"));
+        assert_eq!(config.profile_alias(&chrome, "personal"), Some("Default"));
+        assert_eq!(config.default_profile(&chrome), Some("personal"));
+
+        chrome.kind = BrowserKind::Brave;
+        assert_eq!(config.profile_alias(&chrome, "work"), None);
+        assert_eq!(config.default_profile(&chrome), None);
+    }
+
+    #[test]
+    fn parses_url_handling_options() {
+        let mut mock_fs = MockFileSystem::new();
+        mock_fs.expect_read_to_string().returning(|_| {
+            Ok(
+                "[urls]\ndirectories = \"file-manager\"\n\n[urls.normalize]\nsort_query = true\n"
+                    .to_string(),
+            )
+        });
+
+        let config = Config::load_from(Path::new("/config.toml"), &mock_fs).unwrap();
+        assert_eq!(config.urls.directories, DirectoryHandling::FileManager);
+        assert!(config.urls.normalize.sort_query);
+        assert!(!config.urls.normalize.strip_fragment);
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        let mut mock_fs = MockFileSystem::new();
+        mock_fs
+            .expect_read_to_string()
+            .returning(|_| Ok("[urls]\nallowed_scheme = [\"slack\"]\n".to_string()));
+
+        let result = Config::load_from(Path::new("/config.toml"), &mock_fs);
+        assert!(matches!(result, Err(ConfigError::Parse { .. })));
+
+        let mut mock_fs = MockFileSystem::new();
+        mock_fs
+            .expect_read_to_string()
+            .returning(|_| Ok("timeout_ms = 5000\n".to_string()));
+        let result = Config::load_from(Path::new("/config.toml"), &mock_fs);
+        assert!(matches!(result, Err(ConfigError::Parse { .. })));
+    }
+}
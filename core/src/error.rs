@@ -14,11 +14,45 @@ pub enum PathwayError {
     #[error("File not found: {0}")]
     FileNotFound(String),
 
+    #[error("File URL points to a directory: {0}")]
+    Directory(String),
+
+    #[error("URL contains credentials: {0}")]
+    EmbeddedCredentials(String),
+
     #[error("Failed to canonicalize path: {0}")]
     CanonicalizationError(#[from] std::io::Error),
 
     #[error("URL parse error: {0}")]
     UrlParseError(#[from] url::ParseError),
+
+    #[error("URL of {length} bytes exceeds the limit of {limit} bytes")]
+    UrlTooLong { length: usize, limit: usize },
+
+    #[error("URL contains a control character: {0}")]
+    ControlCharacter(String),
+
+    #[error("URL contains an escaped UTF-16 surrogate: {0}")]
+    UnpairedSurrogate(String),
+}
+
+impl PathwayError {
+    /// A stable name of the error for scripts, reported as `error_code` in JSON output.
+    pub fn code(&self) -> &'static str {
+        match self {
+            PathwayError::InvalidUrl(_) => "invalid_url",
+            PathwayError::UnsupportedScheme(_) => "unsupported_scheme",
+            PathwayError::PathTraversal(_) => "path_traversal",
+            PathwayError::FileNotFound(_) => "file_not_found",
+            PathwayError::Directory(_) => "directory",
+            PathwayError::EmbeddedCredentials(_) => "embedded_credentials",
+            PathwayError::CanonicalizationError(_) => "canonicalization_failed",
+            PathwayError::UrlParseError(_) => "url_parse_error",
+            PathwayError::UrlTooLong { .. } => "url_too_long",
+            PathwayError::ControlCharacter(_) => "control_character",
+            PathwayError::UnpairedSurrogate(_) => "unpaired_surrogate",
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, PathwayError>;
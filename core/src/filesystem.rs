@@ -1,4 +1,4 @@
-use std::io;
+use std::io::{self, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 
 #[cfg(test)]
@@ -25,11 +25,17 @@ pub trait FileSystem {
     /// Read the entire contents of a file into a string
     fn read_to_string(&self, path: &Path) -> io::Result<String>;
 
+    /// Read up to `len` bytes of a file starting at `offset`; fewer at the end of the file
+    fn read_range(&self, path: &Path, offset: u64, len: usize) -> io::Result<Vec<u8>>;
+
     /// Canonicalize a path, returning the absolute form with all components resolved
     fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
 
     /// Get metadata for a file or directory
     fn metadata(&self, path: &Path) -> io::Result<std::fs::Metadata>;
+
+    /// List the paths of the entries in a directory
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
 }
 
 /// Real file system implementation that delegates to std::fs
@@ -61,6 +67,14 @@ impl FileSystem for RealFileSystem {
         std::fs::read_to_string(path)
     }
 
+    fn read_range(&self, path: &Path, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+        let mut file = std::fs::File::open(path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut contents = Vec::with_capacity(len);
+        file.take(len as u64).read_to_end(&mut contents)?;
+        Ok(contents)
+    }
+
     fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
         path.canonicalize()
     }
@@ -68,6 +82,12 @@ impl FileSystem for RealFileSystem {
     fn metadata(&self, path: &Path) -> io::Result<std::fs::Metadata> {
         std::fs::metadata(path)
     }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        std::fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -1,17 +1,31 @@
 pub mod browser;
+pub mod config;
 pub mod error;
 pub mod filesystem;
 pub mod logging;
 pub mod profile;
+pub mod serve;
+pub mod shortcut;
+#[cfg(feature = "expand-urls")]
+pub mod shortlinks;
 pub mod url;
 
+pub use browser::mail::{detect_mail_clients, launch_mail, MailClient, MailClientKind};
 pub use browser::{
-    detect_inventory, launch, launch_with_profile, BrowserChannel, BrowserInfo, BrowserInventory,
-    BrowserKind, LaunchCommand, LaunchError, LaunchOutcome, LaunchTarget, SystemDefaultBrowser,
+    compare_browsers, configure_detection, detect_inventory, detect_inventory_of_kind,
+    detect_inventory_sorted_by, detect_inventory_within, launch, launch_all, launch_with_profile,
+    BrowserChannel, BrowserInfo, BrowserInventory, BrowserKind, InstallScope, InstallationSource,
+    LaunchCommand, LaunchError, LaunchJob, LaunchOutcome, LaunchTarget, SystemDefaultBrowser,
 };
+pub use config::Config;
 pub use error::{PathwayError, Result};
 pub use profile::{
-    validate_profile_options, ProfileInfo, ProfileManager, ProfileOptions, ProfileType,
+    validate_profile_options, ArcSpace, EdgeWorkspace, ProfileEnv, ProfileIdentity, ProfileInfo,
+    ProfileManager, ProfileOptions, ProfileType, SandboxTool, TempProfile, VivaldiWorkspace,
     WindowOptions,
 };
-pub use url::{validate_url, ValidatedUrl, ValidationStatus};
+pub use url::{
+    rewrite_url, route_url, validate_url, validate_url_with_options, AppLink, CredentialHandling,
+    DirectoryHandling, NativeApp, NormalizationOptions, RewriteRule, RouteRule, ValidatedUrl,
+    ValidationOptions, ValidationStatus,
+};
@@ -1,16 +1,39 @@
 use clap::{Parser, ValueEnum};
-use pathway::browser::{default_channel_priority, BrowserChannel};
+use pathway::browser::instance;
+use pathway::browser::mail::find_mail_client;
+use pathway::browser::watch::inventory_changes;
+use pathway::browser::{default_channel_priority, has_scheme_handler, BrowserChannel};
 use pathway::filesystem::RealFileSystem;
+use pathway::profile::archive as profile_archive;
+use pathway::profile::backup::{self as profile_backup, default_backup_dir};
+use pathway::profile::extensions::{profile_extensions, Extension};
+use pathway::profile::launches::{
+    launch_counts_path, read_launch_counts, record_launch, LaunchCount,
+};
+use pathway::profile::timestamp::Timestamp;
+use pathway::profile::usage::{profile_usage, ProfileUsage};
+use pathway::profile::ProfileError;
+use pathway::serve::FileServer;
+use pathway::shortcut::{app_icon, create_shortcut, default_shortcut_dir, Shortcut};
+use pathway::url::DEFAULT_TRACKING_PARAMS;
 use pathway::{
-    detect_inventory, launch_with_profile, logging, validate_profile_options, validate_url,
-    BrowserInfo, BrowserInventory, LaunchCommand, LaunchTarget, ProfileInfo, ProfileManager,
-    ProfileOptions, ProfileType, SystemDefaultBrowser, ValidatedUrl, ValidationStatus,
-    WindowOptions,
+    configure_detection, detect_inventory, detect_inventory_of_kind, detect_inventory_within,
+    detect_mail_clients, launch, launch_all, launch_mail, launch_with_profile, logging,
+    rewrite_url, route_url, validate_profile_options, validate_url_with_options, ArcSpace,
+    BrowserInfo, BrowserInventory, BrowserKind, Config, CredentialHandling, EdgeWorkspace,
+    InstallScope, InstallationSource, LaunchCommand, LaunchJob, LaunchTarget, MailClient,
+    NativeApp, ProfileInfo, ProfileManager, ProfileOptions, ProfileType, RewriteRule, RouteRule,
+    SandboxTool, SystemDefaultBrowser, TempProfile, ValidatedUrl, ValidationOptions,
+    ValidationStatus, WindowOptions,
 };
 use serde::Serialize;
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::process;
-use tracing::{error, info, warn};
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+use url::Url;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about = "URL routing agent for Pathway", long_about = None)]
@@ -23,10 +46,20 @@ struct Args {
     #[arg(long, value_enum, default_value = "human", global = true)]
     format: OutputFormat,
 
+    /// Config file (defaults to $PATHWAY_CONFIG, then pathway/config.toml in the config directory)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Time budget for browser detection in milliseconds (0 waits indefinitely)
+    #[arg(long, value_name = "MS", global = true)]
+    detection_timeout: Option<u64>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+// Parsed once per run, so the size of `Launch` does not matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Parser, Debug)]
 enum Commands {
     /// Open URLs in browsers
@@ -42,6 +75,10 @@ enum Commands {
         #[arg(short = 'c', long, conflicts_with_all = ["system_default", "no_system_default"])]
         channel: Option<String>,
 
+        /// Pick the copy of the browser whose path contains this text (e.g. "/Users/")
+        #[arg(long, value_name = "TEXT", requires = "browser")]
+        path_contains: Option<String>,
+
         /// Use system default browser
         #[arg(long, conflicts_with_all = ["browser"])]
         system_default: bool,
@@ -61,6 +98,49 @@ enum Commands {
         /// Validate URLs but don't launch
         #[arg(long, alias = "dry-run")]
         no_launch: bool,
+
+        /// Launch each browser and profile concurrently, reporting the outcome of every URL
+        #[arg(long)]
+        parallel: bool,
+
+        /// URL validation options
+        #[command(flatten)]
+        url_options: UrlArgs,
+
+        /// Open local files through a temporary server on 127.0.0.1 instead of file://
+        #[arg(long)]
+        serve: bool,
+
+        /// Seconds without requests before the --serve server stops (see `idle_timeout_secs`
+        /// under [serve] in the config)
+        #[arg(long, value_name = "SECS", requires = "serve")]
+        serve_idle_timeout: Option<u64>,
+
+        /// Mail client for mailto: URLs (e.g. "thunderbird"); defaults to the OS handler
+        #[arg(long)]
+        mail_client: Option<String>,
+    },
+
+    /// Validate and normalize URLs without launching anything. Exits with 1 when a URL is
+    /// invalid and 2 when the input cannot be read
+    Validate {
+        /// URLs to validate; read from stdin when none are given and stdin is not a terminal
+        urls: Vec<String>,
+
+        /// Also validate the URLs in this file, one per line ("-" reads stdin); blank lines
+        /// and lines starting with # are skipped
+        #[arg(short, long, value_name = "PATH")]
+        file: Option<PathBuf>,
+
+        /// URL validation options
+        #[command(flatten)]
+        url_options: UrlArgs,
+    },
+
+    /// Manage mail clients used for mailto: URLs
+    Mail {
+        #[command(subcommand)]
+        action: MailAction,
     },
 
     /// Manage browsers
@@ -69,6 +149,30 @@ enum Commands {
         action: BrowserAction,
     },
 
+    /// Delete a temporary profile once the browser using it has closed
+    #[command(hide = true)]
+    DiscardProfile { path: PathBuf },
+
+    /// Serve a file or directory on 127.0.0.1 for `launch --serve`, printing the port and token
+    #[command(hide = true)]
+    ServeFiles {
+        path: PathBuf,
+
+        #[arg(long, value_name = "SECS")]
+        idle_timeout: u64,
+    },
+
+    /// Remove temporary profiles left behind by --temp-profile launches
+    Cleanup {
+        /// Only remove profiles unused for at least this many hours
+        #[arg(long, value_name = "HOURS", default_value_t = 24)]
+        older_than: u64,
+
+        /// Delete the profiles instead of only listing them
+        #[arg(short, long)]
+        yes: bool,
+    },
+
     /// Manage browser profiles
     Profile {
         /// Browser to manage profiles for (e.g. "chrome", "firefox-dev")
@@ -86,6 +190,12 @@ enum Commands {
         #[command(subcommand)]
         action: ProfileAction,
     },
+
+    /// Create desktop shortcuts that open a browser with a profile
+    Shortcut {
+        #[command(subcommand)]
+        action: ShortcutAction,
+    },
 }
 
 #[derive(Parser, Debug)]
@@ -110,17 +220,202 @@ enum BrowserAction {
         #[arg(short = 'c', long)]
         channel: Option<String>,
     },
+    /// Rescan for browsers periodically and report installs, removals and updates
+    ///
+    /// Each rescan is a full detection; directories and the registry are not watched for
+    /// changes, so a change shows up at the next rescan.
+    Watch {
+        /// Seconds between rescans
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+}
+
+#[derive(Parser, Debug)]
+enum ShortcutAction {
+    /// Create a shortcut that runs `pathway launch` with a browser and profile
+    Create {
+        /// Browser to open (e.g. "chrome", "firefox-dev")
+        #[arg(short, long)]
+        browser: String,
+        /// Browser channel (e.g. "stable", "beta", "dev")
+        #[arg(short = 'c', long)]
+        channel: Option<String>,
+        /// Profile to open
+        #[arg(short, long)]
+        profile: Option<String>,
+        /// Name of the shortcut (defaults to "<Browser> (<profile>)")
+        #[arg(long)]
+        name: Option<String>,
+        /// Directory to put the shortcut in (defaults to the applications menu on Linux,
+        /// ~/Applications on macOS and the Start Menu on Windows)
+        #[arg(long, value_name = "DIR")]
+        output: Option<PathBuf>,
+        /// Icon of the shortcut: an image file (`.icns` on macOS, `.ico` on Windows) or, on
+        /// Linux, an icon name (defaults to the browser's icon)
+        #[arg(long, value_name = "ICON")]
+        icon: Option<String>,
+    },
+}
+
+#[derive(Parser, Debug)]
+enum MailAction {
+    /// List detected mail clients
+    List,
 }
 
 #[derive(Parser, Debug)]
 enum ProfileAction {
     /// List available profiles
-    List,
+    List {
+        /// Show how much disk space each profile uses
+        #[arg(long)]
+        sizes: bool,
+        /// Show how often each profile was launched through pathway (see `[stats]` in the
+        /// config)
+        #[arg(long)]
+        stats: bool,
+    },
     /// Show detailed information about a profile
     Info {
         /// Profile name to show info for
         name: String,
+        /// List the extensions installed in the profile
+        #[arg(long)]
+        extensions: bool,
+    },
+    /// Open a new browser window with a profile
+    Open {
+        /// Profile to open
+        name: String,
+        /// Page to show instead of the browser's start page
+        #[arg(long, value_name = "URL")]
+        start_page: Option<String>,
+    },
+    /// Create a new profile (Chromium-based browsers only)
+    Create {
+        /// Display name of the new profile
+        name: String,
+        /// Open the browser with the new profile once it is created
+        #[arg(long)]
+        launch: bool,
+    },
+    /// Delete a profile and its directory
+    Delete {
+        /// Profile name to delete
+        name: String,
+        /// Do not ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Change the display name of a profile
+    Rename {
+        /// Profile to rename
+        name: String,
+        /// New display name
+        new_name: String,
+    },
+    /// Copy a profile, with its logins and settings, into a new profile
+    Clone {
+        /// Profile to copy
+        source: String,
+        /// Display name of the copy
+        name: String,
+    },
+    /// Package a profile into an archive for another machine
+    Export {
+        /// Profile to export
+        name: String,
+        /// Archive to write (e.g. profile.tar.zst)
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    /// Archive a profile into the backup directory, keeping a limited number of backups
+    Backup {
+        /// Profile to back up
+        name: String,
+        /// Backup directory (defaults to `dir` under [backup] in the config, then
+        /// pathway/backups in the local data directory)
+        #[arg(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+        /// Number of backups to keep for the profile (defaults to `keep` under [backup], 5)
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        keep: Option<u64>,
     },
+    /// Create a profile from an archive written by `profile export`
+    Import {
+        /// Archive to read
+        archive: PathBuf,
+        /// Display name of the imported profile (defaults to the archived one)
+        #[arg(long)]
+        name: Option<String>,
+    },
+}
+
+#[derive(Parser, Debug)]
+struct UrlArgs {
+    /// Hand URLs with this non-web scheme (e.g. "zoommtg") to the OS handler (repeatable)
+    #[arg(long = "allow-scheme", value_name = "SCHEME")]
+    allow_schemes: Vec<String>,
+
+    /// Accept data: URLs of this media type (e.g. "text/html", "image/*"), with scripts
+    /// removed (repeatable)
+    #[arg(long = "allow-data-type", value_name = "MEDIA TYPE")]
+    allow_data_types: Vec<String>,
+
+    /// Remove tracking parameters such as utm_* and fbclid (see `strip_tracking` in the
+    /// config)
+    #[arg(long)]
+    strip_tracking: bool,
+
+    /// Open the link inside redirect wrappers such as Outlook Safe Links (see
+    /// `unwrap_redirects` in the config)
+    #[arg(long)]
+    unwrap_redirects: bool,
+
+    /// Resolve bit.ly, t.co and similar short links with HEAD requests before validation
+    /// (requires the `expand-urls` feature)
+    #[arg(long)]
+    expand_short_urls: bool,
+
+    /// Reject URLs with a user name or password (see `credentials` in the config)
+    #[arg(long)]
+    block_credentials: bool,
+}
+
+impl UrlArgs {
+    /// The validation options of the config, extended by these flags.
+    fn validation_options(self, config: &Config) -> ValidationOptions {
+        let mut allowed_schemes = config.urls.allowed_schemes.clone();
+        allowed_schemes.extend(self.allow_schemes);
+        let mut data_url_types = config.urls.data_url_types.clone();
+        data_url_types.extend(self.allow_data_types);
+        let strip_params = if self.strip_tracking || config.urls.strip_tracking {
+            config.urls.tracking_params.clone().unwrap_or_else(|| {
+                DEFAULT_TRACKING_PARAMS
+                    .iter()
+                    .map(|param| param.to_string())
+                    .collect()
+            })
+        } else {
+            Vec::new()
+        };
+        ValidationOptions {
+            allowed_schemes,
+            strip_params,
+            unwrap_redirects: self.unwrap_redirects || config.urls.unwrap_redirects,
+            data_url_types,
+            max_data_url_bytes: config.urls.max_data_url_bytes,
+            max_url_length: config.urls.max_url_length,
+            directories: config.urls.directories,
+            normalization: config.urls.normalize,
+            credentials: if self.block_credentials {
+                CredentialHandling::Block
+            } else {
+                config.urls.credentials
+            },
+        }
+    }
 }
 
 #[derive(Parser, Debug)]
@@ -130,17 +425,25 @@ struct ProfileArgs {
     #[arg(long, conflicts_with_all = ["temp_profile", "guest"])]
     profile: Option<String>,
 
+    /// Use the profile signed in with this account email (Chromium only)
+    #[arg(long, value_name = "EMAIL", conflicts_with_all = ["profile", "temp_profile", "guest"])]
+    profile_email: Option<String>,
+
     /// Use custom user data directory
     #[arg(long, conflicts_with_all = ["temp_profile", "guest"])]
     user_dir: Option<PathBuf>,
 
     /// Create temporary profile (deleted on exit)
-    #[arg(long, conflicts_with_all = ["profile", "user_dir", "guest"])]
+    #[arg(long, conflicts_with_all = ["profile", "profile_email", "user_dir", "guest"])]
     temp_profile: bool,
 
     /// Use guest profile (Chromium only)
-    #[arg(long, conflicts_with_all = ["profile", "user_dir", "temp_profile"])]
+    #[arg(long, conflicts_with_all = ["profile", "profile_email", "user_dir", "temp_profile"])]
     guest: bool,
+
+    /// Launch a temporary copy of this profile, deleted once the browser closes it
+    #[arg(long, value_name = "PROFILE")]
+    ephemeral_copy: Option<String>,
 }
 
 #[derive(Parser, Debug)]
@@ -156,6 +459,48 @@ struct WindowArgs {
     /// Kiosk mode (fullscreen, no UI)
     #[arg(long)]
     kiosk: bool,
+
+    /// Kiosk mode plus hardening flags (no print dialog, pinch zoom or swipe navigation; Chromium only)
+    #[arg(long)]
+    kiosk_strict: bool,
+
+    /// Open a private window with Tor (Brave only)
+    #[arg(long)]
+    tor_window: bool,
+
+    /// Run the browser inside a sandbox tool (Linux only)
+    #[arg(long, value_enum)]
+    sandbox: Option<SandboxArg>,
+
+    /// Launch a separate instance of the browser app (macOS only)
+    #[arg(long)]
+    new_instance: bool,
+
+    /// Arc Space to open the URLs in (macOS only)
+    #[arg(long, value_name = "NAME")]
+    arc_space: Option<String>,
+
+    /// Edge Workspace to open, by name or id
+    #[arg(long, value_name = "NAME")]
+    edge_workspace: Option<String>,
+
+    /// Window name for window manager rules (sets WM_CLASS on Linux, Chromium only)
+    #[arg(long, value_name = "NAME")]
+    window_name: Option<String>,
+
+    /// DevTools port of a running Chromium instance to open tabs in (requires the `cdp` feature)
+    #[arg(long, value_name = "PORT")]
+    cdp_port: Option<u16>,
+
+    /// Fail if the browser is already running with the selected profile
+    #[arg(long)]
+    require_new_instance: bool,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+enum SandboxArg {
+    Firejail,
+    Bwrap,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
@@ -195,6 +540,28 @@ struct LaunchJsonResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     command: Option<LaunchCommand>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    reused_existing: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    launches: Option<Vec<UrlLaunchJson>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    handed_off: Option<Vec<UrlLaunchJson>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct UrlLaunchJson {
+    url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    browser: Option<String>,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command: Option<LaunchCommand>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reused_existing: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     message: Option<String>,
 }
 
@@ -203,6 +570,14 @@ struct ListJsonResponse {
     action: &'static str,
     browsers: Vec<BrowserInfo>,
     system_default: SystemDefaultBrowser,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    detection_incomplete: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct MailListJsonResponse {
+    action: &'static str,
+    clients: Vec<MailClient>,
 }
 
 #[derive(Debug, Serialize)]
@@ -233,6 +608,20 @@ struct WindowOptionsJson {
     new_window: bool,
     incognito: bool,
     kiosk: bool,
+    kiosk_strict: bool,
+    tor_window: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sandbox: Option<SandboxTool>,
+    new_instance: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    arc_space: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    edge_workspace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    window_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cdp_port: Option<u16>,
+    require_new_instance: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -240,6 +629,16 @@ struct ListProfilesResponse {
     action: &'static str,
     browser: String,
     profiles: Vec<ProfileInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spaces: Option<Vec<ArcSpace>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    workspaces: Option<Vec<EdgeWorkspace>>,
+    /// Disk usage by profile name, with `--sizes`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<BTreeMap<String, ProfileUsage>>,
+    /// Launches through pathway by profile name, with `--stats`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    launches: Option<BTreeMap<String, LaunchCount>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -247,6 +646,45 @@ struct ProfileInfoResponse {
     action: &'static str,
     browser: String,
     profile: ProfileInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<ProfileUsage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    extensions: Option<Vec<Extension>>,
+}
+
+#[derive(Debug, Serialize)]
+struct ProfileArchiveResponse {
+    action: &'static str,
+    browser: String,
+    profile: ProfileInfo,
+    archive: PathBuf,
+    files: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warning: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ProfileBackupResponse {
+    action: &'static str,
+    browser: String,
+    profile: ProfileInfo,
+    archive: PathBuf,
+    files: usize,
+    pruned: Vec<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+struct CleanupResponse {
+    action: &'static str,
+    dry_run: bool,
+    profiles: Vec<TempProfile>,
+    removed: Vec<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+struct ConfigErrorResponse {
+    action: &'static str,
+    message: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -265,6 +703,13 @@ struct LaunchCommandParams {
     profile_args: ProfileArgs,
     window_args: WindowArgs,
     no_launch: bool,
+    parallel: bool,
+    validation: ValidationOptions,
+    expand_short_urls: bool,
+    /// Serve local files over HTTP, stopping after this long without requests
+    serve_idle_timeout: Option<Duration>,
+    mail_client: Option<String>,
+    config: Config,
     format: OutputFormat,
 }
 
@@ -326,7 +771,87 @@ fn main() {
         logging::setup_logging(args.verbose, false);
     }
 
-    let inventory = detect_inventory();
+    let config = match Config::load(args.config.as_deref(), &RealFileSystem) {
+        Ok(config) => config,
+        Err(err) => {
+            if args.format == OutputFormat::Human {
+                error!("{}", err);
+            } else {
+                let response = ConfigErrorResponse {
+                    action: "config",
+                    message: err.to_string(),
+                };
+                println!("{}", serde_json::to_string_pretty(&response).unwrap());
+            }
+            process::exit(1);
+        }
+    };
+
+    if let Commands::DiscardProfile { path } = &args.command {
+        if let Err(e) = ProfileManager::discard_temp_profile(path) {
+            error!("Failed to discard {}: {}", path.display(), e);
+            process::exit(1);
+        }
+        return;
+    }
+    if let Commands::ServeFiles { path, idle_timeout } = &args.command {
+        if let Err(e) = serve_files(path, Duration::from_secs(*idle_timeout)) {
+            error!("Failed to serve {}: {}", path.display(), e);
+            process::exit(1);
+        }
+        return;
+    }
+    if let Commands::Validate {
+        urls,
+        file,
+        url_options,
+    } = args.command
+    {
+        let expand = url_options.expand_short_urls;
+        let options = url_options.validation_options(&config);
+        process::exit(validate_command(
+            urls,
+            file.as_deref(),
+            &options,
+            expand,
+            &config.rewrite,
+            args.format,
+        ));
+    }
+    if let Commands::Cleanup { older_than, yes } = args.command {
+        cleanup_temp_profiles(
+            Duration::from_secs(older_than.saturating_mul(3600)),
+            yes,
+            args.format,
+        );
+        return;
+    }
+
+    let budget = Duration::from_millis(
+        args.detection_timeout
+            .unwrap_or(config.detection.timeout_ms),
+    );
+    configure_detection(config.detection.clone());
+    let budget = Some(budget).filter(|budget| !budget.is_zero());
+    let mut inventory = match &args.command {
+        Commands::Launch {
+            browser: Some(token),
+            channel,
+            system_default: false,
+            ..
+        } => targeted_inventory(token, channel.as_deref(), budget),
+        _ => detect_inventory_with_budget(None, budget),
+    };
+    if let Commands::Launch {
+        path_contains: Some(text),
+        ..
+    } = &args.command
+    {
+        retain_paths_containing(&mut inventory, text);
+    }
+    if inventory.detection_incomplete && args.format == OutputFormat::Human {
+        warn!("{}", DETECTION_INCOMPLETE_WARNING);
+    }
 
     match args.command {
         Commands::Launch {
@@ -338,7 +863,14 @@ fn main() {
             profile,
             window,
             no_launch,
+            parallel,
+            url_options,
+            serve,
+            serve_idle_timeout,
+            mail_client,
+            path_contains: _,
         } => {
+            let expand_short_urls = url_options.expand_short_urls;
             let params = LaunchCommandParams {
                 urls,
                 browser,
@@ -348,83 +880,445 @@ fn main() {
                 profile_args: profile,
                 window_args: window,
                 no_launch,
+                parallel,
+                validation: url_options.validation_options(&config),
+                expand_short_urls,
+                serve_idle_timeout: serve.then(|| {
+                    Duration::from_secs(
+                        serve_idle_timeout.unwrap_or(config.serve.idle_timeout_secs),
+                    )
+                }),
+                mail_client: mail_client.or(config.mail.client.clone()),
+                config,
                 format: args.format,
             };
             handle_launch_command(&inventory, params);
         }
+        Commands::Browser {
+            action: BrowserAction::Watch { interval },
+        } => {
+            watch_inventory(
+                inventory,
+                Duration::from_secs(interval),
+                budget,
+                args.format,
+            );
+        }
         Commands::Browser { action } => {
             handle_browser_command(&inventory, action, args.format, args.verbose);
         }
+        Commands::Mail { action } => {
+            handle_mail_command(action, args.format);
+        }
         Commands::Profile {
             browser,
             channel,
             user_dir,
             action,
         } => {
-            handle_profile_command(&inventory, browser, channel, user_dir, action, args.format);
+            handle_profile_command(
+                &inventory,
+                browser,
+                channel,
+                user_dir,
+                action,
+                &config,
+                args.format,
+            );
+        }
+        Commands::Shortcut { action } => {
+            handle_shortcut_command(&inventory, action, args.format);
+        }
+        Commands::Cleanup { .. }
+        | Commands::DiscardProfile { .. }
+        | Commands::ServeFiles { .. }
+        | Commands::Validate { .. } => {
+            unreachable!("handled before detection")
         }
     }
 }
 
-/// Validate a list of URL strings and return per-URL validation results plus a flag indicating
-/// whether any URL failed validation.
-///
-/// On success each entry is a `ValidatedUrl` (may include a non-fatal `warning`). On failure the
-/// corresponding `ValidatedUrl` will have `status == ValidationStatus::Invalid` and its `warning`
-/// will contain the validation error message. When `format == OutputFormat::Human` the function
-/// emits informational or error messages for each URL.
-///
-/// # Returns
-///
-/// A tuple `(Vec<ValidatedUrl>, bool)` where the boolean is `true` if any URL failed validation.
-///
-/// # Examples
-///
-/// ```
-/// let urls = vec![
-///     "https://example.com".to_string(),
-///     "not-a-url".to_string(),
-/// ];
-/// let (results, has_error) = validate_urls(&urls, OutputFormat::Json);
-/// assert_eq!(results.len(), 2);
-/// assert!(has_error);
-/// assert_eq!(results[0].status, ValidationStatus::Valid);
-/// assert_eq!(results[1].status, ValidationStatus::Invalid);
-/// ```
-fn validate_urls(urls: &[String], format: OutputFormat) -> (Vec<ValidatedUrl>, bool) {
-    let mut results = Vec::new();
-    let mut has_error = false;
+#[derive(Debug, Serialize)]
+struct ValidateResponse {
+    action: &'static str,
+    status: &'static str,
+    valid: usize,
+    invalid: usize,
+    urls: Vec<ValidatedUrl>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
 
-    for (index, url) in urls.iter().enumerate() {
-        match validate_url(url, &RealFileSystem) {
-            Ok(validated) => {
-                if format == OutputFormat::Human {
-                    if let Some(warning) = &validated.warning {
-                        info!(
-                            "URL {}: {} (scheme: {}) - WARNING: {}",
-                            index + 1,
-                            validated.normalized,
-                            validated.scheme,
-                            warning
-                        );
-                    } else {
-                        info!(
-                            "URL validated: {} (scheme: {})",
-                            validated.normalized, validated.scheme
-                        );
-                    }
-                }
-                results.push(validated);
-            }
-            Err(e) => {
-                has_error = true;
-                let invalid = ValidatedUrl {
+/// Runs `pathway validate` and returns its exit code: 0 when every URL is valid, 1 when one is
+/// not and 2 when the input cannot be read.
+fn validate_command(
+    mut urls: Vec<String>,
+    file: Option<&Path>,
+    options: &ValidationOptions,
+    expand: bool,
+    rules: &[RewriteRule],
+    format: OutputFormat,
+) -> i32 {
+    let input = match file {
+        Some(path) if path == Path::new("-") => Some(io::read_to_string(io::stdin())),
+        Some(path) => Some(std::fs::read_to_string(path)),
+        None if urls.is_empty() && !io::stdin().is_terminal() => {
+            Some(io::read_to_string(io::stdin()))
+        }
+        None => None,
+    };
+    match input {
+        Some(Ok(text)) => urls.extend(
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string),
+        ),
+        Some(Err(e)) => {
+            let message = format!("Failed to read URLs: {}", e);
+            if format == OutputFormat::Human {
+                error!("{}", message);
+            } else {
+                let response = ValidateResponse {
+                    action: "validate",
+                    status: "error",
+                    valid: 0,
+                    invalid: 0,
+                    urls: Vec::new(),
+                    message: Some(message),
+                };
+                println!("{}", serde_json::to_string_pretty(&response).unwrap());
+            }
+            return 2;
+        }
+        None => {}
+    }
+
+    let (mut results, has_error) = expand_and_validate_urls(&urls, options, expand, format);
+    apply_rewrite_rules(&mut results, rules, format);
+    let invalid = results
+        .iter()
+        .filter(|url| matches!(url.status, ValidationStatus::Invalid))
+        .count();
+    if format == OutputFormat::Json {
+        let response = ValidateResponse {
+            action: "validate",
+            status: if has_error { "invalid" } else { "valid" },
+            valid: results.len() - invalid,
+            invalid,
+            urls: results,
+            message: None,
+        };
+        println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    } else if results.is_empty() {
+        warn!("No URLs to validate");
+    } else {
+        info!("{} valid, {} invalid", results.len() - invalid, invalid);
+    }
+    i32::from(has_error)
+}
+
+/// Lists leftover temporary profiles older than `min_age` and, with `delete`, removes them.
+///
+/// Profiles a browser still has open are never removed.
+fn cleanup_temp_profiles(min_age: Duration, delete: bool, format: OutputFormat) {
+    let stale: Vec<TempProfile> = ProfileManager::find_temp_profiles()
+        .into_iter()
+        .filter(|profile| !profile.in_use && profile.age_secs >= min_age.as_secs())
+        .collect();
+
+    let mut removed = Vec::new();
+    let mut failed = false;
+    if delete {
+        for profile in &stale {
+            match std::fs::remove_dir_all(&profile.path) {
+                Ok(()) => removed.push(profile.path.clone()),
+                Err(e) => {
+                    failed = true;
+                    if format == OutputFormat::Human {
+                        error!("Failed to remove {}: {}", profile.path.display(), e);
+                    }
+                }
+            }
+        }
+    }
+
+    match format {
+        OutputFormat::Human => {
+            if stale.is_empty() {
+                eprintln!("No temporary profiles to clean up");
+            }
+            for profile in &stale {
+                let verb = if !delete {
+                    "Would remove"
+                } else if removed.contains(&profile.path) {
+                    "Removed"
+                } else {
+                    continue;
+                };
+                eprintln!(
+                    "{} {} ({} old, {})",
+                    verb,
+                    profile.path.display(),
+                    format_age(profile.age_secs),
+                    format_size(profile.size_bytes)
+                );
+            }
+            if !delete && !stale.is_empty() {
+                eprintln!("Run again with --yes to delete them");
+            }
+        }
+        OutputFormat::Json => {
+            let response = CleanupResponse {
+                action: "cleanup",
+                dry_run: !delete,
+                profiles: stale,
+                removed,
+            };
+            println!("{}", serde_json::to_string_pretty(&response).unwrap());
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn format_age(secs: u64) -> String {
+    match secs {
+        s if s >= 86_400 => format!("{}d", s / 86_400),
+        s if s >= 3600 => format!("{}h", s / 3600),
+        s => format!("{}m", s / 60),
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Rescans for browsers every `interval` and reports what changed since the previous rescan,
+/// one line per change. This is polling, not change notification: every rescan runs a full
+/// detection, and the inventory is only kept in memory for the next comparison.
+///
+/// JSON output is a stream of JSON Lines for tooling to follow; runs until interrupted.
+fn watch_inventory(
+    mut inventory: BrowserInventory,
+    interval: Duration,
+    budget: Option<Duration>,
+    format: OutputFormat,
+) {
+    if format == OutputFormat::Human {
+        eprintln!(
+            "Rescanning {} browsers every {}s for changes (Ctrl+C to stop)",
+            inventory.browsers.len(),
+            interval.as_secs()
+        );
+    }
+
+    loop {
+        std::thread::sleep(interval);
+        let current = detect_inventory_with_budget(None, budget);
+        // A detection cut short by the budget would report missing browsers as removed.
+        if current.detection_incomplete {
+            continue;
+        }
+
+        for event in inventory_changes(&inventory.browsers, &current.browsers) {
+            match format {
+                OutputFormat::Human => {
+                    let browser = event.browser();
+                    let version = browser
+                        .version
+                        .as_deref()
+                        .map(|version| format!(" {}", version))
+                        .unwrap_or_default();
+                    eprintln!(
+                        "{}: {} ({}) - {}{}",
+                        event.name(),
+                        browser.alias(),
+                        browser.channel.canonical_name(),
+                        browser.display_name,
+                        version
+                    );
+                }
+                OutputFormat::Json => println!("{}", serde_json::to_string(&event).unwrap()),
+            }
+        }
+        inventory = current;
+    }
+}
+
+/// [`validate_urls`], after expanding short links when `expand` is set. Expanded URLs keep the
+/// short link as `original` and list the hops in `redirect_chain`.
+fn expand_and_validate_urls(
+    urls: &[String],
+    options: &ValidationOptions,
+    expand: bool,
+    format: OutputFormat,
+) -> (Vec<ValidatedUrl>, bool) {
+    if !expand {
+        return validate_urls(urls, options, format);
+    }
+    let chains = expand_urls(&short_link_candidates(urls, options), format);
+    let expanded: Vec<String> = urls
+        .iter()
+        .zip(&chains)
+        .map(|(url, chain)| chain.last().unwrap_or(url).clone())
+        .collect();
+    let (mut results, has_error) = validate_urls(&expanded, options, format);
+    for ((result, url), chain) in results.iter_mut().zip(urls).zip(chains) {
+        if !chain.is_empty() {
+            result.original = url.clone();
+            result.redirect_chain = chain;
+        }
+    }
+    (results, has_error)
+}
+
+/// The URLs to try expanding: the normalized form of each valid URL, so that `bit.ly/abc`
+/// is looked up as `https://bit.ly/abc`.
+fn short_link_candidates(urls: &[String], options: &ValidationOptions) -> Vec<String> {
+    urls.iter()
+        .map(
+            |url| match validate_url_with_options(url, &RealFileSystem, options) {
+                Ok(validated) => validated.normalized,
+                Err(_) => url.clone(),
+            },
+        )
+        .collect()
+}
+
+/// Time allowed for each request while expanding a short link.
+#[cfg(feature = "expand-urls")]
+const SHORT_URL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Per URL, the redirects its short link resolves to, or nothing when it is no short link.
+/// A link that fails to resolve is kept as given.
+#[cfg(feature = "expand-urls")]
+fn expand_urls(urls: &[String], format: OutputFormat) -> Vec<Vec<String>> {
+    use pathway::shortlinks::expand_short_url;
+
+    urls.iter()
+        .map(|url| match expand_short_url(url, SHORT_URL_TIMEOUT) {
+            Ok(chain) => {
+                if let (Some(target), OutputFormat::Human) = (chain.last(), format) {
+                    info!("Expanded {} to {}", url, target);
+                }
+                chain
+            }
+            Err(e) => {
+                warn!("Could not expand {}: {}", url, e);
+                Vec::new()
+            }
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "expand-urls"))]
+fn expand_urls(urls: &[String], _format: OutputFormat) -> Vec<Vec<String>> {
+    warn!("--expand-short-urls requires pathway to be built with the `expand-urls` feature");
+    vec![Vec::new(); urls.len()]
+}
+
+/// Validate a list of URL strings and return per-URL validation results plus a flag indicating
+/// whether any URL failed validation.
+///
+/// On success each entry is a `ValidatedUrl` (may include a non-fatal `warning`). On failure the
+/// corresponding `ValidatedUrl` will have `status == ValidationStatus::Invalid` and its `warning`
+/// will contain the validation error message. When `format == OutputFormat::Human` the function
+/// emits informational or error messages for each URL.
+///
+/// # Returns
+///
+/// A tuple `(Vec<ValidatedUrl>, bool)` where the boolean is `true` if any URL failed validation.
+///
+/// # Examples
+///
+/// ```
+/// let urls = vec![
+///     "https://example.com".to_string(),
+///     "not-a-url".to_string(),
+/// ];
+/// let (results, has_error) = validate_urls(&urls, &ValidationOptions::default(), OutputFormat::Json);
+/// assert_eq!(results.len(), 2);
+/// assert!(has_error);
+/// assert_eq!(results[0].status, ValidationStatus::Valid);
+/// assert_eq!(results[1].status, ValidationStatus::Invalid);
+/// ```
+fn validate_urls(
+    urls: &[String],
+    options: &ValidationOptions,
+    format: OutputFormat,
+) -> (Vec<ValidatedUrl>, bool) {
+    let mut results = Vec::new();
+    let mut has_error = false;
+
+    for (index, url) in urls.iter().enumerate() {
+        match validate_url_with_options(url, &RealFileSystem, options) {
+            Ok(validated) => {
+                if format == OutputFormat::Human {
+                    if let Some(warning) = &validated.warning {
+                        info!(
+                            "URL {}: {} (scheme: {}) - WARNING: {}",
+                            index + 1,
+                            validated.normalized,
+                            validated.scheme,
+                            warning
+                        );
+                    } else {
+                        info!(
+                            "URL validated: {} (scheme: {})",
+                            validated.normalized, validated.scheme
+                        );
+                    }
+                    if let (Some(unicode), Some(ascii)) =
+                        (&validated.host_unicode, &validated.host_ascii)
+                    {
+                        if unicode != ascii {
+                            info!("Host: {} (punycode: {})", unicode, ascii);
+                        }
+                    }
+                    if let Some(wrapper) = validated.unwrapped_from.first() {
+                        info!("Unwrapped from redirect: {}", wrapper);
+                    }
+                    if !validated.removed_params.is_empty() {
+                        info!(
+                            "Removed tracking parameters: {}",
+                            validated.removed_params.join(", ")
+                        );
+                    }
+                }
+                results.push(validated);
+            }
+            Err(e) => {
+                has_error = true;
+                let invalid = ValidatedUrl {
                     original: url.clone(),
                     url: url.clone(),
                     normalized: url.clone(),
                     scheme: String::new(),
+                    host_unicode: None,
+                    host_ascii: None,
                     status: ValidationStatus::Invalid,
                     warning: Some(e.to_string()),
+                    error_code: Some(e.code().to_string()),
+                    removed_params: Vec::new(),
+                    unwrapped_from: Vec::new(),
+                    redirect_chain: Vec::new(),
+                    file_manager: false,
+                    app_link: None,
+                    rewritten_from: None,
                 };
                 results.push(invalid);
 
@@ -438,6 +1332,75 @@ fn validate_urls(urls: &[String], format: OutputFormat) -> (Vec<ValidatedUrl>, b
     (results, has_error)
 }
 
+/// Detect only the browser kind named by `token` when launching a specific browser.
+///
+/// Falls back to a full scan when the token names no known kind or the browser is missing, so
+/// the fallback choice and the "available browsers" warning still see everything.
+fn targeted_inventory(
+    token: &str,
+    channel: Option<&str>,
+    budget: Option<Duration>,
+) -> BrowserInventory {
+    let (kind_name, _) = split_browser_token(split_scope_suffix(token).0);
+    if let Some(kind) = BrowserKind::from_canonical_name(kind_name) {
+        let inventory = detect_inventory_with_budget(Some(kind), budget);
+        if select_browser(&inventory, Some(token), channel, false).is_some() {
+            return inventory;
+        }
+    }
+    detect_inventory_with_budget(None, budget)
+}
+
+/// Keeps only browsers whose executable path contains `text`, ignoring case, so one of several
+/// copies of the same browser can be picked.
+fn retain_paths_containing(inventory: &mut BrowserInventory, text: &str) {
+    let text = text.to_lowercase();
+    inventory.browsers.retain(|browser| {
+        browser
+            .executable_path
+            .to_string_lossy()
+            .to_lowercase()
+            .contains(&text)
+    });
+}
+
+/// Split an install scope suffix off a browser token like `chrome@system`.
+fn split_scope_suffix(token: &str) -> (&str, Option<&str>) {
+    match token.split_once('@') {
+        Some((token, scope)) => (token, Some(scope)),
+        None => (token, None),
+    }
+}
+
+/// Split a browser token like `chrome-canary` into its kind and channel names.
+///
+/// Kind names may contain dashes themselves (`ungoogled-chromium`), so a token that names a
+/// kind is never split.
+fn split_browser_token(token: &str) -> (&str, Option<&str>) {
+    if BrowserKind::from_canonical_name(token).is_some() {
+        return (token, None);
+    }
+    match token.rsplit_once('-') {
+        Some((kind, channel)) => (kind, Some(channel)),
+        None => (token, None),
+    }
+}
+
+const DETECTION_INCOMPLETE_WARNING: &str =
+    "Browser detection did not finish within its time budget; some browsers may be missing (raise --detection-timeout)";
+
+/// Detect browsers (only `kind`, when given), giving up after `budget` if one is set.
+fn detect_inventory_with_budget(
+    kind: Option<BrowserKind>,
+    budget: Option<Duration>,
+) -> BrowserInventory {
+    match (budget, kind) {
+        (Some(budget), kind) => detect_inventory_within(budget, kind),
+        (None, Some(kind)) => detect_inventory_of_kind(kind),
+        (None, None) => detect_inventory(),
+    }
+}
+
 /// Choose a BrowserInfo from the inventory unless the system default is requested.
 ///
 /// Returns:
@@ -461,6 +1424,23 @@ fn select_browser<'a>(
     browser_token: Option<&str>,
     channel_token: Option<&str>,
     system_default: bool,
+) -> Option<&'a BrowserInfo> {
+    select_browser_matching(
+        inventory,
+        browser_token,
+        channel_token,
+        system_default,
+        |_| true,
+    )
+}
+
+/// [`select_browser`] among the browsers for which `keep` returns true.
+fn select_browser_matching<'a>(
+    inventory: &'a BrowserInventory,
+    browser_token: Option<&str>,
+    channel_token: Option<&str>,
+    system_default: bool,
+    keep: impl Fn(&BrowserInfo) -> bool,
 ) -> Option<&'a BrowserInfo> {
     if system_default {
         return None;
@@ -471,15 +1451,17 @@ fn select_browser<'a>(
         return None;
     }
 
-    let mut candidates: Vec<&BrowserInfo> = inventory.browsers.iter().collect();
+    let mut candidates: Vec<&BrowserInfo> = inventory.browsers.iter().filter(|b| keep(b)).collect();
     let mut token_specified_channel = false;
 
     if let Some(token) = browser_token {
-        let (kind_str, channel_str) = if let Some((k, c)) = token.rsplit_once('-') {
-            (k, Some(c))
-        } else {
-            (token, None)
-        };
+        let (token, scope_str) = split_scope_suffix(token);
+        let (kind_str, channel_str) = split_browser_token(token);
+
+        // Filter by install scope, e.g. `chrome@system`
+        if let Some(scope_str) = scope_str {
+            candidates.retain(|b| b.scope.canonical_name() == scope_str);
+        }
 
         // Filter by kind
         candidates.retain(|b| b.kind.canonical_name() == kind_str);
@@ -504,6 +1486,8 @@ fn select_browser<'a>(
         candidates.sort_by(|a, b| {
             default_channel_priority(&a.channel)
                 .cmp(&default_channel_priority(&b.channel))
+                .then_with(|| b.runs_natively().cmp(&a.runs_natively()))
+                .then_with(|| a.scope.cmp(&b.scope))
                 .then_with(|| a.display_name.cmp(&b.display_name))
         });
     }
@@ -513,23 +1497,22 @@ fn select_browser<'a>(
 
 /// Validate profile/window CLI arguments and convert them to runtime options.
 ///
-/// Converts `ProfileArgs` and `WindowArgs` into `ProfileOptions` and `WindowOptions`,
+/// Converts `ProfileArgs` and `WindowArgs` into `ProfileOptions` and `WindowOptions`
+/// (resolving profile aliases from `config`),
 /// runs platform-specific validation when a concrete `browser` is provided, and
 /// collects any warnings produced during conversion or validation.
 ///
 /// Behavior:
 /// - If `browser` is `Some`, calls `validate_profile_options(browser, &profile_options, &window_options)`.
-///   Any warnings from that validation are appended to the returned warnings. Validation errors
-///   are logged with `error!` in Human format; in non-human formats the error message is added
-///   to the returned warnings.
+///   Any warnings from that validation are appended to the returned warnings; a validation error
+///   is returned for the caller to report.
 /// - If `browser` is `None` (system-default mode), profile- or window-related options that
 ///   require an explicit browser are not validated; instead a warning is produced for each such
 ///   option indicating that `--browser` is required. Warnings are also logged with `warn!` in
 ///   Human format.
 ///
 /// Returns a tuple `(ProfileOptions, WindowOptions, Vec<String>)` where the vector contains
-/// accumulated warning/error messages (strings). The function does not return a `Result` and
-/// never panics on validation failures — those are reported through logging and the warnings vector.
+/// accumulated warning messages, or the error of options that cannot work with the browser.
 ///
 /// # Examples
 ///
@@ -541,43 +1524,47 @@ fn select_browser<'a>(
 ///     None, // use system default browser
 ///     &profile_args,
 ///     &window_args,
+///     &Config::default(),
 ///     OutputFormat::Human,
-/// );
+/// )
+/// .unwrap();
 /// assert!(warnings.is_empty() || warnings.iter().all(|w| w.contains("--browser") || !w.is_empty()));
 /// ```
 fn validate_and_prepare_options(
     browser: Option<&BrowserInfo>,
     profile_args: &ProfileArgs,
     window_args: &WindowArgs,
+    config: &Config,
     format: OutputFormat,
-) -> (ProfileOptions, WindowOptions, Vec<String>) {
+) -> Result<(ProfileOptions, WindowOptions, Vec<String>), ProfileError> {
     let mut warnings = Vec::new();
-    let profile_options = convert_profile_args(profile_args, &mut warnings);
-    let window_options = convert_window_args(window_args);
+    let mut profile_options = convert_profile_args(profile_args, browser, config, &mut warnings)?;
+    let mut window_options = convert_window_args(window_args);
 
     if let Some(browser) = browser {
-        match validate_profile_options(browser, &profile_options, &window_options) {
-            Ok(profile_warnings) => {
-                if format == OutputFormat::Human {
-                    for warning in &profile_warnings {
-                        warn!("{}", warning);
-                    }
-                }
-                warnings.extend(profile_warnings);
-            }
-            Err(e) => {
-                if format == OutputFormat::Human {
-                    error!("Profile validation error: {}", e);
-                } else {
-                    warnings.push(format!("Profile validation error: {}", e));
-                }
+        ProfileManager::select_arc_space(browser, &mut profile_options, &mut window_options);
+        let profile_warnings =
+            validate_profile_options(browser, &profile_options, &window_options)?;
+        if format == OutputFormat::Human {
+            for warning in &profile_warnings {
+                warn!("{}", warning);
             }
         }
+        warnings.extend(profile_warnings);
     } else {
         // Validate system default limitations
         let has_profile_options = !matches!(profile_options.profile_type, ProfileType::Default);
-        let has_window_options =
-            window_options.new_window || window_options.incognito || window_options.kiosk;
+        let has_window_options = window_options.new_window
+            || window_options.incognito
+            || window_options.kiosk
+            || window_options.tor_window
+            || window_options.sandbox.is_some()
+            || window_options.new_instance
+            || window_options.arc_space.is_some()
+            || window_options.edge_workspace.is_some()
+            || window_options.window_name.is_some()
+            || window_options.cdp_port.is_some()
+            || window_options.require_new_instance;
 
         if has_profile_options {
             let warning = "Profile options require specifying a browser with --browser".to_string();
@@ -596,7 +1583,7 @@ fn validate_and_prepare_options(
         }
     }
 
-    (profile_options, window_options, warnings)
+    Ok((profile_options, window_options, warnings))
 }
 
 /// Handle the "launch" subcommand: validate URLs, resolve the target browser (or system default),
@@ -636,17 +1623,86 @@ fn handle_launch_command(inventory: &BrowserInventory, params: LaunchCommandPara
         profile_args,
         window_args,
         no_launch,
+        parallel,
+        validation,
+        expand_short_urls,
+        serve_idle_timeout,
+        mail_client,
+        config,
         format,
     } = params;
 
-    let (results, has_error) = validate_urls(&urls, format);
-    let normalized_urls: Vec<String> = results.iter().map(|url| url.normalized.clone()).collect();
+    let (mut results, has_error) =
+        expand_and_validate_urls(&urls, &validation, expand_short_urls, format);
 
     if has_error {
+        let normalized_urls: Vec<String> =
+            results.iter().map(|url| url.normalized.clone()).collect();
         handle_url_validation_error(&normalized_urls, &results, format);
         process::exit(1);
     }
 
+    apply_rewrite_rules(&mut results, &config.rewrite, format);
+    if let (Some(idle_timeout), false) = (serve_idle_timeout, no_launch) {
+        serve_local_files(&mut results, idle_timeout, format);
+    }
+    if !config.urls.native_apps.is_empty() {
+        open_in_native_apps(&mut results, &config.urls.native_apps, format);
+    }
+
+    // tel: URLs, URLs with allowed non-web schemes, directories meant for the file manager
+    // and links for native apps skip browser selection and go to their OS handler.
+    // mailto: URLs go to the configured mail client instead.
+    let mut passthrough_urls = Vec::new();
+    let mut normalized_urls = Vec::new();
+    for url in &results {
+        if url.is_passthrough() {
+            passthrough_urls.push(url);
+        } else {
+            normalized_urls.push(url.normalized.clone());
+        }
+    }
+
+    let mut handoff_warnings = Vec::new();
+    let has_mailto = passthrough_urls.iter().any(|url| url.is_mailto());
+    let mail_clients = if mail_client.is_some() && has_mailto {
+        detect_mail_clients()
+    } else {
+        Vec::new()
+    };
+    let selected_mail_client = match mail_client.as_deref() {
+        Some(name) if has_mailto => {
+            let found = find_mail_client(&mail_clients, name);
+            if found.is_none() {
+                let warning = format!(
+                    "Mail client '{}' not found. Using the system mail handler.",
+                    name
+                );
+                if format == OutputFormat::Human {
+                    warn!("{}", warning);
+                }
+                handoff_warnings.push(warning);
+            }
+            found
+        }
+        _ => None,
+    };
+
+    let handed_off = if no_launch {
+        Vec::new()
+    } else {
+        hand_off_urls(&passthrough_urls, selected_mail_client, format)
+    };
+    let handoff_failed = handed_off.iter().any(|launch| launch.status == "error");
+
+    if normalized_urls.is_empty() && !handed_off.is_empty() {
+        respond_handoffs_only(&results, &handed_off, &handoff_warnings, format);
+        if handoff_failed {
+            process::exit(1);
+        }
+        return;
+    }
+
     let mut selected_browser = select_browser(
         inventory,
         browser.as_deref(),
@@ -680,19 +1736,26 @@ fn handle_launch_command(inventory: &BrowserInventory, params: LaunchCommandPara
         is_fallback,
     );
 
-    let (profile_options, window_options, mut warnings) =
-        validate_and_prepare_options(selected_browser, &profile_args, &window_args, format);
+    let (profile_options, window_options, mut warnings) = match validate_and_prepare_options(
+        selected_browser,
+        &profile_args,
+        &window_args,
+        &config,
+        format,
+    ) {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            if format == OutputFormat::Human {
+                error!("{}", e);
+            } else {
+                print_launch_error_json(&normalized_urls, &results, &e.to_string());
+            }
+            process::exit(1);
+        }
+    };
 
     warnings.extend(additional_warnings);
-
-    let launch_target = if is_fallback {
-        // Use the fallback browser directly instead of system default
-        LaunchTarget::Browser(selected_browser.unwrap())
-    } else {
-        selected_browser
-            .map(LaunchTarget::Browser)
-            .unwrap_or(LaunchTarget::SystemDefault)
-    };
+    warnings.extend(handoff_warnings);
 
     if no_launch {
         let response_data = LaunchResponseData {
@@ -701,45 +1764,435 @@ fn handle_launch_command(inventory: &BrowserInventory, params: LaunchCommandPara
             normalized_urls: &normalized_urls,
             results: &results,
             warnings: &warnings,
+            handed_off: &handed_off,
             format,
         };
         handle_no_launch_response(&profile_options, &window_options, response_data);
         return;
     }
 
+    let mut profile_options = profile_options;
+    let mut ephemeral_copy = None;
+    if let (Some(browser), ProfileType::Named(source)) = (
+        selected_browser.filter(|_| profile_args.ephemeral_copy.is_some()),
+        &profile_options.profile_type,
+    ) {
+        match EphemeralCopy::create(browser, source) {
+            Ok(copy) => {
+                profile_options.profile_type = ProfileType::Temporary(copy.path.clone());
+                ephemeral_copy = Some(copy);
+            }
+            Err(e) => {
+                if format == OutputFormat::Human {
+                    error!("{}", e);
+                } else {
+                    print_launch_error_json(&normalized_urls, &results, &e.to_string());
+                }
+                process::exit(1);
+            }
+        }
+    }
+
+    // `[[route]]` rules only pick browsers for launches that name none
+    let rules: &[RouteRule] = if browser.is_none() && channel.is_none() && !system_default {
+        &config.route
+    } else {
+        &[]
+    };
+    let default_group = LaunchGroup {
+        browser: selected_browser,
+        profile_options: profile_options.clone(),
+        urls: Vec::new(),
+    };
+    let known_warnings = warnings.len();
+    let groups = group_launches(
+        inventory,
+        &results,
+        rules,
+        default_group,
+        &window_options,
+        &config,
+        &mut warnings,
+    );
+    if format == OutputFormat::Human {
+        warnings[known_warnings..]
+            .iter()
+            .for_each(|warning| warn!("{}", warning));
+    }
+
     let response_data = LaunchResponseData {
         selected_browser,
         inventory,
         normalized_urls: &normalized_urls,
         results: &results,
         warnings: &warnings,
+        handed_off: &handed_off,
         format,
     };
-    execute_launch_and_respond(
-        launch_target,
-        &profile_options,
-        &window_options,
-        response_data,
-    );
+    if let Some(copy) = &mut ephemeral_copy {
+        copy.launched = true;
+    }
+    let launched = match groups.as_slice() {
+        [group] if !parallel => execute_launch_and_respond(
+            group.target(),
+            &group.profile_options,
+            &window_options,
+            LaunchResponseData {
+                selected_browser: group.browser,
+                ..response_data
+            },
+        ),
+        _ => execute_grouped_launch_and_respond(
+            &groups,
+            parallel,
+            &profile_options,
+            &window_options,
+            response_data,
+        ),
+    };
+    for group in groups.iter().filter(|_| launched && config.stats.launches) {
+        if let Some(browser) = group.browser {
+            record_profile_launch(browser, &group.profile_options);
+        }
+    }
+    drop(ephemeral_copy);
+
+    if handoff_failed || !launched {
+        process::exit(1);
+    }
 }
 
-/// Response data for browser launch operations
-struct LaunchResponseData<'a> {
+/// Applies the `[[rewrite]]` rules of the config to the valid URLs among `results`.
+fn apply_rewrite_rules(results: &mut [ValidatedUrl], rules: &[RewriteRule], format: OutputFormat) {
+    if rules.is_empty() {
+        return;
+    }
+    for result in results
+        .iter_mut()
+        .filter(|result| matches!(result.status, ValidationStatus::Valid))
+    {
+        if rewrite_url(result, rules) && format == OutputFormat::Human {
+            info!(
+                "Rewrote {} to {}",
+                result.rewritten_from.as_deref().unwrap_or_default(),
+                result.normalized
+            );
+        }
+    }
+}
+
+/// Replaces the links of `apps` among `results` with their app links, so that they are handed
+/// to the app. Links of apps that are not installed stay with the browser.
+fn open_in_native_apps(results: &mut [ValidatedUrl], apps: &[NativeApp], format: OutputFormat) {
+    let mut installed = BTreeMap::new();
+    for result in results.iter_mut() {
+        let Some(link) = result
+            .app_link
+            .clone()
+            .filter(|link| apps.contains(&link.app))
+        else {
+            continue;
+        };
+        let scheme = link.app.scheme();
+        if !*installed
+            .entry(scheme)
+            .or_insert_with(|| has_scheme_handler(scheme))
+        {
+            if format == OutputFormat::Human {
+                info!(
+                    "{} is not installed; opening {} in the browser",
+                    link.app.display_name(),
+                    result.normalized
+                );
+            }
+            continue;
+        }
+        if format == OutputFormat::Human {
+            info!(
+                "Opening {} in {}",
+                result.normalized,
+                link.app.display_name()
+            );
+        }
+        result.normalized = link.url;
+        result.scheme = scheme.to_string();
+    }
+}
+
+/// Runs the hidden `serve-files` command: reports the port and token on stdout, then serves
+/// `path`.
+fn serve_files(path: &Path, idle_timeout: Duration) -> io::Result<()> {
+    let server = FileServer::bind(path)?;
+    println!("{} {}", server.port(), server.token());
+    io::stdout().flush()?;
+    server.run(idle_timeout)
+}
+
+/// Points the local file URLs among `results` at servers started for them, one per file or
+/// directory. Files that cannot be served stay `file://` URLs.
+fn serve_local_files(results: &mut [ValidatedUrl], idle_timeout: Duration, format: OutputFormat) {
+    let mut servers: BTreeMap<PathBuf, Url> = BTreeMap::new();
+    for result in results
+        .iter_mut()
+        .filter(|result| result.scheme == "file" && !result.is_passthrough())
+    {
+        let Some(path) = Url::parse(&result.normalized)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+        else {
+            continue;
+        };
+        let served = match servers.get(&path) {
+            Some(served) => served.clone(),
+            None => match start_file_server(&path, idle_timeout) {
+                Ok((port, token)) => {
+                    let mut served = Url::parse(&format!("http://127.0.0.1:{}/{}/", port, token))
+                        .expect("server URL is valid");
+                    if !path.is_dir() {
+                        if let Some(file) = path.file_name() {
+                            served
+                                .path_segments_mut()
+                                .expect("http URLs have path segments")
+                                .pop_if_empty()
+                                .push(&file.to_string_lossy());
+                        }
+                    }
+                    if format == OutputFormat::Human {
+                        info!("Serving {} at {}", path.display(), served);
+                    }
+                    servers.insert(path, served.clone());
+                    served
+                }
+                Err(e) => {
+                    warn!("Failed to serve {}: {}", path.display(), e);
+                    continue;
+                }
+            },
+        };
+        result.normalized = served.to_string();
+    }
+}
+
+/// Starts `pathway serve-files` for `path` in the background and returns its port and token.
+fn start_file_server(path: &Path, idle_timeout: Duration) -> io::Result<(u16, String)> {
+    let mut child = process::Command::new(std::env::current_exe()?)
+        .arg("serve-files")
+        .arg(path)
+        .arg("--idle-timeout")
+        .arg(idle_timeout.as_secs().to_string())
+        .stdin(process::Stdio::null())
+        .stdout(process::Stdio::piped())
+        .stderr(process::Stdio::null())
+        .spawn()?;
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let mut line = String::new();
+    io::BufReader::new(stdout).read_line(&mut line)?;
+    let (port, token) = line
+        .trim()
+        .split_once(' ')
+        .ok_or_else(|| io::Error::other("the server did not report its port"))?;
+    let port = port
+        .parse()
+        .map_err(|_| io::Error::other("the server did not report its port"))?;
+    Ok((port, token.to_string()))
+}
+
+/// The profile copy of an `--ephemeral-copy` launch, which is removed again however the launch
+/// ends: right away if no launch was attempted, once the browser has closed it otherwise.
+///
+/// `process::exit` skips destructors, so the guard must be dropped before exiting.
+struct EphemeralCopy {
+    path: PathBuf,
+    launched: bool,
+}
+
+impl EphemeralCopy {
+    /// Copies the profile `source` of `browser` for a launch.
+    fn create(browser: &BrowserInfo, source: &str) -> Result<Self, ProfileError> {
+        let path = ProfileManager::create_ephemeral_copy(browser, source)?;
+        info!("Copied profile '{}' to {}", source, path.display());
+        Ok(EphemeralCopy {
+            path,
+            launched: false,
+        })
+    }
+}
+
+impl Drop for EphemeralCopy {
+    fn drop(&mut self) {
+        if self.launched {
+            discard_when_closed(&self.path);
+        } else if let Err(e) = std::fs::remove_dir_all(&self.path) {
+            warn!(
+                "Failed to remove the profile copy {} (run `pathway cleanup` later): {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Leaves a background `pathway discard-profile` behind that deletes the temporary profile
+/// at `path` once the browser has closed it. `pathway cleanup` catches the copies it misses.
+fn discard_when_closed(path: &std::path::Path) {
+    let spawned = std::env::current_exe().and_then(|program| {
+        process::Command::new(program)
+            .arg("discard-profile")
+            .arg(path)
+            .stdin(process::Stdio::null())
+            .stdout(process::Stdio::null())
+            .stderr(process::Stdio::null())
+            .spawn()
+    });
+    if let Err(e) = spawned {
+        warn!(
+            "Failed to schedule the removal of {} (run `pathway cleanup` later): {}",
+            path.display(),
+            e
+        );
+    }
+}
+
+/// Counts a successful launch for `profile list --stats`. Profiles are recorded under their
+/// directory name, whatever name or alias they were picked by; temporary, guest and custom
+/// directory launches are not counted.
+fn record_profile_launch(browser: &BrowserInfo, profile_options: &ProfileOptions) {
+    let profile = match &profile_options.profile_type {
+        ProfileType::Default => ProfileManager::discover_profiles_in_directory(browser, None)
+            .ok()
+            .and_then(|profiles| profiles.into_iter().find(|profile| profile.is_default))
+            .map(|profile| profile.name),
+        ProfileType::Named(name) => Some(
+            ProfileManager::find_profile_in_directory(browser, name, None)
+                .map(|profile| profile.name)
+                .unwrap_or_else(|_| name.clone()),
+        ),
+        _ => None,
+    };
+    let (Some(profile), Some(path)) = (profile, launch_counts_path()) else {
+        return;
+    };
+    if let Err(e) = record_launch(
+        &path,
+        &browser.alias(),
+        &profile,
+        Timestamp::now().unix_secs,
+    ) {
+        warn!("Failed to record the launch of '{}': {}", profile, e);
+    }
+}
+
+/// Open each passthrough URL with the OS handler registered for its scheme, or `mailto:` URLs
+/// with the selected mail client.
+fn hand_off_urls(
+    urls: &[&ValidatedUrl],
+    mail_client: Option<&MailClient>,
+    format: OutputFormat,
+) -> Vec<UrlLaunchJson> {
+    urls.iter()
+        .map(|validated| {
+            let url = &validated.normalized;
+            let result = if validated.is_mailto() {
+                launch_mail(mail_client, std::slice::from_ref(url))
+            } else {
+                launch(LaunchTarget::SystemDefault, std::slice::from_ref(url))
+            };
+            match result {
+                Ok(outcome) => {
+                    if format == OutputFormat::Human {
+                        info!("Handing off to scheme handler: {}", url);
+                    }
+                    UrlLaunchJson {
+                        url: url.clone(),
+                        browser: None,
+                        status: "success",
+                        command: Some(outcome.command),
+                        pid: outcome.pid,
+                        reused_existing: None,
+                        message: None,
+                    }
+                }
+                Err(err) => {
+                    let message = format!("Failed to open scheme handler: {}", err);
+                    if format == OutputFormat::Human {
+                        error!("{}: {}", url, message);
+                    }
+                    UrlLaunchJson {
+                        url: url.clone(),
+                        browser: None,
+                        status: "error",
+                        command: None,
+                        pid: None,
+                        reused_existing: None,
+                        message: Some(message),
+                    }
+                }
+            }
+        })
+        .collect()
+}
+
+/// Report a launch where every URL went to a scheme handler and no browser was involved.
+fn respond_handoffs_only(
+    results: &[ValidatedUrl],
+    handed_off: &[UrlLaunchJson],
+    warnings: &[String],
+    format: OutputFormat,
+) {
+    if format == OutputFormat::Human {
+        return;
+    }
+
+    let failed = handed_off
+        .iter()
+        .filter(|launch| launch.status == "error")
+        .count();
+    let status = match failed {
+        0 => "success",
+        n if n == handed_off.len() => "error",
+        _ => "partial",
+    };
+    let response = LaunchJsonResponse {
+        action: "launch",
+        status,
+        urls: Vec::new(),
+        url: None,
+        validated: results.to_vec(),
+        warnings: (!warnings.is_empty()).then(|| warnings.to_vec()),
+        browser: None,
+        profile: None,
+        window_options: None,
+        command: None,
+        reused_existing: None,
+        launches: None,
+        handed_off: Some(handed_off.to_vec()),
+        message: None,
+    };
+    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+}
+
+fn handed_off_json(handed_off: &[UrlLaunchJson]) -> Option<Vec<UrlLaunchJson>> {
+    (!handed_off.is_empty()).then(|| handed_off.to_vec())
+}
+
+/// Response data for browser launch operations
+struct LaunchResponseData<'a> {
     selected_browser: Option<&'a BrowserInfo>,
     inventory: &'a BrowserInventory,
     normalized_urls: &'a [String],
     results: &'a [ValidatedUrl],
     warnings: &'a [String],
+    handed_off: &'a [UrlLaunchJson],
     format: OutputFormat,
 }
 
-/// Execute the browser launch and handle the response
+/// Execute the browser launch and handle the response, returning whether the launch succeeded
 fn execute_launch_and_respond(
     launch_target: LaunchTarget,
     profile_options: &ProfileOptions,
     window_options: &WindowOptions,
     response_data: LaunchResponseData,
-) {
+) -> bool {
     let (profile_opts, window_opts) = if response_data.selected_browser.is_some() {
         (Some(profile_options), Some(window_options))
     } else {
@@ -756,10 +2209,16 @@ fn execute_launch_and_respond(
             if response_data.format == OutputFormat::Human {
                 if let Some(browser) = response_data.selected_browser {
                     let profile_info = get_profile_description(profile_options);
+                    let reused = if outcome.reused_existing {
+                        " (running instance)"
+                    } else {
+                        ""
+                    };
                     info!(
-                        "Launching in {}{}: {}",
+                        "Launching in {}{}{}: {}",
                         browser.display_name,
                         profile_info,
+                        reused,
                         response_data.normalized_urls.join(", ")
                     );
                 } else {
@@ -786,7 +2245,7 @@ fn execute_launch_and_respond(
                             .map(BrowserJson::from_system_default)
                     });
 
-                let response = build_launch_json_response(
+                let mut response = build_launch_json_response(
                     "success",
                     response_data.normalized_urls,
                     response_data.results,
@@ -798,8 +2257,11 @@ fn execute_launch_and_respond(
                     Some(outcome.command.clone()),
                     None,
                 );
+                response.reused_existing = Some(outcome.reused_existing);
+                response.handed_off = handed_off_json(response_data.handed_off);
                 println!("{}", serde_json::to_string_pretty(&response).unwrap());
             }
+            true
         }
         Err(err) => {
             let message = format!("Failed to launch browser: {}", err);
@@ -815,7 +2277,7 @@ fn execute_launch_and_respond(
                         ))
                     });
 
-                let response = build_launch_json_response(
+                let mut response = build_launch_json_response(
                     "error",
                     response_data.normalized_urls,
                     response_data.results,
@@ -827,9 +2289,269 @@ fn execute_launch_and_respond(
                     None,
                     Some(message.clone()),
                 );
+                response.handed_off = handed_off_json(response_data.handed_off);
                 println!("{}", serde_json::to_string_pretty(&response).unwrap());
             }
-            process::exit(1);
+            false
+        }
+    }
+}
+
+/// URLs that open together in one browser with one profile.
+struct LaunchGroup<'a> {
+    browser: Option<&'a BrowserInfo>,
+    profile_options: ProfileOptions,
+    urls: Vec<String>,
+}
+
+impl<'a> LaunchGroup<'a> {
+    fn target(&self) -> LaunchTarget<'a> {
+        self.browser
+            .map(LaunchTarget::Browser)
+            .unwrap_or(LaunchTarget::SystemDefault)
+    }
+
+    fn opens_like(&self, browser: Option<&BrowserInfo>, profile_options: &ProfileOptions) -> bool {
+        self.browser.map(|b| &b.executable_path) == browser.map(|b| &b.executable_path)
+            && self.profile_options == *profile_options
+    }
+}
+
+/// Sorts the web URLs of `results` into launch groups.
+///
+/// URLs matching one of `rules` go to the browser and profile of the rule; all others go to
+/// `default`. A rule whose browser is not installed or whose profile is unusable is skipped
+/// with a warning.
+fn group_launches<'a>(
+    inventory: &'a BrowserInventory,
+    results: &[ValidatedUrl],
+    rules: &[RouteRule],
+    default: LaunchGroup<'a>,
+    window_options: &WindowOptions,
+    config: &Config,
+    warnings: &mut Vec<String>,
+) -> Vec<LaunchGroup<'a>> {
+    let mut warn_once = |warning: String| {
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    };
+    let mut groups: Vec<LaunchGroup<'a>> = Vec::new();
+    for url in results.iter().filter(|url| !url.is_passthrough()) {
+        let routed = route_url(url, rules).and_then(|rule| {
+            let browser =
+                select_browser_matching(inventory, Some(&rule.browser), None, false, |b| {
+                    !rule.native_only || b.runs_natively()
+                });
+            let Some(browser) = browser else {
+                let build = if rule.native_only {
+                    "native build of "
+                } else {
+                    ""
+                };
+                warn_once(format!(
+                    "No {}browser '{}' for the route for {} found. Using the default browser.",
+                    build, rule.browser, rule.host
+                ));
+                return None;
+            };
+            let profile_type = match rule.profile.as_deref() {
+                Some(profile) => ProfileType::Named(
+                    config
+                        .profile_alias(browser, profile)
+                        .unwrap_or(profile)
+                        .to_string(),
+                ),
+                None => ProfileType::Default,
+            };
+            let profile_options = ProfileOptions {
+                profile_type,
+                custom_args: Vec::new(),
+            };
+            match validate_profile_options(browser, &profile_options, window_options) {
+                Ok(profile_warnings) => {
+                    profile_warnings.into_iter().for_each(&mut warn_once);
+                    Some((Some(browser), profile_options))
+                }
+                Err(e) => {
+                    warn_once(format!(
+                        "Route for {} ignored: {}. Using the default browser.",
+                        rule.host, e
+                    ));
+                    None
+                }
+            }
+        });
+        let (browser, profile_options) =
+            routed.unwrap_or_else(|| (default.browser, default.profile_options.clone()));
+
+        match groups
+            .iter_mut()
+            .find(|group| group.opens_like(browser, &profile_options))
+        {
+            Some(group) => group.urls.push(url.normalized.clone()),
+            None => groups.push(LaunchGroup {
+                browser,
+                profile_options,
+                urls: vec![url.normalized.clone()],
+            }),
+        }
+    }
+
+    if groups.is_empty() {
+        groups.push(default);
+    }
+    groups
+}
+
+/// Launch each group of URLs as its own job and report per-URL outcomes.
+///
+/// With `parallel` the groups launch concurrently, except that groups sharing a browser and
+/// profile still launch one after another. A failed launch does not stop the others; returns
+/// whether every launch succeeded.
+fn execute_grouped_launch_and_respond(
+    groups: &[LaunchGroup],
+    parallel: bool,
+    profile_options: &ProfileOptions,
+    window_options: &WindowOptions,
+    response_data: LaunchResponseData,
+) -> bool {
+    let jobs: Vec<LaunchJob> = groups
+        .iter()
+        .map(|group| LaunchJob {
+            target: group.target(),
+            urls: group.urls.clone(),
+            profile_opts: group.browser.map(|_| group.profile_options.clone()),
+            window_opts: group.browser.map(|_| window_options.clone()),
+        })
+        .collect();
+    let outcomes = if parallel {
+        launch_all(&jobs)
+    } else {
+        jobs.iter()
+            .map(|job| {
+                launch_with_profile(
+                    job.target.clone(),
+                    &job.urls,
+                    job.profile_opts.as_ref(),
+                    job.window_opts.as_ref(),
+                )
+            })
+            .collect()
+    };
+
+    let system_default_name = response_data.inventory.system_default.display_name.as_str();
+    let mut launches: Vec<UrlLaunchJson> = Vec::new();
+    for (group, outcome) in groups.iter().zip(outcomes) {
+        let browser_name = group
+            .browser
+            .map(|b| b.display_name.as_str())
+            .unwrap_or(system_default_name);
+        for url in &group.urls {
+            launches.push(match &outcome {
+                Ok(outcome) => UrlLaunchJson {
+                    url: url.clone(),
+                    browser: Some(browser_name.to_string()),
+                    status: "success",
+                    command: Some(outcome.command.clone()),
+                    pid: outcome.pid,
+                    reused_existing: Some(outcome.reused_existing),
+                    message: None,
+                },
+                Err(err) => UrlLaunchJson {
+                    url: url.clone(),
+                    browser: Some(browser_name.to_string()),
+                    status: "error",
+                    command: None,
+                    pid: None,
+                    reused_existing: None,
+                    message: Some(format!("Failed to launch browser: {}", err)),
+                },
+            });
+        }
+    }
+    // Report the URLs in the order they were given
+    launches.sort_by_key(|launch| {
+        response_data
+            .normalized_urls
+            .iter()
+            .position(|url| *url == launch.url)
+    });
+
+    let failed = launches
+        .iter()
+        .filter(|launch| launch.status == "error")
+        .count();
+    let status = match failed {
+        0 => "success",
+        n if n == launches.len() => "error",
+        _ => "partial",
+    };
+
+    if response_data.format == OutputFormat::Human {
+        for launch in &launches {
+            match &launch.message {
+                Some(message) => error!("{}: {}", launch.url, message),
+                None => info!(
+                    "Launching in {}: {}",
+                    launch.browser.as_deref().unwrap_or_default(),
+                    launch.url
+                ),
+            }
+        }
+    } else {
+        let browser_json = response_data
+            .selected_browser
+            .map(|info| BrowserJson::from_browser(info, false))
+            .or_else(|| {
+                Some(BrowserJson::from_system_default(
+                    &response_data.inventory.system_default,
+                ))
+            });
+
+        let mut response = build_launch_json_response(
+            status,
+            response_data.normalized_urls,
+            response_data.results,
+            response_data.warnings,
+            browser_json,
+            response_data.selected_browser,
+            profile_options,
+            window_options,
+            None,
+            (failed > 0).then(|| format!("{} of {} launches failed", failed, launches.len())),
+        );
+        response.launches = Some(launches);
+        response.handed_off = handed_off_json(response_data.handed_off);
+        println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    }
+
+    failed == 0
+}
+
+/// Handle the `mail` subcommand: list the mail clients available for `mailto:` URLs.
+fn handle_mail_command(action: MailAction, format: OutputFormat) {
+    match action {
+        MailAction::List => {
+            let clients = detect_mail_clients();
+            match format {
+                OutputFormat::Human => {
+                    eprintln!("Detected mail clients:");
+                    if clients.is_empty() {
+                        eprintln!("  (none)");
+                    }
+                    for client in &clients {
+                        eprintln!("{} - {}", client.alias(), client.display_name);
+                    }
+                }
+                OutputFormat::Json => {
+                    let response = MailListJsonResponse {
+                        action: "list-mail-clients",
+                        clients,
+                    };
+                    println!("{}", serde_json::to_string_pretty(&response).unwrap());
+                }
+            }
         }
     }
 }
@@ -849,7 +2571,7 @@ fn execute_launch_and_respond(
 /// - `inventory`: the detected browser inventory to query.
 /// - `action`: the browser action to perform (`List` or `Check`).
 /// - `format`: output format (`Human` or `Json`).
-/// - `verbose`: toggles extra human-readable diagnostics (each entry includes its unique identifier).
+/// - `verbose`: toggles extra human-readable diagnostics (each entry includes its unique identifier and executable path).
 ///
 /// # Examples
 ///
@@ -876,16 +2598,43 @@ fn handle_browser_command(
                     eprintln!("  (none)");
                 } else {
                     for browser in &inventory.browsers {
-                        let channel_name = browser.channel.canonical_name();
+                        let mut details = vec![browser.channel.canonical_name()];
+                        if browser.source != InstallationSource::Unknown {
+                            details.push(browser.source.canonical_name());
+                        }
+                        let scope = browser.scope.canonical_name();
+                        if browser.scope == InstallScope::User && !details.contains(&scope) {
+                            details.push(scope);
+                        }
+                        let channel_name = details.join(", ");
                         let alias = browser.alias();
 
+                        let version = browser
+                            .version
+                            .as_deref()
+                            .map(|version| format!(" {}", version))
+                            .unwrap_or_default();
+                        let version = if browser.is_default {
+                            format!("{} (system default)", version)
+                        } else {
+                            version
+                        };
+
                         if verbose {
                             eprintln!(
-                                "{} ({}) - {} [{}]",
-                                alias, channel_name, browser.display_name, browser.unique_id
+                                "{} ({}) - {}{} [{}] {}",
+                                alias,
+                                channel_name,
+                                browser.display_name,
+                                version,
+                                browser.unique_id,
+                                browser.executable_path.display()
                             );
                         } else {
-                            eprintln!("{} ({}) - {}", alias, channel_name, browser.display_name);
+                            eprintln!(
+                                "{} ({}) - {}{}",
+                                alias, channel_name, browser.display_name, version
+                            );
                         }
                     }
                 }
@@ -896,6 +2645,7 @@ fn handle_browser_command(
                     action: "list-browsers",
                     browsers: inventory.browsers.clone(),
                     system_default: inventory.system_default.clone(),
+                    detection_incomplete: inventory.detection_incomplete,
                 };
                 println!("{}", serde_json::to_string_pretty(&response).unwrap());
             }
@@ -912,6 +2662,16 @@ fn handle_browser_command(
                         eprintln!("  Display Name: {}", info.display_name);
                         eprintln!("  Kind: {}", info.kind.canonical_name());
                         eprintln!("  Channel: {}", info.channel.canonical_name());
+                        if let Some(version) = &info.version {
+                            eprintln!("  Version: {}", version);
+                        }
+                        if info.source != InstallationSource::Unknown {
+                            eprintln!("  Source: {}", info.source.canonical_name());
+                        }
+                        eprintln!("  Scope: {}", info.scope.canonical_name());
+                        if let Some(arch) = info.arch {
+                            eprintln!("  Architecture: {}", arch.canonical_name());
+                        }
                         if let Some(exec_command) = &info.exec_command {
                             eprintln!("  Launch Command: {}", exec_command);
                         }
@@ -1005,6 +2765,9 @@ fn handle_browser_command(
                 }
             }
         }
+        BrowserAction::Watch { .. } => {
+            unreachable!("browser watch is dispatched to watch_inventory")
+        }
         BrowserAction::Check { browser, channel } => {
             let result = select_browser(inventory, Some(&browser), channel.as_deref(), false);
 
@@ -1061,8 +2824,8 @@ fn handle_browser_command(
 /// Resolves the requested browser (honoring an optional `channel`) and then:
 /// - ProfileAction::List: discovers profiles for that browser (optionally within `user_dir`) and
 ///   prints a human-readable listing or emits a JSON `ListProfilesResponse`.
-/// - ProfileAction::Info { name }: finds a specific profile by name and prints detailed info or
-///   emits a JSON `ProfileInfoResponse`.
+/// - ProfileAction::Info { name, extensions }: finds a specific profile by name and prints
+///   detailed info, optionally with its extensions, or emits a JSON `ProfileInfoResponse`.
 ///
 /// Output format is chosen by `format`: `OutputFormat::Human` prints to stdout/stderr; the JSON
 /// branch prints pretty-serialized responses to stdout. On resolution failures (browser not found,
@@ -1077,7 +2840,7 @@ fn handle_browser_command(
 ///
 /// ```rust,no_run
 /// // Resolve inventory earlier (not shown) and call:
-/// handle_profile_command(&inventory, Some("chrome".to_string()), None, None, None, ProfileAction::List, OutputFormat::Human);
+/// handle_profile_command(&inventory, Some("chrome".to_string()), None, None, ProfileAction::List { sizes: false, stats: false }, &Config::default(), OutputFormat::Human);
 /// ```
 fn handle_profile_command(
     inventory: &BrowserInventory,
@@ -1085,6 +2848,7 @@ fn handle_profile_command(
     channel: Option<String>,
     user_dir: Option<PathBuf>,
     action: ProfileAction,
+    config: &Config,
     format: OutputFormat,
 ) {
     let browser_name = browser.as_deref().unwrap_or("chrome");
@@ -1098,124 +2862,864 @@ fn handle_profile_command(
                 available_tokens(&inventory.browsers).join(", ")
             );
 
-            if format == OutputFormat::Human {
-                error!("{}", error_msg);
-            } else {
-                print_profile_error_json("profile-error", browser_name, error_msg);
+            if format == OutputFormat::Human {
+                error!("{}", error_msg);
+            } else {
+                print_profile_error_json("profile-error", browser_name, error_msg);
+            }
+            process::exit(1);
+        }
+    };
+
+    let custom_dir = user_dir.as_deref();
+
+    match action {
+        ProfileAction::List { sizes, stats } => {
+            match ProfileManager::discover_profiles_in_directory(browser, custom_dir) {
+                Ok(profiles) => {
+                    let launches = stats.then(|| {
+                        let mut counts = launch_counts_path()
+                            .map(|path| read_launch_counts(&path))
+                            .unwrap_or_default()
+                            .remove(&browser.alias())
+                            .unwrap_or_default();
+                        profiles
+                            .iter()
+                            .map(|profile| {
+                                let count = counts.remove(&profile.name).unwrap_or_default();
+                                (profile.name.clone(), count)
+                            })
+                            .collect::<BTreeMap<_, _>>()
+                    });
+                    if format == OutputFormat::Human
+                        && launches
+                            .as_ref()
+                            .is_some_and(|launches| launches.values().all(|l| l.count == 0))
+                    {
+                        warn!("No launches recorded; set `launches = true` under [stats] in the config to count them");
+                    }
+                    let usage = sizes.then(|| {
+                        profiles
+                            .iter()
+                            .filter_map(|profile| {
+                                Some((profile.name.clone(), profile_usage(profile)?))
+                            })
+                            .collect::<BTreeMap<_, _>>()
+                    });
+                    let spaces = (browser.kind == BrowserKind::Arc)
+                        .then(|| ProfileManager::discover_arc_spaces(browser, custom_dir).ok())
+                        .flatten();
+                    let workspaces = (browser.kind == BrowserKind::Edge)
+                        .then(|| ProfileManager::discover_edge_workspaces(browser, custom_dir).ok())
+                        .flatten();
+
+                    if format == OutputFormat::Human {
+                        eprintln!("{} profiles:", browser.display_name);
+                        if profiles.is_empty() {
+                            eprintln!("  (none)");
+                        } else {
+                            let now = Timestamp::now();
+                            for profile in &profiles {
+                                let default_marker =
+                                    if profile.is_default { " (default)" } else { "" };
+                                let in_use_marker = if profile.in_use { " (in use)" } else { "" };
+                                let account = profile
+                                    .identity
+                                    .as_ref()
+                                    .and_then(|identity| identity.email.as_ref())
+                                    .map(|email| format!(" <{}>", email))
+                                    .unwrap_or_default();
+                                let last_used = profile
+                                    .last_used
+                                    .map(|t| format!(" - Last used: {}", t.relative_to(now)))
+                                    .unwrap_or_default();
+
+                                // Show directory name if different from display name
+                                let dir_info = if profile.arc_space.is_some() {
+                                    " (Space)".to_string()
+                                } else if profile.name != profile.display_name
+                                    && !profile.path.as_os_str().is_empty()
+                                {
+                                    format!(" [{}]", profile.name)
+                                } else {
+                                    String::new()
+                                };
+
+                                let size = usage
+                                    .as_ref()
+                                    .and_then(|usage| usage.get(&profile.name))
+                                    .map(|usage| {
+                                        format!(
+                                            " - {} ({} cache)",
+                                            format_size(usage.total_bytes),
+                                            format_size(usage.cache_bytes)
+                                        )
+                                    })
+                                    .unwrap_or_default();
+                                let launched = launches
+                                    .as_ref()
+                                    .and_then(|launches| launches.get(&profile.name))
+                                    .map(|launch| match launch.last_launched {
+                                        Some(last) if launch.count > 0 => format!(
+                                            " - Launched {} time{}, last {}",
+                                            launch.count,
+                                            if launch.count == 1 { "" } else { "s" },
+                                            Timestamp::from_unix_secs(last).relative_to(now)
+                                        ),
+                                        _ => " - Never launched".to_string(),
+                                    })
+                                    .unwrap_or_default();
+
+                                eprintln!(
+                                    "  {}{}{}{}{}{}{}{}",
+                                    profile.display_name,
+                                    dir_info,
+                                    account,
+                                    default_marker,
+                                    in_use_marker,
+                                    last_used,
+                                    size,
+                                    launched
+                                );
+                            }
+                        }
+
+                        if let Some(workspaces) = workspaces.as_ref().filter(|w| !w.is_empty()) {
+                            eprintln!("{} workspaces:", browser.display_name);
+                            for workspace in workspaces {
+                                eprintln!("  {} [{}]", workspace.name, workspace.profile);
+                            }
+                        }
+                    } else {
+                        let response = ListProfilesResponse {
+                            action: "list-profiles",
+                            browser: browser.display_name.clone(),
+                            profiles,
+                            spaces,
+                            workspaces,
+                            usage,
+                            launches,
+                        };
+                        println!("{}", serde_json::to_string_pretty(&response).unwrap());
+                    }
+                }
+                Err(e) => {
+                    let error_msg = format!("Failed to discover profiles: {}", e);
+                    if format == OutputFormat::Human {
+                        error!("{}", error_msg);
+                    } else {
+                        print_profile_error_json(
+                            "list-profiles",
+                            browser.display_name.as_str(),
+                            error_msg,
+                        );
+                    }
+                    process::exit(1);
+                }
+            }
+        }
+        ProfileAction::Info { name, extensions } => {
+            match ProfileManager::find_profile_in_directory(browser, &name, custom_dir) {
+                Ok(profile) => {
+                    let usage = profile_usage(&profile);
+                    let extensions = extensions.then(|| profile_extensions(&profile));
+                    if format == OutputFormat::Human {
+                        eprintln!("Profile: {}", profile.display_name);
+                        eprintln!("  Name: {}", profile.name);
+                        eprintln!("  Path: {}", profile.path.display());
+                        eprintln!(
+                            "  Default: {}",
+                            if profile.is_default { "Yes" } else { "No" }
+                        );
+                        eprintln!("  In use: {}", if profile.in_use { "Yes" } else { "No" });
+                        if let Some(identity) = &profile.identity {
+                            match (&identity.account_name, &identity.email) {
+                                (Some(name), Some(email)) => {
+                                    eprintln!("  Account: {} <{}>", name, email)
+                                }
+                                (None, Some(email)) => eprintln!("  Account: {}", email),
+                                _ => {}
+                            }
+                            if identity.uses_account_picture {
+                                eprintln!("  Avatar: account picture");
+                            } else if let Some(icon) = &identity.avatar_icon {
+                                eprintln!("  Avatar: {}", icon);
+                            }
+                        }
+                        if !profile.workspaces.is_empty() {
+                            let names: Vec<String> = profile
+                                .workspaces
+                                .iter()
+                                .map(|workspace| match &workspace.emoji {
+                                    Some(emoji) => format!("{} {}", emoji, workspace.name),
+                                    None => workspace.name.clone(),
+                                })
+                                .collect();
+                            eprintln!("  Workspaces: {}", names.join(", "));
+                        }
+                        if let Some(last_used) = profile.last_used {
+                            eprintln!(
+                                "  Last used: {} ({})",
+                                last_used.relative_to(Timestamp::now()),
+                                last_used.to_rfc3339()
+                            );
+                        }
+                        if let Some(usage) = &usage {
+                            eprintln!(
+                                "  Disk usage: {} (cache {}, data {})",
+                                format_size(usage.total_bytes),
+                                format_size(usage.cache_bytes),
+                                format_size(usage.data_bytes)
+                            );
+                        }
+                        match extensions.as_deref() {
+                            Some([]) => eprintln!("  Extensions: none"),
+                            Some(extensions) => {
+                                eprintln!("  Extensions:");
+                                for extension in extensions {
+                                    eprintln!(
+                                        "    {} {} ({})",
+                                        extension.name, extension.version, extension.id
+                                    );
+                                }
+                            }
+                            None => {}
+                        }
+                        eprintln!("  Browser: {}", browser.display_name);
+                    } else {
+                        let response = ProfileInfoResponse {
+                            action: "profile-info",
+                            browser: browser.display_name.clone(),
+                            profile,
+                            usage,
+                            extensions,
+                        };
+                        println!("{}", serde_json::to_string_pretty(&response).unwrap());
+                    }
+                }
+                Err(e) => {
+                    let error_msg = format!("Profile '{}' not found: {}", name, e);
+                    if format == OutputFormat::Human {
+                        error!("{}", error_msg);
+                    } else {
+                        print_profile_error_json(
+                            "profile-info",
+                            browser.display_name.as_str(),
+                            error_msg,
+                        );
+                    }
+                    process::exit(1);
+                }
+            }
+        }
+        ProfileAction::Open { name, start_page } => {
+            open_profile(browser, &name, start_page.as_deref(), custom_dir, format);
+        }
+        ProfileAction::Create { name, launch } => {
+            create_profile(browser, &name, custom_dir, launch, format);
+        }
+        ProfileAction::Delete { name, yes } => {
+            delete_profile(browser, &name, custom_dir, yes, format);
+        }
+        ProfileAction::Rename { name, new_name } => {
+            rename_profile(browser, &name, &new_name, custom_dir, format);
+        }
+        ProfileAction::Clone { source, name } => {
+            clone_profile(browser, &source, &name, custom_dir, format);
+        }
+        ProfileAction::Export { name, output } => {
+            export_profile(browser, &name, output, custom_dir, format);
+        }
+        ProfileAction::Backup { name, dir, keep } => {
+            let keep = keep.map_or(config.backup.keep, |keep| keep as usize);
+            let dir = dir.or_else(|| config.backup.dir.clone());
+            backup_profile(browser, &name, dir, keep, custom_dir, format);
+        }
+        ProfileAction::Import { archive, name } => {
+            import_profile(browser, archive, name.as_deref(), custom_dir, format);
+        }
+    }
+}
+
+/// Writes a profile that is not in use to an archive.
+fn export_profile(
+    browser: &BrowserInfo,
+    name: &str,
+    output: PathBuf,
+    custom_dir: Option<&std::path::Path>,
+    format: OutputFormat,
+) {
+    let fail = |error_msg: String| -> ! {
+        if format == OutputFormat::Human {
+            error!("{}", error_msg);
+        } else {
+            print_profile_error_json("export-profile", browser.display_name.as_str(), error_msg);
+        }
+        process::exit(1);
+    };
+
+    let profile = match ProfileManager::find_profile_exactly_in_directory(browser, name, custom_dir)
+    {
+        Ok(profile) => profile,
+        Err(e) => fail(format!("Profile '{}' not found: {}", name, e)),
+    };
+    // Databases of a running browser may be caught mid-write.
+    if profile_in_use(browser, &profile, custom_dir) {
+        fail(format!(
+            "Close {} before exporting profile '{}'",
+            browser.display_name, profile.display_name
+        ));
+    }
+
+    let manifest = match profile_archive::export_profile(browser, &profile, &output) {
+        Ok(manifest) => manifest,
+        Err(e) => fail(format!("Failed to export profile '{}': {}", name, e)),
+    };
+
+    if format == OutputFormat::Human {
+        eprintln!(
+            "Exported {} profile '{}' ({} files) to {}",
+            browser.display_name,
+            profile.display_name,
+            manifest.files.len(),
+            output.display()
+        );
+    } else {
+        let response = ProfileArchiveResponse {
+            action: "export-profile",
+            browser: browser.display_name.clone(),
+            profile,
+            archive: output,
+            files: manifest.files.len(),
+            warning: None,
+        };
+        println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    }
+}
+
+/// Writes a timestamped backup of a profile that is not in use and prunes old backups.
+fn backup_profile(
+    browser: &BrowserInfo,
+    name: &str,
+    dir: Option<PathBuf>,
+    keep: usize,
+    custom_dir: Option<&std::path::Path>,
+    format: OutputFormat,
+) {
+    let fail = |error_msg: String| -> ! {
+        if format == OutputFormat::Human {
+            error!("{}", error_msg);
+        } else {
+            print_profile_error_json("backup-profile", browser.display_name.as_str(), error_msg);
+        }
+        process::exit(1);
+    };
+
+    let Some(dir) = dir.or_else(default_backup_dir) else {
+        fail("Could not determine a backup directory; pass --dir".to_string());
+    };
+    let profile = match ProfileManager::find_profile_exactly_in_directory(browser, name, custom_dir)
+    {
+        Ok(profile) => profile,
+        Err(e) => fail(format!("Profile '{}' not found: {}", name, e)),
+    };
+    if profile_in_use(browser, &profile, custom_dir) {
+        fail(format!(
+            "Close {} before backing up profile '{}'",
+            browser.display_name, profile.display_name
+        ));
+    }
+
+    let backup =
+        match profile_backup::backup_profile(browser, &profile, &dir, keep, Timestamp::now()) {
+            Ok(backup) => backup,
+            Err(e) => fail(format!("Failed to back up profile '{}': {}", name, e)),
+        };
+
+    if format == OutputFormat::Human {
+        eprintln!(
+            "Backed up {} profile '{}' ({} files) to {}",
+            browser.display_name,
+            profile.display_name,
+            backup.manifest.files.len(),
+            backup.path.display()
+        );
+        for old in &backup.pruned {
+            eprintln!("  Removed old backup {}", old.display());
+        }
+    } else {
+        let response = ProfileBackupResponse {
+            action: "backup-profile",
+            browser: browser.display_name.clone(),
+            profile,
+            archive: backup.path,
+            files: backup.manifest.files.len(),
+            pruned: backup.pruned,
+        };
+        println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    }
+}
+
+/// Creates a profile from an archive while the browser is closed.
+fn import_profile(
+    browser: &BrowserInfo,
+    archive: PathBuf,
+    name: Option<&str>,
+    custom_dir: Option<&std::path::Path>,
+    format: OutputFormat,
+) {
+    let fail = |error_msg: String| -> ! {
+        if format == OutputFormat::Human {
+            error!("{}", error_msg);
+        } else {
+            print_profile_error_json("import-profile", browser.display_name.as_str(), error_msg);
+        }
+        process::exit(1);
+    };
+
+    if instance::is_running(browser, Some(&user_data_options(custom_dir))) {
+        fail(format!(
+            "Close {} before importing profiles; it would overwrite the new one on exit",
+            browser.display_name
+        ));
+    }
+
+    let (profile, manifest) =
+        match profile_archive::import_profile(browser, &archive, name, custom_dir) {
+            Ok(imported) => imported,
+            Err(e) => fail(format!(
+                "Failed to import profile from {}: {}",
+                archive.display(),
+                e
+            )),
+        };
+    let warning = manifest.is_newer_than(browser).then(|| {
+        format!(
+            "The profile comes from {} {}, newer than the installed {}; it may not open",
+            browser.display_name,
+            manifest.browser_version.as_deref().unwrap_or_default(),
+            browser.version.as_deref().unwrap_or_default()
+        )
+    });
+
+    if format == OutputFormat::Human {
+        if let Some(warning) = &warning {
+            warn!("{}", warning);
+        }
+        eprintln!(
+            "Imported {} profile '{}' ({} files) into {}",
+            browser.display_name,
+            profile.display_name,
+            manifest.files.len(),
+            profile.path.display()
+        );
+    } else {
+        let response = ProfileArchiveResponse {
+            action: "import-profile",
+            browser: browser.display_name.clone(),
+            profile,
+            archive,
+            files: manifest.files.len(),
+            warning,
+        };
+        println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    }
+}
+
+/// Copies a profile that is not in use into a new one and prints the copy.
+fn clone_profile(
+    browser: &BrowserInfo,
+    source: &str,
+    name: &str,
+    custom_dir: Option<&std::path::Path>,
+    format: OutputFormat,
+) {
+    let fail = |error_msg: String| -> ! {
+        if format == OutputFormat::Human {
+            error!("{}", error_msg);
+        } else {
+            print_profile_error_json("clone-profile", browser.display_name.as_str(), error_msg);
+        }
+        process::exit(1);
+    };
+
+    let original =
+        match ProfileManager::find_profile_exactly_in_directory(browser, source, custom_dir) {
+            Ok(profile) => profile,
+            Err(e) => fail(format!("Profile '{}' not found: {}", source, e)),
+        };
+    // A running browser keeps changing the files being copied.
+    if profile_in_use(browser, &original, custom_dir) {
+        fail(format!(
+            "Close {} before cloning profile '{}'",
+            browser.display_name, original.display_name
+        ));
+    }
+
+    let profile = match ProfileManager::clone_profile(browser, source, name, custom_dir) {
+        Ok(profile) => profile,
+        Err(e) => fail(format!("Failed to clone profile '{}': {}", source, e)),
+    };
+
+    if format == OutputFormat::Human {
+        eprintln!(
+            "Cloned {} profile '{}' to '{}' in {}",
+            browser.display_name,
+            original.display_name,
+            profile.display_name,
+            profile.path.display()
+        );
+    } else {
+        let response = ProfileInfoResponse {
+            action: "clone-profile",
+            browser: browser.display_name.clone(),
+            profile,
+            usage: None,
+            extensions: None,
+        };
+        println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    }
+}
+
+/// Whether `browser` runs with `profile`, or with the user data directory listing it.
+///
+/// Firefox locks the profile itself, Chromium the user data directory. Either way the
+/// running browser would overwrite changes to the profile list.
+fn profile_in_use(
+    browser: &BrowserInfo,
+    profile: &ProfileInfo,
+    custom_dir: Option<&std::path::Path>,
+) -> bool {
+    let in_profile = ProfileOptions {
+        profile_type: ProfileType::CustomDirectory(profile.path.clone()),
+        custom_args: Vec::new(),
+    };
+    instance::is_running(browser, Some(&in_profile))
+        || instance::is_running(browser, Some(&user_data_options(custom_dir)))
+}
+
+/// Profile options selecting the user data directory: `custom_dir` or the browser's own.
+fn user_data_options(custom_dir: Option<&std::path::Path>) -> ProfileOptions {
+    ProfileOptions {
+        profile_type: custom_dir
+            .map(|dir| ProfileType::CustomDirectory(dir.to_path_buf()))
+            .unwrap_or(ProfileType::Default),
+        custom_args: Vec::new(),
+    }
+}
+
+/// Renames a profile that is not in use and prints the updated profile.
+fn rename_profile(
+    browser: &BrowserInfo,
+    name: &str,
+    new_name: &str,
+    custom_dir: Option<&std::path::Path>,
+    format: OutputFormat,
+) {
+    let fail = |error_msg: String| -> ! {
+        if format == OutputFormat::Human {
+            error!("{}", error_msg);
+        } else {
+            print_profile_error_json("rename-profile", browser.display_name.as_str(), error_msg);
+        }
+        process::exit(1);
+    };
+
+    let profile = match ProfileManager::find_profile_exactly_in_directory(browser, name, custom_dir)
+    {
+        Ok(profile) => profile,
+        Err(e) => fail(format!("Profile '{}' not found: {}", name, e)),
+    };
+    if profile_in_use(browser, &profile, custom_dir) {
+        fail(format!(
+            "Close {} before renaming profile '{}'",
+            browser.display_name, profile.display_name
+        ));
+    }
+
+    let profile = match ProfileManager::rename_profile(browser, name, new_name, custom_dir) {
+        Ok(profile) => profile,
+        Err(e) => fail(format!("Failed to rename profile '{}': {}", name, e)),
+    };
+
+    if format == OutputFormat::Human {
+        eprintln!(
+            "Renamed {} profile '{}' to '{}'",
+            browser.display_name, name, profile.display_name
+        );
+    } else {
+        let response = ProfileInfoResponse {
+            action: "rename-profile",
+            browser: browser.display_name.clone(),
+            profile,
+            usage: None,
+            extensions: None,
+        };
+        println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    }
+}
+
+/// Deletes a profile after checking that it is not in use and, unless `yes`, asking first.
+fn delete_profile(
+    browser: &BrowserInfo,
+    name: &str,
+    custom_dir: Option<&std::path::Path>,
+    yes: bool,
+    format: OutputFormat,
+) {
+    let fail = |error_msg: String| -> ! {
+        if format == OutputFormat::Human {
+            error!("{}", error_msg);
+        } else {
+            print_profile_error_json("delete-profile", browser.display_name.as_str(), error_msg);
+        }
+        process::exit(1);
+    };
+
+    let profile = match ProfileManager::find_profile_exactly_in_directory(browser, name, custom_dir)
+    {
+        Ok(profile) => profile,
+        Err(e) => fail(format!("Profile '{}' not found: {}", name, e)),
+    };
+
+    if profile_in_use(browser, &profile, custom_dir) {
+        fail(format!(
+            "Close {} before deleting profile '{}'",
+            browser.display_name, profile.display_name
+        ));
+    }
+
+    if !yes {
+        if format != OutputFormat::Human || !std::io::stdin().is_terminal() {
+            fail(format!(
+                "Refusing to delete profile '{}' without confirmation; pass --yes",
+                profile.display_name
+            ));
+        }
+        eprint!(
+            "Delete {} profile '{}' at {}? [y/N] ",
+            browser.display_name,
+            profile.display_name,
+            profile.path.display()
+        );
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer).is_err()
+            || !matches!(answer.trim(), "y" | "Y" | "yes")
+        {
+            eprintln!("Aborted");
+            process::exit(1);
+        }
+    }
+
+    let profile = match ProfileManager::delete_profile(browser, name, custom_dir) {
+        Ok(profile) => profile,
+        Err(e) => fail(format!("Failed to delete profile '{}': {}", name, e)),
+    };
+
+    if format == OutputFormat::Human {
+        eprintln!(
+            "Deleted {} profile '{}'",
+            browser.display_name, profile.display_name
+        );
+    } else {
+        let response = ProfileInfoResponse {
+            action: "delete-profile",
+            browser: browser.display_name.clone(),
+            profile,
+            usage: None,
+            extensions: None,
+        };
+        println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    }
+}
+
+/// Opens a new window of the profile `name`, showing `start_page` if given.
+fn open_profile(
+    browser: &BrowserInfo,
+    name: &str,
+    start_page: Option<&str>,
+    custom_dir: Option<&std::path::Path>,
+    format: OutputFormat,
+) {
+    let fail = |error_msg: String| -> ! {
+        if format == OutputFormat::Human {
+            error!("{}", error_msg);
+        } else {
+            print_profile_error_json("open-profile", browser.display_name.as_str(), error_msg);
+        }
+        process::exit(1);
+    };
+
+    let profile = match ProfileManager::find_profile_in_directory(browser, name, custom_dir) {
+        Ok(profile) => profile,
+        Err(e) => fail(format!("Profile '{}' not found: {}", name, e)),
+    };
+    let urls = match start_page {
+        Some(url) => {
+            match validate_url_with_options(url, &RealFileSystem, &ValidationOptions::default()) {
+                Ok(validated) => vec![validated.normalized],
+                Err(e) => fail(format!("Invalid start page '{}': {}", url, e)),
+            }
+        }
+        None => Vec::new(),
+    };
+
+    let mut profile_opts = open_profile_options(browser, &profile, custom_dir);
+    let mut window_opts = WindowOptions {
+        new_window: true,
+        ..WindowOptions::default()
+    };
+    ProfileManager::select_arc_space(browser, &mut profile_opts, &mut window_opts);
+
+    if let Err(e) = launch_with_profile(
+        LaunchTarget::Browser(browser),
+        &urls,
+        Some(&profile_opts),
+        Some(&window_opts),
+    ) {
+        fail(format!(
+            "Failed to open profile '{}': {}",
+            profile.display_name, e
+        ));
+    }
+
+    if format == OutputFormat::Human {
+        eprintln!(
+            "Opened {} profile '{}'",
+            browser.display_name, profile.display_name
+        );
+    } else {
+        let response = ProfileInfoResponse {
+            action: "open-profile",
+            browser: browser.display_name.clone(),
+            profile,
+            usage: None,
+            extensions: None,
+        };
+        println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    }
+}
+
+/// Profile options that open `profile` from `custom_dir`, or by name without one.
+///
+/// Profiles in a custom directory are opened by path, since names only resolve in the
+/// browser's own directory.
+fn open_profile_options(
+    browser: &BrowserInfo,
+    profile: &ProfileInfo,
+    custom_dir: Option<&std::path::Path>,
+) -> ProfileOptions {
+    match custom_dir {
+        None => ProfileOptions {
+            profile_type: ProfileType::Named(profile.name.clone()),
+            custom_args: Vec::new(),
+        },
+        Some(_)
+            if matches!(
+                browser.kind,
+                BrowserKind::Firefox
+                    | BrowserKind::Waterfox
+                    | BrowserKind::LibreWolf
+                    | BrowserKind::Zen
+                    | BrowserKind::Floorp
+                    | BrowserKind::PaleMoon
+                    | BrowserKind::Basilisk
+            ) =>
+        {
+            ProfileOptions {
+                profile_type: ProfileType::CustomDirectory(profile.path.clone()),
+                custom_args: Vec::new(),
             }
-            process::exit(1);
         }
-    };
+        Some(_) => ProfileOptions {
+            custom_args: vec![format!("--profile-directory={}", profile.name)],
+            ..user_data_options(custom_dir)
+        },
+    }
+}
 
-    let custom_dir = user_dir.as_deref();
+/// Creates a Chromium profile and optionally opens the browser with it once.
+fn create_profile(
+    browser: &BrowserInfo,
+    name: &str,
+    custom_dir: Option<&std::path::Path>,
+    launch: bool,
+    format: OutputFormat,
+) {
+    let fail = |error_msg: String| -> ! {
+        if format == OutputFormat::Human {
+            error!("{}", error_msg);
+        } else {
+            print_profile_error_json("create-profile", browser.display_name.as_str(), error_msg);
+        }
+        process::exit(1);
+    };
 
-    match action {
-        ProfileAction::List => {
-            match ProfileManager::discover_profiles_in_directory(browser, custom_dir) {
-                Ok(profiles) => {
-                    if format == OutputFormat::Human {
-                        eprintln!("{} profiles:", browser.display_name);
-                        if profiles.is_empty() {
-                            eprintln!("  (none)");
-                        } else {
-                            for profile in &profiles {
-                                let default_marker =
-                                    if profile.is_default { " (default)" } else { "" };
-                                let last_used = profile
-                                    .last_used
-                                    .as_ref()
-                                    .map(|t| format!(" - Last used: {}", t))
-                                    .unwrap_or_default();
+    let user_data = user_data_options(custom_dir);
+    // The browser writes its own copy of `Local State` on exit, dropping the new entry.
+    if instance::is_running(browser, Some(&user_data)) {
+        fail(format!(
+            "Close {} before creating profiles; it would overwrite the new one on exit",
+            browser.display_name
+        ));
+    }
 
-                                // Show directory name if different from display name
-                                let dir_info = if profile.name != profile.display_name
-                                    && !profile.path.as_os_str().is_empty()
-                                {
-                                    format!(" [{}]", profile.name)
-                                } else {
-                                    String::new()
-                                };
+    let profile = match ProfileManager::create_chromium_profile(browser, name, custom_dir) {
+        Ok(profile) => profile,
+        Err(e) => fail(format!("Failed to create profile '{}': {}", name, e)),
+    };
 
-                                eprintln!(
-                                    "  {}{}{}{}",
-                                    profile.display_name, dir_info, default_marker, last_used
-                                );
-                            }
-                        }
-                    } else {
-                        let response = ListProfilesResponse {
-                            action: "list-profiles",
-                            browser: browser.display_name.clone(),
-                            profiles,
-                        };
-                        println!("{}", serde_json::to_string_pretty(&response).unwrap());
-                    }
-                }
-                Err(e) => {
-                    let error_msg = format!("Failed to discover profiles: {}", e);
-                    if format == OutputFormat::Human {
-                        error!("{}", error_msg);
-                    } else {
-                        print_profile_error_json(
-                            "list-profiles",
-                            browser.display_name.as_str(),
-                            error_msg,
-                        );
-                    }
-                    process::exit(1);
-                }
-            }
-        }
-        ProfileAction::Info { name } => {
-            match ProfileManager::find_profile_in_directory(browser, &name, custom_dir) {
-                Ok(profile) => {
-                    if format == OutputFormat::Human {
-                        eprintln!("Profile: {}", profile.display_name);
-                        eprintln!("  Name: {}", profile.name);
-                        eprintln!("  Path: {}", profile.path.display());
-                        eprintln!(
-                            "  Default: {}",
-                            if profile.is_default { "Yes" } else { "No" }
-                        );
-                        if let Some(last_used) = &profile.last_used {
-                            eprintln!("  Last used: {}", last_used);
-                        }
-                        eprintln!("  Browser: {}", browser.display_name);
-                    } else {
-                        let response = ProfileInfoResponse {
-                            action: "profile-info",
-                            browser: browser.display_name.clone(),
-                            profile,
-                        };
-                        println!("{}", serde_json::to_string_pretty(&response).unwrap());
-                    }
-                }
-                Err(e) => {
-                    let error_msg = format!("Profile '{}' not found: {}", name, e);
-                    if format == OutputFormat::Human {
-                        error!("{}", error_msg);
-                    } else {
-                        print_profile_error_json(
-                            "profile-info",
-                            browser.display_name.as_str(),
-                            error_msg,
-                        );
-                    }
-                    process::exit(1);
-                }
-            }
+    if launch {
+        let profile_opts = ProfileOptions {
+            custom_args: vec![format!("--profile-directory={}", profile.name)],
+            ..user_data
+        };
+        if let Err(e) = launch_with_profile(
+            LaunchTarget::Browser(browser),
+            &[],
+            Some(&profile_opts),
+            None,
+        ) {
+            fail(format!(
+                "Created profile '{}' but failed to launch it: {}",
+                name, e
+            ));
         }
     }
+
+    if format == OutputFormat::Human {
+        eprintln!(
+            "Created {} profile '{}' in {}",
+            browser.display_name,
+            profile.display_name,
+            profile.path.display()
+        );
+    } else {
+        let response = ProfileInfoResponse {
+            action: "create-profile",
+            browser: browser.display_name.clone(),
+            profile,
+            usage: None,
+            extensions: None,
+        };
+        println!("{}", serde_json::to_string_pretty(&response).unwrap());
+    }
 }
 
 /// Convert CLI profile arguments into a runtime ProfileOptions.
 ///
 /// Chooses a ProfileType based on ProfileArgs:
-/// - If `temp_profile` is set, attempts to create a temporary profile directory; on failure falls back to `Default` and appends a warning.
+/// - If `ephemeral_copy` is set, checks that the profile can be copied and returns it as
+///   `Named`; the launch swaps in the copy (see `EphemeralCopy`).
+/// - If `temp_profile` is set, attempts to create a temporary profile directory (in a location the
+///   selected `browser` can access); on failure falls back to `Default` and appends a warning.
 /// - If `user_dir` is provided, attempts to prepare that custom directory; on failure falls back to `Default` and appends a warning.
 /// - If `guest` is set, returns `Guest`.
-/// - If a named `profile` is provided, returns `Named(name)`.
-/// - Otherwise returns `Default`.
+/// - If a named `profile` is provided, returns `Named(name)`, with `name` replaced by its target
+///   when `config` defines it as a profile alias for `browser`.
+/// - If `profile_email` is provided, returns `Named` with the profile signed in to that account.
+/// - Otherwise returns the `default_profile` configured for `browser` (resolving aliases), or
+///   `Default` when there is none.
 ///
 /// The function may have side effects: creating a temporary profile directory via `ProfileManager::create_temp_profile`
 /// or preparing a custom directory via `ProfileManager::prepare_custom_directory`. Any user-visible issues encountered
@@ -1232,14 +3736,36 @@ fn handle_profile_command(
 ///     user_dir: None,
 ///     guest: false,
 ///     profile: None,
+///     profile_email: None,
+///     ephemeral_copy: None,
 /// };
-/// let opts = convert_profile_args(&args, &mut warnings);
+/// let opts = convert_profile_args(&args, None, &Config::default(), &mut warnings).unwrap();
 /// assert!(matches!(opts.profile_type, ProfileType::Default));
 /// assert!(warnings.is_empty());
 /// ```
-fn convert_profile_args(profile_args: &ProfileArgs, warnings: &mut Vec<String>) -> ProfileOptions {
-    let profile_type = if profile_args.temp_profile {
-        match ProfileManager::create_temp_profile() {
+fn convert_profile_args(
+    profile_args: &ProfileArgs,
+    browser: Option<&BrowserInfo>,
+    config: &Config,
+    warnings: &mut Vec<String>,
+) -> Result<ProfileOptions, ProfileError> {
+    let profile_type = if let Some(source) = &profile_args.ephemeral_copy {
+        // Falling back to the profile itself would defeat the point of the copy
+        let Some(browser) = browser else {
+            return Err(ProfileError::UnsupportedBrowser(
+                "the system default browser (use --browser with --ephemeral-copy)".to_string(),
+            ));
+        };
+        // The copy itself is only made once the launch is certain, see `EphemeralCopy::create`
+        let source = config.profile_alias(browser, source).unwrap_or(source);
+        ProfileManager::ephemeral_copy_source(browser, source)?;
+        ProfileType::Named(source.to_string())
+    } else if profile_args.temp_profile {
+        let temp_profile = match browser {
+            Some(browser) => ProfileManager::create_temp_profile_for(browser),
+            None => ProfileManager::create_temp_profile(),
+        };
+        match temp_profile {
             Ok(temp_path) => {
                 info!(
                     "Created temporary profile directory: {}",
@@ -1267,26 +3793,67 @@ fn convert_profile_args(profile_args: &ProfileArgs, warnings: &mut Vec<String>)
     } else if profile_args.guest {
         ProfileType::Guest
     } else if let Some(profile_name) = &profile_args.profile {
-        ProfileType::Named(profile_name.clone())
+        named_profile(profile_name, browser, config)
+    } else if let Some(email) = &profile_args.profile_email {
+        match browser {
+            Some(browser) => match ProfileManager::find_profile_by_email(browser, email, None) {
+                Ok(profile) => ProfileType::Named(profile.name),
+                Err(e) => {
+                    warnings.push(format!("Failed to select profile by email: {}", e));
+                    ProfileType::Default
+                }
+            },
+            // Reported as needing --browser below
+            None => ProfileType::Named(email.clone()),
+        }
+    } else if let Some(default_profile) =
+        browser.and_then(|browser| config.default_profile(browser))
+    {
+        debug!("Using the configured default profile '{}'", default_profile);
+        named_profile(default_profile, browser, config)
     } else {
         ProfileType::Default
     };
 
-    ProfileOptions {
+    Ok(ProfileOptions {
         profile_type,
         custom_args: Vec::new(),
+    })
+}
+
+/// The profile `name` picks, after resolving a configured alias.
+fn named_profile(name: &str, browser: Option<&BrowserInfo>, config: &Config) -> ProfileType {
+    match browser.and_then(|browser| config.profile_alias(browser, name)) {
+        Some(target) => {
+            debug!("Profile alias '{}' resolves to '{}'", name, target);
+            ProfileType::Named(target.to_string())
+        }
+        None => ProfileType::Named(name.to_string()),
     }
 }
 
 /// Convert CLI window argument flags into a WindowOptions value used for launches.
 ///
-/// The returned `WindowOptions` mirrors the `new_window`, `incognito`, and `kiosk` flags
-/// from the provided `WindowArgs`.
+/// The returned `WindowOptions` mirrors the window-related options from the provided
+/// `WindowArgs`.
 ///
 /// # Examples
 ///
 /// ```
-/// let args = WindowArgs { new_window: true, incognito: false, kiosk: false };
+/// let args = WindowArgs {
+///     new_window: true,
+///     incognito: false,
+///     kiosk: false,
+///     kiosk_strict: false,
+///     tor_window: false,
+///     sandbox: None,
+///     new_instance: false,
+///     arc_space: None,
+///     edge_workspace: None,
+///     window_name: None,
+///     cdp_port: None,
+///     require_new_instance: false,
+/// };
 /// let opts = convert_window_args(&args);
 /// assert!(opts.new_window && !opts.incognito && !opts.kiosk);
 /// ```
@@ -1294,7 +3861,19 @@ fn convert_window_args(window_args: &WindowArgs) -> WindowOptions {
     WindowOptions {
         new_window: window_args.new_window,
         incognito: window_args.incognito,
-        kiosk: window_args.kiosk,
+        kiosk: window_args.kiosk || window_args.kiosk_strict,
+        kiosk_strict: window_args.kiosk_strict,
+        tor_window: window_args.tor_window,
+        sandbox: window_args.sandbox.map(|tool| match tool {
+            SandboxArg::Firejail => SandboxTool::Firejail,
+            SandboxArg::Bwrap => SandboxTool::Bwrap,
+        }),
+        new_instance: window_args.new_instance,
+        arc_space: window_args.arc_space.clone(),
+        edge_workspace: window_args.edge_workspace.clone(),
+        window_name: window_args.window_name.clone(),
+        cdp_port: window_args.cdp_port,
+        require_new_instance: window_args.require_new_instance,
     }
 }
 
@@ -1419,12 +3998,12 @@ impl WindowOptionsJson {
     /// Create a JSON-serializable representation of window options.
     ///
     /// Converts a Pathway `WindowOptions` into the module's `WindowOptionsJson` shape
-    /// by copying the `new_window`, `incognito`, and `kiosk` flags.
+    /// by copying each window option.
     ///
     /// # Examples
     ///
     /// ```
-    /// let opts = WindowOptions { new_window: true, incognito: false, kiosk: false };
+    /// let opts = WindowOptions { new_window: true, ..Default::default() };
     /// let json = WindowOptionsJson::from_window_options(&opts);
     /// assert_eq!(json.new_window, true);
     /// assert_eq!(json.incognito, false);
@@ -1435,7 +4014,125 @@ impl WindowOptionsJson {
             new_window: window_opts.new_window,
             incognito: window_opts.incognito,
             kiosk: window_opts.kiosk,
+            kiosk_strict: window_opts.kiosk_strict,
+            tor_window: window_opts.tor_window,
+            sandbox: window_opts.sandbox,
+            new_instance: window_opts.new_instance,
+            arc_space: window_opts.arc_space.clone(),
+            edge_workspace: window_opts.edge_workspace.clone(),
+            window_name: window_opts.window_name.clone(),
+            cdp_port: window_opts.cdp_port,
+            require_new_instance: window_opts.require_new_instance,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ShortcutResponse {
+    action: &'static str,
+    browser: String,
+    name: String,
+    path: PathBuf,
+    command: Vec<String>,
+}
+
+/// Handles `shortcut create`: writes a launcher for `pathway launch` with the browser,
+/// channel and profile given, and prints where it went.
+fn handle_shortcut_command(
+    inventory: &BrowserInventory,
+    action: ShortcutAction,
+    format: OutputFormat,
+) {
+    let ShortcutAction::Create {
+        browser,
+        channel,
+        profile,
+        name,
+        output,
+        icon,
+    } = action;
+    let fail = |error_msg: String| -> ! {
+        if format == OutputFormat::Human {
+            error!("{}", error_msg);
+        } else {
+            print_profile_error_json("create-shortcut", &browser, error_msg);
+        }
+        process::exit(1);
+    };
+
+    let Some(info) = select_browser(inventory, Some(&browser), channel.as_deref(), false) else {
+        fail(format!(
+            "Browser '{}' not found. Available browsers: {}",
+            browser,
+            available_tokens(&inventory.browsers).join(", ")
+        ));
+    };
+    if let Some(profile) = &profile {
+        // Profile aliases from the config file only resolve at launch
+        if let Err(e) = ProfileManager::find_profile(info, profile) {
+            if format == OutputFormat::Human {
+                warn!("{}; the shortcut passes the name on as given", e);
+            }
+        }
+    }
+
+    let mut args = vec![
+        "launch".to_string(),
+        "--browser".to_string(),
+        browser.clone(),
+    ];
+    if let Some(channel) = &channel {
+        args.extend(["--channel".to_string(), channel.clone()]);
+    }
+    if let Some(profile) = &profile {
+        args.extend(["--profile".to_string(), profile.clone()]);
+    }
+    let program = match std::env::current_exe() {
+        Ok(program) => program,
+        Err(e) => fail(format!("Failed to locate the pathway executable: {}", e)),
+    };
+    let icon = icon.or_else(|| match info.unique_id.strip_suffix(".desktop") {
+        Some(desktop_id) => Some(desktop_id.to_string()),
+        None if cfg!(windows) => Some(info.executable_path.to_string_lossy().into_owned()),
+        None if cfg!(target_os = "macos") => {
+            app_icon(&info.executable_path).map(|icon| icon.to_string_lossy().into_owned())
         }
+        None => None,
+    });
+    let shortcut = Shortcut {
+        name: name.unwrap_or_else(|| match &profile {
+            Some(profile) => format!("{} ({})", info.display_name, profile),
+            None => info.display_name.clone(),
+        }),
+        program,
+        args,
+        icon,
+    };
+
+    let Some(dir) = output.or_else(default_shortcut_dir) else {
+        fail("Could not determine where to put shortcuts; pass --output".to_string());
+    };
+    let path = match create_shortcut(&shortcut, &dir) {
+        Ok(path) => path,
+        Err(e) => fail(format!(
+            "Failed to create shortcut '{}': {}",
+            shortcut.name, e
+        )),
+    };
+
+    if format == OutputFormat::Human {
+        eprintln!("Created shortcut '{}' at {}", shortcut.name, path.display());
+    } else {
+        let response = ShortcutResponse {
+            action: "create-shortcut",
+            browser: info.display_name.clone(),
+            name: shortcut.name,
+            path,
+            command: std::iter::once(shortcut.program.to_string_lossy().into_owned())
+                .chain(shortcut.args)
+                .collect(),
+        };
+        println!("{}", serde_json::to_string_pretty(&response).unwrap());
     }
 }
 
@@ -1460,6 +4157,9 @@ fn print_launch_error_json(normalized_urls: &[String], results: &[ValidatedUrl],
         profile: None,
         window_options: None,
         command: None,
+        reused_existing: None,
+        launches: None,
+        handed_off: None,
         message: Some(message.to_string()),
     };
     println!("{}", serde_json::to_string_pretty(&response).unwrap());
@@ -1502,6 +4202,9 @@ fn build_launch_json_response(
             None
         },
         command,
+        reused_existing: None,
+        launches: None,
+        handed_off: None,
         message,
     }
 }
@@ -1550,6 +4253,9 @@ fn handle_url_validation_error(
             profile: None,
             window_options: None,
             command: None,
+            reused_existing: None,
+            launches: None,
+            handed_off: None,
             message: Some("URL validation failed".to_string()),
         };
         println!("{}", serde_json::to_string_pretty(&response).unwrap());
@@ -1567,6 +4273,11 @@ fn generate_browser_warnings(
 ) -> Vec<String> {
     let mut warnings = Vec::new();
 
+    // Logged once right after detection; only recorded here for JSON output.
+    if inventory.detection_incomplete {
+        warnings.push(DETECTION_INCOMPLETE_WARNING.to_string());
+    }
+
     if is_fallback {
         debug_assert!(
             selected_browser.is_some(),
@@ -1651,10 +4362,11 @@ fn handle_no_launch_response(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pathway::browser::arch::Arch;
     #[cfg(target_os = "macos")]
     use pathway::browser::channels::SafariChannel;
     use pathway::browser::channels::{BrowserChannel, ChromiumChannel, FirefoxChannel};
-    use pathway::BrowserKind;
+    use pathway::{BrowserKind, InstallScope, InstallationSource};
 
     fn make_inventory(mut browsers: Vec<BrowserInfo>) -> BrowserInventory {
         let system_default = SystemDefaultBrowser {
@@ -1669,6 +4381,7 @@ mod tests {
         BrowserInventory {
             browsers,
             system_default,
+            detection_incomplete: false,
         }
     }
 
@@ -1681,6 +4394,10 @@ mod tests {
             version: Some("1.2.3".into()),
             unique_id: format!("chrome-{}", channel.canonical_name()),
             exec_command: None,
+            source: InstallationSource::Unknown,
+            scope: InstallScope::System,
+            arch: None,
+            is_default: false,
         }
     }
 
@@ -1693,6 +4410,10 @@ mod tests {
             version: Some("1.2.3".into()),
             unique_id: format!("firefox-{}", channel.canonical_name()),
             exec_command: None,
+            source: InstallationSource::Unknown,
+            scope: InstallScope::System,
+            arch: None,
+            is_default: false,
         }
     }
 
@@ -1706,6 +4427,10 @@ mod tests {
             version: Some("17.0".into()),
             unique_id: "com.apple.Safari".into(),
             exec_command: None,
+            source: InstallationSource::Unknown,
+            scope: InstallScope::System,
+            arch: None,
+            is_default: false,
         }
     }
 
@@ -1722,6 +4447,10 @@ mod tests {
             version: Some("1.2.3".into()),
             unique_id: "edge-stable".into(),
             exec_command: None,
+            source: InstallationSource::Unknown,
+            scope: InstallScope::System,
+            arch: None,
+            is_default: false,
         }
     }
 
@@ -1767,6 +4496,337 @@ mod tests {
         assert_eq!(chosen.display_name, "Google Chrome Canary");
     }
 
+    #[test]
+    fn select_browser_prefers_per_user_installs_unless_scoped() {
+        let mut per_user = chromium_browser("Google Chrome", ChromiumChannel::Stable);
+        per_user.scope = InstallScope::User;
+        let inventory = make_inventory(vec![
+            chromium_browser("Google Chrome", ChromiumChannel::Stable),
+            per_user,
+        ]);
+
+        let chosen = select_browser(&inventory, Some("chrome"), None, false)
+            .expect("expected browser selection");
+        assert_eq!(chosen.scope, InstallScope::User);
+
+        let chosen = select_browser(&inventory, Some("chrome@system"), None, false)
+            .expect("expected system-wide install");
+        assert_eq!(chosen.scope, InstallScope::System);
+
+        let chosen = select_browser(&inventory, Some("chrome-stable@user"), None, false)
+            .expect("expected per-user install");
+        assert_eq!(chosen.scope, InstallScope::User);
+    }
+
+    #[test]
+    fn path_filter_picks_one_copy_of_a_browser() {
+        let mut user_copy = chromium_browser("Google Chrome", ChromiumChannel::Stable);
+        user_copy.executable_path =
+            PathBuf::from("/Users/me/Applications/Google Chrome.app/Contents/MacOS/Google Chrome");
+        let mut inventory = make_inventory(vec![
+            chromium_browser("Google Chrome", ChromiumChannel::Stable),
+            user_copy,
+        ]);
+
+        retain_paths_containing(&mut inventory, "/users/me/");
+
+        let chosen = select_browser(&inventory, Some("chrome"), None, false)
+            .expect("expected the copy in the home folder");
+        assert!(chosen.executable_path.starts_with("/Users/me"));
+        assert_eq!(inventory.browsers.len(), 1);
+    }
+
+    #[test]
+    fn browser_tokens_split_into_kind_and_channel() {
+        assert_eq!(split_browser_token("chrome"), ("chrome", None));
+        assert_eq!(
+            split_browser_token("chrome-canary"),
+            ("chrome", Some("canary"))
+        );
+        assert_eq!(
+            split_browser_token("ungoogled-chromium"),
+            ("ungoogled-chromium", None)
+        );
+    }
+
+    #[test]
+    fn routed_urls_are_grouped_by_browser_and_profile() {
+        let config: Config = toml::from_str(
+            r#"
+            [chrome.profiles]
+            work = "Profile 3"
+
+            [[route]]
+            host = "*.corp.example"
+            browser = "chrome-beta"
+            profile = "work"
+
+            [[route]]
+            host = "mail.example"
+            browser = "chrome-beta"
+            profile = "work"
+
+            [[route]]
+            host = "old.example"
+            browser = "chrome-canary"
+            "#,
+        )
+        .unwrap();
+        let inventory = make_inventory(vec![
+            chromium_browser("Google Chrome", ChromiumChannel::Stable),
+            chromium_browser("Google Chrome Beta", ChromiumChannel::Beta),
+        ]);
+        let (results, has_error) = validate_urls(
+            &[
+                "https://wiki.corp.example/".to_string(),
+                "https://example.com/".to_string(),
+                "https://mail.example/".to_string(),
+                "https://old.example/".to_string(),
+            ],
+            &ValidationOptions::default(),
+            OutputFormat::Json,
+        );
+        assert!(!has_error);
+        let default = LaunchGroup {
+            browser: inventory
+                .browsers
+                .iter()
+                .find(|b| b.display_name == "Google Chrome"),
+            profile_options: ProfileOptions {
+                profile_type: ProfileType::Default,
+                custom_args: Vec::new(),
+            },
+            urls: Vec::new(),
+        };
+        let mut warnings = Vec::new();
+
+        let groups = group_launches(
+            &inventory,
+            &results,
+            &config.route,
+            default,
+            &WindowOptions::default(),
+            &config,
+            &mut warnings,
+        );
+
+        let summary: Vec<_> = groups
+            .iter()
+            .map(|group| {
+                (
+                    group.browser.map(|b| b.display_name.as_str()),
+                    &group.profile_options.profile_type,
+                    group.urls.len(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    Some("Google Chrome Beta"),
+                    &ProfileType::Named("Profile 3".to_string()),
+                    2
+                ),
+                (Some("Google Chrome"), &ProfileType::Default, 2),
+            ]
+        );
+        assert_eq!(
+            groups[1].urls,
+            ["https://example.com/", "https://old.example/"]
+        );
+        assert!(warnings.iter().any(|w| w.contains("chrome-canary")));
+    }
+
+    #[test]
+    fn routes_can_require_native_builds() {
+        let Some(host) = Arch::host() else {
+            return;
+        };
+        let emulated = if host == Arch::Arm64 {
+            Arch::X86_64
+        } else {
+            Arch::Arm64
+        };
+        let config: Config = toml::from_str(
+            r#"
+            [[route]]
+            host = "native.example"
+            browser = "chrome-beta"
+            native_only = true
+
+            [[route]]
+            host = "any.example"
+            browser = "chrome-beta"
+            "#,
+        )
+        .unwrap();
+        let mut beta = chromium_browser("Google Chrome Beta", ChromiumChannel::Beta);
+        beta.arch = Some(emulated);
+        let inventory = make_inventory(vec![
+            chromium_browser("Google Chrome", ChromiumChannel::Stable),
+            beta,
+        ]);
+        let (results, _) = validate_urls(
+            &[
+                "https://native.example/".to_string(),
+                "https://any.example/".to_string(),
+            ],
+            &ValidationOptions::default(),
+            OutputFormat::Json,
+        );
+        let default = LaunchGroup {
+            browser: inventory
+                .browsers
+                .iter()
+                .find(|b| b.display_name == "Google Chrome"),
+            profile_options: ProfileOptions {
+                profile_type: ProfileType::Default,
+                custom_args: Vec::new(),
+            },
+            urls: Vec::new(),
+        };
+        let mut warnings = Vec::new();
+
+        let groups = group_launches(
+            &inventory,
+            &results,
+            &config.route,
+            default,
+            &WindowOptions::default(),
+            &config,
+            &mut warnings,
+        );
+
+        let browsers: Vec<_> = groups
+            .iter()
+            .map(|group| {
+                (
+                    group.browser.unwrap().display_name.as_str(),
+                    &group.urls[..],
+                )
+            })
+            .collect();
+        assert_eq!(
+            browsers,
+            [
+                (
+                    "Google Chrome",
+                    &["https://native.example/".to_string()][..]
+                ),
+                (
+                    "Google Chrome Beta",
+                    &["https://any.example/".to_string()][..]
+                ),
+            ]
+        );
+        assert!(warnings.iter().any(|w| w.contains("native build")));
+    }
+
+    #[test]
+    fn unlaunched_ephemeral_copies_are_removed() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("copy");
+        std::fs::create_dir_all(path.join("Default")).unwrap();
+
+        drop(EphemeralCopy {
+            path: path.clone(),
+            launched: false,
+        });
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn configured_default_profile_applies_without_profile_flags() {
+        let config: Config = toml::from_str(
+            r#"
+            [chrome]
+            default_profile = "work"
+
+            [chrome.profiles]
+            work = "Profile 3"
+            "#,
+        )
+        .unwrap();
+        let chrome = chromium_browser("Google Chrome", ChromiumChannel::Stable);
+        let args = |profile: Option<&str>, guest: bool| ProfileArgs {
+            profile: profile.map(str::to_string),
+            profile_email: None,
+            user_dir: None,
+            temp_profile: false,
+            guest,
+            ephemeral_copy: None,
+        };
+        let profile_type = |args: &ProfileArgs, browser| {
+            convert_profile_args(args, browser, &config, &mut Vec::new())
+                .unwrap()
+                .profile_type
+        };
+
+        assert!(matches!(
+            profile_type(&args(None, false), Some(&chrome)),
+            ProfileType::Named(name) if name == "Profile 3"
+        ));
+        assert!(matches!(
+            profile_type(&args(Some("Default"), false), Some(&chrome)),
+            ProfileType::Named(name) if name == "Default"
+        ));
+        assert!(matches!(
+            profile_type(&args(None, true), Some(&chrome)),
+            ProfileType::Guest
+        ));
+        assert!(matches!(
+            profile_type(&args(None, false), None),
+            ProfileType::Default
+        ));
+    }
+
+    #[test]
+    fn profiles_in_custom_dirs_are_opened_by_path() {
+        let profile = |kind, name: &str, path: &str| ProfileInfo {
+            name: name.to_string(),
+            display_name: "Work".to_string(),
+            path: PathBuf::from(path),
+            is_default: false,
+            last_used: None,
+            browser_kind: kind,
+            in_use: false,
+            identity: None,
+            workspaces: Vec::new(),
+            arc_space: None,
+        };
+        let chrome = chromium_browser("Google Chrome", ChromiumChannel::Stable);
+        let firefox = firefox_browser("Mozilla Firefox", FirefoxChannel::Stable);
+        let custom_dir = Some(Path::new("/data/profiles"));
+        let chrome_profile = profile(BrowserKind::Chrome, "Profile 1", "/data/profiles/Profile 1");
+        let firefox_profile = profile(BrowserKind::Firefox, "work", "/data/profiles/b2.work");
+
+        let options = open_profile_options(&chrome, &chrome_profile, None);
+        assert!(matches!(&options.profile_type, ProfileType::Named(name) if name == "Profile 1"));
+        assert!(options.custom_args.is_empty());
+
+        let new_window = WindowOptions {
+            new_window: true,
+            ..WindowOptions::default()
+        };
+        let args = |browser, profile| {
+            let options = open_profile_options(browser, profile, custom_dir);
+            ProfileManager::generate_profile_args(browser, &options, &new_window)
+        };
+        assert_eq!(
+            args(&chrome, &chrome_profile),
+            vec![
+                "--user-data-dir=/data/profiles",
+                "--new-window",
+                "--profile-directory=Profile 1",
+            ]
+        );
+        assert_eq!(
+            args(&firefox, &firefox_profile),
+            vec!["--profile", "/data/profiles/b2.work", "--new-window"]
+        );
+    }
+
     #[test]
     fn fallback_browser_prefers_platform_defaults() {
         #[allow(unused_mut)]
@@ -1799,4 +4859,11 @@ mod tests {
             panic!("Expected fallback browser to be available");
         }
     }
+
+    #[test]
+    fn short_links_are_looked_up_by_their_normalized_url() {
+        let urls = vec!["bit.ly/abc".to_string(), "http://".to_string()];
+        let candidates = short_link_candidates(&urls, &ValidationOptions::default());
+        assert_eq!(candidates, ["https://bit.ly/abc", "http://"]);
+    }
 }
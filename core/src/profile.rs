@@ -1,17 +1,37 @@
 use crate::browser::channels::{BrowserChannel, ChromiumChannel};
+use crate::browser::instance;
+use crate::browser::sources::{self, InstallationSource, LinuxInstallationSource};
 use crate::browser::{BrowserInfo, BrowserKind};
-use crate::filesystem::FileSystem;
+use crate::filesystem::{FileSystem, RealFileSystem};
 use serde::Serialize;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
+use timestamp::Timestamp;
 use tracing::{debug, warn};
 
+pub mod archive;
+pub mod backup;
+pub mod extensions;
+pub mod guest;
+mod installs;
+pub mod launches;
+pub mod timestamp;
+pub mod usage;
+
 #[derive(Debug, Error)]
 pub enum ProfileError {
     #[error("Profile '{0}' not found")]
     ProfileNotFound(String),
+    #[error("Several profiles are signed in as {0}: {1}")]
+    AmbiguousEmail(String, String),
+    #[error("Profile '{0}' is ambiguous, candidates were: {1}")]
+    AmbiguousProfile(String, String),
+    #[error("Profile '{0}' already exists")]
+    ProfileExists(String),
+    #[error("Profile '{0}' is the default profile")]
+    DefaultProfile(String),
     #[error("Invalid profile directory: {0}")]
     InvalidDirectory(String),
     #[error("Permission denied: {0}")]
@@ -22,6 +42,12 @@ pub enum ProfileError {
     JsonError(#[from] serde_json::Error),
     #[error("Browser does not support profiles: {0}")]
     UnsupportedBrowser(String),
+    #[error("Invalid profile archive: {0}")]
+    InvalidArchive(String),
+    #[error("Guest mode is disabled for {0} by policy ({1})")]
+    GuestModeDisabled(String, String),
+    #[error("Profile directory is still locked: {0}")]
+    StillLocked(String),
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -30,17 +56,79 @@ pub struct ProfileInfo {
     pub display_name: String,
     pub path: PathBuf,
     pub is_default: bool,
-    pub last_used: Option<String>,
+    pub last_used: Option<Timestamp>,
     pub browser_kind: BrowserKind,
+    /// A running browser holds the lock on the profile, or on its user data directory and
+    /// lists the profile as open there
+    pub in_use: bool,
+    /// Signed-in account and avatar (Chromium only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity: Option<ProfileIdentity>,
+    /// Workspaces that group the profile's tabs (Vivaldi only)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub workspaces: Vec<VivaldiWorkspace>,
+    /// Title of the Arc Space this entry opens; `path` is the profile directory it uses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arc_space: Option<String>,
+}
+
+/// Account and avatar details Chromium keeps for a profile in `Local State`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ProfileIdentity {
+    /// Email of the signed-in account (`user_name`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// Full name of the signed-in account (`gaia_name`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_name: Option<String>,
+    /// Built-in avatar, e.g. `chrome://theme/IDR_PROFILE_AVATAR_26` (`avatar_icon`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar_icon: Option<String>,
+    /// The account picture is shown instead of the built-in avatar (`use_gaia_picture`)
+    pub uses_account_picture: bool,
+}
+
+impl ProfileIdentity {
+    /// Reads the identity from a `profile.info_cache` entry; `None` when it has none.
+    fn from_info_cache(entry: &serde_json::Value) -> Option<Self> {
+        let text = |key: &str| {
+            entry
+                .get(key)
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        let identity = ProfileIdentity {
+            email: text("user_name"),
+            account_name: text("gaia_name"),
+            avatar_icon: text("avatar_icon"),
+            uses_account_picture: entry
+                .get("use_gaia_picture")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        };
+        (identity != ProfileIdentity::default()).then_some(identity)
+    }
 }
 
+/// A temporary profile directory left behind by an earlier `--temp-profile` launch.
 #[derive(Debug, Clone, Serialize)]
+pub struct TempProfile {
+    pub path: PathBuf,
+    /// Seconds since the directory or one of its entries last changed
+    pub age_secs: u64,
+    pub size_bytes: u64,
+    /// A browser still holds the profile lock
+    pub in_use: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ProfileOptions {
     pub profile_type: ProfileType,
     pub custom_args: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum ProfileType {
     Default,
     Named(String),
@@ -54,10 +142,148 @@ pub struct WindowOptions {
     pub new_window: bool,
     pub incognito: bool,
     pub kiosk: bool,
+    /// Wrap the browser process in a sandbox tool (Linux only)
+    pub sandbox: Option<SandboxTool>,
+    /// Start a separate instance of the browser app (macOS only)
+    pub new_instance: bool,
+    /// Arc Space to open the URLs in (macOS only)
+    pub arc_space: Option<String>,
+    /// Edge Workspace (name or id) to open
+    pub edge_workspace: Option<String>,
+    /// Window name (and WM_CLASS on Linux) for window manager rules (Chromium only)
+    pub window_name: Option<String>,
+    /// Add the hardening flags from [`CHROMIUM_KIOSK_STRICT_ARGS`]; implies `kiosk`
+    pub kiosk_strict: bool,
+    /// Open a private window with Tor (Brave only)
+    pub tor_window: bool,
+    /// DevTools port of a running Chromium instance to open tabs in (`cdp` feature)
+    pub cdp_port: Option<u16>,
+    /// Fail instead of handing URLs to an instance already running with the profile
+    pub require_new_instance: bool,
+}
+
+/// Flags added to `--kiosk` by `--kiosk-strict` for Chromium-based browsers.
+///
+/// They print without a dialog, skip first-run and crash-restore UI, and disable pinch
+/// zoom, swipe navigation, error dialogs and the translate bar. Firefox has no equivalent
+/// switches, so strict kiosk mode only adds `--kiosk` there.
+pub const CHROMIUM_KIOSK_STRICT_ARGS: &[&str] = &[
+    "--kiosk-printing",
+    "--no-first-run",
+    "--no-default-browser-check",
+    "--disable-pinch",
+    "--overscroll-history-navigation=0",
+    "--noerrdialogs",
+    "--disable-session-crashed-bubble",
+    "--disable-infobars",
+    "--disable-features=TranslateUI",
+];
+
+/// An Edge Workspace cached in a profile's `Workspaces/WorkspacesCache` file.
+#[derive(Debug, Clone, Serialize)]
+pub struct EdgeWorkspace {
+    pub id: String,
+    pub name: String,
+    /// Profile directory the workspace is cached in
+    pub profile: String,
+}
+
+/// First Edge major version with Workspaces available to all users.
+const EDGE_WORKSPACES_MIN_VERSION: u32 = 114;
+
+/// A Space in Arc's sidebar.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ArcSpace {
+    pub id: String,
+    pub title: String,
+    /// Profile directory the Space belongs to, when it is not the default profile
+    pub profile: Option<String>,
+}
+
+/// A Vivaldi workspace, from `vivaldi.workspaces.list` in the profile's `Preferences`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VivaldiWorkspace {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SandboxTool {
+    Firejail,
+    Bwrap,
+}
+
+/// The file system and home directory profile discovery reads from.
+///
+/// [`ProfileEnv::host`] is the machine pathway runs on; tests pass a mock file system and
+/// a made-up home instead.
+pub struct ProfileEnv<'a> {
+    pub fs: &'a dyn FileSystem,
+    pub home: Option<PathBuf>,
+}
+
+impl ProfileEnv<'static> {
+    pub fn host() -> Self {
+        Self {
+            fs: &RealFileSystem,
+            home: dirs_next::home_dir(),
+        }
+    }
+}
+
+impl ProfileEnv<'_> {
+    fn home(&self) -> Result<&Path, ProfileError> {
+        self.home.as_deref().ok_or_else(|| {
+            ProfileError::InvalidDirectory("Could not determine home directory".to_string())
+        })
+    }
 }
 
 pub struct ProfileManager;
 
+/// Which profiles the lock of a Chromium user data directory stands for.
+enum UserDataLock {
+    Unlocked,
+    /// The running browser has these profile directories open
+    Profiles(Vec<String>),
+    /// Locked, but `Local State` does not tell by which profiles
+    Unknown,
+}
+
+impl UserDataLock {
+    fn of(user_data_dir: &Path) -> Self {
+        if !instance::is_directory_locked(user_data_dir) {
+            return UserDataLock::Unlocked;
+        }
+        let active = fs::read_to_string(user_data_dir.join("Local State"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|local_state| {
+                let profiles = local_state
+                    .pointer("/profile/last_active_profiles")?
+                    .as_array()?
+                    .iter()
+                    .filter_map(|profile| profile.as_str().map(str::to_string))
+                    .collect::<Vec<_>>();
+                (!profiles.is_empty()).then_some(profiles)
+            });
+        match active {
+            Some(profiles) => UserDataLock::Profiles(profiles),
+            None => UserDataLock::Unknown,
+        }
+    }
+
+    fn covers(&self, profile_dir: &str) -> bool {
+        match self {
+            UserDataLock::Unlocked => false,
+            UserDataLock::Profiles(profiles) => profiles.iter().any(|p| p == profile_dir),
+            UserDataLock::Unknown => true,
+        }
+    }
+}
+
 impl ProfileManager {
     /// Discover available profiles for the specified browser.
     ///
@@ -80,9 +306,13 @@ impl ProfileManager {
     /// Discover profiles for `browser` using an optional custom base directory.
     ///
     /// Returns a vector of discovered `ProfileInfo` entries for the given browser:
-    /// - For Chromium-family browsers (Chrome, Edge, Brave, Vivaldi, Arc, Helium, Opera, Chromium)
+    /// - For Chromium-family browsers (Chrome, Edge, Brave, Vivaldi, Helium, Thorium,
+    ///   Chromium, Ungoogled-Chromium, Yandex, Wavebox, Sidekick)
     ///   this delegates to Chromium-specific discovery and may return multiple profiles.
-    /// - For Firefox and Waterfox this delegates to Firefox-specific discovery and may return multiple profiles.
+    /// - For Arc this returns one entry per Space, or its Chromium profiles when it has none.
+    /// - For Opera and Opera GX this returns the main profile followed by any side profiles.
+    /// - For Firefox and its forks (Waterfox, LibreWolf, Zen, Floorp, Pale Moon, Basilisk) this delegates to Firefox-specific
+    ///   discovery and may return multiple profiles.
     /// - For Safari and unknown/other browsers this returns a single default profile whose path is the
     ///   provided `custom_base_dir` (if any) or an empty `PathBuf` otherwise.
     ///
@@ -102,55 +332,71 @@ impl ProfileManager {
     pub fn discover_profiles_in_directory(
         browser: &BrowserInfo,
         custom_base_dir: Option<&Path>,
+    ) -> Result<Vec<ProfileInfo>, ProfileError> {
+        let mut profiles =
+            Self::discover_profiles_with_env(&ProfileEnv::host(), browser, custom_base_dir)?;
+        let mut user_data_locks: HashMap<PathBuf, UserDataLock> = HashMap::new();
+        for profile in &mut profiles {
+            if profile.path.as_os_str().is_empty() {
+                continue;
+            }
+            // Firefox locks the profile itself, Chromium the user data directory above it
+            profile.in_use = instance::is_directory_locked(&profile.path)
+                || profile.path.parent().is_some_and(|user_data_dir| {
+                    let lock = user_data_locks
+                        .entry(user_data_dir.to_path_buf())
+                        .or_insert_with(|| UserDataLock::of(user_data_dir));
+                    profile
+                        .path
+                        .file_name()
+                        .is_some_and(|name| lock.covers(&name.to_string_lossy()))
+                });
+        }
+        Ok(profiles)
+    }
+
+    /// Discovery proper, reading through `env`; `in_use` is filled in by
+    /// [`Self::discover_profiles_in_directory`].
+    pub fn discover_profiles_with_env(
+        env: &ProfileEnv,
+        browser: &BrowserInfo,
+        custom_base_dir: Option<&Path>,
     ) -> Result<Vec<ProfileInfo>, ProfileError> {
         match browser.kind {
             BrowserKind::Chrome
             | BrowserKind::Edge
             | BrowserKind::Brave
             | BrowserKind::Vivaldi
-            | BrowserKind::Arc
             | BrowserKind::Helium
+            | BrowserKind::Thorium
+            | BrowserKind::UngoogledChromium
+            | BrowserKind::Yandex
+            | BrowserKind::Wavebox
+            | BrowserKind::Sidekick
             | BrowserKind::Chromium => {
-                Self::discover_chromium_profiles_in_dir(browser, custom_base_dir)
+                Self::discover_chromium_profiles_with_env(env, browser, custom_base_dir)
             }
-            BrowserKind::Firefox | BrowserKind::Waterfox => {
-                Self::discover_firefox_profiles_in_dir(browser, custom_base_dir)
+            BrowserKind::Arc => Self::discover_arc_profiles_in_dir(env, browser, custom_base_dir),
+            BrowserKind::Firefox
+            | BrowserKind::Waterfox
+            | BrowserKind::LibreWolf
+            | BrowserKind::Zen
+            | BrowserKind::Floorp
+            | BrowserKind::PaleMoon
+            | BrowserKind::Basilisk => {
+                Self::discover_firefox_profiles_in_dir(env, browser, custom_base_dir)
             }
             BrowserKind::Safari => {
-                // Safari doesn't support multiple profiles
-                let path = match custom_base_dir {
-                    Some(dir) => dir.to_path_buf(),
-                    None => Self::get_default_browser_dir(browser)?,
-                };
-                Ok(vec![ProfileInfo {
-                    name: "default".to_string(),
-                    display_name: "Default".to_string(),
-                    path,
-                    is_default: true,
-                    last_used: None,
-                    browser_kind: browser.kind,
-                }])
+                Self::discover_safari_profiles_in_dir(env, browser, custom_base_dir)
             }
             BrowserKind::Opera => {
-                // Opera uses Chromium-based profiles but we don't have channel support yet
-                let path = match custom_base_dir {
-                    Some(dir) => dir.to_path_buf(),
-                    None => Self::get_default_browser_dir(browser)?,
-                };
-                Ok(vec![ProfileInfo {
-                    name: "default".to_string(),
-                    display_name: "Default".to_string(),
-                    path,
-                    is_default: true,
-                    last_used: None,
-                    browser_kind: browser.kind,
-                }])
+                Self::discover_opera_profiles_in_dir(env, browser, custom_base_dir)
             }
             _ => {
                 // Other browsers - assume single profile
                 let path = match custom_base_dir {
                     Some(dir) => dir.to_path_buf(),
-                    None => Self::get_default_browser_dir(browser)?,
+                    None => Self::default_browser_dir_with_env(env, browser)?,
                 };
                 Ok(vec![ProfileInfo {
                     name: "default".to_string(),
@@ -159,6 +405,10 @@ impl ProfileManager {
                     is_default: true,
                     last_used: None,
                     browser_kind: browser.kind,
+                    in_use: false,
+                    identity: None,
+                    workspaces: Vec::new(),
+                    arc_space: None,
                 }])
             }
         }
@@ -191,11 +441,13 @@ impl ProfileManager {
     /// Find and return a profile whose `name` or `display_name` matches `profile_name`.
     ///
     /// This performs profile discovery (optionally under `custom_base_dir`) and searches the
-    /// resulting profiles for an exact match against either `ProfileInfo::name` or
-    /// `ProfileInfo::display_name`. If found, the matching `ProfileInfo` is returned.
+    /// resulting profiles for `ProfileInfo::name` or `ProfileInfo::display_name`: exactly, then
+    /// ignoring case, then by prefix and finally by any part of the name, so `work` finds
+    /// "Work (Contoso)". If found, the matching `ProfileInfo` is returned.
     ///
     /// Errors:
     /// - Returns `ProfileError::ProfileNotFound` if no matching profile is found.
+    /// - Returns `ProfileError::AmbiguousProfile` if several profiles match equally well.
     /// - Propagates errors returned by `discover_profiles_in_directory` (I/O, parsing, etc.).
     ///
     /// # Examples
@@ -216,16 +468,124 @@ impl ProfileManager {
         profile_name: &str,
         custom_base_dir: Option<&Path>,
     ) -> Result<ProfileInfo, ProfileError> {
-        let profiles = Self::discover_profiles_in_directory(browser, custom_base_dir)?;
-        profiles
-            .into_iter()
-            .find(|p| p.name == profile_name || p.display_name == profile_name)
-            .ok_or_else(|| ProfileError::ProfileNotFound(profile_name.to_string()))
+        let mut profiles = Self::discover_profiles_in_directory(browser, custom_base_dir)?;
+        let index = Self::match_profile(&profiles, profile_name)?;
+        Ok(profiles.swap_remove(index))
+    }
+
+    /// Like [`Self::find_profile_in_directory`], but `profile_name` must be the whole name or
+    /// display name, ignoring case. Commands that change or copy a profile use this, so a
+    /// partial name can't pick the wrong one.
+    pub fn find_profile_exactly_in_directory(
+        browser: &BrowserInfo,
+        profile_name: &str,
+        custom_base_dir: Option<&Path>,
+    ) -> Result<ProfileInfo, ProfileError> {
+        let mut profiles = Self::discover_profiles_in_directory(browser, custom_base_dir)?;
+        let index = Self::match_profile_exactly(&profiles, profile_name)?;
+        Ok(profiles.swap_remove(index))
+    }
+
+    /// Index of the profile `query` names, trying looser matches only when stricter ones find
+    /// nothing. Several exact matches resolve to the first; several looser ones are ambiguous.
+    fn match_profile(profiles: &[ProfileInfo], query: &str) -> Result<usize, ProfileError> {
+        Self::match_profile_in_tiers(profiles, query, true)
+    }
+
+    /// Index of the profile `query` names in full, ignoring case if nothing matches exactly.
+    fn match_profile_exactly(profiles: &[ProfileInfo], query: &str) -> Result<usize, ProfileError> {
+        Self::match_profile_in_tiers(profiles, query, false)
+    }
+
+    fn match_profile_in_tiers(
+        profiles: &[ProfileInfo],
+        query: &str,
+        loose: bool,
+    ) -> Result<usize, ProfileError> {
+        if let Some(index) = profiles
+            .iter()
+            .position(|p| p.name == query || p.display_name == query)
+        {
+            return Ok(index);
+        }
+
+        let query_lower = query.to_lowercase();
+        let tiers: [&dyn Fn(&str) -> bool; 3] = [
+            &|name| name == query_lower,
+            &|name| name.starts_with(&query_lower),
+            &|name| name.contains(&query_lower),
+        ];
+        let tiers = if loose { &tiers[..] } else { &tiers[..1] };
+        for matches in tiers {
+            let found: Vec<usize> = profiles
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| {
+                    matches(&p.name.to_lowercase()) || matches(&p.display_name.to_lowercase())
+                })
+                .map(|(index, _)| index)
+                .collect();
+            match found.as_slice() {
+                [] => continue,
+                [index] => return Ok(*index),
+                _ => {
+                    return Err(ProfileError::AmbiguousProfile(
+                        query.to_string(),
+                        found
+                            .iter()
+                            .map(|&index| {
+                                format!(
+                                    "{} ({})",
+                                    profiles[index].display_name, profiles[index].name
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    ))
+                }
+            }
+        }
+        Err(ProfileError::ProfileNotFound(query.to_string()))
+    }
+
+    /// Finds the profile signed in to the account `email` (case-insensitive).
+    ///
+    /// Only Chromium-based browsers record the account, in `Local State`. Fails with
+    /// `ProfileError::AmbiguousEmail` when several profiles use the same account.
+    pub fn find_profile_by_email(
+        browser: &BrowserInfo,
+        email: &str,
+        custom_base_dir: Option<&Path>,
+    ) -> Result<ProfileInfo, ProfileError> {
+        let mut matches: Vec<ProfileInfo> =
+            Self::discover_profiles_in_directory(browser, custom_base_dir)?
+                .into_iter()
+                .filter(|profile| {
+                    profile
+                        .identity
+                        .as_ref()
+                        .and_then(|identity| identity.email.as_deref())
+                        .is_some_and(|address| address.eq_ignore_ascii_case(email))
+                })
+                .collect();
+        match matches.len() {
+            0 => Err(ProfileError::ProfileNotFound(email.to_string())),
+            1 => Ok(matches.remove(0)),
+            _ => Err(ProfileError::AmbiguousEmail(
+                email.to_string(),
+                matches
+                    .iter()
+                    .map(|profile| format!("{} ({})", profile.display_name, profile.name))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )),
+        }
     }
 
     /// Build command-line arguments to launch a browser according to the selected profile and window options.
     ///
-    /// Chooses a browser-specific argument builder (Chromium-family, Firefox, Safari) based on `browser.kind`,
+    /// Chooses a browser-specific argument builder (Chromium-family, Firefox, Safari, qutebrowser, Epiphany,
+    /// Falkon) based on `browser.kind`,
     /// then appends any custom arguments from `profile_opts.custom_args`. Returns the full argument list to
     /// pass to the browser executable.
     ///
@@ -253,6 +613,11 @@ impl ProfileManager {
             | BrowserKind::Vivaldi
             | BrowserKind::Arc
             | BrowserKind::Helium
+            | BrowserKind::Thorium
+            | BrowserKind::UngoogledChromium
+            | BrowserKind::Yandex
+            | BrowserKind::Wavebox
+            | BrowserKind::Sidekick
             | BrowserKind::Chromium => {
                 args.extend(Self::chromium_profile_args(
                     browser,
@@ -260,7 +625,13 @@ impl ProfileManager {
                     window_opts,
                 ));
             }
-            BrowserKind::Firefox | BrowserKind::Waterfox => {
+            BrowserKind::Firefox
+            | BrowserKind::Waterfox
+            | BrowserKind::LibreWolf
+            | BrowserKind::Zen
+            | BrowserKind::Floorp
+            | BrowserKind::PaleMoon
+            | BrowserKind::Basilisk => {
                 args.extend(Self::firefox_profile_args(
                     browser,
                     profile_opts,
@@ -270,13 +641,23 @@ impl ProfileManager {
             BrowserKind::Safari => {
                 args.extend(Self::safari_profile_args(profile_opts, window_opts));
             }
+            BrowserKind::Qutebrowser => {
+                args.extend(Self::qutebrowser_profile_args(profile_opts, window_opts));
+            }
+            BrowserKind::Epiphany => {
+                args.extend(Self::epiphany_profile_args(profile_opts, window_opts));
+            }
+            BrowserKind::Falkon => {
+                args.extend(Self::falkon_profile_args(profile_opts, window_opts));
+            }
+            BrowserKind::Konqueror => {
+                // Konqueror opens every URL in a new window and has no profile or private mode flags
+            }
+            BrowserKind::DuckDuckGo => {
+                // DuckDuckGo accepts nothing but URLs on its command line
+            }
             BrowserKind::Opera => {
-                // Opera uses Chromium-based arguments but we don't have channel support yet
-                args.extend(Self::chromium_profile_args(
-                    browser,
-                    profile_opts,
-                    window_opts,
-                ));
+                args.extend(Self::opera_profile_args(browser, profile_opts, window_opts));
             }
             _ => {
                 // Other browsers - basic window management only
@@ -377,12 +758,213 @@ impl ProfileManager {
     /// // assert!(dir.exists() && dir.is_dir());
     /// ```
     pub fn create_temp_profile() -> Result<PathBuf, ProfileError> {
-        let temp_dir =
-            std::env::temp_dir().join(format!("pathway_profile_{}", generate_timestamp_id()));
+        Self::create_temp_profile_in(&std::env::temp_dir())
+    }
+
+    /// Create a temporary profile directory in a location the given browser can access.
+    ///
+    /// Confined snap browsers get a private `/tmp`, so their temporary profiles are created
+    /// under `~/snap/<name>/common` instead of the system temporary directory.
+    pub fn create_temp_profile_for(browser: &BrowserInfo) -> Result<PathBuf, ProfileError> {
+        if browser.source == InstallationSource::Linux(LinuxInstallationSource::Snap) {
+            if let (Some(snap), Some(home)) = (
+                sources::snap_name(&browser.executable_path),
+                dirs_next::home_dir(),
+            ) {
+                return Self::create_temp_profile_in(&home.join("snap").join(snap).join("common"));
+            }
+        }
+
+        Self::create_temp_profile()
+    }
+
+    fn create_temp_profile_in(base_dir: &Path) -> Result<PathBuf, ProfileError> {
+        let temp_dir = base_dir.join(format!(
+            "{}{}",
+            TEMP_PROFILE_PREFIX,
+            generate_timestamp_id()
+        ));
         fs::create_dir_all(&temp_dir)?;
         Ok(temp_dir)
     }
 
+    /// The profile `source` that [`Self::create_ephemeral_copy`] copies, and whether `browser`
+    /// is Firefox-family, checked without copying anything.
+    pub fn ephemeral_copy_source(
+        browser: &BrowserInfo,
+        source: &str,
+    ) -> Result<(ProfileInfo, bool), ProfileError> {
+        let original = Self::find_profile_exactly_in_directory(browser, source, None)?;
+        if original.path.as_os_str().is_empty() || original.arc_space.is_some() {
+            return Err(ProfileError::ProfileNotFound(source.to_string()));
+        }
+        let is_firefox = matches!(
+            browser.kind,
+            BrowserKind::Firefox
+                | BrowserKind::Waterfox
+                | BrowserKind::LibreWolf
+                | BrowserKind::Zen
+                | BrowserKind::Floorp
+                | BrowserKind::PaleMoon
+                | BrowserKind::Basilisk
+        );
+        let is_chromium = matches!(
+            browser.kind,
+            BrowserKind::Chrome
+                | BrowserKind::Edge
+                | BrowserKind::Brave
+                | BrowserKind::Vivaldi
+                | BrowserKind::Helium
+                | BrowserKind::Thorium
+                | BrowserKind::UngoogledChromium
+                | BrowserKind::Yandex
+                | BrowserKind::Wavebox
+                | BrowserKind::Sidekick
+                | BrowserKind::Chromium
+        );
+        if !is_firefox && !is_chromium {
+            return Err(ProfileError::UnsupportedBrowser(
+                browser.display_name.clone(),
+            ));
+        }
+        Ok((original, is_firefox))
+    }
+
+    /// Copy the profile `source` into a new temporary profile, for launches that must leave
+    /// the original untouched.
+    ///
+    /// Chromium copies become the `Default` profile of a temporary user data directory, next to
+    /// a copy of `Local State`, which holds the key cookies and passwords are encrypted with on
+    /// Windows. Firefox-family copies are the temporary directory itself. Either way the copy
+    /// is launched as a [`ProfileType::Temporary`] profile.
+    pub fn create_ephemeral_copy(
+        browser: &BrowserInfo,
+        source: &str,
+    ) -> Result<PathBuf, ProfileError> {
+        let (original, is_firefox) = Self::ephemeral_copy_source(browser, source)?;
+        let temp_dir = Self::create_temp_profile_for(browser)?;
+        let copied = if is_firefox {
+            copy_profile_dir(&original.path, &temp_dir, FIREFOX_CLONE_SKIP)
+        } else {
+            copy_profile_dir(
+                &original.path,
+                &temp_dir.join("Default"),
+                CHROMIUM_CLONE_SKIP,
+            )
+            .and_then(|()| {
+                let Some(user_data_dir) = original.path.parent() else {
+                    return Ok(());
+                };
+                match fs::read_to_string(user_data_dir.join("Local State")) {
+                    Ok(content) => {
+                        let local_state =
+                            ephemeral_local_state(&content, &original.name).unwrap_or(content);
+                        fs::write(temp_dir.join("Local State"), local_state)?;
+                        Ok(())
+                    }
+                    Err(_) => Ok(()),
+                }
+            })
+        };
+        if let Err(e) = copied {
+            let _ = fs::remove_dir_all(&temp_dir);
+            return Err(e);
+        }
+        Ok(temp_dir)
+    }
+
+    /// Waits until the browser using the temporary profile at `path` lets go of it, then
+    /// deletes it. A browser that has not locked the profile within a minute is taken to have
+    /// failed to start. A lock still held after a day is taken to be stale, as left behind by a
+    /// crashed browser whose pid was reused; the profile is kept for `pathway cleanup` then.
+    pub fn discard_temp_profile(path: &Path) -> Result<(), ProfileError> {
+        Self::discard_temp_profile_within(
+            path,
+            std::time::Duration::from_secs(60),
+            TEMP_PROFILE_MAX_LOCK,
+        )
+    }
+
+    fn discard_temp_profile_within(
+        path: &Path,
+        start_timeout: std::time::Duration,
+        lock_timeout: std::time::Duration,
+    ) -> Result<(), ProfileError> {
+        let is_temp_profile = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(TEMP_PROFILE_PREFIX));
+        if !is_temp_profile || !path.is_dir() {
+            return Err(ProfileError::InvalidDirectory(path.display().to_string()));
+        }
+
+        let poll = std::time::Duration::from_secs(1).min(start_timeout);
+        let started = std::time::Instant::now();
+        while !instance::is_directory_locked(path) && started.elapsed() < start_timeout {
+            std::thread::sleep(poll);
+        }
+        let locked = std::time::Instant::now();
+        while instance::is_directory_locked(path) {
+            if locked.elapsed() >= lock_timeout {
+                return Err(ProfileError::StillLocked(path.display().to_string()));
+            }
+            std::thread::sleep(std::time::Duration::from_secs(2).min(lock_timeout));
+        }
+        fs::remove_dir_all(path)?;
+        Ok(())
+    }
+
+    /// Find temporary profiles left in the places [`Self::create_temp_profile_for`] uses.
+    ///
+    /// Browsers delete nothing on exit, so every `--temp-profile` launch leaves one behind.
+    pub fn find_temp_profiles() -> Vec<TempProfile> {
+        let mut roots = vec![std::env::temp_dir()];
+        if let Some(Ok(snaps)) = dirs_next::home_dir().map(|home| fs::read_dir(home.join("snap"))) {
+            roots.extend(snaps.flatten().map(|snap| snap.path().join("common")));
+        }
+        Self::find_temp_profiles_in(&roots, std::time::SystemTime::now())
+    }
+
+    fn find_temp_profiles_in(roots: &[PathBuf], now: std::time::SystemTime) -> Vec<TempProfile> {
+        let mut profiles = Vec::new();
+        for root in roots {
+            let Ok(entries) = fs::read_dir(root) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let is_temp_profile = entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| name.starts_with(TEMP_PROFILE_PREFIX));
+                if !is_temp_profile || !entry.file_type().is_ok_and(|t| t.is_dir()) {
+                    continue;
+                }
+
+                let path = entry.path();
+                let modified = fs::read_dir(&path)
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .chain(std::iter::once(entry))
+                    .filter_map(|e| e.metadata().ok()?.modified().ok())
+                    .max();
+                let age_secs = modified
+                    .and_then(|modified| now.duration_since(modified).ok())
+                    .map(|age| age.as_secs())
+                    .unwrap_or(0);
+
+                profiles.push(TempProfile {
+                    size_bytes: usage::directory_size(&path),
+                    in_use: instance::is_directory_locked(&path),
+                    path,
+                    age_secs,
+                });
+            }
+        }
+        profiles.sort_by_key(|profile| std::cmp::Reverse(profile.age_secs));
+        profiles
+    }
+
     /// Discover Chromium-based browser profiles by reading the "Local State" file in
     /// the browser's user data directory (or a provided custom base directory).
     ///
@@ -414,24 +996,32 @@ impl ProfileManager {
     pub fn discover_chromium_profiles_in_dir(
         browser: &BrowserInfo,
         custom_base_dir: Option<&Path>,
+    ) -> Result<Vec<ProfileInfo>, ProfileError> {
+        Self::discover_chromium_profiles_with_env(&ProfileEnv::host(), browser, custom_base_dir)
+    }
+
+    fn discover_chromium_profiles_with_env(
+        env: &ProfileEnv,
+        browser: &BrowserInfo,
+        custom_base_dir: Option<&Path>,
     ) -> Result<Vec<ProfileInfo>, ProfileError> {
         let base_dir = match custom_base_dir {
             Some(custom_dir) => custom_dir.to_path_buf(),
-            None => Self::get_chromium_base_dir(browser)?,
+            None => Self::default_browser_dir_with_env(env, browser)?,
         };
         let local_state_path = base_dir.join("Local State");
 
         let mut profiles = Vec::new();
 
-        if local_state_path.exists() {
-            let local_state_content = fs::read_to_string(&local_state_path)?;
+        if env.fs.exists(&local_state_path) {
+            let local_state_content = env.fs.read_to_string(&local_state_path)?;
             let local_state: serde_json::Value = serde_json::from_str(&local_state_content)?;
             if let Some(profile_info) = local_state.get("profile").and_then(|p| p.get("info_cache"))
             {
                 if let Some(profile_obj) = profile_info.as_object() {
                     for (profile_id, profile_data) in profile_obj {
                         let profile_path = base_dir.join(profile_id);
-                        if !profile_path.exists() {
+                        if !env.fs.exists(&profile_path) {
                             continue;
                         }
 
@@ -448,22 +1038,29 @@ impl ProfileManager {
                             display_name,
                             path: profile_path,
                             is_default,
-                            last_used: profile_data.get("active_time").and_then(|t| {
-                                t.as_str()
-                                    .map(|s| s.to_string())
-                                    .or_else(|| t.as_i64().map(|n| n.to_string()))
-                                    .or_else(|| t.as_u64().map(|n| n.to_string()))
-                            }),
+                            last_used: profile_data
+                                .get("active_time")
+                                .and_then(Timestamp::from_chromium_active_time),
                             browser_kind: browser.kind,
+                            in_use: false,
+                            identity: ProfileIdentity::from_info_cache(profile_data),
+                            workspaces: Vec::new(),
+                            arc_space: None,
                         });
                     }
                 }
             }
         }
 
+        if browser.kind == BrowserKind::Vivaldi {
+            for profile in &mut profiles {
+                Self::read_vivaldi_preferences(env.fs, profile);
+            }
+        }
+
         if profiles.is_empty() {
             let default_path = base_dir.join("Default");
-            if default_path.exists() {
+            if env.fs.exists(&default_path) {
                 profiles.push(ProfileInfo {
                     name: "Default".to_string(),
                     display_name: "Default".to_string(),
@@ -471,6 +1068,10 @@ impl ProfileManager {
                     is_default: true,
                     last_used: None,
                     browser_kind: browser.kind,
+                    in_use: false,
+                    identity: None,
+                    workspaces: Vec::new(),
+                    arc_space: None,
                 });
             }
         }
@@ -482,74 +1083,660 @@ impl ProfileManager {
         Ok(profiles)
     }
 
-    /// Discover Firefox profiles by reading a `profiles.ini` file in the Firefox base directory (or a provided custom directory).
-    ///
-    /// Returns a list of discovered `ProfileInfo` entries. If `profiles.ini` is missing or no valid profiles are parsed,
-    /// a single default profile (from `Self::default_profile`) is returned. The function reads and parses `profiles.ini`
-    /// sections, converts each profile section via `Self::parse_firefox_profile`, and preserves the discovery order.
-    ///
-    /// # Errors
+    /// Fill in the name and workspaces Vivaldi keeps in the profile's `Preferences`.
     ///
-    /// Returns a `ProfileError` if the base directory cannot be resolved or if I/O/JSON operations fail while reading the file.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use std::path::Path;
+    /// Vivaldi leaves the `Local State` name at Chromium's placeholder ("Person 1") and
+    /// stores the name set in its own settings as `profile.name`. Unreadable preferences
+    /// leave the profile as it is.
+    fn read_vivaldi_preferences(fs: &dyn FileSystem, profile: &mut ProfileInfo) {
+        let preferences_path = profile.path.join("Preferences");
+        let preferences: serde_json::Value = match fs
+            .read_to_string(&preferences_path)
+            .map_err(ProfileError::from)
+            .and_then(|content| Ok(serde_json::from_str(&content)?))
+        {
+            Ok(preferences) => preferences,
+            Err(e) => {
+                debug!("Could not read {}: {}", preferences_path.display(), e);
+                return;
+            }
+        };
+
+        if let Some(name) = preferences
+            .pointer("/profile/name")
+            .and_then(|n| n.as_str())
+            .filter(|n| !n.is_empty())
+        {
+            profile.display_name = name.to_string();
+        }
+        if let Some(list) = preferences
+            .pointer("/vivaldi/workspaces/list")
+            .and_then(|l| l.as_array())
+        {
+            profile.workspaces = list
+                .iter()
+                .filter_map(|workspace| {
+                    Some(VivaldiWorkspace {
+                        name: workspace.get("name")?.as_str()?.to_string(),
+                        emoji: workspace
+                            .get("emoji")
+                            .and_then(|e| e.as_str())
+                            .filter(|e| !e.is_empty())
+                            .map(str::to_string),
+                    })
+                })
+                .collect();
+        }
+    }
+
+    /// Discover the main profile and the side profiles of Opera and Opera GX.
     ///
-    /// // Example usage (types shown for clarity; construct `browser` according to your codebase):
-    /// // let browser = BrowserInfo { kind: BrowserKind::Firefox, /* ... */ };
-    /// // let profiles = ProfileManager::discover_firefox_profiles_in_dir(&browser, Some(Path::new("/custom/firefox"))).unwrap();
-    /// ```
-    fn discover_firefox_profiles_in_dir(
+    /// Unlike Chromium, Opera usually keeps its main profile directly in the user data
+    /// directory rather than in `Default` (which newer macOS builds do use). Side profiles
+    /// live in `_side_profiles/<id>` and are registered in `Local State` under that path;
+    /// their `<id>` is the profile name.
+    fn discover_opera_profiles_in_dir(
+        env: &ProfileEnv,
         browser: &BrowserInfo,
         custom_base_dir: Option<&Path>,
     ) -> Result<Vec<ProfileInfo>, ProfileError> {
         let base_dir = match custom_base_dir {
             Some(custom_dir) => custom_dir.to_path_buf(),
-            None => Self::get_firefox_base_dir()?,
+            None => Self::default_browser_dir_with_env(env, browser)?,
         };
-        let profiles_ini_path = base_dir.join("profiles.ini");
-
-        if !profiles_ini_path.exists() {
-            debug!("profiles.ini not found at {}", profiles_ini_path.display());
-            return Ok(vec![Self::default_profile(browser.kind)]);
-        }
-
-        let profiles_ini_content = fs::read_to_string(&profiles_ini_path)?;
-        let mut profiles = Vec::new();
-
-        let mut current_profile: Option<HashMap<String, String>> = None;
 
-        for line in profiles_ini_content.lines() {
-            let line = line.trim();
+        let local_state_path = base_dir.join("Local State");
+        let local_state: serde_json::Value = if env.fs.exists(&local_state_path) {
+            serde_json::from_str(&env.fs.read_to_string(&local_state_path)?)?
+        } else {
+            serde_json::Value::Null
+        };
+        let info_cache = |key: &str| local_state.pointer("/profile/info_cache")?.get(key);
+        let profile = |name: String,
+                       path: PathBuf,
+                       is_default: bool,
+                       entry: Option<&serde_json::Value>| ProfileInfo {
+            display_name: entry
+                .and_then(|entry| entry.get("name"))
+                .and_then(|n| n.as_str())
+                .unwrap_or(if is_default { "Default" } else { &name })
+                .to_string(),
+            name,
+            path,
+            is_default,
+            last_used: entry
+                .and_then(|entry| entry.get("active_time"))
+                .and_then(Timestamp::from_chromium_active_time),
+            browser_kind: browser.kind,
+            in_use: false,
+            identity: entry.and_then(ProfileIdentity::from_info_cache),
+            workspaces: Vec::new(),
+            arc_space: None,
+        };
 
-            if line.starts_with('[') && line.ends_with(']') {
-                if let Some(profile_data) = current_profile.take() {
-                    if let Some(profile_info) =
-                        Self::parse_firefox_profile(profile_data, &base_dir, browser.kind)
-                    {
-                        profiles.push(profile_info);
-                    }
-                }
+        let main = if env.fs.is_dir(&base_dir.join("Default")) {
+            profile(
+                "default".to_string(),
+                base_dir.join("Default"),
+                true,
+                info_cache("Default"),
+            )
+        } else {
+            profile(
+                "default".to_string(),
+                base_dir.clone(),
+                true,
+                info_cache(""),
+            )
+        };
+        let mut profiles = vec![main];
 
-                if line.starts_with("[Profile") {
-                    current_profile = Some(HashMap::new());
-                }
-            } else if let Some(ref mut profile_data) = current_profile {
-                if let Some((key, value)) = line.split_once('=') {
-                    profile_data.insert(key.trim().to_string(), value.trim().to_string());
-                }
+        let side_profiles_dir = base_dir.join("_side_profiles");
+        if let Ok(entries) = env.fs.read_dir(&side_profiles_dir) {
+            let mut ids: Vec<String> = entries
+                .into_iter()
+                .filter(|path| env.fs.is_dir(path))
+                .filter_map(|path| path.file_name()?.to_str().map(str::to_string))
+                .collect();
+            ids.sort();
+            for id in ids {
+                let entry = info_cache(&format!("_side_profiles/{}", id));
+                profiles.push(profile(
+                    id.clone(),
+                    side_profiles_dir.join(&id),
+                    false,
+                    entry,
+                ));
             }
         }
 
-        if let Some(profile_data) = current_profile {
-            if let Some(profile_info) =
-                Self::parse_firefox_profile(profile_data, &base_dir, browser.kind)
-            {
-                profiles.push(profile_info);
-            }
-        }
+        Ok(profiles)
+    }
+
+    /// Create a Chromium profile called `name` and register it in `Local State`.
+    ///
+    /// The directory gets the next free `Profile N` name, as when the browser creates a
+    /// profile itself, and `name` is stored as its display name in `profile.info_cache`.
+    /// The browser rewrites `Local State` on exit, so it should not be running meanwhile.
+    ///
+    /// Returns `ProfileError::ProfileExists` when a profile already uses `name`.
+    pub fn create_chromium_profile(
+        browser: &BrowserInfo,
+        name: &str,
+        custom_base_dir: Option<&Path>,
+    ) -> Result<ProfileInfo, ProfileError> {
+        Self::add_chromium_profile(browser, name, custom_base_dir, None)
+    }
+
+    /// Copy the profile `source`, matched like [`Self::find_profile_in_directory`] does, into a
+    /// new profile called `name`.
+    ///
+    /// Logins, extensions and settings come along; caches and lock files are left behind.
+    /// Chromium clones are registered in `Local State` like [`Self::create_chromium_profile`]
+    /// does; Firefox-family clones get a new `profiles.ini` section next to the original.
+    pub fn clone_profile(
+        browser: &BrowserInfo,
+        source: &str,
+        name: &str,
+        custom_base_dir: Option<&Path>,
+    ) -> Result<ProfileInfo, ProfileError> {
+        let profiles = Self::discover_profiles_in_directory(browser, custom_base_dir)?;
+        let original = &profiles[Self::match_profile_exactly(&profiles, source)?];
+        if original.path.as_os_str().is_empty() {
+            return Err(ProfileError::ProfileNotFound(source.to_string()));
+        }
+
+        Self::add_profile_copy(browser, original, name, custom_base_dir)
+    }
+
+    /// Registers a copy of the profile directory at `original.path` as a new profile `name`.
+    pub(crate) fn add_profile_copy(
+        browser: &BrowserInfo,
+        original: &ProfileInfo,
+        name: &str,
+        custom_base_dir: Option<&Path>,
+    ) -> Result<ProfileInfo, ProfileError> {
+        match browser.kind {
+            BrowserKind::Firefox
+            | BrowserKind::Waterfox
+            | BrowserKind::LibreWolf
+            | BrowserKind::Zen
+            | BrowserKind::Floorp
+            | BrowserKind::PaleMoon
+            | BrowserKind::Basilisk => {
+                let taken = Self::discover_profiles_in_directory(browser, custom_base_dir)?
+                    .iter()
+                    .any(|p| !p.path.as_os_str().is_empty() && p.name.eq_ignore_ascii_case(name));
+                if taken {
+                    return Err(ProfileError::ProfileExists(name.to_string()));
+                }
+                let base_dir = match custom_base_dir {
+                    Some(custom_dir) => custom_dir.to_path_buf(),
+                    None => Self::get_default_browser_dir(browser)?,
+                };
+
+                // Firefox names profile directories `<random>.<name>`, next to the others
+                let id = generate_timestamp_id();
+                let slug: String = name
+                    .chars()
+                    .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                    .collect();
+                let dir_name = format!("{}.{}", &id[id.len().saturating_sub(8)..], slug);
+                let parent = match original.path.parent() {
+                    Some(parent) if parent.starts_with(&base_dir) => parent.to_path_buf(),
+                    _ if base_dir.join("Profiles").is_dir() => base_dir.join("Profiles"),
+                    _ => base_dir.clone(),
+                };
+                let path = parent.join(dir_name);
+                copy_profile_dir(&original.path, &path, FIREFOX_CLONE_SKIP)?;
+
+                let profiles_ini_path = base_dir.join("profiles.ini");
+                let content = if profiles_ini_path.exists() {
+                    fs::read_to_string(&profiles_ini_path)?
+                } else {
+                    String::new()
+                };
+                let updated = add_firefox_profile_section(&content, name, &path, &base_dir);
+                fs::write(&profiles_ini_path, updated)?;
+
+                Ok(ProfileInfo {
+                    name: name.to_string(),
+                    display_name: name.to_string(),
+                    path,
+                    is_default: false,
+                    last_used: None,
+                    browser_kind: browser.kind,
+                    in_use: false,
+                    identity: None,
+                    workspaces: Vec::new(),
+                    arc_space: None,
+                })
+            }
+            _ => Self::add_chromium_profile(browser, name, custom_base_dir, Some(original)),
+        }
+    }
+
+    /// Registers a new Chromium profile, copied from `source` when given.
+    fn add_chromium_profile(
+        browser: &BrowserInfo,
+        name: &str,
+        custom_base_dir: Option<&Path>,
+        source: Option<&ProfileInfo>,
+    ) -> Result<ProfileInfo, ProfileError> {
+        if !matches!(
+            browser.kind,
+            BrowserKind::Chrome
+                | BrowserKind::Edge
+                | BrowserKind::Brave
+                | BrowserKind::Vivaldi
+                | BrowserKind::Helium
+                | BrowserKind::Thorium
+                | BrowserKind::UngoogledChromium
+                | BrowserKind::Yandex
+                | BrowserKind::Wavebox
+                | BrowserKind::Sidekick
+                | BrowserKind::Chromium
+        ) {
+            return Err(ProfileError::UnsupportedBrowser(
+                browser.display_name.clone(),
+            ));
+        }
+
+        let base_dir = match custom_base_dir {
+            Some(custom_dir) => custom_dir.to_path_buf(),
+            None => Self::get_default_browser_dir(browser)?,
+        };
+        let local_state_path = base_dir.join("Local State");
+
+        let mut local_state: serde_json::Value = if local_state_path.exists() {
+            serde_json::from_str(&fs::read_to_string(&local_state_path)?)?
+        } else {
+            serde_json::json!({})
+        };
+        let root = local_state.as_object_mut().ok_or_else(|| {
+            ProfileError::InvalidDirectory(format!(
+                "{} is not a JSON object",
+                local_state_path.display()
+            ))
+        })?;
+        let info_cache = root
+            .entry("profile")
+            .or_insert_with(|| serde_json::json!({}))
+            .as_object_mut()
+            .and_then(|profile| {
+                profile
+                    .entry("info_cache")
+                    .or_insert_with(|| serde_json::json!({}))
+                    .as_object_mut()
+            })
+            .ok_or_else(|| {
+                ProfileError::InvalidDirectory(format!(
+                    "{} has no usable profile.info_cache",
+                    local_state_path.display()
+                ))
+            })?;
+
+        let taken = info_cache.iter().any(|(dir, data)| {
+            dir.eq_ignore_ascii_case(name)
+                || data
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .is_some_and(|existing| existing.eq_ignore_ascii_case(name))
+        });
+        if taken {
+            return Err(ProfileError::ProfileExists(name.to_string()));
+        }
+
+        let dir_name = (1..)
+            .map(|n| format!("Profile {}", n))
+            .find(|dir| !info_cache.contains_key(dir) && !base_dir.join(dir).exists())
+            .expect("profile directory numbers are unbounded");
+        let path = base_dir.join(&dir_name);
+
+        let mut entry = serde_json::json!({
+            "name": name,
+            "is_using_default_name": false,
+            "is_using_default_avatar": true,
+        });
+        match source {
+            Some(source) => {
+                copy_profile_dir(&source.path, &path, CHROMIUM_CLONE_SKIP)?;
+                Self::set_chromium_preferences_name(&RealFileSystem, &path, name)?;
+                // Keep the original's avatar and colors
+                if let Some(original) = info_cache
+                    .get(&source.name)
+                    .and_then(|entry| entry.as_object())
+                {
+                    let mut copy = original.clone();
+                    copy.insert("name".to_string(), name.into());
+                    copy.insert("is_using_default_name".to_string(), false.into());
+                    copy.remove("active_time");
+                    entry = copy.into();
+                }
+            }
+            None => fs::create_dir_all(&path)?,
+        }
+
+        info_cache.insert(dir_name.clone(), entry);
+        fs::write(&local_state_path, serde_json::to_string(&local_state)?)?;
+        debug!("Created profile '{}' in {}", name, path.display());
+
+        Ok(ProfileInfo {
+            name: dir_name,
+            display_name: name.to_string(),
+            path,
+            is_default: false,
+            last_used: None,
+            browser_kind: browser.kind,
+            in_use: false,
+            identity: None,
+            workspaces: Vec::new(),
+            arc_space: None,
+        })
+    }
+
+    /// Stores `name` as the profile name in a Chromium profile's `Preferences`, if it has one.
+    fn set_chromium_preferences_name(
+        fs: &dyn FileSystem,
+        profile_dir: &Path,
+        name: &str,
+    ) -> Result<(), ProfileError> {
+        let preferences_path = profile_dir.join("Preferences");
+        if !fs.exists(&preferences_path) {
+            return Ok(());
+        }
+        let mut preferences: serde_json::Value =
+            serde_json::from_str(&fs.read_to_string(&preferences_path)?)?;
+        if let Some(section) = preferences
+            .get_mut("profile")
+            .and_then(|p| p.as_object_mut())
+        {
+            section.insert("name".to_string(), name.into());
+            fs.write(
+                &preferences_path,
+                serde_json::to_string(&preferences)?.as_bytes(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Delete the profile `name` together with its directory.
+    ///
+    /// Chromium profiles are dropped from `Local State`; Firefox-family profiles lose their
+    /// `profiles.ini` section. The default profile is never deleted, and the browser should
+    /// not be running since it keeps its own copy of the profile list.
+    pub fn delete_profile(
+        browser: &BrowserInfo,
+        name: &str,
+        custom_base_dir: Option<&Path>,
+    ) -> Result<ProfileInfo, ProfileError> {
+        let profile = Self::find_profile_exactly_in_directory(browser, name, custom_base_dir)?;
+        if profile.is_default {
+            return Err(ProfileError::DefaultProfile(profile.display_name));
+        }
+        if profile.path.as_os_str().is_empty() {
+            return Err(ProfileError::ProfileNotFound(name.to_string()));
+        }
+
+        let base_dir = match custom_base_dir {
+            Some(custom_dir) => custom_dir.to_path_buf(),
+            None => Self::get_default_browser_dir(browser)?,
+        };
+
+        match browser.kind {
+            BrowserKind::Chrome
+            | BrowserKind::Edge
+            | BrowserKind::Brave
+            | BrowserKind::Vivaldi
+            | BrowserKind::Helium
+            | BrowserKind::Thorium
+            | BrowserKind::UngoogledChromium
+            | BrowserKind::Yandex
+            | BrowserKind::Wavebox
+            | BrowserKind::Sidekick
+            | BrowserKind::Chromium => {
+                let local_state_path = base_dir.join("Local State");
+                let mut local_state: serde_json::Value =
+                    serde_json::from_str(&fs::read_to_string(&local_state_path)?)?;
+                if let Some(profile_state) = local_state
+                    .get_mut("profile")
+                    .and_then(|p| p.as_object_mut())
+                {
+                    if let Some(info_cache) = profile_state
+                        .get_mut("info_cache")
+                        .and_then(|c| c.as_object_mut())
+                    {
+                        info_cache.remove(&profile.name);
+                    }
+                    if let Some(order) = profile_state
+                        .get_mut("profiles_order")
+                        .and_then(|o| o.as_array_mut())
+                    {
+                        order.retain(|dir| dir.as_str() != Some(profile.name.as_str()));
+                    }
+                    // Otherwise the next start would recreate the profile as an empty one
+                    if profile_state.get("last_used").and_then(|l| l.as_str())
+                        == Some(profile.name.as_str())
+                    {
+                        profile_state.insert("last_used".to_string(), "Default".into());
+                    }
+                }
+                fs::write(&local_state_path, serde_json::to_string(&local_state)?)?;
+            }
+            BrowserKind::Firefox
+            | BrowserKind::Waterfox
+            | BrowserKind::LibreWolf
+            | BrowserKind::Zen
+            | BrowserKind::Floorp
+            | BrowserKind::PaleMoon
+            | BrowserKind::Basilisk => {
+                let profiles_ini_path = base_dir.join("profiles.ini");
+                let content = fs::read_to_string(&profiles_ini_path)?;
+                let updated = remove_firefox_profile_section(&content, &profile.name)?;
+                fs::write(&profiles_ini_path, updated)?;
+            }
+            _ => {
+                return Err(ProfileError::UnsupportedBrowser(
+                    browser.display_name.clone(),
+                ))
+            }
+        }
+
+        fs::remove_dir_all(&profile.path)?;
+        debug!(
+            "Deleted profile '{}' at {}",
+            profile.name,
+            profile.path.display()
+        );
+        Ok(profile)
+    }
+
+    /// Change the display name of the profile `name` to `new_name`.
+    ///
+    /// Directory names stay as they are. Chromium keeps the name in both `Local State` and the
+    /// profile's `Preferences`; Firefox-family browsers only in `profiles.ini`, where it is
+    /// also the profile's name.
+    pub fn rename_profile(
+        browser: &BrowserInfo,
+        name: &str,
+        new_name: &str,
+        custom_base_dir: Option<&Path>,
+    ) -> Result<ProfileInfo, ProfileError> {
+        let profiles = Self::discover_profiles_in_directory(browser, custom_base_dir)?;
+        Self::rename_profile_with_env(
+            &ProfileEnv::host(),
+            browser,
+            &profiles,
+            name,
+            new_name,
+            custom_base_dir,
+        )
+    }
+
+    /// Renaming proper among the already discovered `profiles`, writing through `env`.
+    fn rename_profile_with_env(
+        env: &ProfileEnv,
+        browser: &BrowserInfo,
+        profiles: &[ProfileInfo],
+        name: &str,
+        new_name: &str,
+        custom_base_dir: Option<&Path>,
+    ) -> Result<ProfileInfo, ProfileError> {
+        let mut profile = profiles[Self::match_profile_exactly(profiles, name)?].clone();
+        if profile.path.as_os_str().is_empty() {
+            return Err(ProfileError::ProfileNotFound(name.to_string()));
+        }
+        let taken = profiles.iter().any(|p| {
+            p.path != profile.path
+                && (p.name.eq_ignore_ascii_case(new_name)
+                    || p.display_name.eq_ignore_ascii_case(new_name))
+        });
+        if taken {
+            return Err(ProfileError::ProfileExists(new_name.to_string()));
+        }
+
+        let base_dir = match custom_base_dir {
+            Some(custom_dir) => custom_dir.to_path_buf(),
+            None => Self::default_browser_dir_with_env(env, browser)?,
+        };
+
+        match browser.kind {
+            BrowserKind::Chrome
+            | BrowserKind::Edge
+            | BrowserKind::Brave
+            | BrowserKind::Vivaldi
+            | BrowserKind::Helium
+            | BrowserKind::Thorium
+            | BrowserKind::UngoogledChromium
+            | BrowserKind::Yandex
+            | BrowserKind::Wavebox
+            | BrowserKind::Sidekick
+            | BrowserKind::Chromium => {
+                let local_state_path = base_dir.join("Local State");
+                let mut local_state: serde_json::Value =
+                    serde_json::from_str(&env.fs.read_to_string(&local_state_path)?)?;
+                let entry = local_state
+                    .pointer_mut(&format!("/profile/info_cache/{}", profile.name))
+                    .and_then(|entry| entry.as_object_mut())
+                    .ok_or_else(|| ProfileError::ProfileNotFound(name.to_string()))?;
+                entry.insert("name".to_string(), new_name.into());
+                entry.insert("is_using_default_name".to_string(), false.into());
+                env.fs.write(
+                    &local_state_path,
+                    serde_json::to_string(&local_state)?.as_bytes(),
+                )?;
+
+                Self::set_chromium_preferences_name(env.fs, &profile.path, new_name)?;
+            }
+            BrowserKind::Firefox
+            | BrowserKind::Waterfox
+            | BrowserKind::LibreWolf
+            | BrowserKind::Zen
+            | BrowserKind::Floorp
+            | BrowserKind::PaleMoon
+            | BrowserKind::Basilisk => {
+                let profiles_ini_path = base_dir.join("profiles.ini");
+                let content = env.fs.read_to_string(&profiles_ini_path)?;
+                let updated = rename_firefox_profile_section(&content, &profile.name, new_name)?;
+                env.fs.write(&profiles_ini_path, updated.as_bytes())?;
+                profile.name = new_name.to_string();
+            }
+            _ => {
+                return Err(ProfileError::UnsupportedBrowser(
+                    browser.display_name.clone(),
+                ))
+            }
+        }
+
+        profile.display_name = new_name.to_string();
+        Ok(profile)
+    }
+
+    /// Discover Firefox profiles by reading a `profiles.ini` file in the Firefox base directory (or a provided custom directory).
+    ///
+    /// Returns a list of discovered `ProfileInfo` entries. If `profiles.ini` is missing or no valid profiles are parsed,
+    /// a single default profile (from `Self::default_profile`) is returned. The function reads and parses `profiles.ini`
+    /// sections, converts each profile section via `Self::parse_firefox_profile`, and preserves the discovery order.
+    /// The default profile is the one the `[Install…]` section of `browser`'s installation names, falling back to
+    /// the legacy `Default=1` flag.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ProfileError` if the base directory cannot be resolved or if I/O/JSON operations fail while reading the file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// // Example usage (types shown for clarity; construct `browser` according to your codebase):
+    /// // let browser = BrowserInfo { kind: BrowserKind::Firefox, /* ... */ };
+    /// // let profiles = ProfileManager::discover_firefox_profiles_in_dir(&browser, Some(Path::new("/custom/firefox"))).unwrap();
+    /// ```
+    fn discover_firefox_profiles_in_dir(
+        env: &ProfileEnv,
+        browser: &BrowserInfo,
+        custom_base_dir: Option<&Path>,
+    ) -> Result<Vec<ProfileInfo>, ProfileError> {
+        let base_dir = match custom_base_dir {
+            Some(custom_dir) => custom_dir.to_path_buf(),
+            None => Self::default_browser_dir_with_env(env, browser)?,
+        };
+        let profiles_ini_path = base_dir.join("profiles.ini");
+
+        if !env.fs.exists(&profiles_ini_path) {
+            debug!("profiles.ini not found at {}", profiles_ini_path.display());
+            return Ok(vec![Self::default_profile(browser.kind)]);
+        }
+
+        let profiles_ini_content = env.fs.read_to_string(&profiles_ini_path)?;
+        let mut profiles = Vec::new();
+
+        let mut current_profile: Option<HashMap<String, String>> = None;
+
+        for line in profiles_ini_content.lines() {
+            let line = line.trim();
+
+            if line.starts_with('[') && line.ends_with(']') {
+                if let Some(profile_data) = current_profile.take() {
+                    if let Some(profile_info) =
+                        Self::parse_firefox_profile(env.fs, profile_data, &base_dir, browser.kind)
+                    {
+                        profiles.push(profile_info);
+                    }
+                }
+
+                if line.starts_with("[Profile") {
+                    current_profile = Some(HashMap::new());
+                }
+            } else if let Some(ref mut profile_data) = current_profile {
+                if let Some((key, value)) = line.split_once('=') {
+                    profile_data.insert(key.trim().to_string(), value.trim().to_string());
+                }
+            }
+        }
+
+        if let Some(profile_data) = current_profile {
+            if let Some(profile_info) =
+                Self::parse_firefox_profile(env.fs, profile_data, &base_dir, browser.kind)
+            {
+                profiles.push(profile_info);
+            }
+        }
+
+        // The installation's own default overrides the legacy `Default=1` flag
+        if let Some(default_path) = installs::install_default_profile(
+            env.fs,
+            &base_dir,
+            &profiles_ini_content,
+            &browser.executable_path,
+        ) {
+            if profiles.iter().any(|profile| profile.path == default_path) {
+                for profile in &mut profiles {
+                    profile.is_default = profile.path == default_path;
+                }
+            }
+        }
 
         if profiles.is_empty() {
             profiles.push(Self::default_profile(browser.kind));
@@ -563,7 +1750,8 @@ impl ProfileManager {
     /// Returns `None` when required fields are missing or the resolved profile path does not exist.
     /// - Treats `IsRelative=1` (or missing) as joining `Path` to `base_dir`; when `IsRelative=0` `Path` is used as absolute.
     /// - `Name` becomes both `name` and `display_name`.
-    /// - `Default=1` sets `is_default = true`; otherwise false.
+    /// - `Default=1` sets `is_default = true`; otherwise false. The caller replaces this with
+    ///   the installation's default from an `[Install…]` section when there is one.
     /// - `last_used` is not populated by this parser.
     ///
     /// # Examples
@@ -579,9 +1767,10 @@ impl ProfileManager {
     /// // data.insert("Path".to_string(), "test.profile".to_string());
     /// // data.insert("Default".to_string(), "1".to_string());
     /// //
-    /// // let info = ProfileManager::parse_firefox_profile(data, &base_dir, BrowserKind::Firefox);
+    /// // let info = ProfileManager::parse_firefox_profile(&RealFileSystem, data, &base_dir, BrowserKind::Firefox);
     /// ```
     fn parse_firefox_profile(
+        fs: &dyn FileSystem,
         profile_data: HashMap<String, String>,
         base_dir: &Path,
         browser_kind: BrowserKind,
@@ -604,7 +1793,7 @@ impl ProfileManager {
             return None;
         };
 
-        if !path.exists() {
+        if !fs.exists(&path) {
             return None;
         }
 
@@ -620,41 +1809,438 @@ impl ProfileManager {
             is_default,
             last_used: None,
             browser_kind,
+            in_use: false,
+            identity: None,
+            workspaces: Vec::new(),
+            arc_space: None,
         })
     }
 
-    /// Returns the platform-specific user data base directory for Chromium-family browsers.
+    /// Discover Arc's Spaces as profiles.
     ///
-    /// Given a `BrowserKind` for a Chromium-based browser (Chrome, Edge, Brave, Vivaldi, Arc,
-    /// Helium, Opera, Chromium), this returns the expected base profile directory for the current
-    /// operating system (macOS, Linux, Windows). The returned path is suitable for locating the
-    /// browser's profile subdirectories (e.g. `Default`, `Profile 1`) or for use as `--user-data-dir`.
+    /// Arc users switch between Spaces rather than between its Chromium profiles, which
+    /// usually amount to a single `Default`. Each Space becomes a profile named by its id,
+    /// with its title as display name and the directory of the profile it belongs to as
+    /// path. Without a sidebar the Chromium profiles are returned instead.
+    fn discover_arc_profiles_in_dir(
+        env: &ProfileEnv,
+        browser: &BrowserInfo,
+        custom_base_dir: Option<&Path>,
+    ) -> Result<Vec<ProfileInfo>, ProfileError> {
+        let chromium_profiles =
+            Self::discover_chromium_profiles_with_env(env, browser, custom_base_dir)?;
+        let spaces = Self::discover_arc_spaces_with_env(env, browser, custom_base_dir)
+            .unwrap_or_else(|e| {
+                debug!("Could not read Arc Spaces: {}", e);
+                Vec::new()
+            });
+        if spaces.is_empty() {
+            return Ok(chromium_profiles);
+        }
+
+        let base_dir = match custom_base_dir {
+            Some(custom_dir) => custom_dir.to_path_buf(),
+            None => Self::default_browser_dir_with_env(env, browser)?,
+        };
+        Ok(spaces
+            .into_iter()
+            .map(|space| {
+                let directory = space.profile.as_deref().unwrap_or("Default");
+                let profile = chromium_profiles.iter().find(|p| p.name == directory);
+                ProfileInfo {
+                    name: space.id,
+                    display_name: space.title.clone(),
+                    path: profile
+                        .map(|p| p.path.clone())
+                        .unwrap_or_else(|| base_dir.join(directory)),
+                    is_default: false,
+                    last_used: None,
+                    browser_kind: browser.kind,
+                    in_use: false,
+                    identity: profile.and_then(|p| p.identity.clone()),
+                    workspaces: Vec::new(),
+                    arc_space: Some(space.title),
+                }
+            })
+            .collect())
+    }
+
+    /// Turns `--profile <Space>` for Arc into `--arc-space <Space>`.
     ///
-    /// Returns `Err(ProfileError::InvalidDirectory(_))` if the user's home directory cannot be
-    /// determined, or `Err(ProfileError::UnsupportedBrowser(_))` if `browser_kind` is not a
-    /// supported Chromium-family browser.
+    /// Arc opens a Space through AppleScript rather than a command-line flag, so a named
+    /// profile that resolves to a Space is launched like `--arc-space`. An explicit
+    /// `--arc-space` takes precedence.
+    pub fn select_arc_space(
+        browser: &BrowserInfo,
+        profile_opts: &mut ProfileOptions,
+        window_opts: &mut WindowOptions,
+    ) {
+        if browser.kind != BrowserKind::Arc || window_opts.arc_space.is_some() {
+            return;
+        }
+        let ProfileType::Named(name) = &profile_opts.profile_type else {
+            return;
+        };
+        if let Some(space) = Self::find_profile(browser, name)
+            .ok()
+            .and_then(|profile| profile.arc_space)
+        {
+            debug!("Profile '{}' is the Arc Space '{}'", name, space);
+            window_opts.arc_space = Some(space);
+            profile_opts.profile_type = ProfileType::Default;
+        }
+    }
+
+    /// Discover Arc Spaces from `StorableSidebar.json`.
+    ///
+    /// Arc stores the sidebar next to its `User Data` directory. Each entry of
+    /// `sidebar.containers[].spaces` is either a Space object or its id; only objects are
+    /// returned. Spaces without a title are skipped since they cannot be selected by name.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use pathway::{ProfileManager, BrowserKind};
+    /// use pathway::ProfileManager;
     ///
-    /// // Example: get Chromium base directory
-    /// // let dir = ProfileManager::get_chromium_base_dir(BrowserKind::Chrome).expect("expected to resolve base dir");
-    /// // assert!(dir.is_absolute());
+    /// // Example: list Arc Spaces
+    /// // let spaces = ProfileManager::discover_arc_spaces(&arc, None).unwrap();
+    /// // for space in spaces { println!("{}", space.title); }
     /// ```
-    fn get_chromium_base_dir(browser: &BrowserInfo) -> Result<PathBuf, ProfileError> {
-        let home = dirs_next::home_dir().ok_or_else(|| {
-            ProfileError::InvalidDirectory("Could not determine home directory".to_string())
-        })?;
+    pub fn discover_arc_spaces(
+        browser: &BrowserInfo,
+        custom_base_dir: Option<&Path>,
+    ) -> Result<Vec<ArcSpace>, ProfileError> {
+        Self::discover_arc_spaces_with_env(&ProfileEnv::host(), browser, custom_base_dir)
+    }
 
-        #[cfg(target_os = "macos")]
-        {
-            let app_support = home.join("Library/Application Support");
-            let dir_name = match browser.kind {
-                BrowserKind::Chrome => match browser.channel {
-                    BrowserChannel::Chromium(ChromiumChannel::Stable) => "Google/Chrome",
-                    BrowserChannel::Chromium(ChromiumChannel::Beta) => "Google/Chrome Beta",
+    fn discover_arc_spaces_with_env(
+        env: &ProfileEnv,
+        browser: &BrowserInfo,
+        custom_base_dir: Option<&Path>,
+    ) -> Result<Vec<ArcSpace>, ProfileError> {
+        if browser.kind != BrowserKind::Arc {
+            return Err(ProfileError::UnsupportedBrowser(format!(
+                "{} has no Spaces",
+                browser.display_name
+            )));
+        }
+
+        let base_dir = match custom_base_dir {
+            Some(dir) => dir.to_path_buf(),
+            None => Self::default_browser_dir_with_env(env, browser)?,
+        };
+        let Some(sidebar_path) = [Some(base_dir.as_path()), base_dir.parent()]
+            .into_iter()
+            .flatten()
+            .map(|dir| dir.join("StorableSidebar.json"))
+            .find(|path| env.fs.exists(path))
+        else {
+            return Ok(Vec::new());
+        };
+
+        let sidebar: serde_json::Value =
+            serde_json::from_str(&env.fs.read_to_string(&sidebar_path)?)?;
+        let containers = sidebar
+            .pointer("/sidebar/containers")
+            .and_then(|c| c.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        let mut spaces = Vec::new();
+        for space in containers
+            .iter()
+            .filter_map(|container| container.get("spaces").and_then(|s| s.as_array()))
+            .flatten()
+        {
+            let (Some(id), Some(title)) = (
+                space.get("id").and_then(|v| v.as_str()),
+                space.get("title").and_then(|v| v.as_str()),
+            ) else {
+                continue;
+            };
+
+            let profile = space
+                .pointer("/profile/custom/_0/directoryBasename")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+
+            spaces.push(ArcSpace {
+                id: id.to_string(),
+                title: title.to_string(),
+                profile,
+            });
+        }
+
+        Ok(spaces)
+    }
+
+    /// Discover Edge Workspaces for every profile of an Edge installation.
+    ///
+    /// Edge caches the workspaces a profile has joined in `<profile>/Workspaces/WorkspacesCache`,
+    /// a JSON document with a `workspaces` array. Profiles without the file contribute nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use pathway::ProfileManager;
+    ///
+    /// // Example: list Edge Workspaces
+    /// // let workspaces = ProfileManager::discover_edge_workspaces(&edge, None).unwrap();
+    /// // for workspace in workspaces { println!("{} ({})", workspace.name, workspace.profile); }
+    /// ```
+    pub fn discover_edge_workspaces(
+        browser: &BrowserInfo,
+        custom_base_dir: Option<&Path>,
+    ) -> Result<Vec<EdgeWorkspace>, ProfileError> {
+        if browser.kind != BrowserKind::Edge {
+            return Err(ProfileError::UnsupportedBrowser(format!(
+                "{} has no Workspaces",
+                browser.display_name
+            )));
+        }
+
+        let mut workspaces = Vec::new();
+        for profile in Self::discover_chromium_profiles_in_dir(browser, custom_base_dir)? {
+            let cache_path = profile.path.join("Workspaces").join("WorkspacesCache");
+            let Ok(content) = fs::read_to_string(&cache_path) else {
+                continue;
+            };
+            let cache: serde_json::Value = match serde_json::from_str(&content) {
+                Ok(cache) => cache,
+                Err(e) => {
+                    debug!("Failed to parse {}: {}", cache_path.display(), e);
+                    continue;
+                }
+            };
+
+            for workspace in cache
+                .get("workspaces")
+                .and_then(|w| w.as_array())
+                .into_iter()
+                .flatten()
+            {
+                let (Some(id), Some(name)) = (
+                    workspace.get("id").and_then(|v| v.as_str()),
+                    workspace.get("name").and_then(|v| v.as_str()),
+                ) else {
+                    continue;
+                };
+                workspaces.push(EdgeWorkspace {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                    profile: profile.name.clone(),
+                });
+            }
+        }
+
+        Ok(workspaces)
+    }
+
+    /// Resolve an Edge Workspace by name or id, preferring the given profile directory.
+    fn find_edge_workspace(
+        browser: &BrowserInfo,
+        workspace: &str,
+        profile_dir: Option<&str>,
+    ) -> Option<EdgeWorkspace> {
+        let workspaces = Self::discover_edge_workspaces(browser, None).ok()?;
+        let matches = |w: &&EdgeWorkspace| w.id == workspace || w.name == workspace;
+
+        workspaces
+            .iter()
+            .filter(matches)
+            .find(|w| profile_dir.is_none_or(|dir| w.profile == dir))
+            .or_else(|| workspaces.iter().find(matches))
+            .cloned()
+    }
+
+    /// Discover Safari profiles (Safari 17+) from the `SafariTabs.db` database.
+    ///
+    /// Safari always has the implicit default ("Personal") profile; additional profiles are
+    /// stored as rows of the `bookmarks` table with `subtype = 2`. Sandboxed Safari keeps the
+    /// database in its app container, and the most recently written copy wins. The database
+    /// is read with the system `sqlite3` tool; when that fails (without Full Disk Access, for
+    /// instance) the profile folders next to it are listed under their UUIDs instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use pathway::{ProfileManager, BrowserInfo};
+    ///
+    /// // Example: discover Safari profiles
+    /// // let profiles = ProfileManager::discover_safari_profiles_in_dir(&safari, None).unwrap();
+    /// // assert!(profiles.iter().any(|p| p.is_default));
+    /// ```
+    fn discover_safari_profiles_in_dir(
+        env: &ProfileEnv,
+        browser: &BrowserInfo,
+        custom_base_dir: Option<&Path>,
+    ) -> Result<Vec<ProfileInfo>, ProfileError> {
+        let base_dir = match custom_base_dir {
+            Some(dir) => dir.to_path_buf(),
+            None => Self::default_browser_dir_with_env(env, browser)?,
+        };
+
+        let mut profiles = vec![ProfileInfo {
+            name: "default".to_string(),
+            display_name: "Default".to_string(),
+            path: base_dir.clone(),
+            is_default: true,
+            last_used: None,
+            browser_kind: browser.kind,
+            in_use: false,
+            identity: None,
+            workspaces: Vec::new(),
+            arc_space: None,
+        }];
+
+        // Sandboxed Safari keeps its data in the app container rather than ~/Library/Safari.
+        let mut candidates = vec![base_dir.join("SafariTabs.db")];
+        if custom_base_dir.is_none() {
+            if let Some(home) = &env.home {
+                candidates.push(
+                    home.join(
+                        "Library/Containers/com.apple.Safari/Data/Library/Safari/SafariTabs.db",
+                    ),
+                );
+            }
+        }
+
+        let Some(database) = candidates
+            .into_iter()
+            .filter_map(|path| {
+                let modified = env.fs.metadata(&path).and_then(|m| m.modified()).ok()?;
+                Some((modified, path))
+            })
+            .max_by_key(|(modified, _)| *modified)
+            .map(|(_, path)| path)
+        else {
+            return Ok(profiles);
+        };
+        let profiles_dir = database.with_file_name("Profiles");
+
+        let output = std::process::Command::new("sqlite3")
+            .arg("-readonly")
+            .arg("-separator")
+            .arg("\t")
+            .arg(&database)
+            .arg("SELECT external_uuid, title FROM bookmarks WHERE subtype = 2 AND title != ''")
+            .output();
+
+        let rows = match output {
+            Ok(output) if output.status.success() => {
+                Some(String::from_utf8_lossy(&output.stdout).into_owned())
+            }
+            Ok(output) => {
+                debug!(
+                    "sqlite3 failed to read {}: {}",
+                    database.display(),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+                None
+            }
+            Err(e) => {
+                debug!("Failed to run sqlite3: {}", e);
+                None
+            }
+        };
+        let named = match rows {
+            Some(rows) => Self::safari_profiles_from_rows(&rows, &profiles_dir, browser.kind),
+            None => Self::safari_profiles_from_dirs(env.fs, &profiles_dir, browser.kind),
+        };
+        profiles.extend(named);
+
+        Ok(profiles)
+    }
+
+    /// Parses `external_uuid<TAB>title` rows of the profile query.
+    fn safari_profiles_from_rows(
+        rows: &str,
+        profiles_dir: &Path,
+        browser_kind: BrowserKind,
+    ) -> Vec<ProfileInfo> {
+        rows.lines()
+            .filter_map(|line| line.split_once('\t'))
+            .filter(|(uuid, _)| *uuid != "DefaultProfile")
+            .map(|(uuid, title)| Self::safari_profile(uuid, title, profiles_dir, browser_kind))
+            .collect()
+    }
+
+    /// Lists the profile folders when their titles cannot be read.
+    fn safari_profiles_from_dirs(
+        fs: &dyn FileSystem,
+        profiles_dir: &Path,
+        browser_kind: BrowserKind,
+    ) -> Vec<ProfileInfo> {
+        let Ok(entries) = fs.read_dir(profiles_dir) else {
+            return Vec::new();
+        };
+        let mut uuids: Vec<String> = entries
+            .into_iter()
+            .filter(|path| fs.is_dir(path))
+            .filter_map(|path| path.file_name()?.to_str().map(str::to_string))
+            .filter(|uuid| uuid != "DefaultProfile" && !uuid.starts_with('.'))
+            .collect();
+        uuids.sort();
+        uuids
+            .iter()
+            .map(|uuid| Self::safari_profile(uuid, uuid, profiles_dir, browser_kind))
+            .collect()
+    }
+
+    fn safari_profile(
+        uuid: &str,
+        title: &str,
+        profiles_dir: &Path,
+        browser_kind: BrowserKind,
+    ) -> ProfileInfo {
+        ProfileInfo {
+            name: uuid.to_string(),
+            display_name: title.to_string(),
+            path: profiles_dir.join(uuid),
+            is_default: false,
+            last_used: None,
+            browser_kind,
+            in_use: false,
+            identity: None,
+            workspaces: Vec::new(),
+            arc_space: None,
+        }
+    }
+
+    /// Returns the platform-specific user data base directory for Chromium-family browsers.
+    ///
+    /// Given a `BrowserKind` for a Chromium-based browser (Chrome, Edge, Brave, Vivaldi, Arc,
+    /// Helium, Opera, Chromium), this returns the expected base profile directory for the current
+    /// operating system (macOS, Linux, Windows). Pre-release channels such as Chrome Canary,
+    /// Edge Dev or Brave Nightly get their own directory. The returned path is suitable for locating the
+    /// browser's profile subdirectories (e.g. `Default`, `Profile 1`) or for use as `--user-data-dir`.
+    ///
+    /// Returns `Err(ProfileError::InvalidDirectory(_))` if the user's home directory cannot be
+    /// determined, or `Err(ProfileError::UnsupportedBrowser(_))` if `browser_kind` is not a
+    /// supported Chromium-family browser.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use pathway::{ProfileManager, BrowserKind};
+    ///
+    /// // Example: get Chromium base directory
+    /// // let dir = ProfileManager::get_chromium_base_dir(BrowserKind::Chrome).expect("expected to resolve base dir");
+    /// // assert!(dir.is_absolute());
+    /// ```
+    fn get_chromium_base_dir(
+        env: &ProfileEnv,
+        browser: &BrowserInfo,
+    ) -> Result<PathBuf, ProfileError> {
+        let home = env.home()?;
+
+        #[cfg(target_os = "macos")]
+        {
+            let app_support = home.join("Library/Application Support");
+            let dir_name = match browser.kind {
+                BrowserKind::Chrome => match browser.channel {
+                    BrowserChannel::Chromium(ChromiumChannel::Stable) => "Google/Chrome",
+                    BrowserChannel::Chromium(ChromiumChannel::Beta) => "Google/Chrome Beta",
                     BrowserChannel::Chromium(ChromiumChannel::Dev) => "Google/Chrome Dev",
                     BrowserChannel::Chromium(ChromiumChannel::Canary) => "Google/Chrome Canary",
                     _ => "Google/Chrome",
@@ -666,11 +2252,42 @@ impl ProfileManager {
                     BrowserChannel::Chromium(ChromiumChannel::Canary) => "Microsoft Edge Canary",
                     _ => "Microsoft Edge",
                 },
-                BrowserKind::Brave => "BraveSoftware/Brave-Browser",
-                BrowserKind::Vivaldi => "Vivaldi",
+                BrowserKind::Brave => match browser.channel {
+                    BrowserChannel::Chromium(ChromiumChannel::Beta) => {
+                        "BraveSoftware/Brave-Browser-Beta"
+                    }
+                    BrowserChannel::Chromium(ChromiumChannel::Dev) => {
+                        "BraveSoftware/Brave-Browser-Dev"
+                    }
+                    BrowserChannel::Chromium(ChromiumChannel::Canary) => {
+                        "BraveSoftware/Brave-Browser-Nightly"
+                    }
+                    _ => "BraveSoftware/Brave-Browser",
+                },
+                BrowserKind::Vivaldi => match browser.channel {
+                    BrowserChannel::Chromium(ChromiumChannel::Dev) => "Vivaldi Snapshot",
+                    _ => "Vivaldi",
+                },
+                BrowserKind::Opera if browser.is_opera_gx() => "com.operasoftware.OperaGX",
+                BrowserKind::Opera => match browser.channel {
+                    BrowserChannel::Chromium(ChromiumChannel::Beta) => {
+                        "com.operasoftware.OperaNext"
+                    }
+                    BrowserChannel::Chromium(ChromiumChannel::Dev) => {
+                        "com.operasoftware.OperaDeveloper"
+                    }
+                    _ => "com.operasoftware.Opera",
+                },
                 BrowserKind::Arc => "Arc",
                 BrowserKind::Helium => "net.imput.helium",
-                BrowserKind::Chromium => "Chromium",
+                BrowserKind::Thorium => "Thorium",
+                BrowserKind::Chromium | BrowserKind::UngoogledChromium => "Chromium",
+                BrowserKind::Yandex => match browser.channel {
+                    BrowserChannel::Chromium(ChromiumChannel::Beta) => "Yandex/YandexBrowserBeta",
+                    _ => "Yandex/YandexBrowser",
+                },
+                BrowserKind::Wavebox => "WaveboxApp",
+                BrowserKind::Sidekick => "Sidekick",
                 _ => {
                     return Err(ProfileError::UnsupportedBrowser(format!(
                         "Profile discovery not supported for {:?}",
@@ -687,11 +2304,34 @@ impl ProfileManager {
             let dir_name = match browser.kind {
                 BrowserKind::Chrome => "google-chrome",
                 BrowserKind::Edge => "microsoft-edge",
-                BrowserKind::Brave => "BraveSoftware/Brave-Browser",
-                BrowserKind::Vivaldi => "vivaldi",
+                BrowserKind::Brave => match browser.channel {
+                    BrowserChannel::Chromium(ChromiumChannel::Beta) => {
+                        "BraveSoftware/Brave-Browser-Beta"
+                    }
+                    BrowserChannel::Chromium(ChromiumChannel::Dev) => {
+                        "BraveSoftware/Brave-Browser-Dev"
+                    }
+                    BrowserChannel::Chromium(ChromiumChannel::Canary) => {
+                        "BraveSoftware/Brave-Browser-Nightly"
+                    }
+                    _ => "BraveSoftware/Brave-Browser",
+                },
+                BrowserKind::Vivaldi => match browser.channel {
+                    BrowserChannel::Chromium(ChromiumChannel::Dev) => "vivaldi-snapshot",
+                    _ => "vivaldi",
+                },
+                BrowserKind::Opera => match browser.channel {
+                    BrowserChannel::Chromium(ChromiumChannel::Beta) => "opera-beta",
+                    BrowserChannel::Chromium(ChromiumChannel::Dev) => "opera-developer",
+                    _ => "opera",
+                },
                 BrowserKind::Arc => "arc",
                 BrowserKind::Helium => "helium",
-                BrowserKind::Chromium => "chromium",
+                BrowserKind::Thorium => "thorium",
+                BrowserKind::Chromium | BrowserKind::UngoogledChromium => "chromium",
+                BrowserKind::Yandex => "yandex-browser",
+                BrowserKind::Wavebox => "wavebox",
+                BrowserKind::Sidekick => "sidekick",
                 _ => {
                     return Err(ProfileError::UnsupportedBrowser(format!(
                         "Profile discovery not supported for {:?}",
@@ -699,14 +2339,22 @@ impl ProfileManager {
                     )))
                 }
             };
-            // Only Google Chrome uses the generic -beta / -unstable suffix naming on Linux.
+            // Google Chrome, Edge and Yandex suffix their package names, and with them the
+            // directory, with the channel on Linux; Chrome calls its dev channel "unstable".
             let resolved_dir = match (browser.kind, browser.channel) {
-                (BrowserKind::Chrome, BrowserChannel::Chromium(ChromiumChannel::Beta)) => {
-                    format!("{dir_name}-beta")
-                }
+                (
+                    BrowserKind::Chrome | BrowserKind::Edge | BrowserKind::Yandex,
+                    BrowserChannel::Chromium(ChromiumChannel::Beta),
+                ) => format!("{dir_name}-beta"),
                 (BrowserKind::Chrome, BrowserChannel::Chromium(ChromiumChannel::Dev)) => {
                     format!("{dir_name}-unstable")
                 }
+                (BrowserKind::Edge, BrowserChannel::Chromium(ChromiumChannel::Dev)) => {
+                    format!("{dir_name}-dev")
+                }
+                (BrowserKind::Chrome, BrowserChannel::Chromium(ChromiumChannel::Canary)) => {
+                    format!("{dir_name}-canary")
+                }
                 _ => dir_name.to_string(),
             };
             Ok(config.join(resolved_dir))
@@ -715,7 +2363,7 @@ impl ProfileManager {
         #[cfg(target_os = "windows")]
         {
             let local_app_data = home.join("AppData").join("Local");
-            let _roaming_app_data = home.join("AppData").join("Roaming");
+            let roaming_app_data = home.join("AppData").join("Roaming");
 
             let (mut base_path, components): (PathBuf, &'static [&'static str]) = match browser.kind
             {
@@ -771,9 +2419,40 @@ impl ProfileManager {
                     },
                 ),
                 BrowserKind::Vivaldi => (local_app_data.clone(), &["Vivaldi", "User Data"]),
+                // Opera keeps its data under Roaming, one directory per channel.
+                BrowserKind::Opera if browser.is_opera_gx() => (
+                    roaming_app_data.clone(),
+                    &["Opera Software", "Opera GX Stable"],
+                ),
+                BrowserKind::Opera => (
+                    roaming_app_data.clone(),
+                    match browser.channel {
+                        BrowserChannel::Chromium(ChromiumChannel::Beta) => {
+                            &["Opera Software", "Opera Next"]
+                        }
+                        BrowserChannel::Chromium(ChromiumChannel::Dev) => {
+                            &["Opera Software", "Opera Developer"]
+                        }
+                        _ => &["Opera Software", "Opera Stable"],
+                    },
+                ),
                 BrowserKind::Arc => (local_app_data.clone(), &["Arc", "User Data"]),
                 BrowserKind::Helium => (local_app_data.clone(), &["Helium", "User Data"]),
-                BrowserKind::Chromium => (local_app_data.clone(), &["Chromium", "User Data"]),
+                BrowserKind::Thorium => (local_app_data.clone(), &["Thorium", "User Data"]),
+                BrowserKind::Chromium | BrowserKind::UngoogledChromium => {
+                    (local_app_data.clone(), &["Chromium", "User Data"])
+                }
+                BrowserKind::Yandex => (
+                    local_app_data.clone(),
+                    match browser.channel {
+                        BrowserChannel::Chromium(ChromiumChannel::Beta) => {
+                            &["Yandex", "YandexBrowserBeta", "User Data"]
+                        }
+                        _ => &["Yandex", "YandexBrowser", "User Data"],
+                    },
+                ),
+                BrowserKind::Wavebox => (local_app_data.clone(), &["WaveboxApp", "User Data"]),
+                BrowserKind::Sidekick => (local_app_data.clone(), &["Sidekick", "User Data"]),
                 _ => {
                     return Err(ProfileError::UnsupportedBrowser(format!(
                         "Profile discovery not supported for {:?}",
@@ -797,7 +2476,8 @@ impl ProfileManager {
     /// Returns the platform-specific base directory for Firefox profiles under the current user's home directory.
     ///
     /// On macOS this is `~/Library/Application Support/Firefox`, on Linux `~/.mozilla/firefox`,
-    /// and on Windows `~/AppData/Roaming/Mozilla/Firefox`. If the user's home directory cannot be
+    /// and on Windows `~/AppData/Roaming/Mozilla/Firefox`. Forks that keep their profiles apart
+    /// use their own directory name instead (e.g. `~/.librewolf` on Linux). If the user's home directory cannot be
     /// determined the function returns `ProfileError::InvalidDirectory`. On unsupported platforms
     /// it returns `ProfileError::UnsupportedBrowser`.
     ///
@@ -807,28 +2487,52 @@ impl ProfileManager {
     /// use pathway::ProfileManager;
     ///
     /// // Example: get Firefox base directory
-    /// // let base = ProfileManager::get_firefox_base_dir().expect("failed to locate Firefox base directory");
+    /// // let base = ProfileManager::get_firefox_base_dir(BrowserKind::Firefox).expect("failed to locate Firefox base directory");
     /// // println!("{}", base.display());
     /// ```
-    fn get_firefox_base_dir() -> Result<PathBuf, ProfileError> {
-        let home = dirs_next::home_dir().ok_or_else(|| {
-            ProfileError::InvalidDirectory("Could not determine home directory".to_string())
-        })?;
+    fn get_firefox_base_dir(env: &ProfileEnv, kind: BrowserKind) -> Result<PathBuf, ProfileError> {
+        let home = env.home()?;
 
         #[cfg(target_os = "macos")]
         {
-            Ok(home.join("Library/Application Support/Firefox"))
+            match kind {
+                BrowserKind::LibreWolf => Ok(home.join("Library/Application Support/librewolf")),
+                BrowserKind::Zen => Ok(home.join("Library/Application Support/zen")),
+                BrowserKind::Floorp => Ok(home.join("Library/Application Support/Floorp")),
+                BrowserKind::PaleMoon => Ok(home.join("Library/Application Support/Pale Moon")),
+                BrowserKind::Basilisk => Ok(home.join("Library/Application Support/Basilisk")),
+                _ => Ok(home.join("Library/Application Support/Firefox")),
+            }
         }
         #[cfg(target_os = "linux")]
         {
-            Ok(home.join(".mozilla/firefox"))
+            match kind {
+                BrowserKind::LibreWolf => Ok(home.join(".librewolf")),
+                BrowserKind::Zen => Ok(home.join(".zen")),
+                BrowserKind::Floorp => Ok(home.join(".floorp")),
+                BrowserKind::PaleMoon => Ok(home.join(".moonchild productions/pale moon")),
+                BrowserKind::Basilisk => Ok(home.join(".moonchild productions/basilisk")),
+                _ => Ok(home.join(".mozilla/firefox")),
+            }
         }
         #[cfg(target_os = "windows")]
         {
-            Ok(home.join("AppData/Roaming/Mozilla/Firefox"))
+            match kind {
+                BrowserKind::LibreWolf => Ok(home.join("AppData/Roaming/librewolf")),
+                BrowserKind::Zen => Ok(home.join("AppData/Roaming/zen")),
+                BrowserKind::Floorp => Ok(home.join("AppData/Roaming/Floorp")),
+                BrowserKind::PaleMoon => {
+                    Ok(home.join("AppData/Roaming/Moonchild Productions/Pale Moon"))
+                }
+                BrowserKind::Basilisk => {
+                    Ok(home.join("AppData/Roaming/Moonchild Productions/Basilisk"))
+                }
+                _ => Ok(home.join("AppData/Roaming/Mozilla/Firefox")),
+            }
         }
         #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
         {
+            let _ = kind;
             Err(ProfileError::UnsupportedBrowser(
                 "Unsupported platform".to_string(),
             ))
@@ -841,6 +2545,7 @@ impl ProfileManager {
     /// For Safari on macOS, this returns the user's Library/Safari path.
     /// For Chromium-based browsers, this delegates to get_chromium_base_dir.
     /// For Firefox-based browsers, this delegates to get_firefox_base_dir.
+    /// Flatpak and Snap browsers get the matching directory inside their sandbox.
     /// For unknown browsers or unsupported platforms, this returns an error.
     ///
     /// # Examples
@@ -853,6 +2558,88 @@ impl ProfileManager {
     /// // assert!(dir.to_string_lossy().contains("Library/Safari"));
     /// ```
     pub fn get_default_browser_dir(browser: &BrowserInfo) -> Result<PathBuf, ProfileError> {
+        Self::default_browser_dir_with_env(&ProfileEnv::host(), browser)
+    }
+
+    fn default_browser_dir_with_env(
+        env: &ProfileEnv,
+        browser: &BrowserInfo,
+    ) -> Result<PathBuf, ProfileError> {
+        Self::get_host_browser_dir(env, browser)
+            .map(|dir| Self::sandboxed_browser_dir(env, browser, dir))
+    }
+
+    /// Maps a directory under the user's home into the sandbox of a Flatpak or Snap browser.
+    ///
+    /// Flatpak apps live in `~/.var/app/<app id>`, where `config`, `data` and `cache` stand in
+    /// for the XDG directories and dot-directories such as `.mozilla` keep their name. Snaps
+    /// get `~/snap/<name>/current` as their home, although Firefox and Chromium keep their
+    /// profiles in `~/snap/<name>/common`.
+    fn sandboxed_browser_dir(
+        env: &ProfileEnv,
+        browser: &BrowserInfo,
+        host_dir: PathBuf,
+    ) -> PathBuf {
+        let Some(home) = env.home.as_deref() else {
+            return host_dir;
+        };
+        let relative = match host_dir.strip_prefix(home) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => return host_dir,
+        };
+
+        match browser.source {
+            InstallationSource::Linux(LinuxInstallationSource::Flatpak) => {
+                let Some(app_id) = browser
+                    .exec_command
+                    .as_deref()
+                    .and_then(sources::parse_flatpak_run)
+                    .map(|(_, app_id)| app_id)
+                else {
+                    return host_dir;
+                };
+                let relative = [
+                    (".config", "config"),
+                    (".local/share", "data"),
+                    (".cache", "cache"),
+                ]
+                .iter()
+                .find_map(|(host, sandboxed)| {
+                    relative
+                        .strip_prefix(host)
+                        .ok()
+                        .map(|rest| Path::new(sandboxed).join(rest))
+                })
+                .unwrap_or(relative);
+                home.join(".var/app").join(app_id).join(relative)
+            }
+            InstallationSource::Linux(LinuxInstallationSource::Snap) => {
+                let Some(snap) = sources::snap_name(&browser.executable_path) else {
+                    return host_dir;
+                };
+                let snap_dir = home.join("snap").join(snap);
+                let current = snap_dir.join("current").join(&relative);
+                let common = [
+                    Some(snap_dir.join("common").join(&relative)),
+                    relative
+                        .strip_prefix(".config")
+                        .ok()
+                        .map(|rest| snap_dir.join("common").join(rest)),
+                ];
+                common
+                    .into_iter()
+                    .flatten()
+                    .find(|dir| env.fs.exists(dir))
+                    .unwrap_or(current)
+            }
+            _ => host_dir,
+        }
+    }
+
+    fn get_host_browser_dir(
+        env: &ProfileEnv,
+        browser: &BrowserInfo,
+    ) -> Result<PathBuf, ProfileError> {
         match browser.kind {
             // Chromium-based browsers
             BrowserKind::Chrome
@@ -861,22 +2648,28 @@ impl ProfileManager {
             | BrowserKind::Vivaldi
             | BrowserKind::Arc
             | BrowserKind::Helium
+            | BrowserKind::Thorium
+            | BrowserKind::UngoogledChromium
+            | BrowserKind::Yandex
+            | BrowserKind::Wavebox
+            | BrowserKind::Sidekick
             | BrowserKind::Opera
-            | BrowserKind::Chromium => Self::get_chromium_base_dir(browser),
+            | BrowserKind::Chromium => Self::get_chromium_base_dir(env, browser),
 
             // Firefox-based browsers
-            BrowserKind::Firefox | BrowserKind::Waterfox => Self::get_firefox_base_dir(),
+            BrowserKind::Firefox
+            | BrowserKind::Waterfox
+            | BrowserKind::LibreWolf
+            | BrowserKind::Zen
+            | BrowserKind::Floorp
+            | BrowserKind::PaleMoon
+            | BrowserKind::Basilisk => Self::get_firefox_base_dir(env, browser.kind),
 
             // Safari (macOS only)
             BrowserKind::Safari => {
                 #[cfg(target_os = "macos")]
                 {
-                    let home = dirs_next::home_dir().ok_or_else(|| {
-                        ProfileError::InvalidDirectory(
-                            "Could not determine home directory".to_string(),
-                        )
-                    })?;
-                    Ok(home.join("Library/Safari"))
+                    Ok(env.home()?.join("Library/Safari"))
                 }
                 #[cfg(not(target_os = "macos"))]
                 {
@@ -888,9 +2681,7 @@ impl ProfileManager {
 
             // Tor Browser - has its own directory structure
             BrowserKind::TorBrowser => {
-                let home = dirs_next::home_dir().ok_or_else(|| {
-                    ProfileError::InvalidDirectory("Could not determine home directory".to_string())
-                })?;
+                let home = env.home()?;
 
                 #[cfg(target_os = "macos")]
                 {
@@ -912,6 +2703,71 @@ impl ProfileManager {
                 }
             }
 
+            // qutebrowser keeps its data apart from its config; the data directory holds the
+            // session, cookies and history.
+            BrowserKind::Qutebrowser => {
+                #[cfg(target_os = "windows")]
+                {
+                    dirs_next::data_dir()
+                        .map(|dir| dir.join("qutebrowser").join("data"))
+                        .ok_or_else(|| {
+                            ProfileError::InvalidDirectory(
+                                "Could not determine data directory".to_string(),
+                            )
+                        })
+                }
+                #[cfg(not(target_os = "windows"))]
+                {
+                    dirs_next::data_dir()
+                        .map(|dir| dir.join("qutebrowser"))
+                        .ok_or_else(|| {
+                            ProfileError::InvalidDirectory(
+                                "Could not determine data directory".to_string(),
+                            )
+                        })
+                }
+            }
+
+            // GNOME Web only exists on Linux; the Flatpak keeps the same layout under
+            // `~/.var/app/org.gnome.Epiphany/data`.
+            BrowserKind::Epiphany => {
+                #[cfg(target_os = "linux")]
+                {
+                    dirs_next::data_dir()
+                        .map(|dir| dir.join("epiphany"))
+                        .ok_or_else(|| {
+                            ProfileError::InvalidDirectory(
+                                "Could not determine data directory".to_string(),
+                            )
+                        })
+                }
+                #[cfg(not(target_os = "linux"))]
+                {
+                    Err(ProfileError::UnsupportedBrowser(
+                        "GNOME Web is only supported on Linux".to_string(),
+                    ))
+                }
+            }
+
+            // KDE browsers keep their profiles under the user config and data directories.
+            BrowserKind::Falkon => dirs_next::config_dir()
+                .map(|dir| dir.join("falkon").join("profiles"))
+                .ok_or_else(|| {
+                    ProfileError::InvalidDirectory(
+                        "Could not determine config directory".to_string(),
+                    )
+                }),
+            BrowserKind::Konqueror => dirs_next::data_dir()
+                .map(|dir| dir.join("konqueror"))
+                .ok_or_else(|| {
+                    ProfileError::InvalidDirectory("Could not determine data directory".to_string())
+                }),
+
+            // DuckDuckGo keeps a single sandboxed profile that is not meant to be shared.
+            BrowserKind::DuckDuckGo => Err(ProfileError::UnsupportedBrowser(
+                "DuckDuckGo does not support profile directories".to_string(),
+            )),
+
             // Unknown browsers
             BrowserKind::Other => Err(ProfileError::UnsupportedBrowser(
                 "Cannot determine default directory for unknown browser".to_string(),
@@ -942,7 +2798,49 @@ impl ProfileManager {
             is_default: true,
             last_used: None,
             browser_kind,
+            in_use: false,
+            identity: None,
+            workspaces: Vec::new(),
+            arc_space: None,
+        }
+    }
+
+    /// Build command-line arguments for Opera, which selects side profiles with
+    /// `--side-profile-name=<id>` instead of `--profile-directory`.
+    ///
+    /// The main profile needs no flag; every other option is handled as for Chromium.
+    fn opera_profile_args(
+        browser: &BrowserInfo,
+        profile_opts: &ProfileOptions,
+        window_opts: &WindowOptions,
+    ) -> Vec<String> {
+        let ProfileType::Named(name) = &profile_opts.profile_type else {
+            return Self::chromium_profile_args(browser, profile_opts, window_opts);
+        };
+
+        let mut args = Vec::new();
+        match Self::find_profile(browser, name) {
+            Ok(profile_info) if profile_info.is_default => {}
+            Ok(profile_info) => {
+                args.push(format!("--side-profile-name={}", profile_info.name));
+            }
+            Err(e) => {
+                warn!(
+                    "Profile '{}' not found, using the main profile: {}",
+                    name, e
+                );
+            }
         }
+        let main_profile = ProfileOptions {
+            profile_type: ProfileType::Default,
+            custom_args: Vec::new(),
+        };
+        args.extend(Self::chromium_profile_args(
+            browser,
+            &main_profile,
+            window_opts,
+        ));
+        args
     }
 
     /// Build command-line arguments for launching Chromium-family browsers according to
@@ -953,7 +2851,11 @@ impl ProfileManager {
     ///   supplied `name` is used as the directory name.
     /// - `ProfileType::CustomDirectory` and `ProfileType::Temporary` set `--user-data-dir=<path>`.
     /// - `ProfileType::Guest` adds `--guest`. `ProfileType::Default` adds no profile-specific flags.
-    /// - Window options add `--incognito`, `--new-window`, and `--kiosk` when enabled.
+    /// - Window options add `--incognito`, `--new-window`, and `--kiosk` when enabled;
+    ///   `kiosk_strict` also appends [`CHROMIUM_KIOSK_STRICT_ARGS`], and `tor_window` adds
+    ///   `--tor` for Brave.
+    /// - `window_name` adds `--window-name` (plus `--class` for WM_CLASS on Linux) and
+    ///   `edge_workspace` adds `--launch-workspace` for Edge.
     ///
     /// Returns the assembled argument list (may be empty for defaults).
     ///
@@ -1019,6 +2921,32 @@ impl ProfileManager {
         if window_opts.kiosk {
             args.push("--kiosk".to_string());
         }
+        if window_opts.kiosk_strict {
+            args.extend(CHROMIUM_KIOSK_STRICT_ARGS.iter().map(|arg| arg.to_string()));
+        }
+        if window_opts.tor_window && browser.kind == BrowserKind::Brave {
+            args.push("--tor".to_string());
+        }
+        if let Some(name) = &window_opts.window_name {
+            args.push(format!("--window-name={}", name));
+            if cfg!(target_os = "linux") {
+                args.push(format!("--class={}", name));
+            }
+        }
+
+        if let Some(workspace) = window_opts
+            .edge_workspace
+            .as_deref()
+            .filter(|_| browser.kind == BrowserKind::Edge)
+        {
+            let profile_dir = args
+                .iter()
+                .find_map(|arg| arg.strip_prefix("--profile-directory="));
+            match Self::find_edge_workspace(browser, workspace, profile_dir) {
+                Some(found) => args.push(format!("--launch-workspace={}", found.id)),
+                None => warn!("Edge Workspace '{}' not found", workspace),
+            }
+        }
 
         args
     }
@@ -1041,7 +2969,7 @@ impl ProfileManager {
     ///
     /// // Example: generate Firefox profile arguments
     /// // let profile_opts = ProfileOptions { profile_type: ProfileType::Named("default".into()), custom_args: vec![] };
-    /// // let window_opts = WindowOptions { new_window: true, incognito: false, kiosk: false };
+    /// // let window_opts = WindowOptions { new_window: true, ..Default::default() };
     /// // let args = ProfileManager::firefox_profile_args(&browser, &profile_opts, &window_opts);
     /// ```
     fn firefox_profile_args(
@@ -1091,7 +3019,10 @@ impl ProfileManager {
         if window_opts.new_window {
             args.push("--new-window".to_string());
         }
-        if window_opts.kiosk {
+        // Goanna-based browsers (Pale Moon, Basilisk) predate Firefox's kiosk mode.
+        if window_opts.kiosk
+            && !matches!(browser.kind, BrowserKind::PaleMoon | BrowserKind::Basilisk)
+        {
             args.push("--kiosk".to_string());
         }
 
@@ -1100,10 +3031,9 @@ impl ProfileManager {
 
     /// Build command-line arguments for launching Safari according to the given options.
     ///
-    /// For Safari this function currently does not produce any launch arguments.
-    /// If `window_opts.incognito` is true a warning is emitted because Safari's
-    /// private (incognito) mode cannot be enabled purely via command-line and
-    /// requires AppleScript or other automation.
+    /// Safari does not accept launch arguments, so this always returns an empty list.
+    /// Private windows for `window_opts.incognito` are opened by the macOS launcher through
+    /// AppleScript instead.
     ///
     /// # Examples
     ///
@@ -1121,32 +3051,159 @@ impl ProfileManager {
     /// ```
     fn safari_profile_args(
         _profile_opts: &ProfileOptions,
-        window_opts: &WindowOptions,
+        _window_opts: &WindowOptions,
     ) -> Vec<String> {
-        if window_opts.incognito {
-            warn!(
-                "Safari incognito mode requires AppleScript and is not supported via command line"
-            );
-        }
-
         Vec::new()
     }
 
-    /// Build command-line arguments for generic (non-browser-specific) window options.
+    /// Build command-line arguments for launching qutebrowser according to the given options.
     ///
-    /// Currently only maps `incognito` to the common `--private` flag.
+    /// qutebrowser has no named profiles: all of its config, data and cache live under one base
+    /// directory, so custom and temporary profiles become `--basedir` and guest mode becomes
+    /// `--temp-basedir`. A separate base directory also means a separate instance. URLs on the
+    /// command line are opened like `:open`, in the window or tab chosen by `--target`;
+    /// `window_opts.incognito` selects `private-window`.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use pathway::WindowOptions;
+    /// use pathway::{ProfileOptions, ProfileType, WindowOptions};
     ///
-    /// // Example: generate generic window arguments
-    /// // let opts = WindowOptions { new_window: false, incognito: true, kiosk: false };
-    /// // let args = generic_window_args(&opts);
-    /// // assert_eq!(args, vec!["--private".to_string()]);
+    /// // Example: open URLs in a new window of a separate qutebrowser instance
+    /// // let profile_opts = ProfileOptions {
+    /// //     profile_type: ProfileType::CustomDirectory("/tmp/qb".into()),
+    /// //     custom_args: Vec::new(),
+    /// // };
+    /// // let window_opts = WindowOptions { new_window: true, ..Default::default() };
+    /// // let args = qutebrowser_profile_args(&profile_opts, &window_opts);
+    /// // assert_eq!(args, ["--basedir", "/tmp/qb", "--target", "window"]);
     /// ```
-    fn generic_window_args(window_opts: &WindowOptions) -> Vec<String> {
+    fn qutebrowser_profile_args(
+        profile_opts: &ProfileOptions,
+        window_opts: &WindowOptions,
+    ) -> Vec<String> {
+        let mut args = Vec::new();
+
+        match &profile_opts.profile_type {
+            ProfileType::CustomDirectory(path) | ProfileType::Temporary(path) => {
+                args.push("--basedir".to_string());
+                args.push(path.display().to_string());
+            }
+            ProfileType::Guest => {
+                args.push("--temp-basedir".to_string());
+            }
+            ProfileType::Named(_) | ProfileType::Default => {}
+        }
+
+        let target = if window_opts.incognito {
+            "private-window"
+        } else if window_opts.new_window {
+            "window"
+        } else {
+            "tab"
+        };
+        args.push("--target".to_string());
+        args.push(target.to_string());
+
+        args
+    }
+
+    /// Build command-line arguments for launching GNOME Web (Epiphany) according to the given options.
+    ///
+    /// Custom and temporary profiles are passed with `--profile`, guest mode becomes a
+    /// `--private-instance` with a throwaway profile, and `window_opts.incognito` opens the URLs
+    /// with `--incognito-mode`. Epiphany has no named profiles.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use pathway::{ProfileOptions, ProfileType, WindowOptions};
+    ///
+    /// // Example: open URLs in a new incognito window
+    /// // let profile_opts = ProfileOptions { profile_type: ProfileType::Default, custom_args: Vec::new() };
+    /// // let window_opts = WindowOptions { new_window: true, incognito: true, ..Default::default() };
+    /// // let args = epiphany_profile_args(&profile_opts, &window_opts);
+    /// // assert_eq!(args, ["--incognito-mode", "--new-window"]);
+    /// ```
+    fn epiphany_profile_args(
+        profile_opts: &ProfileOptions,
+        window_opts: &WindowOptions,
+    ) -> Vec<String> {
+        let mut args = Vec::new();
+
+        match &profile_opts.profile_type {
+            ProfileType::CustomDirectory(path) | ProfileType::Temporary(path) => {
+                args.push("--profile".to_string());
+                args.push(path.display().to_string());
+            }
+            ProfileType::Guest => {
+                args.push("--private-instance".to_string());
+            }
+            ProfileType::Named(_) | ProfileType::Default => {}
+        }
+
+        if window_opts.incognito {
+            args.push("--incognito-mode".to_string());
+        }
+        if window_opts.new_window {
+            args.push("--new-window".to_string());
+        }
+
+        args
+    }
+
+    /// Build command-line arguments for launching Falkon according to the given options.
+    ///
+    /// Falkon selects profiles by name (`-p`) from its own profiles directory and cannot point at
+    /// an arbitrary directory, so custom and temporary profiles are left to the validation warnings.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use pathway::{ProfileOptions, ProfileType, WindowOptions};
+    ///
+    /// // Example: open URLs in a private window of the "work" profile
+    /// // let profile_opts = ProfileOptions { profile_type: ProfileType::Named("work".into()), custom_args: Vec::new() };
+    /// // let window_opts = WindowOptions { incognito: true, ..Default::default() };
+    /// // let args = falkon_profile_args(&profile_opts, &window_opts);
+    /// // assert_eq!(args, ["-p", "work", "--private-browsing"]);
+    /// ```
+    fn falkon_profile_args(
+        profile_opts: &ProfileOptions,
+        window_opts: &WindowOptions,
+    ) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if let ProfileType::Named(name) = &profile_opts.profile_type {
+            args.push("-p".to_string());
+            args.push(name.clone());
+        }
+
+        if window_opts.incognito || matches!(profile_opts.profile_type, ProfileType::Guest) {
+            args.push("--private-browsing".to_string());
+        }
+        if window_opts.new_window {
+            args.push("--new-window".to_string());
+        }
+
+        args
+    }
+
+    /// Build command-line arguments for generic (non-browser-specific) window options.
+    ///
+    /// Currently only maps `incognito` to the common `--private` flag.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use pathway::WindowOptions;
+    ///
+    /// // Example: generate generic window arguments
+    /// // let opts = WindowOptions { incognito: true, ..Default::default() };
+    /// // let args = generic_window_args(&opts);
+    /// // assert_eq!(args, vec!["--private".to_string()]);
+    /// ```
+    fn generic_window_args(window_opts: &WindowOptions) -> Vec<String> {
         let mut args = Vec::new();
 
         if window_opts.incognito {
@@ -1157,6 +3214,215 @@ impl ProfileManager {
     }
 }
 
+/// Name prefix of the directories created for `--temp-profile`.
+const TEMP_PROFILE_PREFIX: &str = "pathway_profile_";
+
+/// Longest wait for a browser to let go of a temporary profile before it is left in place.
+const TEMP_PROFILE_MAX_LOCK: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+/// Chromium profile entries that are not copied by [`ProfileManager::clone_profile`].
+const CHROMIUM_CLONE_SKIP: &[&str] = &[
+    "Cache",
+    "Code Cache",
+    "GPUCache",
+    "DawnCache",
+    "GrShaderCache",
+    "Service Worker",
+    "LOCK",
+];
+
+/// Firefox profile entries that are not copied by [`ProfileManager::clone_profile`].
+const FIREFOX_CLONE_SKIP: &[&str] = &[
+    "lock",
+    "parent.lock",
+    ".parentlock",
+    "cache2",
+    "startupCache",
+    "thumbnails",
+];
+
+/// Entries of a `kind` profile directory that are machine-local and not worth copying.
+pub(crate) fn profile_copy_skip(kind: BrowserKind) -> &'static [&'static str] {
+    match kind {
+        BrowserKind::Firefox
+        | BrowserKind::Waterfox
+        | BrowserKind::LibreWolf
+        | BrowserKind::Zen
+        | BrowserKind::Floorp
+        | BrowserKind::PaleMoon
+        | BrowserKind::Basilisk => FIREFOX_CLONE_SKIP,
+        _ => CHROMIUM_CLONE_SKIP,
+    }
+}
+
+/// `Local State` for a user data directory holding only a copy of the profile `source`, as
+/// `Default`, so the browser doesn't open or list profiles that were not copied.
+fn ephemeral_local_state(content: &str, source: &str) -> Option<String> {
+    let mut local_state: serde_json::Value = serde_json::from_str(content).ok()?;
+    let profile = local_state.get_mut("profile")?.as_object_mut()?;
+    let entry = profile
+        .get("info_cache")
+        .and_then(|cache| cache.get(source))
+        .cloned();
+    let mut info_cache = serde_json::Map::new();
+    if let Some(entry) = entry {
+        info_cache.insert("Default".to_string(), entry);
+    }
+    profile.insert("info_cache".to_string(), info_cache.into());
+    profile.insert("last_used".to_string(), "Default".into());
+    profile.insert("last_active_profiles".to_string(), vec!["Default"].into());
+    profile.insert("profiles_order".to_string(), vec!["Default"].into());
+    serde_json::to_string(&local_state).ok()
+}
+
+/// Copies a profile directory, leaving out `skip` entries and symlinks (Chromium's
+/// `Singleton*` locks).
+fn copy_profile_dir(from: &Path, to: &Path, skip: &[&str]) -> Result<(), ProfileError> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        if skip.iter().any(|name| file_name == *name) {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_profile_dir(&entry.path(), &to.join(&file_name), skip)?;
+        } else if file_type.is_file() {
+            fs::copy(entry.path(), to.join(&file_name))?;
+        }
+    }
+    Ok(())
+}
+
+/// Appends a `[ProfileN]` section for `path` to a `profiles.ini` file.
+///
+/// Paths inside `base_dir` are stored relative to it, with `/` separators as Firefox does.
+fn add_firefox_profile_section(content: &str, name: &str, path: &Path, base_dir: &Path) -> String {
+    let index = ini_sections(content)
+        .iter()
+        .filter(|(header, _)| header.starts_with("[Profile"))
+        .count();
+    let (is_relative, path) = match path.strip_prefix(base_dir) {
+        Ok(relative) => (
+            1,
+            relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+        ),
+        Err(_) => (0, path.display().to_string()),
+    };
+
+    let mut output = content.trim_end().to_string();
+    if !output.is_empty() {
+        output.push_str("\n\n");
+    }
+    output.push_str(&format!(
+        "[Profile{}]\nName={}\nIsRelative={}\nPath={}\n",
+        index, name, is_relative, path
+    ));
+    output
+}
+
+/// A `profiles.ini` section: its `[header]` and the lines below it.
+type IniSection<'a> = (&'a str, Vec<&'a str>);
+
+fn ini_sections(content: &str) -> Vec<IniSection<'_>> {
+    let mut sections: Vec<IniSection<'_>> = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            sections.push((trimmed, Vec::new()));
+        } else if let Some((_, lines)) = sections.last_mut() {
+            lines.push(line);
+        }
+    }
+    sections
+}
+
+fn ini_value(lines: &[&str], key: &str) -> Option<String> {
+    lines.iter().find_map(|line| {
+        let (k, v) = line.split_once('=')?;
+        (k.trim() == key).then(|| v.trim().to_string())
+    })
+}
+
+/// Index of the `[ProfileN]` section named `name`.
+fn firefox_profile_section(sections: &[IniSection<'_>], name: &str) -> Result<usize, ProfileError> {
+    sections
+        .iter()
+        .position(|(header, lines)| {
+            header.starts_with("[Profile") && ini_value(lines, "Name").as_deref() == Some(name)
+        })
+        .ok_or_else(|| ProfileError::ProfileNotFound(name.to_string()))
+}
+
+/// Removes the `[ProfileN]` section named `name` from a `profiles.ini` file.
+///
+/// The remaining profile sections are renumbered, since Firefox stops reading at the first
+/// missing `ProfileN`. A profile that an `[Install…]` section uses as its default is refused.
+fn remove_firefox_profile_section(content: &str, name: &str) -> Result<String, ProfileError> {
+    let sections = ini_sections(content);
+    let target = firefox_profile_section(&sections, name)?;
+    let path = ini_value(&sections[target].1, "Path");
+    let installed_default = sections.iter().any(|(header, lines)| {
+        header.starts_with("[Install") && path.is_some() && ini_value(lines, "Default") == path
+    });
+    if installed_default {
+        return Err(ProfileError::DefaultProfile(name.to_string()));
+    }
+
+    let mut output = String::new();
+    let mut profile_index = 0;
+    for (index, (header, lines)) in sections.iter().enumerate() {
+        if index == target {
+            continue;
+        }
+        if header.starts_with("[Profile") {
+            output.push_str(&format!("[Profile{}]\n", profile_index));
+            profile_index += 1;
+        } else {
+            output.push_str(header);
+            output.push('\n');
+        }
+        for line in lines {
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+    Ok(output)
+}
+
+/// Changes the `Name=` of the `[ProfileN]` section named `name` in a `profiles.ini` file.
+fn rename_firefox_profile_section(
+    content: &str,
+    name: &str,
+    new_name: &str,
+) -> Result<String, ProfileError> {
+    let sections = ini_sections(content);
+    let target = firefox_profile_section(&sections, name)?;
+
+    let mut output = String::new();
+    for (index, (header, lines)) in sections.iter().enumerate() {
+        output.push_str(header);
+        output.push('\n');
+        for line in lines {
+            let is_name = line
+                .split_once('=')
+                .is_some_and(|(key, _)| key.trim() == "Name");
+            if index == target && is_name {
+                output.push_str(&format!("Name={}\n", new_name));
+            } else {
+                output.push_str(line);
+                output.push('\n');
+            }
+        }
+    }
+    Ok(output)
+}
+
 /// Generate a hex-encoded, nanosecond-resolution timestamp string.
 ///
 /// The returned string is the current system time since the UNIX epoch, encoded as lowercase hexadecimal
@@ -1191,8 +3457,8 @@ fn generate_timestamp_id() -> String {
 ///
 /// # Returns
 ///
-/// A `Result` containing a `Vec<String>` of warnings. The function does not return an error in
-/// normal validation flows; `ProfileError` is reserved for underlying errors in other APIs.
+/// A `Result` containing a `Vec<String>` of warnings. Combinations that cannot work at all are
+/// errors, such as `--guest` with a browser whose guest mode is disabled by policy.
 ///
 /// # Examples
 ///
@@ -1208,11 +3474,126 @@ pub fn validate_profile_options(
 ) -> Result<Vec<String>, ProfileError> {
     let mut warnings = Vec::new();
 
+    if let Some(space) = &window_opts.arc_space {
+        if browser.kind != BrowserKind::Arc {
+            warnings.push(format!(
+                "--arc-space only applies to Arc and is ignored for {}",
+                browser.display_name
+            ));
+        } else if !cfg!(target_os = "macos") {
+            warnings.push("--arc-space is only supported on macOS".to_string());
+        } else if let Ok(spaces) = ProfileManager::discover_arc_spaces(browser, None) {
+            if !spaces.is_empty() && !spaces.iter().any(|s| &s.title == space) {
+                warnings.push(format!("Arc Space '{}' not found", space));
+            }
+        }
+    }
+
+    if let Some(workspace) = &window_opts.edge_workspace {
+        if browser.kind != BrowserKind::Edge {
+            warnings.push(format!(
+                "--edge-workspace only applies to Microsoft Edge and is ignored for {}",
+                browser.display_name
+            ));
+        } else {
+            if browser
+                .major_version()
+                .is_some_and(|major| major < EDGE_WORKSPACES_MIN_VERSION)
+            {
+                warnings.push(format!(
+                    "Edge Workspaces require Microsoft Edge {} or later (found {})",
+                    EDGE_WORKSPACES_MIN_VERSION,
+                    browser.version.as_deref().unwrap_or_default()
+                ));
+            }
+            if ProfileManager::find_edge_workspace(browser, workspace, None).is_none() {
+                warnings.push(format!("Edge Workspace '{}' not found", workspace));
+            }
+        }
+    }
+
+    let is_chromium = matches!(
+        browser.kind,
+        BrowserKind::Chrome
+            | BrowserKind::Edge
+            | BrowserKind::Brave
+            | BrowserKind::Vivaldi
+            | BrowserKind::Arc
+            | BrowserKind::Helium
+            | BrowserKind::Thorium
+            | BrowserKind::UngoogledChromium
+            | BrowserKind::Yandex
+            | BrowserKind::Wavebox
+            | BrowserKind::Sidekick
+            | BrowserKind::Opera
+            | BrowserKind::Chromium
+    );
+
+    if window_opts.cdp_port.is_some() {
+        if !cfg!(feature = "cdp") {
+            warnings
+                .push("--cdp-port requires pathway to be built with the `cdp` feature".to_string());
+        } else if !is_chromium {
+            warnings.push(format!(
+                "--cdp-port only applies to Chromium-based browsers and is ignored for {}",
+                browser.display_name
+            ));
+        }
+    }
+
+    if window_opts.kiosk_strict && !is_chromium {
+        warnings.push(format!(
+            "Kiosk hardening flags only apply to Chromium-based browsers; {} gets plain kiosk mode",
+            browser.display_name
+        ));
+    }
+
+    if window_opts.tor_window && browser.kind != BrowserKind::Brave {
+        warnings.push(format!(
+            "--tor-window only applies to Brave and is ignored for {}",
+            browser.display_name
+        ));
+    }
+
+    if window_opts.window_name.is_some() && !is_chromium {
+        warnings.push(format!(
+            "--window-name is only supported by Chromium-based browsers and is ignored for {}",
+            browser.display_name
+        ));
+    }
+
+    if window_opts.new_instance && !cfg!(target_os = "macos") {
+        warnings.push("--new-instance is only supported on macOS".to_string());
+    }
+
+    if window_opts.sandbox.is_some() {
+        if !cfg!(target_os = "linux") {
+            warnings.push("Sandboxed launch is only supported on Linux".to_string());
+        } else if matches!(
+            browser.source,
+            InstallationSource::Linux(LinuxInstallationSource::Flatpak)
+                | InstallationSource::Linux(LinuxInstallationSource::Snap)
+        ) {
+            warnings.push(format!(
+                "{} is already sandboxed by {}; --sandbox is ignored",
+                browser.display_name,
+                browser.source.canonical_name()
+            ));
+        }
+    }
+
     match browser.kind {
         BrowserKind::Safari => {
             match &profile_opts.profile_type {
-                ProfileType::Named(_) => {
-                    warnings.push("Safari does not support named profiles".to_string());
+                ProfileType::Named(name) => {
+                    if !cfg!(target_os = "macos") {
+                        warnings.push("Safari profiles are only supported on macOS".to_string());
+                    } else if ProfileManager::find_profile(browser, name).is_err() {
+                        warnings.push(format!(
+                            "Safari profile '{}' not found (profiles require Safari 17 or later)",
+                            name
+                        ));
+                    }
                 }
                 ProfileType::CustomDirectory(_) => {
                     warnings
@@ -1229,7 +3610,7 @@ pub fn validate_profile_options(
 
             if window_opts.incognito {
                 warnings.push(
-                    "Safari incognito mode requires manual activation (not supported via command line)"
+                    "Safari private windows are opened via AppleScript and require Accessibility access for the terminal"
                         .to_string(),
                 );
             }
@@ -1238,13 +3619,41 @@ pub fn validate_profile_options(
             }
         }
 
-        BrowserKind::Firefox | BrowserKind::Waterfox => {
+        BrowserKind::Firefox
+        | BrowserKind::Waterfox
+        | BrowserKind::LibreWolf
+        | BrowserKind::Zen
+        | BrowserKind::Floorp
+        | BrowserKind::PaleMoon
+        | BrowserKind::Basilisk => {
             if matches!(profile_opts.profile_type, ProfileType::Guest) {
                 warnings.push(
                     "Firefox does not support guest mode (use --incognito for private browsing)"
                         .to_string(),
                 );
             }
+            let locked = match &profile_opts.profile_type {
+                ProfileType::Named(name) => {
+                    ProfileManager::find_profile(browser, name).is_ok_and(|profile| profile.in_use)
+                }
+                ProfileType::CustomDirectory(path) => instance::is_directory_locked(path),
+                _ => false,
+            };
+            if locked {
+                warnings.push(format!(
+                    "The profile is in use; {} hands the URLs to the instance that has it open, \
+                     or reports it as already running if that instance does not accept them",
+                    browser.display_name
+                ));
+            }
+            if window_opts.kiosk
+                && matches!(browser.kind, BrowserKind::PaleMoon | BrowserKind::Basilisk)
+            {
+                warnings.push(format!(
+                    "{} does not support kiosk mode",
+                    browser.display_name
+                ));
+            }
         }
 
         BrowserKind::Chrome
@@ -1253,8 +3662,29 @@ pub fn validate_profile_options(
         | BrowserKind::Vivaldi
         | BrowserKind::Arc
         | BrowserKind::Helium
+        | BrowserKind::Thorium
+        | BrowserKind::UngoogledChromium
+        | BrowserKind::Yandex
+        | BrowserKind::Wavebox
+        | BrowserKind::Sidekick
         | BrowserKind::Opera
-        | BrowserKind::Chromium => {}
+        | BrowserKind::Chromium => {
+            if matches!(profile_opts.profile_type, ProfileType::Guest) {
+                match guest::guest_mode(browser) {
+                    guest::GuestMode::Supported => {}
+                    guest::GuestMode::Unsupported => warnings.push(format!(
+                        "{} has no guest mode; --guest opens its regular profile",
+                        browser.display_name
+                    )),
+                    guest::GuestMode::DisabledByPolicy(source) => {
+                        return Err(ProfileError::GuestModeDisabled(
+                            browser.display_name.clone(),
+                            source,
+                        ));
+                    }
+                }
+            }
+        }
 
         BrowserKind::TorBrowser => {
             if !matches!(profile_opts.profile_type, ProfileType::Default) {
@@ -1267,6 +3697,74 @@ pub fn validate_profile_options(
             }
         }
 
+        BrowserKind::Qutebrowser => {
+            if let ProfileType::Named(name) = &profile_opts.profile_type {
+                warnings.push(format!(
+                    "qutebrowser has no named profiles; ignoring '{}' (use --user-dir with a base directory)",
+                    name
+                ));
+            }
+            if window_opts.kiosk {
+                warnings
+                    .push("qutebrowser does not support kiosk mode via command line".to_string());
+            }
+        }
+
+        BrowserKind::Epiphany => {
+            if let ProfileType::Named(name) = &profile_opts.profile_type {
+                warnings.push(format!(
+                    "GNOME Web has no named profiles; ignoring '{}' (use --user-dir with a profile directory)",
+                    name
+                ));
+            }
+            if window_opts.kiosk {
+                warnings.push("GNOME Web does not support kiosk mode via command line".to_string());
+            }
+        }
+
+        BrowserKind::Falkon => match &profile_opts.profile_type {
+            ProfileType::CustomDirectory(_) | ProfileType::Temporary(_) => {
+                warnings.push(
+                    "Falkon only supports named profiles from its profiles directory (use --profile)"
+                        .to_string(),
+                );
+            }
+            ProfileType::Guest => {
+                warnings
+                    .push("Falkon has no guest mode; opening a private window instead".to_string());
+            }
+            ProfileType::Named(_) | ProfileType::Default => {}
+        },
+
+        BrowserKind::Konqueror => {
+            if !matches!(profile_opts.profile_type, ProfileType::Default) {
+                warnings.push("Konqueror does not support profile options".to_string());
+            }
+            if window_opts.incognito || window_opts.kiosk {
+                warnings.push(
+                    "Konqueror does not support private or kiosk windows via command line"
+                        .to_string(),
+                );
+            }
+        }
+
+        BrowserKind::DuckDuckGo => {
+            if !matches!(profile_opts.profile_type, ProfileType::Default) {
+                warnings.push("DuckDuckGo does not support profile options".to_string());
+            }
+            if window_opts.incognito {
+                warnings.push(
+                    "DuckDuckGo cannot open Fire windows via command line; opening a regular window"
+                        .to_string(),
+                );
+            }
+            if window_opts.new_window || window_opts.kiosk {
+                warnings.push(
+                    "DuckDuckGo does not support window options via command line".to_string(),
+                );
+            }
+        }
+
         BrowserKind::Other => {
             if !matches!(profile_opts.profile_type, ProfileType::Default) {
                 warnings.push(
@@ -1285,3 +3783,1523 @@ pub fn validate_profile_options(
 
     Ok(warnings)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::InstallScope;
+    use crate::filesystem::MockFileSystem;
+    use tempfile::TempDir;
+
+    fn chromium() -> BrowserInfo {
+        BrowserInfo {
+            kind: BrowserKind::Chromium,
+            channel: BrowserChannel::Chromium(ChromiumChannel::Stable),
+            display_name: "Chromium".to_string(),
+            executable_path: PathBuf::from("/usr/bin/chromium"),
+            version: None,
+            unique_id: "chromium.desktop".to_string(),
+            exec_command: None,
+            source: InstallationSource::Unknown,
+            scope: InstallScope::System,
+            arch: None,
+            is_default: false,
+        }
+    }
+
+    #[test]
+    fn chromium_profiles_are_managed_through_local_state() {
+        let user_data = TempDir::new().unwrap();
+        let browser = chromium();
+
+        let work =
+            ProfileManager::create_chromium_profile(&browser, "Work", Some(user_data.path()))
+                .unwrap();
+        let play =
+            ProfileManager::create_chromium_profile(&browser, "Play", Some(user_data.path()))
+                .unwrap();
+        assert_eq!(work.name, "Profile 1");
+        assert_eq!(play.name, "Profile 2");
+        assert!(play.path.is_dir());
+
+        let found =
+            ProfileManager::find_profile_in_directory(&browser, "Work", Some(user_data.path()))
+                .unwrap();
+        assert_eq!(found.name, "Profile 1");
+
+        let duplicate =
+            ProfileManager::create_chromium_profile(&browser, "work", Some(user_data.path()));
+        assert!(matches!(duplicate, Err(ProfileError::ProfileExists(_))));
+
+        ProfileManager::delete_profile(&browser, "Work", Some(user_data.path())).unwrap();
+        assert!(!work.path.exists());
+        let remaining: Vec<_> =
+            ProfileManager::discover_profiles_in_directory(&browser, Some(user_data.path()))
+                .unwrap()
+                .into_iter()
+                .map(|profile| profile.display_name)
+                .collect();
+        assert_eq!(remaining, vec!["Play"]);
+
+        let renamed =
+            ProfileManager::rename_profile(&browser, "Play", "Games", Some(user_data.path()))
+                .unwrap();
+        assert_eq!(renamed.name, "Profile 2");
+        assert_eq!(renamed.display_name, "Games");
+        let found =
+            ProfileManager::find_profile_in_directory(&browser, "Games", Some(user_data.path()))
+                .unwrap();
+        assert_eq!(found.name, "Profile 2");
+
+        fs::write(
+            found.path.join("Preferences"),
+            r#"{"profile":{"name":"Games"}}"#,
+        )
+        .unwrap();
+        fs::create_dir(found.path.join("Cache")).unwrap();
+        let clone =
+            ProfileManager::clone_profile(&browser, "Games", "Games 2", Some(user_data.path()))
+                .unwrap();
+        assert_eq!(clone.name, "Profile 1");
+        assert!(!clone.path.join("Cache").exists());
+        let preferences = fs::read_to_string(clone.path.join("Preferences")).unwrap();
+        assert_eq!(preferences, r#"{"profile":{"name":"Games 2"}}"#);
+
+        // The source is matched as loosely as `--profile` is
+        let clone =
+            ProfileManager::clone_profile(&browser, "games", "Games 3", Some(user_data.path()))
+                .unwrap();
+        let preferences = fs::read_to_string(clone.path.join("Preferences")).unwrap();
+        assert_eq!(preferences, r#"{"profile":{"name":"Games 3"}}"#);
+    }
+
+    #[test]
+    fn chromium_identity_is_read_from_info_cache() {
+        let entry = serde_json::json!({
+            "name": "Work",
+            "user_name": "me@example.com",
+            "gaia_name": "Me Myself",
+            "avatar_icon": "chrome://theme/IDR_PROFILE_AVATAR_26",
+            "use_gaia_picture": true,
+        });
+        assert_eq!(
+            ProfileIdentity::from_info_cache(&entry),
+            Some(ProfileIdentity {
+                email: Some("me@example.com".to_string()),
+                account_name: Some("Me Myself".to_string()),
+                avatar_icon: Some("chrome://theme/IDR_PROFILE_AVATAR_26".to_string()),
+                uses_account_picture: true,
+            })
+        );
+
+        let signed_out = serde_json::json!({ "name": "Play", "user_name": "", "gaia_name": "" });
+        assert_eq!(ProfileIdentity::from_info_cache(&signed_out), None);
+    }
+
+    #[test]
+    fn chromium_discovery_goes_through_the_file_system() {
+        let browser = chromium();
+        let home = PathBuf::from("/home/me");
+        let base_dir = ProfileManager::default_browser_dir_with_env(
+            &ProfileEnv {
+                fs: &MockFileSystem::new(),
+                home: Some(home.clone()),
+            },
+            &browser,
+        )
+        .unwrap();
+        assert!(base_dir.starts_with(&home));
+
+        let mut fs = MockFileSystem::new();
+        let present = [
+            base_dir.join("Local State"),
+            base_dir.join("Default"),
+            base_dir.join("Profile 1"),
+        ];
+        fs.expect_exists()
+            .returning(move |path| present.iter().any(|p| p == path));
+        fs.expect_read_to_string()
+            .withf({
+                let local_state = base_dir.join("Local State");
+                move |path| path == local_state
+            })
+            .returning(|_| {
+                Ok(r#"{"profile":{"info_cache":{
+                    "Default":{"name":"Personal","user_name":"me@example.com"},
+                    "Profile 1":{"name":"Work"},
+                    "Profile 2":{"name":"Deleted"}
+                }}}"#
+                    .to_string())
+            });
+        let env = ProfileEnv {
+            fs: &fs,
+            home: Some(home),
+        };
+
+        let profiles = ProfileManager::discover_profiles_with_env(&env, &browser, None).unwrap();
+        let mut names: Vec<_> = profiles
+            .iter()
+            .map(|p| (p.name.as_str(), p.display_name.as_str()))
+            .collect();
+        names.sort();
+        assert_eq!(names, vec![("Default", "Personal"), ("Profile 1", "Work")]);
+        let default = profiles.iter().find(|p| p.is_default).unwrap();
+        assert_eq!(default.path, base_dir.join("Default"));
+        assert_eq!(
+            default.identity.as_ref().and_then(|i| i.email.as_deref()),
+            Some("me@example.com")
+        );
+
+        let homeless = ProfileEnv {
+            fs: &MockFileSystem::new(),
+            home: None,
+        };
+        assert!(matches!(
+            ProfileManager::discover_profiles_with_env(&homeless, &browser, None),
+            Err(ProfileError::InvalidDirectory(_))
+        ));
+    }
+
+    #[test]
+    fn prerelease_channels_have_their_own_user_data_dirs() {
+        let env = ProfileEnv {
+            fs: &MockFileSystem::new(),
+            home: Some(PathBuf::from("/home/me")),
+        };
+        let dir = |kind, channel| {
+            let browser = BrowserInfo {
+                kind,
+                channel: BrowserChannel::Chromium(channel),
+                ..chromium()
+            };
+            ProfileManager::default_browser_dir_with_env(&env, &browser).unwrap()
+        };
+
+        for (kind, channels) in [
+            (
+                BrowserKind::Chrome,
+                &[
+                    ChromiumChannel::Beta,
+                    ChromiumChannel::Dev,
+                    ChromiumChannel::Canary,
+                ][..],
+            ),
+            (
+                BrowserKind::Edge,
+                &[ChromiumChannel::Beta, ChromiumChannel::Dev][..],
+            ),
+            (
+                BrowserKind::Brave,
+                &[
+                    ChromiumChannel::Beta,
+                    ChromiumChannel::Dev,
+                    ChromiumChannel::Canary,
+                ][..],
+            ),
+            (BrowserKind::Yandex, &[ChromiumChannel::Beta][..]),
+        ] {
+            let mut dirs = vec![dir(kind, ChromiumChannel::Stable)];
+            dirs.extend(channels.iter().map(|&channel| dir(kind, channel)));
+            let count = dirs.len();
+            dirs.sort();
+            dirs.dedup();
+            assert_eq!(dirs.len(), count, "{:?} channels share a directory", kind);
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            assert_eq!(
+                dir(BrowserKind::Chrome, ChromiumChannel::Canary),
+                Path::new("/home/me/.config/google-chrome-canary")
+            );
+            assert_eq!(
+                dir(BrowserKind::Edge, ChromiumChannel::Dev),
+                Path::new("/home/me/.config/microsoft-edge-dev")
+            );
+            assert_eq!(
+                dir(BrowserKind::Yandex, ChromiumChannel::Beta),
+                Path::new("/home/me/.config/yandex-browser-beta")
+            );
+        }
+    }
+
+    #[test]
+    fn firefox_discovery_goes_through_the_file_system() {
+        let browser = BrowserInfo {
+            kind: BrowserKind::Firefox,
+            display_name: "Firefox".to_string(),
+            executable_path: PathBuf::from("/usr/bin/firefox"),
+            unique_id: "firefox.desktop".to_string(),
+            ..chromium()
+        };
+        let base_dir = Path::new("/data/firefox");
+
+        let mut fs = MockFileSystem::new();
+        let present = [
+            base_dir.join("profiles.ini"),
+            base_dir.join("a1.default"),
+            base_dir.join("b2.default-release"),
+            PathBuf::from("/mnt/work"),
+        ];
+        fs.expect_exists()
+            .returning(move |path| present.iter().any(|p| p == path));
+        fs.expect_read_to_string()
+            .withf(|path| path == Path::new("/data/firefox/profiles.ini"))
+            .returning(|_| {
+                Ok(
+                    "[Profile0]\nName=default\nIsRelative=1\nPath=a1.default\nDefault=1\n\n\
+                    [Profile1]\nName=default-release\nIsRelative=1\nPath=b2.default-release\n\n\
+                    [Profile2]\nName=work\nIsRelative=0\nPath=/mnt/work\n\n\
+                    [Profile3]\nName=gone\nIsRelative=1\nPath=c3.gone\n\n\
+                    [Install4F96D1932A9F858E]\nDefault=b2.default-release\nLocked=1\n"
+                        .to_string(),
+                )
+            });
+        // /usr/bin/firefox links to the binary of the installation in /usr/lib/firefox
+        fs.expect_canonicalize()
+            .returning(|_| Ok(PathBuf::from("/usr/lib/firefox/firefox")));
+        let env = ProfileEnv {
+            fs: &fs,
+            home: None,
+        };
+
+        let profiles =
+            ProfileManager::discover_profiles_with_env(&env, &browser, Some(base_dir)).unwrap();
+        let names: Vec<_> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["default", "default-release", "work"]);
+        let default = profiles.iter().find(|p| p.is_default).unwrap();
+        assert_eq!(default.name, "default-release");
+        assert_eq!(profiles[2].path, Path::new("/mnt/work"));
+    }
+
+    #[test]
+    fn firefox_profiles_are_renamed_in_profiles_ini() {
+        let browser = BrowserInfo {
+            kind: BrowserKind::Firefox,
+            display_name: "Firefox".to_string(),
+            ..chromium()
+        };
+        let profiles: Vec<ProfileInfo> = [("default", "a1.default"), ("work", "b2.work")]
+            .into_iter()
+            .map(|(name, dir)| ProfileInfo {
+                name: name.to_string(),
+                display_name: name.to_string(),
+                path: Path::new("/data/firefox").join(dir),
+                ..ProfileManager::default_profile(BrowserKind::Firefox)
+            })
+            .collect();
+
+        let mut fs = MockFileSystem::new();
+        fs.expect_read_to_string()
+            .withf(|path| path == Path::new("/data/firefox/profiles.ini"))
+            .returning(|_| {
+                Ok("[Profile0]\nName=default\nPath=a1.default\n\n\
+                    [Profile1]\nName=work\nPath=b2.work\n"
+                    .to_string())
+            });
+        fs.expect_write()
+            .withf(|path, contents| {
+                path == Path::new("/data/firefox/profiles.ini")
+                    && contents
+                        == b"[Profile0]\nName=default\nPath=a1.default\n\n\
+                             [Profile1]\nName=office\nPath=b2.work\n"
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+        let env = ProfileEnv {
+            fs: &fs,
+            home: None,
+        };
+        let rename = |name, new_name| {
+            ProfileManager::rename_profile_with_env(
+                &env,
+                &browser,
+                &profiles,
+                name,
+                new_name,
+                Some(Path::new("/data/firefox")),
+            )
+        };
+
+        assert!(matches!(
+            rename("work", "Default"),
+            Err(ProfileError::ProfileExists(_))
+        ));
+        let renamed = rename("work", "office").unwrap();
+        assert_eq!(renamed.name, "office");
+        assert_eq!(renamed.display_name, "office");
+        assert_eq!(renamed.path, Path::new("/data/firefox/b2.work"));
+    }
+
+    #[test]
+    fn zen_profiles_are_kept_apart_from_firefox() {
+        let zen = BrowserInfo {
+            kind: BrowserKind::Zen,
+            channel: BrowserChannel::Firefox(crate::browser::channels::FirefoxChannel::Twilight),
+            display_name: "Zen Twilight".to_string(),
+            executable_path: PathBuf::from("/usr/bin/zen"),
+            unique_id: "zen.desktop".to_string(),
+            ..chromium()
+        };
+        let mut fs = MockFileSystem::new();
+        fs.expect_exists().returning(|path| {
+            path == Path::new("/home/me/.zen/profiles.ini")
+                || path == Path::new("/home/me/.zen/x9.twilight")
+        });
+        fs.expect_read_to_string().returning(|_| {
+            Ok("[Profile0]\nName=Default (twilight)\nIsRelative=1\nPath=x9.twilight\n".to_string())
+        });
+        fs.expect_canonicalize()
+            .returning(|path| Ok(path.to_path_buf()));
+        let env = ProfileEnv {
+            fs: &fs,
+            home: Some(PathBuf::from("/home/me")),
+        };
+
+        if cfg!(target_os = "linux") {
+            let profiles = ProfileManager::discover_profiles_with_env(&env, &zen, None).unwrap();
+            assert_eq!(profiles.len(), 1);
+            assert_eq!(profiles[0].path, Path::new("/home/me/.zen/x9.twilight"));
+        }
+        let profile_opts = ProfileOptions {
+            profile_type: ProfileType::Guest,
+            custom_args: Vec::new(),
+        };
+        let args =
+            ProfileManager::generate_profile_args(&zen, &profile_opts, &WindowOptions::default());
+        assert_eq!(args, vec!["--private-window"]);
+    }
+
+    #[test]
+    fn qutebrowser_profiles_are_base_directories() {
+        let qutebrowser = BrowserInfo {
+            kind: BrowserKind::Qutebrowser,
+            display_name: "qutebrowser".to_string(),
+            ..chromium()
+        };
+        let args = |profile_type, window_opts| {
+            let profile_opts = ProfileOptions {
+                profile_type,
+                custom_args: Vec::new(),
+            };
+            ProfileManager::generate_profile_args(&qutebrowser, &profile_opts, &window_opts)
+        };
+
+        assert_eq!(
+            args(
+                ProfileType::CustomDirectory(PathBuf::from("/tmp/qb")),
+                WindowOptions {
+                    new_window: true,
+                    ..Default::default()
+                }
+            ),
+            vec!["--basedir", "/tmp/qb", "--target", "window"]
+        );
+        assert_eq!(
+            args(
+                ProfileType::Guest,
+                WindowOptions {
+                    incognito: true,
+                    ..Default::default()
+                }
+            ),
+            vec!["--temp-basedir", "--target", "private-window"]
+        );
+        assert_eq!(
+            args(ProfileType::Default, WindowOptions::default()),
+            vec!["--target", "tab"]
+        );
+
+        let named = ProfileOptions {
+            profile_type: ProfileType::Named("work".to_string()),
+            custom_args: Vec::new(),
+        };
+        let warnings =
+            validate_profile_options(&qutebrowser, &named, &WindowOptions::default()).unwrap();
+        assert_eq!(
+            warnings,
+            vec!["qutebrowser has no named profiles; ignoring 'work' (use --user-dir with a base directory)"]
+        );
+    }
+
+    #[test]
+    fn floorp_profiles_live_in_their_own_directory() {
+        let env = ProfileEnv {
+            fs: &MockFileSystem::new(),
+            home: Some(PathBuf::from("/home/me")),
+        };
+        let floorp = BrowserInfo {
+            kind: BrowserKind::Floorp,
+            channel: BrowserChannel::Firefox(crate::browser::channels::FirefoxChannel::Beta),
+            display_name: "Floorp Beta".to_string(),
+            ..chromium()
+        };
+
+        let expected = if cfg!(target_os = "macos") {
+            "/home/me/Library/Application Support/Floorp"
+        } else if cfg!(target_os = "windows") {
+            "/home/me/AppData/Roaming/Floorp"
+        } else {
+            "/home/me/.floorp"
+        };
+        assert_eq!(
+            ProfileManager::default_browser_dir_with_env(&env, &floorp).unwrap(),
+            Path::new(expected)
+        );
+
+        let profile_opts = ProfileOptions {
+            profile_type: ProfileType::CustomDirectory(PathBuf::from("/tmp/floorp")),
+            custom_args: Vec::new(),
+        };
+        let window_opts = WindowOptions {
+            new_window: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            ProfileManager::generate_profile_args(&floorp, &profile_opts, &window_opts),
+            vec!["--profile", "/tmp/floorp", "--new-window"]
+        );
+    }
+
+    #[test]
+    fn workspace_browsers_use_chromium_user_data_dirs() {
+        let env = ProfileEnv {
+            fs: &MockFileSystem::new(),
+            home: Some(PathBuf::from("/home/me")),
+        };
+        for (kind, expected) in [
+            (
+                BrowserKind::Wavebox,
+                if cfg!(target_os = "macos") {
+                    "/home/me/Library/Application Support/WaveboxApp"
+                } else if cfg!(target_os = "windows") {
+                    "/home/me/AppData/Local/WaveboxApp/User Data"
+                } else {
+                    "/home/me/.config/wavebox"
+                },
+            ),
+            (
+                BrowserKind::Sidekick,
+                if cfg!(target_os = "macos") {
+                    "/home/me/Library/Application Support/Sidekick"
+                } else if cfg!(target_os = "windows") {
+                    "/home/me/AppData/Local/Sidekick/User Data"
+                } else {
+                    "/home/me/.config/sidekick"
+                },
+            ),
+        ] {
+            let browser = BrowserInfo { kind, ..chromium() };
+            assert_eq!(
+                ProfileManager::default_browser_dir_with_env(&env, &browser).unwrap(),
+                Path::new(expected)
+            );
+
+            let profile_opts = ProfileOptions {
+                profile_type: ProfileType::CustomDirectory(PathBuf::from("/tmp/work")),
+                custom_args: Vec::new(),
+            };
+            let window_opts = WindowOptions {
+                incognito: true,
+                ..Default::default()
+            };
+            assert_eq!(
+                ProfileManager::generate_profile_args(&browser, &profile_opts, &window_opts),
+                vec!["--user-data-dir=/tmp/work", "--incognito"]
+            );
+        }
+    }
+
+    #[test]
+    fn duckduckgo_only_takes_urls() {
+        let duckduckgo = BrowserInfo {
+            kind: BrowserKind::DuckDuckGo,
+            display_name: "DuckDuckGo".to_string(),
+            ..chromium()
+        };
+        let profile_opts = ProfileOptions {
+            profile_type: ProfileType::Named("work".to_string()),
+            custom_args: Vec::new(),
+        };
+        let window_opts = WindowOptions {
+            incognito: true,
+            new_window: true,
+            ..Default::default()
+        };
+
+        assert!(
+            ProfileManager::generate_profile_args(&duckduckgo, &profile_opts, &window_opts)
+                .is_empty()
+        );
+        assert_eq!(
+            validate_profile_options(&duckduckgo, &profile_opts, &window_opts).unwrap(),
+            vec![
+                "DuckDuckGo does not support profile options",
+                "DuckDuckGo cannot open Fire windows via command line; opening a regular window",
+                "DuckDuckGo does not support window options via command line",
+            ]
+        );
+        assert!(matches!(
+            ProfileManager::get_default_browser_dir(&duckduckgo),
+            Err(ProfileError::UnsupportedBrowser(_))
+        ));
+    }
+
+    #[test]
+    fn goanna_browsers_have_firefox_profiles_without_kiosk_mode() {
+        let env = ProfileEnv {
+            fs: &MockFileSystem::new(),
+            home: Some(PathBuf::from("/home/me")),
+        };
+        let profile_opts = ProfileOptions {
+            profile_type: ProfileType::Default,
+            custom_args: Vec::new(),
+        };
+        let window_opts = WindowOptions {
+            kiosk: true,
+            new_window: true,
+            ..Default::default()
+        };
+
+        for (kind, name, linux_dir) in [
+            (
+                BrowserKind::PaleMoon,
+                "Pale Moon",
+                "/home/me/.moonchild productions/pale moon",
+            ),
+            (
+                BrowserKind::Basilisk,
+                "Basilisk",
+                "/home/me/.moonchild productions/basilisk",
+            ),
+        ] {
+            let browser = BrowserInfo {
+                kind,
+                display_name: name.to_string(),
+                ..chromium()
+            };
+            if cfg!(target_os = "linux") {
+                assert_eq!(
+                    ProfileManager::default_browser_dir_with_env(&env, &browser).unwrap(),
+                    Path::new(linux_dir)
+                );
+            }
+            assert_eq!(
+                ProfileManager::generate_profile_args(&browser, &profile_opts, &window_opts),
+                vec!["--new-window"]
+            );
+            assert_eq!(
+                validate_profile_options(&browser, &profile_opts, &window_opts).unwrap(),
+                vec![format!("{} does not support kiosk mode", name)]
+            );
+        }
+    }
+
+    #[test]
+    fn gnome_web_takes_profile_directories() {
+        let epiphany = BrowserInfo {
+            kind: BrowserKind::Epiphany,
+            display_name: "GNOME Web".to_string(),
+            ..chromium()
+        };
+        let args = |profile_type, window_opts| {
+            let profile_opts = ProfileOptions {
+                profile_type,
+                custom_args: Vec::new(),
+            };
+            ProfileManager::generate_profile_args(&epiphany, &profile_opts, &window_opts)
+        };
+
+        assert_eq!(
+            args(
+                ProfileType::Temporary(PathBuf::from("/tmp/web")),
+                WindowOptions {
+                    new_window: true,
+                    ..Default::default()
+                }
+            ),
+            vec!["--profile", "/tmp/web", "--new-window"]
+        );
+        assert_eq!(
+            args(
+                ProfileType::Guest,
+                WindowOptions {
+                    incognito: true,
+                    ..Default::default()
+                }
+            ),
+            vec!["--private-instance", "--incognito-mode"]
+        );
+
+        let named = ProfileOptions {
+            profile_type: ProfileType::Named("work".to_string()),
+            custom_args: Vec::new(),
+        };
+        let kiosk = WindowOptions {
+            kiosk: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_profile_options(&epiphany, &named, &kiosk).unwrap(),
+            vec![
+                "GNOME Web has no named profiles; ignoring 'work' (use --user-dir with a profile directory)",
+                "GNOME Web does not support kiosk mode via command line",
+            ]
+        );
+    }
+
+    #[test]
+    fn kde_browsers_map_profiles_and_private_windows() {
+        let falkon = BrowserInfo {
+            kind: BrowserKind::Falkon,
+            display_name: "Falkon".to_string(),
+            ..chromium()
+        };
+        let konqueror = BrowserInfo {
+            kind: BrowserKind::Konqueror,
+            display_name: "Konqueror".to_string(),
+            ..chromium()
+        };
+        let named = ProfileOptions {
+            profile_type: ProfileType::Named("work".to_string()),
+            custom_args: Vec::new(),
+        };
+        let guest = ProfileOptions {
+            profile_type: ProfileType::Guest,
+            custom_args: Vec::new(),
+        };
+        let new_window = WindowOptions {
+            new_window: true,
+            ..Default::default()
+        };
+        let incognito = WindowOptions {
+            incognito: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            ProfileManager::generate_profile_args(&falkon, &named, &new_window),
+            vec!["-p", "work", "--new-window"]
+        );
+        assert_eq!(
+            ProfileManager::generate_profile_args(&falkon, &guest, &WindowOptions::default()),
+            vec!["--private-browsing"]
+        );
+        assert_eq!(
+            validate_profile_options(&falkon, &guest, &WindowOptions::default()).unwrap(),
+            vec!["Falkon has no guest mode; opening a private window instead"]
+        );
+
+        assert!(ProfileManager::generate_profile_args(&konqueror, &named, &incognito).is_empty());
+        assert_eq!(
+            validate_profile_options(&konqueror, &named, &incognito).unwrap(),
+            vec![
+                "Konqueror does not support profile options",
+                "Konqueror does not support private or kiosk windows via command line",
+            ]
+        );
+    }
+
+    #[test]
+    fn profile_names_match_loosely_unless_ambiguous() {
+        let profiles: Vec<ProfileInfo> = [
+            ("Default", "Personal"),
+            ("Profile 1", "Work (Contoso)"),
+            ("Profile 2", "Work (Fabrikam)"),
+            ("Profile 3", "Gaming"),
+            ("Profile 4", "Homework"),
+        ]
+        .into_iter()
+        .map(|(name, display_name)| ProfileInfo {
+            name: name.to_string(),
+            display_name: display_name.to_string(),
+            ..ProfileManager::default_profile(BrowserKind::Chrome)
+        })
+        .collect();
+        let find = |query| ProfileManager::match_profile(&profiles, query);
+
+        assert_eq!(find("Profile 3").unwrap(), 3);
+        assert_eq!(find("personal").unwrap(), 0);
+        assert_eq!(find("gam").unwrap(), 3);
+        assert_eq!(find("contoso").unwrap(), 1);
+        // Only the prefix matches count once there are any, so "Homework" is no candidate
+        match find("work") {
+            Err(ProfileError::AmbiguousProfile(query, candidates)) => {
+                assert_eq!(query, "work");
+                assert_eq!(
+                    candidates,
+                    "Work (Contoso) (Profile 1), Work (Fabrikam) (Profile 2)"
+                );
+            }
+            other => panic!("expected an ambiguous match, got {:?}", other),
+        }
+        assert!(matches!(
+            find("school"),
+            Err(ProfileError::ProfileNotFound(_))
+        ));
+
+        // Commands that change a profile take whole names only
+        let exact = |query| ProfileManager::match_profile_exactly(&profiles, query);
+        assert_eq!(exact("gaming").unwrap(), 3);
+        assert_eq!(exact("Profile 4").unwrap(), 4);
+        for query in ["gam", "contoso", "work"] {
+            assert!(
+                matches!(exact(query), Err(ProfileError::ProfileNotFound(_))),
+                "{} matched",
+                query
+            );
+        }
+        let twins: Vec<ProfileInfo> = ["Work", "WORK"]
+            .into_iter()
+            .enumerate()
+            .map(|(index, display_name)| ProfileInfo {
+                name: format!("Profile {}", index),
+                display_name: display_name.to_string(),
+                ..ProfileManager::default_profile(BrowserKind::Chrome)
+            })
+            .collect();
+        assert_eq!(
+            ProfileManager::match_profile_exactly(&twins, "WORK").unwrap(),
+            1
+        );
+        assert!(matches!(
+            ProfileManager::match_profile_exactly(&twins, "work"),
+            Err(ProfileError::AmbiguousProfile(_, _))
+        ));
+    }
+
+    #[test]
+    fn ephemeral_copies_keep_only_the_copied_profile() {
+        let local_state = r#"{"os_crypt":{"encrypted_key":"RFBBUEk="},"profile":{
+            "info_cache":{"Default":{"name":"Personal"},"Profile 3":{"name":"Work"}},
+            "last_used":"Profile 1","last_active_profiles":["Default","Profile 3"]}}"#;
+        let copy: serde_json::Value =
+            serde_json::from_str(&ephemeral_local_state(local_state, "Profile 3").unwrap())
+                .unwrap();
+        assert_eq!(
+            copy["profile"]["info_cache"],
+            serde_json::json!({"Default": {"name": "Work"}})
+        );
+        assert_eq!(copy["profile"]["last_used"], "Default");
+        assert_eq!(copy["os_crypt"]["encrypted_key"], "RFBBUEk=");
+
+        let dir = TempDir::new().unwrap();
+        assert!(matches!(
+            ProfileManager::discard_temp_profile(dir.path()),
+            Err(ProfileError::InvalidDirectory(_))
+        ));
+        assert!(dir.path().exists());
+    }
+
+    #[test]
+    fn tor_windows_are_brave_only() {
+        let mut brave = chromium();
+        brave.kind = BrowserKind::Brave;
+        let profile_opts = ProfileOptions {
+            profile_type: ProfileType::Default,
+            custom_args: Vec::new(),
+        };
+        let window_opts = WindowOptions {
+            tor_window: true,
+            ..Default::default()
+        };
+
+        let args = ProfileManager::generate_profile_args(&brave, &profile_opts, &window_opts);
+        assert_eq!(args, vec!["--tor"]);
+        assert!(
+            validate_profile_options(&brave, &profile_opts, &window_opts)
+                .unwrap()
+                .is_empty()
+        );
+
+        let chrome = chromium();
+        let args = ProfileManager::generate_profile_args(&chrome, &profile_opts, &window_opts);
+        assert!(args.is_empty());
+        let warnings = validate_profile_options(&chrome, &profile_opts, &window_opts).unwrap();
+        assert!(warnings[0].contains("--tor-window only applies to Brave"));
+    }
+
+    #[test]
+    fn new_instances_are_only_launched_on_macos() {
+        let profile_opts = ProfileOptions {
+            profile_type: ProfileType::Default,
+            custom_args: Vec::new(),
+        };
+        let window_opts = WindowOptions {
+            new_instance: true,
+            ..Default::default()
+        };
+
+        let warnings = validate_profile_options(&chromium(), &profile_opts, &window_opts).unwrap();
+        assert_eq!(
+            warnings.contains(&"--new-instance is only supported on macOS".to_string()),
+            !cfg!(target_os = "macos")
+        );
+        assert!(
+            ProfileManager::generate_profile_args(&chromium(), &profile_opts, &window_opts)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn safari_private_windows_are_opened_through_automation() {
+        let mut safari = chromium();
+        safari.kind = BrowserKind::Safari;
+        let profile_opts = ProfileOptions {
+            profile_type: ProfileType::Default,
+            custom_args: Vec::new(),
+        };
+        let window_opts = WindowOptions {
+            incognito: true,
+            ..Default::default()
+        };
+
+        assert!(
+            ProfileManager::generate_profile_args(&safari, &profile_opts, &window_opts).is_empty()
+        );
+        let warnings = validate_profile_options(&safari, &profile_opts, &window_opts).unwrap();
+        assert_eq!(
+            warnings,
+            vec!["Safari private windows are opened via AppleScript and require Accessibility access for the terminal"]
+        );
+    }
+
+    #[test]
+    fn window_names_are_passed_to_chromium_only() {
+        let profile_opts = ProfileOptions {
+            profile_type: ProfileType::Default,
+            custom_args: Vec::new(),
+        };
+        let window_opts = WindowOptions {
+            window_name: Some("docs".to_string()),
+            ..Default::default()
+        };
+
+        let args = ProfileManager::generate_profile_args(&chromium(), &profile_opts, &window_opts);
+        let mut expected = vec!["--window-name=docs".to_string()];
+        if cfg!(target_os = "linux") {
+            expected.push("--class=docs".to_string());
+        }
+        assert_eq!(args, expected);
+
+        let mut firefox = chromium();
+        firefox.kind = BrowserKind::Firefox;
+        firefox.display_name = "Firefox".to_string();
+        let warnings = validate_profile_options(&firefox, &profile_opts, &window_opts).unwrap();
+        assert_eq!(
+            warnings,
+            vec!["--window-name is only supported by Chromium-based browsers and is ignored for Firefox"]
+        );
+    }
+
+    #[test]
+    fn strict_kiosk_hardens_chromium_only() {
+        let profile_opts = ProfileOptions {
+            profile_type: ProfileType::Default,
+            custom_args: Vec::new(),
+        };
+        let window_opts = WindowOptions {
+            kiosk: true,
+            kiosk_strict: true,
+            ..Default::default()
+        };
+
+        let args = ProfileManager::generate_profile_args(&chromium(), &profile_opts, &window_opts);
+        assert_eq!(args[0], "--kiosk");
+        assert_eq!(&args[1..], CHROMIUM_KIOSK_STRICT_ARGS);
+
+        let mut firefox = chromium();
+        firefox.kind = BrowserKind::Firefox;
+        firefox.display_name = "Firefox".to_string();
+        let args = ProfileManager::generate_profile_args(&firefox, &profile_opts, &window_opts);
+        assert_eq!(args, vec!["--kiosk"]);
+        let warnings = validate_profile_options(&firefox, &profile_opts, &window_opts).unwrap();
+        assert_eq!(
+            warnings,
+            vec!["Kiosk hardening flags only apply to Chromium-based browsers; Firefox gets plain kiosk mode"]
+        );
+    }
+
+    #[test]
+    fn guest_mode_is_checked_per_browser() {
+        let profile_opts = ProfileOptions {
+            profile_type: ProfileType::Guest,
+            custom_args: Vec::new(),
+        };
+        let window_opts = WindowOptions::default();
+
+        let mut arc = chromium();
+        arc.kind = BrowserKind::Arc;
+        arc.display_name = "Arc".to_string();
+        let warnings = validate_profile_options(&arc, &profile_opts, &window_opts).unwrap();
+        assert_eq!(
+            warnings,
+            vec!["Arc has no guest mode; --guest opens its regular profile"]
+        );
+
+        let mut opera = chromium();
+        opera.kind = BrowserKind::Opera;
+        assert_eq!(guest::guest_mode(&opera), guest::GuestMode::Unsupported);
+
+        let mut vivaldi = chromium();
+        vivaldi.kind = BrowserKind::Vivaldi;
+        assert!(
+            validate_profile_options(&vivaldi, &profile_opts, &window_opts)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn safari_profiles_are_read_from_rows_or_folders() {
+        let profiles_dir = TempDir::new().unwrap();
+        let rows = "DefaultProfile\tPersonal\n\
+                    5A1F0E7C-2B3D-4E5F-8A9B-0C1D2E3F4A5B\tWork\n\
+                    malformed row\n";
+        let profiles = ProfileManager::safari_profiles_from_rows(
+            rows,
+            profiles_dir.path(),
+            BrowserKind::Safari,
+        );
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].display_name, "Work");
+        assert_eq!(
+            profiles[0].path,
+            profiles_dir
+                .path()
+                .join("5A1F0E7C-2B3D-4E5F-8A9B-0C1D2E3F4A5B")
+        );
+
+        fs::create_dir(profiles_dir.path().join("DefaultProfile")).unwrap();
+        fs::create_dir(
+            profiles_dir
+                .path()
+                .join("9E8D7C6B-0000-4000-8000-000000000001"),
+        )
+        .unwrap();
+        fs::write(profiles_dir.path().join(".DS_Store"), "").unwrap();
+        let names: Vec<_> = ProfileManager::safari_profiles_from_dirs(
+            &RealFileSystem,
+            profiles_dir.path(),
+            BrowserKind::Safari,
+        )
+        .into_iter()
+        .map(|profile| profile.name)
+        .collect();
+        assert_eq!(names, vec!["9E8D7C6B-0000-4000-8000-000000000001"]);
+    }
+
+    #[test]
+    fn safari_always_has_its_default_profile() {
+        let mut safari = chromium();
+        safari.kind = BrowserKind::Safari;
+        let safari_dir = TempDir::new().unwrap();
+
+        let profiles =
+            ProfileManager::discover_profiles_in_directory(&safari, Some(safari_dir.path()))
+                .unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert!(profiles[0].is_default);
+        assert_eq!(profiles[0].path, safari_dir.path());
+
+        let profile_opts = ProfileOptions {
+            profile_type: ProfileType::Named("Work".to_string()),
+            custom_args: Vec::new(),
+        };
+        let warnings =
+            validate_profile_options(&safari, &profile_opts, &WindowOptions::default()).unwrap();
+        if !cfg!(target_os = "macos") {
+            assert_eq!(
+                warnings,
+                vec!["Safari profiles are only supported on macOS"]
+            );
+        }
+    }
+
+    #[test]
+    fn arc_spaces_are_listed_as_profiles() {
+        let mut arc = chromium();
+        arc.kind = BrowserKind::Arc;
+        let arc_dir = TempDir::new().unwrap();
+        let user_data = arc_dir.path().join("User Data");
+        fs::create_dir_all(user_data.join("Default")).unwrap();
+        fs::create_dir_all(user_data.join("Profile 1")).unwrap();
+        let sidebar = serde_json::json!({
+            "sidebar": { "containers": [
+                { "global": {} },
+                { "spaces": [
+                    "3C9F2E1A",
+                    { "id": "3C9F2E1A", "title": "Personal", "profile": { "default": true } },
+                    {
+                        "id": "7D41B0C2",
+                        "title": "Work",
+                        "profile": { "custom": { "_0": { "directoryBasename": "Profile 1" } } },
+                    },
+                ] },
+            ] }
+        });
+        fs::write(
+            arc_dir.path().join("StorableSidebar.json"),
+            sidebar.to_string(),
+        )
+        .unwrap();
+
+        let profiles =
+            ProfileManager::discover_profiles_in_directory(&arc, Some(&user_data)).unwrap();
+        let spaces: Vec<_> = profiles
+            .iter()
+            .map(|p| (p.name.as_str(), p.arc_space.as_deref(), p.path.clone()))
+            .collect();
+        assert_eq!(
+            spaces,
+            vec![
+                ("3C9F2E1A", Some("Personal"), user_data.join("Default")),
+                ("7D41B0C2", Some("Work"), user_data.join("Profile 1")),
+            ]
+        );
+        assert!(matches!(
+            ProfileManager::delete_profile(&arc, "Work", Some(&user_data)),
+            Err(ProfileError::UnsupportedBrowser(_))
+        ));
+        assert!(user_data.join("Profile 1").exists());
+
+        fs::remove_file(arc_dir.path().join("StorableSidebar.json")).unwrap();
+        let profiles =
+            ProfileManager::discover_profiles_in_directory(&arc, Some(&user_data)).unwrap();
+        assert_eq!(profiles[0].name, "Default");
+        assert_eq!(profiles[0].arc_space, None);
+    }
+
+    #[test]
+    fn arc_spaces_are_read_from_the_sidebar() {
+        let mut arc = chromium();
+        arc.kind = BrowserKind::Arc;
+        arc.display_name = "Arc".to_string();
+        let sidebar = PathBuf::from("/arc/StorableSidebar.json");
+        let mut fs = MockFileSystem::new();
+        fs.expect_exists().returning({
+            let sidebar = sidebar.clone();
+            move |path| path == sidebar
+        });
+        fs.expect_read_to_string().returning(|_| {
+            Ok(r#"{"sidebar":{"containers":[{"spaces":[
+                "A1",
+                {"id":"A1","title":"Home"},
+                {"id":"B2","title":"Work","profile":{"custom":{"_0":{"directoryBasename":"Profile 2"}}}},
+                {"id":"C3"}
+            ]}]}}"#
+                .to_string())
+        });
+        let env = ProfileEnv {
+            fs: &fs,
+            home: None,
+        };
+
+        let spaces = ProfileManager::discover_arc_spaces_with_env(
+            &env,
+            &arc,
+            Some(Path::new("/arc/User Data")),
+        )
+        .unwrap();
+        assert_eq!(
+            spaces,
+            vec![
+                ArcSpace {
+                    id: "A1".to_string(),
+                    title: "Home".to_string(),
+                    profile: None,
+                },
+                ArcSpace {
+                    id: "B2".to_string(),
+                    title: "Work".to_string(),
+                    profile: Some("Profile 2".to_string()),
+                },
+            ]
+        );
+        assert!(matches!(
+            ProfileManager::discover_arc_spaces_with_env(&env, &chromium(), None),
+            Err(ProfileError::UnsupportedBrowser(_))
+        ));
+
+        let profile_opts = ProfileOptions {
+            profile_type: ProfileType::Default,
+            custom_args: Vec::new(),
+        };
+        let window_opts = WindowOptions {
+            arc_space: Some("Work".to_string()),
+            ..Default::default()
+        };
+        let warnings = validate_profile_options(&chromium(), &profile_opts, &window_opts).unwrap();
+        assert_eq!(
+            warnings,
+            vec!["--arc-space only applies to Arc and is ignored for Chromium"]
+        );
+    }
+
+    #[test]
+    fn edge_workspaces_are_read_from_each_profile_cache() {
+        let mut edge = chromium();
+        edge.kind = BrowserKind::Edge;
+        edge.display_name = "Microsoft Edge".to_string();
+        let user_data = TempDir::new().unwrap();
+        let workspaces_dir = user_data.path().join("Profile 1").join("Workspaces");
+        fs::create_dir_all(&workspaces_dir).unwrap();
+        fs::create_dir(user_data.path().join("Default")).unwrap();
+        fs::write(
+            user_data.path().join("Local State"),
+            r#"{"profile":{"info_cache":{"Default":{},"Profile 1":{}}}}"#,
+        )
+        .unwrap();
+        fs::write(
+            workspaces_dir.join("WorkspacesCache"),
+            r#"{"workspaces":[{"id":"ws-1","name":"Launch"},{"id":"ws-2"}]}"#,
+        )
+        .unwrap();
+
+        let workspaces =
+            ProfileManager::discover_edge_workspaces(&edge, Some(user_data.path())).unwrap();
+        let found: Vec<_> = workspaces
+            .iter()
+            .map(|w| (w.id.as_str(), w.name.as_str(), w.profile.as_str()))
+            .collect();
+        assert_eq!(found, vec![("ws-1", "Launch", "Profile 1")]);
+        assert!(matches!(
+            ProfileManager::discover_edge_workspaces(&chromium(), Some(user_data.path())),
+            Err(ProfileError::UnsupportedBrowser(_))
+        ));
+
+        edge.version = Some("110.0.1587.50".to_string());
+        let profile_opts = ProfileOptions {
+            profile_type: ProfileType::Default,
+            custom_args: Vec::new(),
+        };
+        let window_opts = WindowOptions {
+            edge_workspace: Some("Launch".to_string()),
+            ..Default::default()
+        };
+        let warnings = validate_profile_options(&edge, &profile_opts, &window_opts).unwrap();
+        assert!(warnings.contains(
+            &"Edge Workspaces require Microsoft Edge 114 or later (found 110.0.1587.50)"
+                .to_string()
+        ));
+    }
+
+    #[test]
+    fn vivaldi_names_and_workspaces_come_from_preferences() {
+        let mut vivaldi = chromium();
+        vivaldi.kind = BrowserKind::Vivaldi;
+        let user_data = TempDir::new().unwrap();
+        fs::create_dir(user_data.path().join("Default")).unwrap();
+        fs::create_dir(user_data.path().join("Profile 1")).unwrap();
+        let local_state = serde_json::json!({
+            "profile": { "info_cache": {
+                "Default": { "name": "Person 1" },
+                "Profile 1": { "name": "Person 2" },
+            } }
+        });
+        fs::write(
+            user_data.path().join("Local State"),
+            local_state.to_string(),
+        )
+        .unwrap();
+        let preferences = serde_json::json!({
+            "profile": { "name": "Home" },
+            "vivaldi": { "workspaces": { "list": [
+                { "id": 1_700_000_000_000.0_f64, "name": "Research", "emoji": "🔬" },
+                { "id": 1_700_000_000_001.0_f64, "name": "Shopping", "emoji": "" },
+            ] } }
+        });
+        fs::write(
+            user_data.path().join("Default/Preferences"),
+            preferences.to_string(),
+        )
+        .unwrap();
+
+        let mut profiles =
+            ProfileManager::discover_profiles_in_directory(&vivaldi, Some(user_data.path()))
+                .unwrap();
+        profiles.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(profiles[0].display_name, "Home");
+        assert_eq!(
+            profiles[0].workspaces,
+            vec![
+                VivaldiWorkspace {
+                    name: "Research".to_string(),
+                    emoji: Some("🔬".to_string()),
+                },
+                VivaldiWorkspace {
+                    name: "Shopping".to_string(),
+                    emoji: None,
+                },
+            ]
+        );
+        assert_eq!(profiles[1].display_name, "Person 2");
+        assert!(profiles[1].workspaces.is_empty());
+    }
+
+    #[test]
+    fn opera_main_and_side_profiles_are_discovered() {
+        let mut opera = chromium();
+        opera.kind = BrowserKind::Opera;
+        opera.display_name = "Opera GX".to_string();
+        assert!(opera.is_opera_gx());
+
+        // Linux and Windows: the main profile is the user data directory itself
+        let user_data = TempDir::new().unwrap();
+        fs::write(user_data.path().join("Preferences"), "{}").unwrap();
+        fs::create_dir_all(user_data.path().join("_side_profiles/2F8A41C3")).unwrap();
+        fs::create_dir_all(user_data.path().join("_side_profiles/9B07E5D2")).unwrap();
+        let local_state = serde_json::json!({
+            "profile": { "info_cache": {
+                "_side_profiles/2F8A41C3": {
+                    "name": "Gaming",
+                    "user_name": "me@example.com",
+                    "active_time": 1_709_296_200.5,
+                },
+            } }
+        });
+        fs::write(
+            user_data.path().join("Local State"),
+            local_state.to_string(),
+        )
+        .unwrap();
+
+        let profiles =
+            ProfileManager::discover_profiles_in_directory(&opera, Some(user_data.path())).unwrap();
+        let summary: Vec<_> = profiles
+            .iter()
+            .map(|p| (p.name.as_str(), p.display_name.as_str(), p.is_default))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("default", "Default", true),
+                ("2F8A41C3", "Gaming", false),
+                ("9B07E5D2", "9B07E5D2", false),
+            ]
+        );
+        assert_eq!(profiles[0].path, user_data.path());
+        assert_eq!(
+            profiles[1].path,
+            user_data.path().join("_side_profiles/2F8A41C3")
+        );
+        assert!(profiles[1].last_used.is_some());
+        assert_eq!(
+            profiles[1].identity.as_ref().unwrap().email.as_deref(),
+            Some("me@example.com")
+        );
+
+        // macOS: the main profile lives in `Default`
+        let user_data = TempDir::new().unwrap();
+        fs::create_dir(user_data.path().join("Default")).unwrap();
+        let local_state = serde_json::json!({
+            "profile": { "info_cache": { "Default": { "name": "Personal" } } }
+        });
+        fs::write(
+            user_data.path().join("Local State"),
+            local_state.to_string(),
+        )
+        .unwrap();
+        let profiles =
+            ProfileManager::discover_profiles_in_directory(&opera, Some(user_data.path())).unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].display_name, "Personal");
+        assert_eq!(profiles[0].path, user_data.path().join("Default"));
+    }
+
+    #[test]
+    fn profiles_are_found_by_account_email() {
+        let user_data = TempDir::new().unwrap();
+        for dir in ["Default", "Profile 1", "Profile 2"] {
+            fs::create_dir(user_data.path().join(dir)).unwrap();
+        }
+        let local_state = serde_json::json!({
+            "profile": { "info_cache": {
+                "Default": { "name": "Personal", "user_name": "me@example.com" },
+                "Profile 1": { "name": "Work", "user_name": "Me@Corp.com" },
+                "Profile 2": { "name": "Work 2", "user_name": "me@corp.com" },
+            } }
+        });
+        fs::write(
+            user_data.path().join("Local State"),
+            local_state.to_string(),
+        )
+        .unwrap();
+        let browser = chromium();
+
+        let personal = ProfileManager::find_profile_by_email(
+            &browser,
+            "ME@example.com",
+            Some(user_data.path()),
+        )
+        .unwrap();
+        assert_eq!(personal.name, "Default");
+        assert!(matches!(
+            ProfileManager::find_profile_by_email(&browser, "me@corp.com", Some(user_data.path())),
+            Err(ProfileError::AmbiguousEmail(_, _))
+        ));
+        assert!(matches!(
+            ProfileManager::find_profile_by_email(
+                &browser,
+                "nobody@corp.com",
+                Some(user_data.path())
+            ),
+            Err(ProfileError::ProfileNotFound(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn locked_profiles_are_reported_in_use() {
+        let base = TempDir::new().unwrap();
+        fs::write(
+            base.path().join("profiles.ini"),
+            "[Profile0]\nName=work\nPath=a.work\n\n[Profile1]\nName=play\nPath=b.play\n",
+        )
+        .unwrap();
+        fs::create_dir(base.path().join("a.work")).unwrap();
+        fs::create_dir(base.path().join("b.play")).unwrap();
+        std::os::unix::fs::symlink(
+            format!("127.0.0.1:+{}", std::process::id()),
+            base.path().join("a.work/lock"),
+        )
+        .unwrap();
+
+        let mut firefox = chromium();
+        firefox.kind = BrowserKind::Firefox;
+        let in_use: Vec<_> =
+            ProfileManager::discover_profiles_in_directory(&firefox, Some(base.path()))
+                .unwrap()
+                .into_iter()
+                .map(|profile| (profile.name, profile.in_use))
+                .collect();
+        assert_eq!(
+            in_use,
+            vec![("work".to_string(), true), ("play".to_string(), false)]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_locked_user_data_directory_marks_its_open_profiles_in_use() {
+        let user_data = TempDir::new().unwrap();
+        fs::create_dir(user_data.path().join("Default")).unwrap();
+        fs::create_dir(user_data.path().join("Profile 1")).unwrap();
+        let local_state = |active: &str| {
+            format!(
+                r#"{{"profile": {{"info_cache": {{"Default": {{"name": "Personal"}},
+                    "Profile 1": {{"name": "Work"}}}}, "last_active_profiles": [{}]}}}}"#,
+                active
+            )
+        };
+        std::os::unix::fs::symlink(
+            format!("host-{}", std::process::id()),
+            user_data.path().join("SingletonLock"),
+        )
+        .unwrap();
+        let in_use = || {
+            let mut in_use: Vec<_> =
+                ProfileManager::discover_profiles_in_directory(&chromium(), Some(user_data.path()))
+                    .unwrap()
+                    .into_iter()
+                    .map(|profile| (profile.name, profile.in_use))
+                    .collect();
+            in_use.sort();
+            in_use
+        };
+
+        fs::write(
+            user_data.path().join("Local State"),
+            local_state("\"Profile 1\""),
+        )
+        .unwrap();
+        assert_eq!(
+            in_use(),
+            vec![
+                ("Default".to_string(), false),
+                ("Profile 1".to_string(), true)
+            ]
+        );
+
+        // Without a record of the open profiles, the lock counts for all of them
+        fs::write(user_data.path().join("Local State"), local_state("")).unwrap();
+        assert_eq!(
+            in_use(),
+            vec![
+                ("Default".to_string(), true),
+                ("Profile 1".to_string(), true)
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn discarding_gives_up_on_a_lock_that_is_never_released() {
+        let root = TempDir::new().unwrap();
+        let profile = ProfileManager::create_temp_profile_in(root.path()).unwrap();
+        std::os::unix::fs::symlink(
+            format!("host-{}", std::process::id()),
+            profile.join("SingletonLock"),
+        )
+        .unwrap();
+
+        let wait = std::time::Duration::from_millis(50);
+        assert!(matches!(
+            ProfileManager::discard_temp_profile_within(&profile, wait, wait),
+            Err(ProfileError::StillLocked(_))
+        ));
+        assert!(profile.exists());
+
+        fs::remove_file(profile.join("SingletonLock")).unwrap();
+        ProfileManager::discard_temp_profile_within(&profile, wait, wait).unwrap();
+        assert!(!profile.exists());
+    }
+
+    #[test]
+    fn leftover_temp_profiles_are_found_with_age_and_size() {
+        let root = TempDir::new().unwrap();
+        let leftover = ProfileManager::create_temp_profile_in(root.path()).unwrap();
+        fs::write(leftover.join("Preferences"), "{}").unwrap();
+        fs::create_dir(root.path().join("unrelated")).unwrap();
+
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(7200);
+        let found = ProfileManager::find_temp_profiles_in(&[root.path().to_path_buf()], later);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, leftover);
+        assert_eq!(found[0].size_bytes, 2);
+        assert!(found[0].age_secs >= 7200);
+        assert!(!found[0].in_use);
+    }
+
+    #[test]
+    fn profiles_ini_sections_are_edited() {
+        let ini = "[General]\nStartWithLastProfile=1\n\n\
+                   [Profile0]\nName=default\nPath=a.default\nDefault=1\n\n\
+                   [Profile1]\nName=work\nPath=b.work\n\n\
+                   [Profile2]\nName=play\nPath=c.play\n\n\
+                   [Install4F96D1932A9F858E]\nDefault=c.play\n";
+
+        let updated = remove_firefox_profile_section(ini, "work").unwrap();
+        assert!(!updated.contains("Name=work"));
+        assert!(updated.contains("[Profile1]\nName=play"));
+        assert!(!updated.contains("[Profile2]"));
+
+        assert!(matches!(
+            remove_firefox_profile_section(ini, "play"),
+            Err(ProfileError::DefaultProfile(_))
+        ));
+        assert!(matches!(
+            remove_firefox_profile_section(ini, "missing"),
+            Err(ProfileError::ProfileNotFound(_))
+        ));
+
+        let renamed = rename_firefox_profile_section(ini, "work", "office").unwrap();
+        assert!(renamed.contains("[Profile1]\nName=office\nPath=b.work"));
+        assert!(renamed.contains("[Profile2]\nName=play"));
+
+        let base = Path::new("/home/me/.mozilla/firefox");
+        let added = add_firefox_profile_section(ini, "clone", &base.join("d.clone"), base);
+        assert!(added
+            .ends_with("Default=c.play\n\n[Profile3]\nName=clone\nIsRelative=1\nPath=d.clone\n"));
+    }
+}
@@ -0,0 +1,326 @@
+//! Profile archives for moving a profile to another machine.
+//!
+//! An archive is a zstd-compressed tar holding the profile's files under `profile/` and a
+//! `manifest.json` that records the browser and a SHA-256 checksum for every file. Caches
+//! and lock files are left out, as when cloning a profile.
+
+use super::{profile_copy_skip, ProfileError, ProfileInfo, ProfileManager};
+use crate::browser::{BrowserInfo, BrowserKind};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+const MANIFEST_NAME: &str = "manifest.json";
+const FILES_DIR: &str = "profile";
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileManifest {
+    pub format: u32,
+    pub browser_kind: BrowserKind,
+    pub browser_version: Option<String>,
+    pub profile_name: String,
+    pub display_name: String,
+    pub files: Vec<ArchivedFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedFile {
+    /// Path inside the profile directory, with `/` separators
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+impl ProfileManifest {
+    /// Whether the profile was written by a newer major version than `browser`.
+    ///
+    /// Browsers usually refuse or reset profiles from a newer version.
+    pub fn is_newer_than(&self, browser: &BrowserInfo) -> bool {
+        let major = |version: Option<&str>| -> Option<u32> {
+            version?.split('.').next()?.trim().parse().ok()
+        };
+        match (
+            major(self.browser_version.as_deref()),
+            major(browser.version.as_deref()),
+        ) {
+            (Some(archived), Some(installed)) => archived > installed,
+            _ => false,
+        }
+    }
+}
+
+/// Writes `profile` of `browser` to a `.tar.zst` archive at `output`.
+pub fn export_profile(
+    browser: &BrowserInfo,
+    profile: &ProfileInfo,
+    output: &Path,
+) -> Result<ProfileManifest, ProfileError> {
+    if profile.path.as_os_str().is_empty() {
+        return Err(ProfileError::ProfileNotFound(profile.name.clone()));
+    }
+
+    let mut sources = Vec::new();
+    collect_files(
+        &profile.path,
+        "",
+        profile_copy_skip(browser.kind),
+        &mut sources,
+    )?;
+
+    let encoder = zstd::Encoder::new(File::create(output)?, 0)?;
+    let mut builder = tar::Builder::new(encoder);
+    let mut files = Vec::new();
+    for (relative, path) in sources {
+        // Hash what goes into the archive, in case the browser changes the file meanwhile
+        let data = fs::read(&path)?;
+        append_data(&mut builder, &format!("{}/{}", FILES_DIR, relative), &data)?;
+        files.push(ArchivedFile {
+            path: relative,
+            size: data.len() as u64,
+            sha256: sha256_hex(&data),
+        });
+    }
+
+    let manifest = ProfileManifest {
+        format: FORMAT_VERSION,
+        browser_kind: browser.kind,
+        browser_version: browser.version.clone(),
+        profile_name: profile.name.clone(),
+        display_name: profile.display_name.clone(),
+        files,
+    };
+    append_data(
+        &mut builder,
+        MANIFEST_NAME,
+        &serde_json::to_vec_pretty(&manifest)?,
+    )?;
+    builder.into_inner()?.finish()?;
+
+    Ok(manifest)
+}
+
+/// Unpacks an archive written by [`export_profile`] into a new profile of `browser`.
+///
+/// The profile is called `name`, or keeps its archived display name. Every file is checked
+/// against the manifest before the profile is registered.
+pub fn import_profile(
+    browser: &BrowserInfo,
+    archive: &Path,
+    name: Option<&str>,
+    custom_base_dir: Option<&Path>,
+) -> Result<(ProfileInfo, ProfileManifest), ProfileError> {
+    let staging = ProfileManager::create_temp_profile()?;
+    let result = unpack(archive, &staging).and_then(|manifest| {
+        if manifest.browser_kind != browser.kind {
+            return Err(ProfileError::InvalidArchive(format!(
+                "it holds a {} profile, not {}",
+                manifest.browser_kind.canonical_name(),
+                browser.kind.canonical_name()
+            )));
+        }
+        let staged = ProfileInfo {
+            name: manifest.profile_name.clone(),
+            display_name: manifest.display_name.clone(),
+            path: staging.clone(),
+            is_default: false,
+            last_used: None,
+            browser_kind: manifest.browser_kind,
+            in_use: false,
+            identity: None,
+            workspaces: Vec::new(),
+            arc_space: None,
+        };
+        let name = name.unwrap_or(&manifest.display_name);
+        let profile = ProfileManager::add_profile_copy(browser, &staged, name, custom_base_dir)?;
+        Ok((profile, manifest))
+    });
+    let _ = fs::remove_dir_all(&staging);
+    result
+}
+
+/// Extracts the profile files to `target` and verifies them against the manifest.
+fn unpack(archive: &Path, target: &Path) -> Result<ProfileManifest, ProfileError> {
+    let mut tar = tar::Archive::new(zstd::Decoder::new(File::open(archive)?)?);
+    let mut manifest: Option<ProfileManifest> = None;
+    let mut unpacked = HashSet::new();
+
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        if path == Path::new(MANIFEST_NAME) {
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            manifest = Some(serde_json::from_str(&content)?);
+            continue;
+        }
+
+        let Ok(relative) = path.strip_prefix(FILES_DIR) else {
+            continue;
+        };
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(ProfileError::InvalidArchive(format!(
+                "unsafe path {}",
+                path.display()
+            )));
+        }
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let destination = target.join(relative);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        entry.unpack(&destination)?;
+        unpacked.insert(archive_path(relative));
+    }
+
+    let manifest =
+        manifest.ok_or_else(|| ProfileError::InvalidArchive("no manifest.json".to_string()))?;
+    if manifest.format != FORMAT_VERSION {
+        return Err(ProfileError::InvalidArchive(format!(
+            "unsupported format {}",
+            manifest.format
+        )));
+    }
+    for file in &manifest.files {
+        if !unpacked.remove(&file.path) {
+            return Err(ProfileError::InvalidArchive(format!(
+                "{} is missing",
+                file.path
+            )));
+        }
+        let data = fs::read(target.join(&file.path))?;
+        if data.len() as u64 != file.size || sha256_hex(&data) != file.sha256 {
+            return Err(ProfileError::InvalidArchive(format!(
+                "checksum mismatch for {}",
+                file.path
+            )));
+        }
+    }
+    if let Some(extra) = unpacked.into_iter().next() {
+        return Err(ProfileError::InvalidArchive(format!(
+            "{} is not in the manifest",
+            extra
+        )));
+    }
+
+    Ok(manifest)
+}
+
+/// Lists the regular files below `dir` as (archive path, file path), skipping `skip` entries.
+fn collect_files(
+    dir: &Path,
+    prefix: &str,
+    skip: &[&str],
+    files: &mut Vec<(String, PathBuf)>,
+) -> Result<(), ProfileError> {
+    let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let file_name = entry.file_name();
+        if skip.iter().any(|name| file_name == *name) {
+            continue;
+        }
+        let relative = format!("{}{}", prefix, file_name.to_string_lossy());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(&entry.path(), &format!("{}/", relative), skip, files)?;
+        } else if file_type.is_file() {
+            files.push((relative, entry.path()));
+        }
+    }
+    Ok(())
+}
+
+fn append_data<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    data: &[u8],
+) -> Result<(), ProfileError> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, path, data)?;
+    Ok(())
+}
+
+fn archive_path(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::channels::{BrowserChannel, ChromiumChannel};
+    use crate::browser::{InstallScope, InstallationSource};
+    use tempfile::TempDir;
+
+    fn chromium(version: &str) -> BrowserInfo {
+        BrowserInfo {
+            kind: BrowserKind::Chromium,
+            channel: BrowserChannel::Chromium(ChromiumChannel::Stable),
+            display_name: "Chromium".to_string(),
+            executable_path: PathBuf::from("/usr/bin/chromium"),
+            version: Some(version.to_string()),
+            unique_id: "chromium.desktop".to_string(),
+            exec_command: None,
+            source: InstallationSource::Unknown,
+            scope: InstallScope::System,
+            arch: None,
+            is_default: false,
+        }
+    }
+
+    #[test]
+    fn exported_profiles_import_on_another_machine() {
+        let source = TempDir::new().unwrap();
+        let browser = chromium("120.0.6099.71");
+        let work =
+            ProfileManager::create_chromium_profile(&browser, "Work", Some(source.path())).unwrap();
+        fs::create_dir_all(work.path.join("Extensions/abc")).unwrap();
+        fs::write(work.path.join("Extensions/abc/manifest.json"), "{}").unwrap();
+        fs::write(work.path.join("Bookmarks"), "bookmarks").unwrap();
+        fs::create_dir(work.path.join("Cache")).unwrap();
+        fs::write(work.path.join("Cache/data_0"), "cached").unwrap();
+
+        let archive = source.path().join("work.tar.zst");
+        let manifest = export_profile(&browser, &work, &archive).unwrap();
+        let paths: Vec<_> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["Bookmarks", "Extensions/abc/manifest.json"]);
+
+        let target = TempDir::new().unwrap();
+        let older = chromium("119.0");
+        let (imported, manifest) =
+            import_profile(&older, &archive, None, Some(target.path())).unwrap();
+        assert_eq!(imported.display_name, "Work");
+        assert!(manifest.is_newer_than(&older));
+        assert_eq!(
+            fs::read_to_string(imported.path.join("Bookmarks")).unwrap(),
+            "bookmarks"
+        );
+        assert!(!imported.path.join("Cache").exists());
+
+        let mut firefox = chromium("120.0");
+        firefox.kind = BrowserKind::Firefox;
+        assert!(matches!(
+            import_profile(&firefox, &archive, None, Some(target.path())),
+            Err(ProfileError::InvalidArchive(_))
+        ));
+    }
+}
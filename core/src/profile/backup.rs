@@ -0,0 +1,175 @@
+//! Timestamped profile backups with retention.
+//!
+//! Backups are [profile archives](super::archive) kept in
+//! `<backup dir>/<browser token>/<profile>/<time>.tar.zst`, so every profile has a folder of
+//! its own and file names sort by age. `profile import` turns a backup into a profile again.
+
+use super::archive::{export_profile, ProfileManifest};
+use super::timestamp::Timestamp;
+use super::{ProfileError, ProfileInfo};
+use crate::browser::BrowserInfo;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const BACKUP_EXTENSION: &str = ".tar.zst";
+
+#[derive(Debug)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub manifest: ProfileManifest,
+    /// Older backups removed to keep within the retention limit
+    pub pruned: Vec<PathBuf>,
+}
+
+/// Default location: `<local data dir>/pathway/backups`.
+pub fn default_backup_dir() -> Option<PathBuf> {
+    dirs_next::data_local_dir().map(|dir| dir.join("pathway").join("backups"))
+}
+
+/// Archives `profile` of `browser` below `backup_dir`, then removes all but the `keep` newest
+/// backups of the profile. `keep` is at least one, the backup just written.
+pub fn backup_profile(
+    browser: &BrowserInfo,
+    profile: &ProfileInfo,
+    backup_dir: &Path,
+    keep: usize,
+    now: Timestamp,
+) -> Result<Backup, ProfileError> {
+    let dir = profile_backup_dir(backup_dir, browser, profile);
+    fs::create_dir_all(&dir)?;
+    // `:` is not allowed in Windows file names
+    let stamp = now.to_rfc3339().replace(':', "-");
+    let path = dir.join(format!("{}{}", stamp, BACKUP_EXTENSION));
+    if path.exists() {
+        return Err(ProfileError::ProfileExists(path.display().to_string()));
+    }
+
+    // An interrupted backup must not count towards the retained ones
+    let partial = dir.join(format!("{}.partial", stamp));
+    let manifest = match export_profile(browser, profile, &partial) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+    };
+    fs::rename(&partial, &path)?;
+
+    let backups = list_backups(&dir)?;
+    let excess = backups.len().saturating_sub(keep.max(1));
+    let mut pruned = Vec::new();
+    for old in backups.into_iter().take(excess) {
+        fs::remove_file(&old)?;
+        pruned.push(old);
+    }
+    Ok(Backup {
+        path,
+        manifest,
+        pruned,
+    })
+}
+
+/// The folder with the backups of `profile`.
+pub fn profile_backup_dir(
+    backup_dir: &Path,
+    browser: &BrowserInfo,
+    profile: &ProfileInfo,
+) -> PathBuf {
+    let folder: String = profile
+        .name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c => c,
+        })
+        .collect();
+    backup_dir.join(browser.alias()).join(folder)
+}
+
+/// Backups in `dir`, oldest first.
+pub fn list_backups(dir: &Path) -> Result<Vec<PathBuf>, ProfileError> {
+    let mut backups: Vec<PathBuf> = fs::read_dir(dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.ends_with(BACKUP_EXTENSION))
+        })
+        .collect();
+    backups.sort();
+    Ok(backups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::channels::{BrowserChannel, ChromiumChannel};
+    use crate::browser::{BrowserKind, InstallScope, InstallationSource};
+    use tempfile::TempDir;
+
+    #[test]
+    fn backups_are_pruned_to_the_newest() {
+        let browser = BrowserInfo {
+            kind: BrowserKind::Chromium,
+            channel: BrowserChannel::Chromium(ChromiumChannel::Stable),
+            display_name: "Chromium".to_string(),
+            executable_path: PathBuf::from("/usr/bin/chromium"),
+            version: Some("126.0.6478.126".to_string()),
+            unique_id: "chromium.desktop".to_string(),
+            exec_command: None,
+            source: InstallationSource::Unknown,
+            scope: InstallScope::System,
+            arch: None,
+            is_default: false,
+        };
+        let user_data = TempDir::new().unwrap();
+        let profile_dir = user_data.path().join("Profile 1");
+        fs::create_dir_all(profile_dir.join("Cache")).unwrap();
+        fs::write(profile_dir.join("Preferences"), "{}").unwrap();
+        fs::write(profile_dir.join("Cache/data_0"), "cached").unwrap();
+        let profile = ProfileInfo {
+            name: "Profile 1".to_string(),
+            display_name: "Work".to_string(),
+            path: profile_dir,
+            is_default: false,
+            last_used: None,
+            browser_kind: BrowserKind::Chromium,
+            in_use: false,
+            identity: None,
+            workspaces: Vec::new(),
+            arc_space: None,
+        };
+
+        let backups = TempDir::new().unwrap();
+        let mut written = Vec::new();
+        for secs in [1_700_000_000, 1_700_000_060, 1_700_086_400] {
+            let backup = backup_profile(
+                &browser,
+                &profile,
+                backups.path(),
+                2,
+                Timestamp::from_unix_secs(secs),
+            )
+            .unwrap();
+            assert_eq!(backup.manifest.files.len(), 1);
+            written.push(backup.path);
+        }
+
+        let dir = backups.path().join("chromium/Profile 1");
+        assert_eq!(written[0], dir.join("2023-11-14T22-13-20Z.tar.zst"));
+        assert_eq!(list_backups(&dir).unwrap(), written[1..].to_vec());
+        assert!(matches!(
+            backup_profile(
+                &browser,
+                &profile,
+                backups.path(),
+                2,
+                Timestamp::from_unix_secs(1_700_086_400)
+            ),
+            Err(ProfileError::ProfileExists(_))
+        ));
+    }
+}
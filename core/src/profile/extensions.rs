@@ -0,0 +1,243 @@
+//! Extensions installed in a profile.
+//!
+//! Chromium unpacks every extension to `Extensions/<id>/<version>/` inside the profile, with
+//! its `manifest.json` next to the code. Names starting with `__MSG_` refer to the extension's
+//! `_locales/<default_locale>/messages.json`. Firefox records its add-ons in the profile's
+//! `extensions.json`, which also lists themes, dictionaries and the built-in system add-ons.
+
+use super::ProfileInfo;
+use crate::browser::BrowserKind;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Extension {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+}
+
+/// Extensions of `profile`, sorted by name; empty for browsers without a known layout.
+pub fn profile_extensions(profile: &ProfileInfo) -> Vec<Extension> {
+    let mut extensions = match profile.browser_kind {
+        BrowserKind::Chrome
+        | BrowserKind::Edge
+        | BrowserKind::Brave
+        | BrowserKind::Vivaldi
+        | BrowserKind::Arc
+        | BrowserKind::Helium
+        | BrowserKind::Thorium
+        | BrowserKind::UngoogledChromium
+        | BrowserKind::Yandex
+        | BrowserKind::Wavebox
+        | BrowserKind::Sidekick
+        | BrowserKind::Opera
+        | BrowserKind::Chromium => chromium_extensions(&profile.path.join("Extensions")),
+        BrowserKind::Firefox
+        | BrowserKind::Waterfox
+        | BrowserKind::LibreWolf
+        | BrowserKind::Zen
+        | BrowserKind::Floorp
+        | BrowserKind::PaleMoon
+        | BrowserKind::Basilisk
+        | BrowserKind::TorBrowser => fs::read_to_string(profile.path.join("extensions.json"))
+            .map(|content| firefox_extensions(&content))
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    extensions.sort_by_key(|extension| extension.name.to_lowercase());
+    extensions
+}
+
+fn chromium_extensions(extensions_dir: &Path) -> Vec<Extension> {
+    let Ok(entries) = fs::read_dir(extensions_dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let id = entry.file_name().into_string().ok()?;
+            // An update leaves the previous version behind until the browser restarts
+            let newest = fs::read_dir(entry.path())
+                .ok()?
+                .flatten()
+                .map(|version| version.path())
+                .filter(|path| path.join("manifest.json").is_file())
+                .max_by_key(|path| version_key(path))?;
+            chromium_extension(id, &newest)
+        })
+        .collect()
+}
+
+fn chromium_extension(id: String, dir: &Path) -> Option<Extension> {
+    let manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("manifest.json")).ok()?).ok()?;
+    let name = manifest.get("name")?.as_str()?;
+    let name = match name
+        .strip_prefix("__MSG_")
+        .and_then(|key| key.strip_suffix("__"))
+    {
+        Some(key) => localized_message(dir, &manifest, key).unwrap_or_else(|| id.clone()),
+        None => name.to_string(),
+    };
+    Some(Extension {
+        id,
+        name,
+        version: manifest.get("version")?.as_str()?.to_string(),
+    })
+}
+
+/// The message `key` in the extension's default locale; keys are case-insensitive.
+fn localized_message(dir: &Path, manifest: &serde_json::Value, key: &str) -> Option<String> {
+    let locale = manifest
+        .get("default_locale")
+        .and_then(|l| l.as_str())
+        .unwrap_or("en");
+    let messages: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(dir.join("_locales").join(locale).join("messages.json")).ok()?,
+    )
+    .ok()?;
+    messages
+        .as_object()?
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(key))?
+        .1
+        .get("message")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Orders version directories such as `1.10.2_0` numerically.
+fn version_key(path: &Path) -> Vec<u64> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .split(['.', '_'])
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+/// Add-on locations of the features Firefox ships as add-ons.
+const FIREFOX_BUILTIN_LOCATIONS: &[&str] =
+    &["app-builtin", "app-system-defaults", "app-system-addons"];
+
+/// Extensions from `extensions.json`, leaving out themes, language packs and the add-ons
+/// Firefox ships with. Extensions installed system-wide or through the registry are kept.
+fn firefox_extensions(content: &str) -> Vec<Extension> {
+    let Ok(document) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    document
+        .get("addons")
+        .and_then(|a| a.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|addon| addon.get("type").and_then(|t| t.as_str()) == Some("extension"))
+        .filter(|addon| {
+            !addon
+                .get("location")
+                .and_then(|l| l.as_str())
+                .is_some_and(|location| FIREFOX_BUILTIN_LOCATIONS.contains(&location))
+        })
+        .filter_map(|addon| {
+            let id = addon.get("id")?.as_str()?.to_string();
+            Some(Extension {
+                name: addon
+                    .pointer("/defaultLocale/name")
+                    .and_then(|n| n.as_str())
+                    .unwrap_or(&id)
+                    .to_string(),
+                version: addon.get("version")?.as_str()?.to_string(),
+                id,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn chromium_extensions_come_from_their_manifests() {
+        let extensions = TempDir::new().unwrap();
+        let ublock = extensions.path().join("cjpalhdlnbpafiamejdnhcphjbkeiagm");
+        for version in ["1.9.0_0", "1.10.2_0"] {
+            fs::create_dir_all(ublock.join(version)).unwrap();
+            fs::write(
+                ublock.join(version).join("manifest.json"),
+                format!(
+                    r#"{{"name":"uBlock Origin","version":"{}"}}"#,
+                    version.trim_end_matches("_0")
+                ),
+            )
+            .unwrap();
+        }
+        let docs = extensions
+            .path()
+            .join("ghbmnnjooekpmoecnnnilnnbdlolhkhi/1.2_0");
+        fs::create_dir_all(docs.join("_locales/en_US")).unwrap();
+        fs::write(
+            docs.join("manifest.json"),
+            r#"{"name":"__MSG_extName__","version":"1.2","default_locale":"en_US"}"#,
+        )
+        .unwrap();
+        fs::write(
+            docs.join("_locales/en_US/messages.json"),
+            r#"{"extname":{"message":"Google Docs Offline"}}"#,
+        )
+        .unwrap();
+        fs::create_dir(extensions.path().join("Temp")).unwrap();
+
+        let mut found = chromium_extensions(extensions.path());
+        found.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(
+            found,
+            vec![
+                Extension {
+                    id: "ghbmnnjooekpmoecnnnilnnbdlolhkhi".to_string(),
+                    name: "Google Docs Offline".to_string(),
+                    version: "1.2".to_string(),
+                },
+                Extension {
+                    id: "cjpalhdlnbpafiamejdnhcphjbkeiagm".to_string(),
+                    name: "uBlock Origin".to_string(),
+                    version: "1.10.2".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn firefox_extensions_skip_themes_and_system_add_ons() {
+        let content = r#"{"schemaVersion":36,"addons":[
+            {"id":"uBlock0@raymondhill.net","type":"extension","location":"app-profile",
+             "version":"1.58.0","defaultLocale":{"name":"uBlock Origin"}},
+            {"id":"formautofill@mozilla.org","type":"extension","location":"app-system-defaults",
+             "version":"1.0.1","defaultLocale":{"name":"Form Autofill"}},
+            {"id":"firefox-compact-dark@mozilla.org","type":"theme","location":"app-builtin",
+             "version":"1.3","defaultLocale":{"name":"Dark"}},
+            {"id":"policy@corp.example","type":"extension","location":"app-system-share",
+             "version":"2.0","defaultLocale":{"name":"Corp Policy"}}
+        ]}"#;
+        let found = firefox_extensions(content);
+        assert_eq!(
+            found,
+            vec![
+                Extension {
+                    id: "uBlock0@raymondhill.net".to_string(),
+                    name: "uBlock Origin".to_string(),
+                    version: "1.58.0".to_string(),
+                },
+                Extension {
+                    id: "policy@corp.example".to_string(),
+                    name: "Corp Policy".to_string(),
+                    version: "2.0".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Whether a browser honors `--guest`.
+//!
+//! Guest mode comes from Chromium, but not every browser built on it kept it: Arc, Opera
+//! (including Opera GX), Wavebox and Sidekick accept the flag and open their regular profile.
+//! Chrome, Edge, Brave and Chromium also let administrators turn guest mode off with the
+//! `BrowserGuestModeEnabled` policy, in which case no guest window opens at all.
+
+use crate::browser::{BrowserInfo, BrowserKind};
+use std::path::{Path, PathBuf};
+
+const GUEST_POLICY: &str = "BrowserGuestModeEnabled";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuestMode {
+    Supported,
+    /// The browser ignores `--guest`
+    Unsupported,
+    /// A policy turns guest mode off; holds where the policy was found
+    DisabledByPolicy(String),
+}
+
+pub fn guest_mode(browser: &BrowserInfo) -> GuestMode {
+    match browser.kind {
+        BrowserKind::Chrome
+        | BrowserKind::Edge
+        | BrowserKind::Brave
+        | BrowserKind::Vivaldi
+        | BrowserKind::Helium
+        | BrowserKind::Thorium
+        | BrowserKind::UngoogledChromium
+        | BrowserKind::Yandex
+        | BrowserKind::Chromium => match guest_policy_source(browser.kind) {
+            Some(source) => GuestMode::DisabledByPolicy(source),
+            None => GuestMode::Supported,
+        },
+        _ => GuestMode::Unsupported,
+    }
+}
+
+/// Where a browser reads its policies: the Linux directory of managed JSON policies, the
+/// macOS preferences domain and the Windows registry key below `SOFTWARE\Policies`.
+fn policy_locations(kind: BrowserKind) -> Option<(&'static str, &'static str, &'static str)> {
+    match kind {
+        BrowserKind::Chrome => Some((
+            "/etc/opt/chrome/policies/managed",
+            "com.google.Chrome",
+            r"Google\Chrome",
+        )),
+        BrowserKind::Edge => Some((
+            "/etc/opt/edge/policies/managed",
+            "com.microsoft.Edge",
+            r"Microsoft\Edge",
+        )),
+        BrowserKind::Brave => Some((
+            "/etc/brave/policies/managed",
+            "com.brave.Browser",
+            r"BraveSoftware\Brave",
+        )),
+        BrowserKind::Chromium | BrowserKind::UngoogledChromium => Some((
+            "/etc/chromium/policies/managed",
+            "org.chromium.Chromium",
+            "Chromium",
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn guest_policy_source(kind: BrowserKind) -> Option<String> {
+    let (dir, _, _) = policy_locations(kind)?;
+    json_policy_disabling_guest(Path::new(dir)).map(|file| file.display().to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn guest_policy_source(kind: BrowserKind) -> Option<String> {
+    let (_, domain, _) = policy_locations(kind)?;
+    let managed = Path::new("/Library/Managed Preferences");
+    let user = std::env::var("USER").ok();
+    std::iter::once(managed.join(format!("{domain}.plist")))
+        .chain(user.map(|user| managed.join(user).join(format!("{domain}.plist"))))
+        .find(|plist| {
+            plist.is_file()
+                && std::process::Command::new("plutil")
+                    .args(["-extract", GUEST_POLICY, "raw", "-o", "-"])
+                    .arg(plist)
+                    .output()
+                    .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "false")
+        })
+        .map(|plist| plist.display().to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn guest_policy_source(kind: BrowserKind) -> Option<String> {
+    use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+    use winreg::RegKey;
+
+    let (_, _, key) = policy_locations(kind)?;
+    let path = format!(r"SOFTWARE\Policies\{key}");
+    [(HKEY_LOCAL_MACHINE, "HKLM"), (HKEY_CURRENT_USER, "HKCU")]
+        .into_iter()
+        .find(|(hive, _)| {
+            RegKey::predef(*hive)
+                .open_subkey(&path)
+                .and_then(|key| key.get_value::<u32, _>(GUEST_POLICY))
+                .is_ok_and(|value| value == 0)
+        })
+        .map(|(_, name)| format!(r"{name}\{path}"))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn guest_policy_source(kind: BrowserKind) -> Option<String> {
+    let _ = policy_locations(kind);
+    None
+}
+
+/// The policy file in `dir` that sets `BrowserGuestModeEnabled` to false, if any.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn json_policy_disabling_guest(dir: &Path) -> Option<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+        .collect();
+    files.sort();
+    files.into_iter().find(|file| {
+        std::fs::read_to_string(file)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|policies| policies.get(GUEST_POLICY)?.as_bool())
+            == Some(false)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn managed_policies_can_disable_guest_mode() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(json_policy_disabling_guest(dir.path()), None);
+
+        std::fs::write(
+            dir.path().join("a.json"),
+            r#"{"BrowserGuestModeEnabled": true}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "BrowserGuestModeEnabled").unwrap();
+        assert_eq!(json_policy_disabling_guest(dir.path()), None);
+
+        let disabling = dir.path().join("corp.json");
+        std::fs::write(
+            &disabling,
+            r#"{"HomepageLocation": "https://intranet", "BrowserGuestModeEnabled": false}"#,
+        )
+        .unwrap();
+        assert_eq!(json_policy_disabling_guest(dir.path()), Some(disabling));
+    }
+}
@@ -0,0 +1,294 @@
+//! Per-installation default profiles of Firefox.
+//!
+//! Since Firefox 67 every installation has its own default profile. It is recorded in an
+//! `[Install<hash>]` section of `profiles.ini`, mirrored in `installs.ini`, where the hash
+//! is the CityHash64 of the installation directory as UTF-16. The `Default=1` flag of the
+//! profile sections only applies to older versions.
+
+use super::{ini_sections, ini_value};
+use crate::filesystem::FileSystem;
+use std::path::{Path, PathBuf};
+
+const K0: u64 = 0xc3a5_c85c_97cb_3127;
+const K1: u64 = 0xb492_b66f_be98_f273;
+const K2: u64 = 0x9ae1_6a3b_2f90_404f;
+const K3: u64 = 0xc949_d7c7_509e_6557;
+
+/// Path of the profile that the installation running `executable` opens by default.
+///
+/// Falls back to the only `[Install…]` section when none matches the installation, which
+/// covers sandboxed packages whose install path differs from the detected executable.
+pub(crate) fn install_default_profile(
+    fs: &dyn FileSystem,
+    base_dir: &Path,
+    profiles_ini: &str,
+    executable: &Path,
+) -> Option<PathBuf> {
+    let mut installs = install_defaults(profiles_ini);
+    if installs.is_empty() {
+        let installs_ini = fs.read_to_string(&base_dir.join("installs.ini")).ok()?;
+        installs = install_defaults(&installs_ini);
+    }
+
+    let hash = install_hash(&install_dir(fs, executable)?);
+    let default = match installs.iter().position(|(id, _)| *id == hash) {
+        Some(index) => installs.swap_remove(index).1,
+        None if installs.len() == 1 => installs.remove(0).1,
+        None => return None,
+    };
+    Some(base_dir.join(default))
+}
+
+/// `(hash, Default)` of every `[Install…]` section.
+fn install_defaults(content: &str) -> Vec<(String, String)> {
+    ini_sections(content)
+        .into_iter()
+        .filter_map(|(header, lines)| {
+            let hash = header.strip_prefix("[Install")?.strip_suffix(']')?;
+            Some((hash.to_string(), ini_value(&lines, "Default")?))
+        })
+        .collect()
+}
+
+/// The directory Firefox hashes: the app bundle on macOS, else the directory of the binary.
+fn install_dir(fs: &dyn FileSystem, executable: &Path) -> Option<PathBuf> {
+    if let Some(bundle) = executable
+        .ancestors()
+        .find(|path| path.extension().and_then(|s| s.to_str()) == Some("app"))
+    {
+        return Some(bundle.to_path_buf());
+    }
+    // Launchers like /usr/bin/firefox link to the binary in the installation directory
+    #[cfg(unix)]
+    let executable = fs
+        .canonicalize(executable)
+        .unwrap_or_else(|_| executable.to_path_buf());
+    #[cfg(not(unix))]
+    let _ = fs;
+    executable.parent().map(Path::to_path_buf)
+}
+
+/// The `[Install…]` section id of the installation in `dir`.
+fn install_hash(dir: &Path) -> String {
+    let bytes: Vec<u8> = dir
+        .to_string_lossy()
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    format!("{:X}", city_hash64(&bytes))
+}
+
+fn fetch64(s: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(s[at..at + 8].try_into().unwrap())
+}
+
+fn fetch32(s: &[u8], at: usize) -> u64 {
+    u64::from(u32::from_le_bytes(s[at..at + 4].try_into().unwrap()))
+}
+
+fn shift_mix(value: u64) -> u64 {
+    value ^ (value >> 47)
+}
+
+fn hash_len16(u: u64, v: u64) -> u64 {
+    const MUL: u64 = 0x9ddf_ea08_eb38_2d69;
+    let mut a = (u ^ v).wrapping_mul(MUL);
+    a ^= a >> 47;
+    let mut b = (v ^ a).wrapping_mul(MUL);
+    b ^= b >> 47;
+    b.wrapping_mul(MUL)
+}
+
+fn hash_len0to16(s: &[u8]) -> u64 {
+    let len = s.len();
+    if len > 8 {
+        let a = fetch64(s, 0);
+        let b = fetch64(s, len - 8);
+        return hash_len16(a, b.wrapping_add(len as u64).rotate_right(len as u32)) ^ b;
+    }
+    if len >= 4 {
+        let a = fetch32(s, 0);
+        return hash_len16((len as u64).wrapping_add(a << 3), fetch32(s, len - 4));
+    }
+    if len > 0 {
+        let y = u64::from(s[0]).wrapping_add(u64::from(s[len >> 1]) << 8);
+        let z = (len as u64).wrapping_add(u64::from(s[len - 1]) << 2);
+        return shift_mix(y.wrapping_mul(K2) ^ z.wrapping_mul(K3)).wrapping_mul(K2);
+    }
+    K2
+}
+
+fn hash_len17to32(s: &[u8]) -> u64 {
+    let len = s.len();
+    let a = fetch64(s, 0).wrapping_mul(K1);
+    let b = fetch64(s, 8);
+    let c = fetch64(s, len - 8).wrapping_mul(K2);
+    let d = fetch64(s, len - 16).wrapping_mul(K0);
+    hash_len16(
+        a.wrapping_sub(b)
+            .rotate_right(43)
+            .wrapping_add(c.rotate_right(30))
+            .wrapping_add(d),
+        a.wrapping_add((b ^ K3).rotate_right(20))
+            .wrapping_sub(c)
+            .wrapping_add(len as u64),
+    )
+}
+
+fn hash_len33to64(s: &[u8]) -> u64 {
+    let len = s.len();
+    let mut z = fetch64(s, 24);
+    let mut a = fetch64(s, 0).wrapping_add(
+        (len as u64)
+            .wrapping_add(fetch64(s, len - 16))
+            .wrapping_mul(K0),
+    );
+    let mut b = a.wrapping_add(z).rotate_right(52);
+    let mut c = a.rotate_right(37);
+    a = a.wrapping_add(fetch64(s, 8));
+    c = c.wrapping_add(a.rotate_right(7));
+    a = a.wrapping_add(fetch64(s, 16));
+    let vf = a.wrapping_add(z);
+    let vs = b.wrapping_add(a.rotate_right(31)).wrapping_add(c);
+
+    a = fetch64(s, 16).wrapping_add(fetch64(s, len - 32));
+    z = fetch64(s, len - 8);
+    b = a.wrapping_add(z).rotate_right(52);
+    c = a.rotate_right(37);
+    a = a.wrapping_add(fetch64(s, len - 24));
+    c = c.wrapping_add(a.rotate_right(7));
+    a = a.wrapping_add(fetch64(s, len - 16));
+    let wf = a.wrapping_add(z);
+    let ws = b.wrapping_add(a.rotate_right(31)).wrapping_add(c);
+
+    let r = shift_mix(
+        vf.wrapping_add(ws)
+            .wrapping_mul(K2)
+            .wrapping_add(wf.wrapping_add(vs).wrapping_mul(K0)),
+    );
+    shift_mix(r.wrapping_mul(K0).wrapping_add(vs)).wrapping_mul(K2)
+}
+
+fn weak_hash_len32_with_seeds(s: &[u8], at: usize, a: u64, b: u64) -> (u64, u64) {
+    let w = fetch64(s, at);
+    let x = fetch64(s, at + 8);
+    let y = fetch64(s, at + 16);
+    let z = fetch64(s, at + 24);
+    let mut a = a.wrapping_add(w);
+    let mut b = b.wrapping_add(a).wrapping_add(z).rotate_right(21);
+    let c = a;
+    a = a.wrapping_add(x).wrapping_add(y);
+    b = b.wrapping_add(a.rotate_right(44));
+    (a.wrapping_add(z), b.wrapping_add(c))
+}
+
+/// CityHash64 1.0, the version Firefox bundles.
+fn city_hash64(s: &[u8]) -> u64 {
+    let len = s.len();
+    if len <= 16 {
+        return hash_len0to16(s);
+    }
+    if len <= 32 {
+        return hash_len17to32(s);
+    }
+    if len <= 64 {
+        return hash_len33to64(s);
+    }
+
+    let mut x = fetch64(s, 0);
+    let mut y = fetch64(s, len - 16) ^ K1;
+    let mut z = fetch64(s, len - 56) ^ K0;
+    let mut v = weak_hash_len32_with_seeds(s, len - 64, len as u64, y);
+    let mut w = weak_hash_len32_with_seeds(s, len - 32, (len as u64).wrapping_mul(K1), K0);
+    z = z.wrapping_add(shift_mix(v.1).wrapping_mul(K1));
+    x = z.wrapping_add(x).rotate_right(39).wrapping_mul(K1);
+    y = y.rotate_right(33).wrapping_mul(K1);
+
+    let mut at = 0;
+    let mut remaining = (len - 1) & !63;
+    while remaining != 0 {
+        x = x
+            .wrapping_add(y)
+            .wrapping_add(v.0)
+            .wrapping_add(fetch64(s, at + 16))
+            .rotate_right(37)
+            .wrapping_mul(K1);
+        y = y
+            .wrapping_add(v.1)
+            .wrapping_add(fetch64(s, at + 48))
+            .rotate_right(42)
+            .wrapping_mul(K1);
+        x ^= w.1;
+        y ^= v.0;
+        z = (z ^ w.0).rotate_right(33);
+        v = weak_hash_len32_with_seeds(s, at, v.1.wrapping_mul(K1), x.wrapping_add(w.0));
+        w = weak_hash_len32_with_seeds(s, at + 32, z.wrapping_add(w.1), y);
+        std::mem::swap(&mut z, &mut x);
+        at += 64;
+        remaining -= 64;
+    }
+    hash_len16(
+        hash_len16(v.0, w.0)
+            .wrapping_add(shift_mix(y).wrapping_mul(K1))
+            .wrapping_add(z),
+        hash_len16(v.1, w.1).wrapping_add(x),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filesystem::RealFileSystem;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn install_hashes_match_firefox() {
+        assert_eq!(
+            install_hash(Path::new("/usr/lib/firefox")),
+            "4F96D1932A9F858E"
+        );
+        assert_eq!(
+            install_hash(Path::new("C:\\Program Files\\Mozilla Firefox")),
+            "308046B0AF4A39CB"
+        );
+    }
+
+    #[test]
+    fn each_installation_has_its_own_default() {
+        let base = TempDir::new().unwrap();
+        let install = base.path().join("firefox");
+        fs::create_dir(&install).unwrap();
+        let executable = install.join("firefox");
+        fs::write(&executable, "").unwrap();
+        let hash = install_hash(&install_dir(&RealFileSystem, &executable).unwrap());
+
+        let profiles_ini = "[Profile0]\nName=default\nPath=a.default\nDefault=1\n\n\
+                            [Profile1]\nName=release\nPath=b.default-release\n";
+        let installs_ini = format!(
+            "[InstallABCDEF0123456789]\nDefault=a.default\n\n\
+             [Install{}]\nDefault=b.default-release\nLocked=1\n",
+            hash
+        );
+        fs::write(base.path().join("installs.ini"), installs_ini).unwrap();
+        assert_eq!(
+            install_default_profile(&RealFileSystem, base.path(), profiles_ini, &executable),
+            Some(base.path().join("b.default-release"))
+        );
+
+        let other = Path::new("/opt/firefox-esr/firefox");
+        assert_eq!(
+            install_default_profile(&RealFileSystem, base.path(), profiles_ini, other),
+            None
+        );
+
+        let single = format!(
+            "{}\n[InstallABCDEF0123456789]\nDefault=a.default\n",
+            profiles_ini
+        );
+        assert_eq!(
+            install_default_profile(&RealFileSystem, base.path(), &single, other),
+            Some(base.path().join("a.default"))
+        );
+    }
+}
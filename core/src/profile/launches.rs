@@ -0,0 +1,84 @@
+//! Counts of the launches made through pathway, per browser and profile.
+//!
+//! Counting is opt-in through `launches = true` under `[stats]` in the config. The counts
+//! stay on this machine, in `pathway/launches.json` in the local data directory, keyed by
+//! browser token (`chrome`, `firefox-beta`) and profile directory name.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaunchCount {
+    pub count: u64,
+    /// Unix seconds of the latest launch
+    pub last_launched: Option<i64>,
+}
+
+/// Launch counts by browser token, then by profile name.
+pub type LaunchCounts = BTreeMap<String, BTreeMap<String, LaunchCount>>;
+
+/// Default location: `<local data dir>/pathway/launches.json`.
+pub fn launch_counts_path() -> Option<PathBuf> {
+    dirs_next::data_local_dir().map(|dir| dir.join("pathway").join("launches.json"))
+}
+
+/// The counts stored at `path`; empty when nothing was recorded yet or the file is damaged.
+pub fn read_launch_counts(path: &Path) -> LaunchCounts {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Counts a launch of `profile` in `browser` at `unix_secs`.
+pub fn record_launch(path: &Path, browser: &str, profile: &str, unix_secs: i64) -> io::Result<()> {
+    let mut counts = read_launch_counts(path);
+    let entry = counts
+        .entry(browser.to_string())
+        .or_default()
+        .entry(profile.to_string())
+        .or_default();
+    entry.count += 1;
+    entry.last_launched = Some(unix_secs);
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // Replaced in one step so that concurrent launches never read a half-written file
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, serde_json::to_string_pretty(&counts)?)?;
+    fs::rename(&temp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn launches_are_counted_per_browser_and_profile() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("pathway/launches.json");
+        assert!(read_launch_counts(&path).is_empty());
+
+        record_launch(&path, "chrome", "Profile 3", 100).unwrap();
+        record_launch(&path, "chrome", "Profile 3", 200).unwrap();
+        record_launch(&path, "chrome-beta", "Profile 3", 300).unwrap();
+
+        let counts = read_launch_counts(&path);
+        assert_eq!(
+            counts["chrome"]["Profile 3"],
+            LaunchCount {
+                count: 2,
+                last_launched: Some(200)
+            }
+        );
+        assert_eq!(counts["chrome-beta"]["Profile 3"].count, 1);
+
+        fs::write(&path, "{").unwrap();
+        assert!(read_launch_counts(&path).is_empty());
+    }
+}
@@ -0,0 +1,116 @@
+//! Timestamps read from browser profile metadata.
+
+use serde::{Serialize, Serializer};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A point in time with second precision, serialized as an RFC 3339 UTC timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp {
+    pub unix_secs: i64,
+}
+
+impl Timestamp {
+    pub fn from_unix_secs(unix_secs: i64) -> Self {
+        Timestamp { unix_secs }
+    }
+
+    /// Reads Chromium's `active_time`: seconds since the Unix epoch, usually with a fraction.
+    pub fn from_chromium_active_time(value: &serde_json::Value) -> Option<Self> {
+        let secs = match value {
+            serde_json::Value::Number(n) => n.as_f64()?,
+            serde_json::Value::String(s) => s.trim().parse().ok()?,
+            _ => return None,
+        };
+        (secs.is_finite() && secs > 0.0).then(|| Timestamp::from_unix_secs(secs as i64))
+    }
+
+    pub fn now() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        Timestamp::from_unix_secs(secs)
+    }
+
+    /// `2024-03-01T12:30:00Z`
+    pub fn to_rfc3339(self) -> String {
+        let days = self.unix_secs.div_euclid(86_400);
+        let secs_of_day = self.unix_secs.rem_euclid(86_400);
+        let (year, month, day) = civil_from_days(days);
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            year,
+            month,
+            day,
+            secs_of_day / 3600,
+            secs_of_day % 3600 / 60,
+            secs_of_day % 60
+        )
+    }
+
+    /// How long before `now` this was, e.g. "3 days ago".
+    pub fn relative_to(self, now: Timestamp) -> String {
+        let elapsed = now.unix_secs - self.unix_secs;
+        let (count, unit) = match elapsed {
+            s if s < 60 => return "just now".to_string(),
+            s if s < 3600 => (s / 60, "minute"),
+            s if s < 86_400 => (s / 3600, "hour"),
+            s if s < 30 * 86_400 => (s / 86_400, "day"),
+            s if s < 365 * 86_400 => (s / (30 * 86_400), "month"),
+            s => (s / (365 * 86_400), "year"),
+        };
+        let plural = if count == 1 { "" } else { "s" };
+        format!("{} {}{} ago", count, unit, plural)
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_rfc3339())
+    }
+}
+
+/// Converts days since 1970-01-01 to a (year, month, day) date in the proleptic Gregorian calendar.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chromium_active_times_become_rfc3339_and_relative_times() {
+        let active = serde_json::json!(1_709_296_200.123_456);
+        let timestamp = Timestamp::from_chromium_active_time(&active).unwrap();
+        assert_eq!(timestamp.to_rfc3339(), "2024-03-01T12:30:00Z");
+        assert_eq!(
+            Timestamp::from_chromium_active_time(&serde_json::json!("951782400")),
+            Some(Timestamp::from_unix_secs(951_782_400))
+        );
+        assert_eq!(
+            Timestamp::from_unix_secs(951_782_400).to_rfc3339(),
+            "2000-02-29T00:00:00Z"
+        );
+        assert_eq!(
+            Timestamp::from_chromium_active_time(&serde_json::json!(0)),
+            None
+        );
+
+        let now = Timestamp::from_unix_secs(timestamp.unix_secs + 3 * 86_400 + 5);
+        assert_eq!(timestamp.relative_to(now), "3 days ago");
+        let now = Timestamp::from_unix_secs(timestamp.unix_secs + 3600);
+        assert_eq!(timestamp.relative_to(now), "1 hour ago");
+        assert_eq!(timestamp.relative_to(timestamp), "just now");
+    }
+}
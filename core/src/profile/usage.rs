@@ -0,0 +1,161 @@
+//! Disk usage of profile directories, split into cache and data.
+//!
+//! Browsers keep part of their cache outside the profile: Chromium and Firefox on Linux
+//! use `~/.cache`, macOS `~/Library/Caches` and Windows the local rather than the roaming
+//! application data folder. Those directories count as cache too.
+
+use super::ProfileInfo;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directories inside a profile that only hold caches, at any depth.
+const CACHE_DIRS: &[&str] = &[
+    "Cache",
+    "Code Cache",
+    "GPUCache",
+    "DawnCache",
+    "DawnGraphiteCache",
+    "DawnWebGPUCache",
+    "GrShaderCache",
+    "ShaderCache",
+    "CacheStorage",
+    "ScriptCache",
+    "cache2",
+    "startupCache",
+    "thumbnails",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ProfileUsage {
+    pub total_bytes: u64,
+    pub cache_bytes: u64,
+    pub data_bytes: u64,
+}
+
+/// Measures `profile`; `None` for profiles without a directory of their own.
+pub fn profile_usage(profile: &ProfileInfo) -> Option<ProfileUsage> {
+    if profile.path.as_os_str().is_empty() || !profile.path.is_dir() {
+        return None;
+    }
+
+    let mut usage = ProfileUsage::default();
+    measure(&profile.path, false, &mut usage);
+    if let Some(cache_dir) = external_cache_dir(
+        &profile.path,
+        dirs_next::home_dir().as_deref(),
+        dirs_next::config_dir().as_deref(),
+        dirs_next::cache_dir().as_deref(),
+    ) {
+        measure(&cache_dir, true, &mut usage);
+    }
+    usage.total_bytes = usage.cache_bytes + usage.data_bytes;
+    Some(usage)
+}
+
+/// Total size of the regular files below `dir`.
+pub(crate) fn directory_size(dir: &Path) -> u64 {
+    let mut usage = ProfileUsage::default();
+    measure(dir, false, &mut usage);
+    usage.cache_bytes + usage.data_bytes
+}
+
+/// Adds the regular files below `dir` to `usage`; symlinks are not followed.
+fn measure(dir: &Path, in_cache: bool, usage: &mut ProfileUsage) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => {
+                let is_cache = in_cache || CACHE_DIRS.iter().any(|name| entry.file_name() == *name);
+                measure(&entry.path(), is_cache, usage);
+            }
+            Ok(file_type) if file_type.is_file() => {
+                let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                if in_cache {
+                    usage.cache_bytes += size;
+                } else {
+                    usage.data_bytes += size;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Where the browser keeps the rest of the cache for the profile at `profile_dir`.
+///
+/// Profiles under the config directory map to the same path under the cache directory.
+/// Dot-directories in the home directory (`~/.mozilla/firefox/x.default`) map to the cache
+/// directory without the dot (`~/.cache/mozilla/firefox/x.default`).
+fn external_cache_dir(
+    profile_dir: &Path,
+    home: Option<&Path>,
+    config_dir: Option<&Path>,
+    cache_dir: Option<&Path>,
+) -> Option<PathBuf> {
+    let cache_dir = cache_dir?;
+    let candidate =
+        if let Some(relative) = config_dir.and_then(|dir| profile_dir.strip_prefix(dir).ok()) {
+            cache_dir.join(relative)
+        } else {
+            let relative = profile_dir.strip_prefix(home?).ok()?;
+            let mut components = relative.components();
+            let first = components.next()?.as_os_str().to_str()?;
+            cache_dir
+                .join(first.strip_prefix('.')?)
+                .join(components.as_path())
+        };
+    (candidate != profile_dir && candidate.is_dir()).then_some(candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn caches_are_counted_apart_from_data() {
+        let home = TempDir::new().unwrap();
+        let config = home.path().join(".config");
+        let cache = home.path().join(".cache");
+        let profile = config.join("chromium/Default");
+        fs::create_dir_all(profile.join("Service Worker/CacheStorage")).unwrap();
+        fs::write(profile.join("History"), [0; 100]).unwrap();
+        fs::write(profile.join("Service Worker/CacheStorage/entry"), [0; 10]).unwrap();
+        fs::create_dir_all(cache.join("chromium/Default/Cache")).unwrap();
+        fs::write(cache.join("chromium/Default/Cache/data_1"), [0; 1000]).unwrap();
+
+        let external = external_cache_dir(&profile, Some(home.path()), Some(&config), Some(&cache));
+        assert_eq!(external, Some(cache.join("chromium/Default")));
+
+        let mut usage = ProfileUsage::default();
+        measure(&profile, false, &mut usage);
+        measure(&external.unwrap(), true, &mut usage);
+        assert_eq!(usage.data_bytes, 100);
+        assert_eq!(usage.cache_bytes, 1010);
+    }
+
+    #[test]
+    fn dot_directories_map_into_the_cache_directory() {
+        let home = TempDir::new().unwrap();
+        let cache = home.path().join(".cache");
+        let profile = home.path().join(".mozilla/firefox/abc.default");
+        fs::create_dir_all(cache.join("mozilla/firefox/abc.default")).unwrap();
+
+        assert_eq!(
+            external_cache_dir(&profile, Some(home.path()), None, Some(&cache)),
+            Some(cache.join("mozilla/firefox/abc.default"))
+        );
+        assert_eq!(
+            external_cache_dir(
+                &home.path().join("profiles/work"),
+                Some(home.path()),
+                None,
+                Some(&cache)
+            ),
+            None
+        );
+    }
+}
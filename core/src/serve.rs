@@ -0,0 +1,503 @@
+//! A throwaway static file server on localhost for `launch --serve`.
+//!
+//! Pages opened from `file://` cannot load module scripts or fetch their neighbours in most
+//! browsers. Serving them over `http://127.0.0.1` avoids that. The server only answers `GET`
+//! and `HEAD` for requests that carry its `Host` (against DNS rebinding) and start with a random
+//! per-run token (against other local users and pages). Dot-files are never served, and a served
+//! file only exposes itself and the files it references, not its whole directory. The server
+//! stops once no authorized request came in for the idle timeout.
+
+use crate::url::percent_decode;
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Default time without requests after which the server stops.
+pub const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 300;
+
+/// How often the idle timeout is checked while no connection arrives.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Most files a served page may reference, directly or through other references.
+const MAX_REFERENCED_FILES: usize = 1000;
+
+/// Largest file that is scanned for references.
+const MAX_SCANNED_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Extensions of files whose text may reference other files.
+const SCANNED_EXTENSIONS: &[&str] = &["html", "htm", "xhtml", "js", "mjs", "css", "svg", "json"];
+
+pub struct FileServer {
+    listener: TcpListener,
+    site: Site,
+}
+
+/// What a server hands out, and to which requests.
+struct Site {
+    root: PathBuf,
+    token: String,
+    port: u16,
+    /// The files that may be served, or everything below `root` when `None`
+    allowed: Option<HashSet<PathBuf>>,
+}
+
+impl FileServer {
+    /// Listens on a free port of 127.0.0.1 for `path`: a directory serves everything below it,
+    /// a file serves itself and the files it references.
+    pub fn bind(path: &Path) -> io::Result<Self> {
+        let path = path.canonicalize()?;
+        let (root, allowed) = if path.is_dir() {
+            (path, None)
+        } else {
+            let root = path
+                .parent()
+                .ok_or_else(|| io::Error::other("the file has no parent directory"))?
+                .to_path_buf();
+            let allowed = referenced_files(&root, &path);
+            (root, Some(allowed))
+        };
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let port = listener.local_addr()?.port();
+        Ok(FileServer {
+            listener,
+            site: Site {
+                root,
+                token: random_token()?,
+                port,
+                allowed,
+            },
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.site.port
+    }
+
+    /// The secret first path segment of every request.
+    pub fn token(&self) -> &str {
+        &self.site.token
+    }
+
+    /// Serves requests until no authorized one arrived for `idle_timeout`.
+    pub fn run(self, idle_timeout: Duration) -> io::Result<()> {
+        self.listener.set_nonblocking(true)?;
+        let last_request = Arc::new(Mutex::new(Instant::now()));
+        let site = Arc::new(self.site);
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    let site = Arc::clone(&site);
+                    let last_request = Arc::clone(&last_request);
+                    thread::spawn(move || match handle_connection(stream, &site) {
+                        Ok(true) => {
+                            if let Ok(mut last) = last_request.lock() {
+                                *last = Instant::now();
+                            }
+                        }
+                        Ok(false) => {}
+                        Err(e) => debug!("Request failed: {}", e),
+                    });
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    let idle = last_request
+                        .lock()
+                        .map(|last| last.elapsed())
+                        .unwrap_or_default();
+                    if idle >= idle_timeout {
+                        debug!("No requests for {:?}, stopping", idle);
+                        return Ok(());
+                    }
+                    thread::sleep(POLL_INTERVAL);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Answers one request; returns whether it was authorized.
+fn handle_connection(stream: TcpStream, site: &Site) -> io::Result<bool> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut host = None;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim().is_empty() {
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("host") {
+                host = Some(value.trim().to_string());
+            }
+        }
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or("/"),
+    );
+    let mut stream = &stream;
+    let with_body = method == "GET";
+    match site.route(method, target, host.as_deref()) {
+        Ok(path) => match fs::read(&path) {
+            Ok(body) => {
+                respond(&mut stream, "200 OK", content_type(&path), &body, with_body).map(|_| true)
+            }
+            Err(_) => respond(
+                &mut stream,
+                "404 Not Found",
+                "text/plain",
+                b"Not found",
+                with_body,
+            )
+            .map(|_| true),
+        },
+        Err(status) => respond(&mut stream, status, "text/plain", b"", with_body).map(|_| false),
+    }
+}
+
+impl Site {
+    /// The file a request reads, or the status it is refused with.
+    fn route(
+        &self,
+        method: &str,
+        target: &str,
+        host: Option<&str>,
+    ) -> Result<PathBuf, &'static str> {
+        if !matches!(method, "GET" | "HEAD") {
+            return Err("405 Method Not Allowed");
+        }
+        let host_allowed = host.is_some_and(|host| {
+            ["127.0.0.1", "localhost"]
+                .iter()
+                .any(|name| host.eq_ignore_ascii_case(&format!("{}:{}", name, self.port)))
+        });
+        if !host_allowed {
+            return Err("403 Forbidden");
+        }
+        let path = target
+            .strip_prefix('/')
+            .and_then(|path| path.strip_prefix(self.token.as_str()))
+            .filter(|rest| rest.starts_with('/'))
+            .ok_or("404 Not Found")?;
+
+        let file = resolve(&self.root, path).ok_or("404 Not Found")?;
+        match &self.allowed {
+            Some(allowed) if !allowed.contains(&file) => Err("404 Not Found"),
+            _ => Ok(file),
+        }
+    }
+}
+
+fn respond(
+    stream: &mut impl Write,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+    with_body: bool,
+) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    if with_body {
+        stream.write_all(body)?;
+    }
+    stream.flush()
+}
+
+/// The file below `root` that a request for `target` reads; directories serve their
+/// `index.html`. Nothing outside `root` is ever returned, including through symlinks, and
+/// neither are dot-files or anything in dot-directories.
+fn resolve(root: &Path, target: &str) -> Option<PathBuf> {
+    let path = target.split(['?', '#']).next().unwrap_or_default();
+    let decoded = percent_decode(path)?;
+
+    let mut file = root.to_path_buf();
+    for component in Path::new(decoded.trim_start_matches('/')).components() {
+        match component {
+            Component::Normal(part) if !part.to_string_lossy().starts_with('.') => file.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    if file.is_dir() {
+        file.push("index.html");
+    }
+    let file = file.canonicalize().ok()?;
+    let hidden = file.strip_prefix(root).ok()?.components().any(|component| {
+        matches!(component, Component::Normal(part) if part.to_string_lossy().starts_with('.'))
+    });
+    (!hidden && file.is_file()).then_some(file)
+}
+
+/// `entry` and the files below `root` it references, directly or through the pages, scripts
+/// and stylesheets it references. Any quoted string or `url(...)` naming an existing file
+/// counts as a reference.
+fn referenced_files(root: &Path, entry: &Path) -> HashSet<PathBuf> {
+    let mut files = HashSet::from([entry.to_path_buf()]);
+    let mut queue = VecDeque::from([entry.to_path_buf()]);
+    while let Some(file) = queue.pop_front() {
+        let scanned = file
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| {
+                SCANNED_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+            });
+        let small = fs::metadata(&file).is_ok_and(|metadata| metadata.len() <= MAX_SCANNED_BYTES);
+        if !scanned || !small {
+            continue;
+        }
+        let Ok(text) = fs::read_to_string(&file) else {
+            continue;
+        };
+        let directory = file.parent().unwrap_or(root);
+        for reference in references(&text) {
+            if files.len() >= MAX_REFERENCED_FILES {
+                return files;
+            }
+            let (base, relative) = match reference.strip_prefix('/') {
+                Some(relative) => (root, relative),
+                None => (directory, reference),
+            };
+            let Ok(base) = base.strip_prefix(root) else {
+                continue;
+            };
+            // `..` may lead to a sibling directory, but not above `root`
+            let mut segments: Vec<String> = base
+                .iter()
+                .map(|segment| segment.to_string_lossy().into_owned())
+                .collect();
+            let mut above_root = false;
+            for segment in relative.split('/') {
+                match segment {
+                    "" | "." => {}
+                    ".." => above_root |= segments.pop().is_none(),
+                    segment => segments.push(segment.to_string()),
+                }
+            }
+            if above_root {
+                continue;
+            }
+            if let Some(found) = resolve(root, &segments.join("/")) {
+                if files.insert(found.clone()) {
+                    queue.push_back(found);
+                }
+            }
+        }
+    }
+    files
+}
+
+/// Words of `text` that may be relative URLs of files: split at quotes, parentheses,
+/// whitespace and other characters of markup, with a dot or slash, and without a scheme.
+fn references(text: &str) -> HashSet<&str> {
+    text.split(|c: char| c.is_whitespace() || "\"'`()[]{}<>=,;".contains(c))
+        .map(|word| word.split(['?', '#']).next().unwrap_or_default())
+        .filter(|word| word.contains(['.', '/']) && !word.contains(':') && !word.starts_with("//"))
+        .collect()
+}
+
+/// 128 random bits as hex.
+fn random_token() -> io::Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).map_err(|e| io::Error::other(e.to_string()))?;
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// The `Content-Type` of a file by its extension. Scripts must be served with a JavaScript
+/// type for browsers to run them as modules.
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "json" | "map" => "application/json",
+        "txt" | "md" => "text/plain; charset=utf-8",
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "ico" => "image/x-icon",
+        "wasm" => "application/wasm",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "pdf" => "application/pdf",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mp3" => "audio/mpeg",
+        "ogg" => "audio/ogg",
+        "wav" => "audio/wav",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use tempfile::TempDir;
+
+    fn site(dir: &TempDir) -> PathBuf {
+        let site = dir.path().join("site");
+        fs::create_dir_all(site.join("js")).unwrap();
+        fs::create_dir_all(site.join(".git")).unwrap();
+        fs::write(
+            site.join("index.html"),
+            "<h1>Hi</h1><script type=\"module\" src=\"js/app%20one.mjs?v=2\"></script>\
+             <div style=\"background: url(bg.png)\"></div>",
+        )
+        .unwrap();
+        fs::write(
+            site.join("js/app one.mjs"),
+            "import { x } from './util.js';",
+        )
+        .unwrap();
+        fs::write(site.join("js/util.js"), "export const x = 1;").unwrap();
+        fs::write(site.join("bg.png"), "png").unwrap();
+        fs::write(site.join("notes.txt"), "private notes").unwrap();
+        fs::write(site.join(".env"), "TOKEN=x").unwrap();
+        fs::write(site.join(".git/config"), "[core]").unwrap();
+        fs::write(dir.path().join("secret.txt"), "secret").unwrap();
+        site.canonicalize().unwrap()
+    }
+
+    #[test]
+    fn resolves_files_below_the_root_only() {
+        let dir = TempDir::new().unwrap();
+        let root = site(&dir);
+
+        assert_eq!(resolve(&root, "/"), Some(root.join("index.html")));
+        assert_eq!(
+            resolve(&root, "/js/app%20one.mjs?v=2"),
+            Some(root.join("js/app one.mjs"))
+        );
+        assert_eq!(resolve(&root, "/../secret.txt"), None);
+        assert_eq!(resolve(&root, "/%2E%2E/secret.txt"), None);
+        assert_eq!(resolve(&root, "/missing.html"), None);
+        assert_eq!(resolve(&root, "/.env"), None);
+        assert_eq!(resolve(&root, "/.git/config"), None);
+        assert_eq!(
+            content_type(&root.join("js/app one.mjs")),
+            "text/javascript; charset=utf-8"
+        );
+    }
+
+    #[test]
+    fn a_served_file_exposes_only_what_it_references() {
+        let dir = TempDir::new().unwrap();
+        let root = site(&dir);
+
+        let files = referenced_files(&root, &root.join("index.html"));
+        let expected: HashSet<PathBuf> = ["index.html", "js/app one.mjs", "js/util.js", "bg.png"]
+            .iter()
+            .map(|file| root.join(file))
+            .collect();
+        assert_eq!(files, expected);
+
+        let server = FileServer::bind(&root.join("index.html")).unwrap();
+        let site = &server.site;
+        let host = format!("127.0.0.1:{}", server.port());
+        let get =
+            |path: &str| site.route("GET", &format!("/{}{}", server.token(), path), Some(&host));
+        assert_eq!(get("/js/util.js"), Ok(root.join("js/util.js")));
+        assert_eq!(get("/notes.txt"), Err("404 Not Found"));
+    }
+
+    #[test]
+    fn requests_need_the_host_and_token() {
+        let dir = TempDir::new().unwrap();
+        let root = site(&dir);
+        let server = FileServer::bind(&root).unwrap();
+        let port = server.port();
+        let token = server.token().to_string();
+        let site = &server.site;
+        let host = format!("localhost:{}", port);
+        let index = format!("/{}/index.html", token);
+
+        assert_eq!(
+            site.route("GET", &index, Some(&host)),
+            Ok(root.join("index.html"))
+        );
+        assert_eq!(
+            site.route("GET", &format!("/{}/notes.txt", token), Some(&host)),
+            Ok(root.join("notes.txt"))
+        );
+        assert_eq!(
+            site.route("GET", &index, Some("attacker.example:80")),
+            Err("403 Forbidden")
+        );
+        assert_eq!(
+            site.route("GET", &index, Some(&format!("127.0.0.1:{}", port + 1))),
+            Err("403 Forbidden")
+        );
+        assert_eq!(site.route("GET", &index, None), Err("403 Forbidden"));
+        assert_eq!(
+            site.route("GET", "/index.html", Some(&host)),
+            Err("404 Not Found")
+        );
+        assert_eq!(
+            site.route("GET", &format!("/{}x/index.html", token), Some(&host)),
+            Err("404 Not Found")
+        );
+        assert_eq!(
+            site.route("GET", &format!("/{}/.env", token), Some(&host)),
+            Err("404 Not Found")
+        );
+        assert_eq!(
+            site.route("POST", &index, Some(&host)),
+            Err("405 Method Not Allowed")
+        );
+    }
+
+    #[test]
+    fn serves_over_http_until_idle() {
+        let dir = TempDir::new().unwrap();
+        let root = site(&dir);
+        let server = FileServer::bind(&root).unwrap();
+        let port = server.port();
+        let token = server.token().to_string();
+        let handle = thread::spawn(move || server.run(Duration::from_millis(500)));
+
+        let request = |request: String| {
+            let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = request(format!(
+            "GET /{}/bg.png HTTP/1.1\r\nHost: 127.0.0.1:{}\r\n\r\n",
+            token, port
+        ));
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\npng"));
+        let response = request(format!(
+            "GET /{}/bg.png HTTP/1.1\r\nHost: rebound.example:{}\r\n\r\n",
+            token, port
+        ));
+        assert!(response.starts_with("HTTP/1.1 403 Forbidden\r\n"));
+        handle.join().unwrap().unwrap();
+    }
+}
@@ -0,0 +1,517 @@
+//! Launchers that open a browser with a given profile through pathway.
+//!
+//! Each platform gets the kind of shortcut its desktop shows: a `.desktop` entry on Linux, a
+//! small app bundle running a shell script on macOS, and a `.lnk` file on Windows, which is
+//! written through the `WScript.Shell` COM object since the format is binary. All of them
+//! run `pathway launch` with the stored arguments.
+//!
+//! The macOS bundles have their own identifier, name and icon, and stay open while the
+//! browser process they started runs, so each profile gets a Dock icon and a Cmd-Tab entry
+//! of its own.
+//!
+//! On Windows the `.lnk` files go to the Start Menu and carry an AppUserModelID of their own,
+//! which the taskbar keys pinned icons and jump lists on, so the pins of two profiles don't
+//! replace each other. `WScript.Shell` can't set that property, so it is written through the
+//! shell's property store afterwards.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ShortcutError {
+    #[error("Shortcut name must not be empty")]
+    EmptyName,
+    #[error("Shortcut '{0}' already exists")]
+    Exists(PathBuf),
+    #[error("Failed to create the shortcut: {0}")]
+    CreationFailed(String),
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// A launcher running `program` with `args`.
+#[derive(Debug, Clone)]
+pub struct Shortcut {
+    /// Name shown on the desktop or in the applications menu
+    pub name: String,
+    /// The pathway executable
+    pub program: PathBuf,
+    /// Arguments for `program`, e.g. `launch --browser chrome --profile Work`
+    pub args: Vec<String>,
+    /// Icon name or path, where the platform supports one; macOS bundles copy an icon file
+    /// (preferably `.icns`) into their resources
+    pub icon: Option<String>,
+}
+
+/// Where shortcuts go unless told otherwise: the applications menu on Linux
+/// (`~/.local/share/applications`), `~/Applications` on macOS and the Start Menu on Windows.
+pub fn default_shortcut_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "macos") {
+        dirs_next::home_dir().map(|home| home.join("Applications"))
+    } else if cfg!(windows) {
+        dirs_next::data_dir().map(|dir| dir.join(r"Microsoft\Windows\Start Menu\Programs"))
+    } else {
+        dirs_next::data_dir().map(|dir| dir.join("applications"))
+    }
+}
+
+/// Writes `shortcut` to `dir` and returns its path. Existing shortcuts are not replaced.
+pub fn create_shortcut(shortcut: &Shortcut, dir: &Path) -> Result<PathBuf, ShortcutError> {
+    if shortcut.name.trim().is_empty() {
+        return Err(ShortcutError::EmptyName);
+    }
+    let path = dir.join(shortcut_file_name(&shortcut.name));
+    if path.exists() {
+        return Err(ShortcutError::Exists(path));
+    }
+    fs::create_dir_all(dir)?;
+
+    if cfg!(target_os = "macos") {
+        write_app_stub(shortcut, &path)?;
+    } else if cfg!(windows) {
+        write_lnk(shortcut, &path)?;
+    } else {
+        fs::write(&path, desktop_entry(shortcut))?;
+        set_executable(&path)?;
+    }
+    Ok(path)
+}
+
+/// File name of the shortcut called `name` on this platform.
+fn shortcut_file_name(name: &str) -> String {
+    let safe: String = name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c => c,
+        })
+        .collect();
+    if cfg!(target_os = "macos") {
+        format!("{safe}.app")
+    } else if cfg!(windows) {
+        format!("{safe}.lnk")
+    } else {
+        // Desktop file ids are best kept to letters, digits and dashes
+        let id: String = safe
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect();
+        let id: Vec<&str> = id.split('-').filter(|part| !part.is_empty()).collect();
+        format!("pathway-{}.desktop", id.join("-"))
+    }
+}
+
+/// A desktop entry whose `Exec` line passes dropped URLs on through `%U`.
+fn desktop_entry(shortcut: &Shortcut) -> String {
+    let exec: Vec<String> = std::iter::once(shortcut.program.to_string_lossy().into_owned())
+        .chain(shortcut.args.iter().cloned())
+        .map(|arg| quote_exec_arg(&arg))
+        .collect();
+    let mut entry = format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name={}\n\
+         Exec={} %U\n\
+         Terminal=false\n\
+         Categories=Network;WebBrowser;\n",
+        shortcut.name.replace('\n', " "),
+        exec.join(" ")
+    );
+    if let Some(icon) = &shortcut.icon {
+        entry.push_str(&format!("Icon={}\n", icon));
+    }
+    entry
+}
+
+/// Quotes an `Exec` argument as the desktop entry specification asks: reserved characters
+/// need double quotes, inside which `"`, `` ` ``, `$` and `\` are escaped. A literal `%` is
+/// always written as `%%`.
+fn quote_exec_arg(arg: &str) -> String {
+    let arg = arg.replace('%', "%%");
+    let reserved = |c: char| {
+        c.is_whitespace()
+            || matches!(
+                c,
+                '"' | '\''
+                    | '\\'
+                    | '>'
+                    | '<'
+                    | '~'
+                    | '|'
+                    | '&'
+                    | ';'
+                    | '$'
+                    | '*'
+                    | '?'
+                    | '#'
+                    | '('
+                    | ')'
+                    | '`'
+            )
+    };
+    if !arg.is_empty() && !arg.contains(reserved) {
+        return arg;
+    }
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Quotes `arg` for a POSIX shell.
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// The script inside the app bundle, passing on any arguments it is started with.
+///
+/// It waits for the browser process pathway reports in its JSON output, since the Dock only
+/// shows the bundle while its own process runs. A browser that hands the window to an
+/// instance already running exits at once, and so does the script.
+fn app_stub_script(shortcut: &Shortcut) -> String {
+    let command: Vec<String> = [
+        shortcut.program.to_string_lossy().into_owned(),
+        "--format".to_string(),
+        "json".to_string(),
+    ]
+    .into_iter()
+    .chain(shortcut.args.iter().cloned())
+    .map(|arg| shell_quote(&arg))
+    .collect();
+    format!(
+        "#!/bin/sh\n\
+         pid=$({} \"$@\" | sed -n 's/^ *\"pid\": *\\([0-9]*\\).*/\\1/p' | head -n 1)\n\
+         while [ -n \"$pid\" ] && kill -0 \"$pid\" 2>/dev/null; do sleep 2; done\n",
+        command.join(" ")
+    )
+}
+
+/// The icon of the app bundle `executable` belongs to, for shortcuts that look like the
+/// browser they open.
+pub fn app_icon(executable: &Path) -> Option<PathBuf> {
+    let app = executable
+        .ancestors()
+        .find(|path| path.extension().and_then(|s| s.to_str()) == Some("app"))?;
+    let output = Command::new("plutil")
+        .args(["-extract", "CFBundleIconFile", "raw", "-o", "-"])
+        .arg(app.join("Contents/Info.plist"))
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let mut icon = app.join("Contents/Resources").join(name);
+    if icon.extension().is_none() {
+        icon.set_extension("icns");
+    }
+    icon.is_file().then_some(icon)
+}
+
+/// The part of bundle identifiers and AppUserModelIDs that tells shortcuts apart.
+fn shortcut_identifier(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// AppUserModelID of the shortcut called `name`; the taskbar allows up to 128 characters.
+fn app_user_model_id(name: &str) -> String {
+    format!("Pathway.Shortcut.{}", shortcut_identifier(name))
+        .chars()
+        .take(128)
+        .collect()
+}
+
+fn app_stub_info_plist(shortcut: &Shortcut, icon_file: Option<&str>) -> String {
+    let escape = |s: &str| {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    let identifier = shortcut_identifier(&shortcut.name);
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>CFBundleExecutable</key>
+    <string>launch</string>
+    <key>CFBundleIdentifier</key>
+    <string>dev.pathway.shortcut.{}</string>
+    <key>CFBundleName</key>
+    <string>{name}</string>
+    <key>CFBundleDisplayName</key>
+    <string>{name}</string>
+    <key>CFBundlePackageType</key>
+    <string>APPL</string>{icon}
+</dict>
+</plist>
+"#,
+        identifier,
+        name = escape(&shortcut.name),
+        icon = icon_file
+            .map(|file| format!(
+                "\n    <key>CFBundleIconFile</key>\n    <string>{}</string>",
+                escape(file)
+            ))
+            .unwrap_or_default()
+    )
+}
+
+fn write_app_stub(shortcut: &Shortcut, app: &Path) -> Result<(), ShortcutError> {
+    let macos_dir = app.join("Contents/MacOS");
+    fs::create_dir_all(&macos_dir)?;
+    let icon_file = match shortcut.icon.as_deref().map(Path::new) {
+        Some(icon) if icon.is_file() => {
+            let extension = icon.extension().and_then(|e| e.to_str()).unwrap_or("icns");
+            let file = format!("AppIcon.{}", extension);
+            let resources = app.join("Contents/Resources");
+            fs::create_dir_all(&resources)?;
+            fs::copy(icon, resources.join(&file))?;
+            Some(file)
+        }
+        _ => None,
+    };
+    fs::write(
+        app.join("Contents/Info.plist"),
+        app_stub_info_plist(shortcut, icon_file.as_deref()),
+    )?;
+    let script = macos_dir.join("launch");
+    fs::write(&script, app_stub_script(shortcut))?;
+    set_executable(&script)?;
+    Ok(())
+}
+
+/// Sets `System.AppUserModel.ID` on a saved `.lnk` through `IPropertyStore`.
+const SET_APP_ID_TYPE: &str = r#"
+using System;
+using System.Runtime.InteropServices;
+
+[ComImport, Guid("886D8EEB-8CF2-4446-8D02-CDBA1DBDCF99"), InterfaceType(ComInterfaceType.InterfaceIsIUnknown)]
+public interface IPathwayPropertyStore {
+    void GetCount(out uint count);
+    void GetAt(uint index, out PathwayPropertyKey key);
+    void GetValue(ref PathwayPropertyKey key, out PathwayPropVariant value);
+    void SetValue(ref PathwayPropertyKey key, ref PathwayPropVariant value);
+    void Commit();
+}
+
+[StructLayout(LayoutKind.Sequential)]
+public struct PathwayPropertyKey { public Guid FormatId; public uint PropertyId; }
+
+[StructLayout(LayoutKind.Explicit)]
+public struct PathwayPropVariant {
+    [FieldOffset(0)] public ushort VarType;
+    [FieldOffset(8)] public IntPtr Value;
+    [FieldOffset(16)] public IntPtr Padding;
+}
+
+public static class PathwayShortcut {
+    [DllImport("shell32.dll", CharSet = CharSet.Unicode, PreserveSig = false)]
+    static extern void SHGetPropertyStoreFromParsingName(
+        string path, IntPtr bindContext, int flags, ref Guid iid, out IPathwayPropertyStore store);
+
+    public static void SetAppId(string path, string appId) {
+        Guid iid = typeof(IPathwayPropertyStore).GUID;
+        IPathwayPropertyStore store;
+        // GPS_READWRITE
+        SHGetPropertyStoreFromParsingName(path, IntPtr.Zero, 2, ref iid, out store);
+        PathwayPropertyKey key = new PathwayPropertyKey {
+            FormatId = new Guid("9F4C2855-9F79-4B39-A8D0-E1D42DE1D5F3"), PropertyId = 5 };
+        // VT_LPWSTR
+        PathwayPropVariant value = new PathwayPropVariant {
+            VarType = 31, Value = Marshal.StringToCoTaskMemUni(appId) };
+        try {
+            store.SetValue(ref key, ref value);
+            store.Commit();
+        } finally {
+            Marshal.FreeCoTaskMem(value.Value);
+            Marshal.ReleaseComObject(store);
+        }
+    }
+}
+"#;
+
+/// PowerShell that saves a `.lnk` at `path` through `WScript.Shell` and gives it the
+/// shortcut's AppUserModelID.
+fn lnk_script(shortcut: &Shortcut, path: &Path) -> String {
+    let literal = |s: &str| format!("'{}'", s.replace('\'', "''"));
+    let arguments: Vec<String> = shortcut
+        .args
+        .iter()
+        .map(|arg| {
+            if arg.is_empty() || arg.contains([' ', '\t', '"']) {
+                format!("\"{}\"", arg.replace('"', "\\\""))
+            } else {
+                arg.clone()
+            }
+        })
+        .collect();
+    let mut script = format!(
+        "$s = (New-Object -ComObject WScript.Shell).CreateShortcut({}); \
+         $s.TargetPath = {}; $s.Arguments = {}; $s.WindowStyle = 7; ",
+        literal(&path.to_string_lossy()),
+        literal(&shortcut.program.to_string_lossy()),
+        literal(&arguments.join(" "))
+    );
+    if let Some(icon) = &shortcut.icon {
+        script.push_str(&format!("$s.IconLocation = {}; ", literal(icon)));
+    }
+    script.push_str(&format!(
+        "$s.Save()\nAdd-Type -TypeDefinition @'{}'@\n[PathwayShortcut]::SetAppId({}, {})",
+        SET_APP_ID_TYPE,
+        literal(&path.to_string_lossy()),
+        literal(&app_user_model_id(&shortcut.name))
+    ));
+    script
+}
+
+fn write_lnk(shortcut: &Shortcut, path: &Path) -> Result<(), ShortcutError> {
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command"])
+        .arg(lnk_script(shortcut, path))
+        .output()?;
+    if !output.status.success() {
+        return Err(ShortcutError::CreationFailed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn work() -> Shortcut {
+        Shortcut {
+            name: "Chrome (Work)".to_string(),
+            program: PathBuf::from("/opt/path way/pathway"),
+            args: vec![
+                "launch".to_string(),
+                "--browser".to_string(),
+                "chrome".to_string(),
+                "--profile".to_string(),
+                "Work \"EU\" 100%".to_string(),
+            ],
+            icon: Some("google-chrome".to_string()),
+        }
+    }
+
+    #[test]
+    fn desktop_entries_quote_their_exec_line() {
+        assert_eq!(
+            desktop_entry(&work()),
+            "[Desktop Entry]\n\
+             Type=Application\n\
+             Name=Chrome (Work)\n\
+             Exec=\"/opt/path way/pathway\" launch --browser chrome --profile \"Work \\\"EU\\\" 100%%\" %U\n\
+             Terminal=false\n\
+             Categories=Network;WebBrowser;\n\
+             Icon=google-chrome\n"
+        );
+    }
+
+    #[test]
+    fn app_stubs_and_lnk_scripts_keep_arguments_apart() {
+        let script = app_stub_script(&work());
+        assert!(script.starts_with(
+            "#!/bin/sh\npid=$('/opt/path way/pathway' '--format' 'json' 'launch' '--browser' \
+             'chrome' '--profile' 'Work \"EU\" 100%' \"$@\" | sed"
+        ));
+        assert!(script.contains("kill -0 \"$pid\""));
+        let plist = app_stub_info_plist(&work(), Some("AppIcon.icns"));
+        assert!(plist.contains("<string>dev.pathway.shortcut.Chrome--Work-</string>"));
+        assert!(plist.contains("<key>CFBundleIconFile</key>\n    <string>AppIcon.icns</string>"));
+        assert!(!app_stub_info_plist(&work(), None).contains("CFBundleIconFile"));
+
+        let script = lnk_script(&work(), Path::new(r"C:\Users\me\Desktop\Chrome (Work).lnk"));
+        assert!(script.contains(r"CreateShortcut('C:\Users\me\Desktop\Chrome (Work).lnk')"));
+        assert!(script
+            .contains(r#"$s.Arguments = 'launch --browser chrome --profile "Work \"EU\" 100%"'"#));
+        assert!(script.contains("\n'@\n[PathwayShortcut]::SetAppId("));
+        assert!(script.ends_with(r"(Work).lnk', 'Pathway.Shortcut.Chrome--Work-')"));
+    }
+
+    #[test]
+    fn app_user_model_ids_tell_profiles_apart() {
+        assert_eq!(
+            app_user_model_id("Chrome (Work)"),
+            "Pathway.Shortcut.Chrome--Work-"
+        );
+        assert_ne!(
+            app_user_model_id("Chrome (Work)"),
+            app_user_model_id("Chrome (Home)")
+        );
+        assert_eq!(app_user_model_id(&"x".repeat(200)).len(), 128);
+
+        #[cfg(windows)]
+        assert!(default_shortcut_dir()
+            .unwrap()
+            .ends_with(r"Microsoft\Windows\Start Menu\Programs"));
+    }
+
+    #[test]
+    fn app_stubs_bundle_their_own_icon() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let icon = dir.path().join("chrome.icns");
+        fs::write(&icon, b"icns").unwrap();
+        let shortcut = Shortcut {
+            icon: Some(icon.to_string_lossy().into_owned()),
+            ..work()
+        };
+        let app = dir.path().join("Chrome (Work).app");
+
+        write_app_stub(&shortcut, &app).unwrap();
+        assert_eq!(
+            fs::read(app.join("Contents/Resources/AppIcon.icns")).unwrap(),
+            b"icns"
+        );
+        let plist = fs::read_to_string(app.join("Contents/Info.plist")).unwrap();
+        assert!(
+            plist.contains("<key>CFBundleDisplayName</key>\n    <string>Chrome (Work)</string>")
+        );
+        assert!(plist.contains("<string>AppIcon.icns</string>"));
+        assert!(!plist.contains("LSUIElement"));
+        assert!(fs::read_to_string(app.join("Contents/MacOS/launch"))
+            .unwrap()
+            .contains("while [ -n \"$pid\" ]"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn shortcuts_are_written_once() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = create_shortcut(&work(), dir.path()).unwrap();
+        assert_eq!(path, dir.path().join("pathway-chrome-work.desktop"));
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains("Name=Chrome (Work)"));
+        assert!(matches!(
+            create_shortcut(&work(), dir.path()),
+            Err(ShortcutError::Exists(_))
+        ));
+    }
+}
@@ -0,0 +1,120 @@
+//! Expanding links from URL shorteners (the `expand-urls` feature).
+//!
+//! Only links on known shortener hosts are expanded, so other URLs never cause a request.
+//! Each hop is a `HEAD` request whose `Location` header is followed by hand, which keeps
+//! the whole redirect chain for reporting.
+
+use std::time::Duration;
+use thiserror::Error;
+use tracing::debug;
+use url::Url;
+
+/// Redirects followed from a short link before giving up.
+const MAX_HOPS: usize = 10;
+
+/// Hosts of URL shorteners whose links are expanded.
+pub const SHORTENER_HOSTS: &[&str] = &[
+    "bit.ly",
+    "bitly.com",
+    "t.co",
+    "tinyurl.com",
+    "goo.gl",
+    "ow.ly",
+    "buff.ly",
+    "is.gd",
+    "v.gd",
+    "t.ly",
+    "cutt.ly",
+    "rb.gy",
+    "rebrand.ly",
+    "shorturl.at",
+    "tiny.cc",
+    "lnkd.in",
+    "dlvr.it",
+    "trib.al",
+    "aka.ms",
+    "amzn.to",
+    "fb.me",
+];
+
+#[derive(Debug, Error)]
+pub enum ExpandError {
+    #[error("Request failed: {0}")]
+    Http(#[from] Box<ureq::Error>),
+    #[error("Invalid redirect to '{0}'")]
+    InvalidRedirect(String),
+    #[error("More than {MAX_HOPS} redirects")]
+    TooManyRedirects,
+}
+
+/// Whether `url` is a link of a known URL shortener.
+pub fn is_short_url(url: &Url) -> bool {
+    url.host_str().is_some_and(|host| {
+        let host = host.to_ascii_lowercase();
+        let host = host.strip_prefix("www.").unwrap_or(&host);
+        SHORTENER_HOSTS.contains(&host)
+    })
+}
+
+/// Follows the redirects of `url`, each request limited to `timeout`, and returns the URLs it
+/// passed through, ending with the destination. Empty when `url` is no short link.
+pub fn expand_short_url(url: &str, timeout: Duration) -> Result<Vec<String>, ExpandError> {
+    let Ok(mut current) = Url::parse(url) else {
+        return Ok(Vec::new());
+    };
+    if !is_short_url(&current) {
+        return Ok(Vec::new());
+    }
+
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(timeout))
+        .max_redirects(0)
+        .http_status_as_error(false)
+        .build()
+        .into();
+    let mut chain = Vec::new();
+    loop {
+        let response = agent.head(current.as_str()).call().map_err(Box::new)?;
+        if !response.status().is_redirection() {
+            return Ok(chain);
+        }
+        let Some(location) = response
+            .headers()
+            .get("location")
+            .and_then(|value| value.to_str().ok())
+        else {
+            return Ok(chain);
+        };
+        let next = current
+            .join(location)
+            .map_err(|_| ExpandError::InvalidRedirect(location.to_string()))?;
+        if !matches!(next.scheme(), "http" | "https") {
+            return Err(ExpandError::InvalidRedirect(next.to_string()));
+        }
+        debug!("{} redirects to {}", current, next);
+        if chain.len() == MAX_HOPS {
+            return Err(ExpandError::TooManyRedirects);
+        }
+        chain.push(next.to_string());
+        current = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_shortener_links_are_expanded() {
+        let short = |url: &str| is_short_url(&Url::parse(url).unwrap());
+        assert!(short("https://bit.ly/3xYz"));
+        assert!(short("https://www.TinyURL.com/abc"));
+        assert!(!short("https://example.com/bit.ly"));
+        assert!(!short("https://notbit.ly/abc"));
+        assert!(
+            expand_short_url("https://example.com/", Duration::from_secs(1))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! Web links that a native app opens better than a browser.
+//!
+//! Zoom meetings, Spotify items, Teams links and Figma files are recognized during
+//! validation and reported as an [`AppLink`]. Whether they are handed to the app instead of
+//! the browser is up to the caller, see `native_apps` in the config.
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NativeApp {
+    Zoom,
+    Spotify,
+    Teams,
+    Figma,
+}
+
+impl NativeApp {
+    pub fn display_name(self) -> &'static str {
+        match self {
+            NativeApp::Zoom => "Zoom",
+            NativeApp::Spotify => "Spotify",
+            NativeApp::Teams => "Microsoft Teams",
+            NativeApp::Figma => "Figma",
+        }
+    }
+
+    /// The custom scheme the app registers for its links.
+    pub fn scheme(self) -> &'static str {
+        match self {
+            NativeApp::Zoom => "zoommtg",
+            NativeApp::Spotify => "spotify",
+            NativeApp::Teams => "msteams",
+            NativeApp::Figma => "figma",
+        }
+    }
+}
+
+/// A web link with its counterpart in a native app.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppLink {
+    pub app: NativeApp,
+    /// The link in the app's custom scheme
+    pub url: String,
+}
+
+const SPOTIFY_TYPES: &[&str] = &["track", "album", "playlist", "artist", "show", "episode"];
+
+const FIGMA_TYPES: &[&str] = &["file", "design", "proto", "board", "slides"];
+
+/// The native app link for a web `url`, if it is one of the recognized kinds.
+pub fn app_link(url: &Url) -> Option<AppLink> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = url.host_str()?;
+    let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+
+    let (app, link) = if host == "zoom.us" || host.ends_with(".zoom.us") {
+        let ["j" | "s", meeting] = segments.as_slice() else {
+            return None;
+        };
+        if !meeting.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let mut link = Url::parse("zoommtg://zoom.us/join").ok()?;
+        link.query_pairs_mut()
+            .append_pair("action", "join")
+            .append_pair("confno", meeting);
+        if let Some(password) = param("pwd") {
+            link.query_pairs_mut().append_pair("pwd", &password);
+        }
+        (NativeApp::Zoom, link.to_string())
+    } else if host == "open.spotify.com" {
+        // Localized links start with a segment such as "intl-de"
+        let segments = match segments.split_first() {
+            Some((first, rest)) if first.starts_with("intl-") => rest,
+            _ => &segments[..],
+        };
+        let [kind, id] = segments else {
+            return None;
+        };
+        if !SPOTIFY_TYPES.contains(kind) || !id.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+        (NativeApp::Spotify, format!("spotify:{}:{}", kind, id))
+    } else if matches!(host, "teams.microsoft.com" | "teams.live.com")
+        && url.path().starts_with("/l/")
+    {
+        let query = url.query().map(|q| format!("?{}", q)).unwrap_or_default();
+        (NativeApp::Teams, format!("msteams:{}{}", url.path(), query))
+    } else if matches!(host, "figma.com" | "www.figma.com") {
+        let [kind, _key, ..] = segments.as_slice() else {
+            return None;
+        };
+        if !FIGMA_TYPES.contains(kind) {
+            return None;
+        }
+        let query = url.query().map(|q| format!("?{}", q)).unwrap_or_default();
+        (NativeApp::Figma, format!("figma:/{}{}", url.path(), query))
+    } else {
+        return None;
+    };
+    Some(AppLink { app, url: link })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(url: &str) -> Option<String> {
+        app_link(&Url::parse(url).unwrap()).map(|link| link.url)
+    }
+
+    #[test]
+    fn recognizes_links_of_native_apps() {
+        assert_eq!(
+            link("https://us02web.zoom.us/j/85012345678?pwd=abc.1"),
+            Some("zoommtg://zoom.us/join?action=join&confno=85012345678&pwd=abc.1".to_string())
+        );
+        assert_eq!(
+            link("https://open.spotify.com/intl-de/track/4uLU6hMCjMI75M1A2tKUQC?si=x"),
+            Some("spotify:track:4uLU6hMCjMI75M1A2tKUQC".to_string())
+        );
+        assert_eq!(
+            link("https://teams.microsoft.com/l/meetup-join/19%3ameeting_x%40thread.v2/0?context=%7b%7d"),
+            Some("msteams:/l/meetup-join/19%3ameeting_x%40thread.v2/0?context=%7b%7d".to_string())
+        );
+        assert_eq!(
+            link("https://www.figma.com/design/AbC123/Landing-page?node-id=1-2"),
+            Some("figma://design/AbC123/Landing-page?node-id=1-2".to_string())
+        );
+
+        assert_eq!(link("https://zoom.us/pricing"), None);
+        assert_eq!(link("https://open.spotify.com/search/jazz"), None);
+        assert_eq!(link("https://teams.microsoft.com/v2/"), None);
+        assert_eq!(link("https://www.figma.com/community"), None);
+    }
+}
@@ -0,0 +1,440 @@
+//! Opt-in support for `data:` URLs.
+//!
+//! `data:` URLs stay rejected unless their media type is allowlisted through
+//! [`ValidationOptions::data_url_types`]. Accepted ones are decoded, checked against a size
+//! limit and re-encoded as base64. HTML and SVG documents lose their comments, `<script>`
+//! elements, `on*` event handler attributes, links to anything but web, mail and phone URLs,
+//! embedded frames and objects, `<meta>` and `<base>` elements and SVG animations on the way.
+//! HTML documents with elements whose content is parsed as raw text, such as `<textarea>` or
+//! `<style>`, are refused.
+
+use super::{percent_decode_bytes, ValidationOptions};
+use crate::error::{PathwayError, Result};
+use base64::engine::general_purpose::{STANDARD, STANDARD_PAD_INDIFFERENT};
+use base64::Engine;
+use url::{Position, Url};
+
+/// Largest decoded `data:` payload accepted when no limit is configured.
+pub const DEFAULT_MAX_DATA_URL_BYTES: usize = 2 * 1024 * 1024;
+
+/// Media types whose documents can run scripts and are sanitized.
+const ACTIVE_TYPES: &[&str] = &["text/html", "application/xhtml+xml", "image/svg+xml"];
+
+/// Attributes holding a URL that a document may navigate to or load.
+const URL_ATTRIBUTES: &[&str] = &[
+    "href",
+    "src",
+    "action",
+    "formaction",
+    "xlink:href",
+    "data",
+    "poster",
+    "background",
+];
+
+/// Elements dropped together with their attributes, keeping their (sanitized) content: ones
+/// that embed other documents, change where the document or its links lead, or animate
+/// attributes, which can turn a link into `javascript:` after sanitizing.
+const DROPPED_ELEMENTS: &[&str] = &[
+    "iframe",
+    "frame",
+    "frameset",
+    "object",
+    "embed",
+    "applet",
+    "portal",
+    "meta",
+    "base",
+    "animate",
+    "animatemotion",
+    "animatetransform",
+    "set",
+];
+
+/// HTML elements whose content the HTML parser reads as text up to their end tag (RCDATA and
+/// RAWTEXT), hiding anything that looks like markup inside them from the sanitizer.
+const RAW_TEXT_ELEMENTS: &[&str] = &[
+    "title",
+    "textarea",
+    "style",
+    "xmp",
+    "iframe",
+    "noembed",
+    "noframes",
+    "noscript",
+    "plaintext",
+];
+
+/// Schemes allowed in [`URL_ATTRIBUTES`].
+const SAFE_LINK_SCHEMES: &[&str] = &["http", "https", "mailto", "tel"];
+
+pub(super) struct DataUrl {
+    /// The sanitized URL, base64-encoded
+    pub normalized: String,
+    /// Scripts, event handlers and links that were removed
+    pub removed: usize,
+}
+
+/// Validates `url` against the allowlisted media types and size limit of `options`.
+pub(super) fn sanitize_data_url(url: &Url, options: &ValidationOptions) -> Result<DataUrl> {
+    let invalid = |what: String| PathwayError::InvalidUrl(format!("data: URL {}", what));
+
+    // The fragment is not part of the payload, but a query is
+    let content = &url[Position::BeforePath..Position::AfterQuery];
+    let (header, body) = content
+        .split_once(',')
+        .ok_or_else(|| invalid("has no ','".to_string()))?;
+    let (media_type, base64) = match header.trim_end().rsplit_once(';') {
+        Some((media_type, encoding)) if encoding.trim().eq_ignore_ascii_case("base64") => {
+            (media_type, true)
+        }
+        _ => (header, false),
+    };
+    let media_type = match media_type.trim() {
+        "" => "text/plain;charset=US-ASCII".to_string(),
+        media_type => media_type.to_ascii_lowercase(),
+    };
+    let essence = media_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    if !options.allows_data_type(&essence) {
+        return Err(PathwayError::UnsupportedScheme(format!(
+            "data ({})",
+            essence
+        )));
+    }
+
+    let mut payload =
+        percent_decode_bytes(body).ok_or_else(|| invalid("has a malformed escape".to_string()))?;
+    if base64 {
+        payload.retain(|byte| !byte.is_ascii_whitespace());
+        payload = STANDARD_PAD_INDIFFERENT
+            .decode(&payload)
+            .map_err(|e| invalid(format!("has invalid base64: {}", e)))?;
+    }
+    let limit = options
+        .max_data_url_bytes
+        .unwrap_or(DEFAULT_MAX_DATA_URL_BYTES);
+    if payload.len() > limit {
+        return Err(invalid(format!(
+            "of {} bytes exceeds the limit of {} bytes",
+            payload.len(),
+            limit
+        )));
+    }
+
+    let mut removed = 0;
+    if ACTIVE_TYPES.contains(&essence.as_str()) {
+        let (sanitized, count) =
+            strip_scripts(&String::from_utf8_lossy(&payload), essence == "text/html")?;
+        payload = sanitized.into_bytes();
+        removed = count;
+    }
+    Ok(DataUrl {
+        normalized: format!("data:{};base64,{}", media_type, STANDARD.encode(&payload)),
+        removed,
+    })
+}
+
+/// Removes comments, `<script>` elements, the tags of [`DROPPED_ELEMENTS`], event handler
+/// attributes and script links from an HTML or SVG document, returning the document and the
+/// number of removals.
+///
+/// Tags are written back with their attribute values quoted and `<`, `>` and `"` escaped, so a
+/// browser reads each of them as the single tag it was sanitized as. An HTML document containing
+/// one of [`RAW_TEXT_ELEMENTS`] is refused.
+fn strip_scripts(document: &str, html: bool) -> Result<(String, usize)> {
+    let mut output = String::with_capacity(document.len());
+    let mut removed = 0;
+    let mut rest = document;
+    while let Some(start) = rest.find('<') {
+        output.push_str(&rest[..start]);
+        let markup = &rest[start..];
+
+        // Browsers end comments at `--!>` as well; either way their content is dropped
+        if markup.starts_with("<!--") {
+            let end = ["-->", "--!>"]
+                .iter()
+                .filter_map(|close| {
+                    markup[4..]
+                        .find(close)
+                        .map(|offset| 4 + offset + close.len())
+                })
+                .min()
+                .unwrap_or(markup.len());
+            rest = &markup[end..];
+            continue;
+        }
+
+        let closing = markup[1..].starts_with('/');
+        let name_start = if closing { 2 } else { 1 };
+        let name_len = markup[name_start..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == ':'))
+            .unwrap_or(markup.len() - name_start);
+        if name_len == 0 {
+            // A lone `<` in text
+            output.push('<');
+            rest = &markup[1..];
+            continue;
+        }
+        let name = markup[name_start..name_start + name_len].to_ascii_lowercase();
+        if html && RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+            return Err(PathwayError::InvalidUrl(format!(
+                "data: URL contains a <{}> element, which can't be sanitized",
+                name
+            )));
+        }
+        let (tag, end, count) = clean_tag(markup, name_start + name_len);
+
+        if name == "script" || name.ends_with(":script") {
+            removed += 1;
+            if closing {
+                rest = &markup[end..];
+                continue;
+            }
+            let lower = markup.to_ascii_lowercase();
+            let close = format!("</{}", name);
+            rest = match lower[end..].find(&close) {
+                Some(offset) => {
+                    let close_start = end + offset;
+                    &markup[close_start + clean_tag(&markup[close_start..], close.len()).1..]
+                }
+                None => "",
+            };
+            continue;
+        }
+
+        let local_name = name.rsplit(':').next().unwrap_or_default();
+        if DROPPED_ELEMENTS.contains(&local_name) {
+            removed += 1;
+            rest = &markup[end..];
+            continue;
+        }
+
+        output.push_str(&tag);
+        removed += count;
+        rest = &markup[end..];
+    }
+    output.push_str(rest);
+    Ok((output, removed))
+}
+
+/// Parses the tag at the start of `markup`, whose name ends at `name_end`, the way an HTML
+/// tokenizer does: it ends at the first `>` outside a quoted attribute value, and quotes only
+/// open a value right after `=`. Returns the tag without its unsafe attributes, its length in
+/// `markup` and the number of attributes dropped.
+fn clean_tag(markup: &str, name_end: usize) -> (String, usize, usize) {
+    let bytes = markup.as_bytes();
+    let skip_space = |mut index: usize| {
+        while index < bytes.len() && bytes[index].is_ascii_whitespace() {
+            index += 1;
+        }
+        index
+    };
+
+    let mut output = markup[..name_end].to_string();
+    let mut removed = 0;
+    let mut index = name_end;
+    loop {
+        let mut self_closing = false;
+        while index < bytes.len() && (bytes[index].is_ascii_whitespace() || bytes[index] == b'/') {
+            self_closing = bytes[index] == b'/';
+            index += 1;
+        }
+        if index >= bytes.len() {
+            return (output, index, removed);
+        }
+        if bytes[index] == b'>' {
+            output.push_str(if self_closing { "/>" } else { ">" });
+            return (output, index + 1, removed);
+        }
+
+        // A name may start with `=`
+        let name_start = index;
+        index += 1;
+        while index < bytes.len()
+            && !bytes[index].is_ascii_whitespace()
+            && !matches!(bytes[index], b'=' | b'>' | b'/')
+        {
+            index += 1;
+        }
+        let name = markup[name_start..index].to_ascii_lowercase();
+
+        let mut value = None;
+        let after_name = skip_space(index);
+        if bytes.get(after_name) == Some(&b'=') {
+            let value_start = skip_space(after_name + 1);
+            index = match bytes.get(value_start) {
+                Some(&quote @ (b'"' | b'\'')) => {
+                    let close = markup[value_start + 1..]
+                        .find(quote as char)
+                        .map_or(markup.len(), |offset| value_start + 1 + offset);
+                    value = Some(&markup[(value_start + 1).min(close)..close]);
+                    (close + 1).min(markup.len())
+                }
+                _ => {
+                    let end = markup[value_start..]
+                        .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                        .map_or(markup.len(), |offset| value_start + offset);
+                    value = Some(&markup[value_start..end]);
+                    end
+                }
+            };
+        }
+
+        let valid_name = name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b':' | b'.'));
+        let unsafe_link = URL_ATTRIBUTES.contains(&name.as_str())
+            && value.is_some_and(|link| !is_safe_link(link));
+        let animates_link = name == "attributename"
+            && value.is_some_and(|target| target.trim().to_ascii_lowercase().ends_with("href"));
+        if !valid_name || name.starts_with("on") || name == "srcdoc" || unsafe_link || animates_link
+        {
+            removed += 1;
+            continue;
+        }
+        output.push(' ');
+        output.push_str(&markup[name_start..name_start + name.len()]);
+        if let Some(value) = value {
+            let escaped = value
+                .replace('"', "&quot;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;");
+            output.push_str(&format!("=\"{}\"", escaped));
+        }
+    }
+}
+
+/// Whether a link leads to a web, mail or phone URL, or is relative. Character references
+/// before the scheme could hide `javascript:` and make the link unsafe.
+fn is_safe_link(link: &str) -> bool {
+    let link: String = link
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    let prefix_end = link.find(['/', '?', '#']).unwrap_or(link.len());
+    let prefix = &link[..prefix_end];
+    if prefix.contains('&') {
+        return false;
+    }
+    match prefix.split_once(':') {
+        Some((scheme, _)) => SAFE_LINK_SCHEMES.contains(&scheme),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_are_stripped_from_documents() {
+        let (html, removed) = strip_scripts(
+            "<h1 class=title onclick=\"steal()\">a < b</h1>\
+             <SCRIPT type=\"module\">alert('<b>')</SCRIPT>\
+             <a href=\" javascript:alert(1)\" title='x>y'>x</a>\
+             <a href=\"jav&#x61;script:alert(1)\">y</a>\
+             <a href=\"/docs?q=1\">z</a><img src=https://example.com/i.png onerror=alert(1)>\
+             <!-- <script> -->",
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            html,
+            "<h1 class=\"title\">a < b</h1>\
+             <a title=\"x&gt;y\">x</a><a>y</a>\
+             <a href=\"/docs?q=1\">z</a><img src=\"https://example.com/i.png\">"
+        );
+        assert_eq!(removed, 5);
+
+        let (svg, removed) =
+            strip_scripts("<svg><svg:script>x</svg:script><circle/></svg>", false).unwrap();
+        assert_eq!(svg, "<svg><circle/></svg>");
+        assert_eq!(removed, 1);
+    }
+
+    #[test]
+    fn animations_and_embedding_elements_are_dropped() {
+        let cases = [
+            (
+                "<svg><a><animate attributeName=\"href\" values=\"javascript:alert(1)\"/><text>x</text></a></svg>",
+                "<svg><a><text>x</text></a></svg>",
+            ),
+            (
+                "<svg><a><set attributeName=\"xlink:href\" to=\"javascript:alert(1)\"></set>x</a></svg>",
+                "<svg><a>x</a></svg>",
+            ),
+            (
+                "<svg><svg:animateTransform attributeName=\"transform\" type=\"rotate\"/></svg>",
+                "<svg></svg>",
+            ),
+            (
+                "<head><meta http-equiv=\"refresh\" content=\"0;url=javascript:alert(1)\"></head>",
+                "<head></head>",
+            ),
+            ("<base href=\"https://evil.example/\"><a href=\"x\">x</a>", "<a href=\"x\">x</a>"),
+            ("<iframe src=\"https://example.com/\"></iframe>", ""),
+            ("<object data=\"https://example.com/x.swf\">fallback</object>", "fallback"),
+            ("<embed src=\"https://example.com/x.swf\">", ""),
+            (
+                "<svg><rect attributeName=\"HREF\" width=\"1\"/></svg>",
+                "<svg><rect width=\"1\"/></svg>",
+            ),
+        ];
+        for (document, expected) in cases {
+            let (sanitized, removed) = strip_scripts(document, false).unwrap();
+            assert_eq!(sanitized, expected, "sanitizing {}", document);
+            assert!(removed > 0, "nothing removed from {}", document);
+        }
+    }
+
+    #[test]
+    fn markup_hidden_from_the_sanitizer_does_not_get_through() {
+        let cases = [
+            // Comments are dropped whichever way they end
+            ("<!-- --!><script>alert(1)</script> -->", " -->"),
+            ("<p>a<!-- x -->b</p>", "<p>ab</p>"),
+            // Quotes only open attribute values, and tags are written back unambiguously
+            (
+                "<a b\"c><script>alert(1)</script><a d=\">\">x</a>",
+                "<a><a d=\"&gt;\">x</a>",
+            ),
+            (
+                "<a =\"><img src=x onerror=alert(1)>\">x</a>",
+                "<a><img src=\"x\">\">x</a>",
+            ),
+            (
+                "<svg/onload=alert(1)><circle r=1 / ></svg>",
+                "<svg><circle r=\"1\"></svg>",
+            ),
+        ];
+        for (document, expected) in cases {
+            let (sanitized, _) = strip_scripts(document, true).unwrap();
+            assert_eq!(sanitized, expected, "sanitizing {}", document);
+        }
+
+        for document in [
+            "<textarea><!--</textarea><script>alert(1)</script>-->",
+            "<title><a title=\"</title><script>alert(1)</script>\"></title>",
+            "<STYLE>x</STYLE>",
+            "<xmp></xmp>",
+            "<noscript><p title=\"</noscript><img src=x onerror=alert(1)>\"></noscript>",
+        ] {
+            assert!(
+                matches!(
+                    strip_scripts(document, true),
+                    Err(PathwayError::InvalidUrl(_))
+                ),
+                "accepted {}",
+                document
+            );
+        }
+        // SVG is parsed as XML, where `<style>` holds markup like any other element
+        assert!(strip_scripts("<svg><style>circle{fill:red}</style></svg>", false).is_ok());
+    }
+}
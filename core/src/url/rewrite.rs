@@ -0,0 +1,179 @@
+//! Rewrite rules from the `[[rewrite]]` tables of the config.
+//!
+//! Rules apply to validated web URLs before a browser is picked, in the order they are
+//! listed; every matching rule applies. A URL that changed keeps its previous form in
+//! [`ValidatedUrl::rewritten_from`].
+
+use super::{apps, ValidatedUrl};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use url::Url;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RewriteRule {
+    /// Host the rule applies to, compared case-insensitively; `*.example.com` matches
+    /// example.com and all its subdomains
+    pub host: String,
+    /// Only URLs whose path starts with this
+    pub path_prefix: Option<String>,
+    /// Host to switch to (e.g. "old.reddit.com")
+    pub set_host: Option<String>,
+    /// Query parameters to add, replacing any of the same name
+    pub set_query: BTreeMap<String, String>,
+    /// Query parameters to remove
+    pub remove_query: Vec<String>,
+}
+
+/// Whether `url` is on a host `pattern` covers (compared case-insensitively; `*.example.com`
+/// covers example.com and all its subdomains) and its path starts with `path_prefix`.
+pub(super) fn url_matches(pattern: &str, path_prefix: Option<&str>, url: &Url) -> bool {
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let pattern = pattern.to_ascii_lowercase();
+    let host_matches = match pattern.strip_prefix("*.") {
+        Some(domain) => {
+            host == domain
+                || host
+                    .strip_suffix(domain)
+                    .is_some_and(|sub| sub.ends_with('.'))
+        }
+        None => host == pattern,
+    };
+    host_matches && path_prefix.is_none_or(|prefix| url.path().starts_with(prefix))
+}
+
+impl RewriteRule {
+    fn matches(&self, url: &Url) -> bool {
+        url_matches(&self.host, self.path_prefix.as_deref(), url)
+    }
+
+    fn apply(&self, url: &mut Url) {
+        if let Some(host) = &self.set_host {
+            let _ = url.set_host(Some(host));
+        }
+        if self.set_query.is_empty() && self.remove_query.is_empty() {
+            return;
+        }
+
+        // Untouched parameters keep their original encoding
+        let replaced = |name: &str| {
+            self.set_query.contains_key(name) || self.remove_query.iter().any(|r| r == name)
+        };
+        let mut query: Vec<String> = url
+            .query()
+            .unwrap_or_default()
+            .split('&')
+            .filter(|pair| {
+                let name = url::form_urlencoded::parse(pair.as_bytes())
+                    .next()
+                    .map(|(name, _)| name.into_owned())
+                    .unwrap_or_default();
+                !pair.is_empty() && !replaced(&name)
+            })
+            .map(str::to_string)
+            .collect();
+        for (name, value) in &self.set_query {
+            query.push(
+                url::form_urlencoded::Serializer::new(String::new())
+                    .append_pair(name, value)
+                    .finish(),
+            );
+        }
+        let query = query.join("&");
+        url.set_query((!query.is_empty()).then_some(query.as_str()));
+    }
+}
+
+/// Applies the matching `rules` to a valid web URL. Returns whether it changed.
+pub fn rewrite_url(validated: &mut ValidatedUrl, rules: &[RewriteRule]) -> bool {
+    if !matches!(validated.scheme.as_str(), "http" | "https") {
+        return false;
+    }
+    let Ok(mut url) = Url::parse(&validated.normalized) else {
+        return false;
+    };
+    for rule in rules {
+        if rule.matches(&url) {
+            rule.apply(&mut url);
+        }
+    }
+    if url.as_str() == validated.normalized {
+        return false;
+    }
+
+    validated.rewritten_from = Some(std::mem::replace(
+        &mut validated.normalized,
+        url.to_string(),
+    ));
+    validated.url = url.to_string();
+    validated.host_ascii = url.domain().map(str::to_string);
+    validated.host_unicode = validated
+        .host_ascii
+        .as_deref()
+        .map(|host| idna::domain_to_unicode(host).0);
+    validated.app_link = apps::app_link(&url);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filesystem::MockFileSystem;
+    use crate::url::validate_url;
+
+    #[test]
+    fn matching_rules_rewrite_in_order() {
+        let rules: Vec<RewriteRule> = toml::from_str::<BTreeMap<String, Vec<RewriteRule>>>(
+            r#"
+            [[rewrite]]
+            host = "*.reddit.com"
+            set_host = "old.reddit.com"
+
+            [[rewrite]]
+            host = "www.google.com"
+            path_prefix = "/search"
+            set_query = { hl = "en" }
+            remove_query = ["sca_esv"]
+
+            [[rewrite]]
+            host = "twitter.com"
+            set_host = "nitter.net"
+            "#,
+        )
+        .unwrap()
+        .remove("rewrite")
+        .unwrap();
+
+        let rewrite = |url: &str| {
+            let mut validated = validate_url(url, &MockFileSystem::new()).unwrap();
+            rewrite_url(&mut validated, &rules).then_some(validated)
+        };
+        let reddit = rewrite("https://www.reddit.com/r/rust/").unwrap();
+        assert_eq!(reddit.normalized, "https://old.reddit.com/r/rust/");
+        assert_eq!(
+            reddit.rewritten_from.as_deref(),
+            Some("https://www.reddit.com/r/rust/")
+        );
+        assert_eq!(reddit.host_ascii.as_deref(), Some("old.reddit.com"));
+        assert_eq!(
+            rewrite("https://reddit.com/").unwrap().normalized,
+            "https://old.reddit.com/"
+        );
+
+        assert_eq!(
+            rewrite("https://www.google.com/search?q=a%20b&sca_esv=1&hl=de")
+                .unwrap()
+                .normalized,
+            "https://www.google.com/search?q=a%20b&hl=en"
+        );
+        assert!(rewrite("https://www.google.com/maps?hl=de").is_none());
+        assert!(rewrite("https://notreddit.com/").is_none());
+        assert!(rewrite("https://old.reddit.com/").is_none());
+        assert_eq!(
+            rewrite("https://twitter.com/rustlang").unwrap().normalized,
+            "https://nitter.net/rustlang"
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! Routing rules from the `[[route]]` tables of the config.
+//!
+//! A launch that names no browser opens each web URL in the browser of the first rule that
+//! matches it; URLs no rule matches go to the default browser.
+
+use super::rewrite::url_matches;
+use super::ValidatedUrl;
+use serde::Deserialize;
+use url::Url;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RouteRule {
+    /// Host the rule applies to, compared case-insensitively; `*.example.com` matches
+    /// example.com and all its subdomains
+    pub host: String,
+    /// Only URLs whose path starts with this
+    pub path_prefix: Option<String>,
+    /// Browser token to open matching URLs in, e.g. "firefox" or "chrome-beta"
+    pub browser: String,
+    /// Profile (or alias) to open them with
+    pub profile: Option<String>,
+    /// Only use a build of the browser that runs natively, e.g. not an x86_64 build under
+    /// Rosetta on Apple Silicon
+    pub native_only: bool,
+}
+
+impl RouteRule {
+    pub fn matches(&self, validated: &ValidatedUrl) -> bool {
+        matches!(validated.scheme.as_str(), "http" | "https")
+            && Url::parse(&validated.normalized)
+                .is_ok_and(|url| url_matches(&self.host, self.path_prefix.as_deref(), &url))
+    }
+}
+
+/// The first of `rules` that matches `validated`.
+pub fn route_url<'a>(validated: &ValidatedUrl, rules: &'a [RouteRule]) -> Option<&'a RouteRule> {
+    rules.iter().find(|rule| rule.matches(validated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filesystem::MockFileSystem;
+    use crate::url::validate_url;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn the_first_matching_rule_routes_a_url() {
+        let rules: Vec<RouteRule> = toml::from_str::<BTreeMap<String, Vec<RouteRule>>>(
+            r#"
+            [[route]]
+            host = "github.com"
+            path_prefix = "/corp/"
+            browser = "edge"
+            profile = "work"
+
+            [[route]]
+            host = "*.github.com"
+            browser = "firefox"
+            "#,
+        )
+        .unwrap()
+        .remove("route")
+        .unwrap();
+
+        let route = |url: &str| {
+            let validated = validate_url(url, &MockFileSystem::new()).unwrap();
+            route_url(&validated, &rules).map(|rule| rule.browser.as_str())
+        };
+        assert_eq!(route("https://github.com/corp/app"), Some("edge"));
+        assert_eq!(route("https://GitHub.com/rust-lang"), Some("firefox"));
+        assert_eq!(route("https://gist.github.com/"), Some("firefox"));
+        assert_eq!(route("https://example.com/"), None);
+    }
+}
@@ -130,6 +130,133 @@ fn test_dangerous_url_schemes() {
         .stderr(predicate::str::contains("Unsupported scheme"));
 }
 
+#[test]
+fn test_allow_scheme_passthrough() {
+    let mut cmd = Command::cargo_bin("pathway").unwrap();
+    cmd.args(["launch", "--no-launch", "slack://open"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unsupported scheme"));
+
+    let mut cmd = Command::cargo_bin("pathway").unwrap();
+    cmd.args([
+        "launch",
+        "--no-launch",
+        "--allow-scheme",
+        "slack",
+        "slack://open",
+    ])
+    .assert()
+    .success()
+    .stderr(predicate::str::contains("scheme: slack"));
+
+    // The allowlist does not unlock dangerous schemes
+    let mut cmd = Command::cargo_bin("pathway").unwrap();
+    cmd.args([
+        "launch",
+        "--no-launch",
+        "--allow-scheme",
+        "javascript",
+        "javascript:alert(1)",
+    ])
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("Unsupported scheme"));
+}
+
+#[test]
+fn test_mailto_accepted() {
+    let mut cmd = Command::cargo_bin("pathway").unwrap();
+    cmd.args(["launch", "--no-launch", "mailto:someone@example.com"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("scheme: mailto"));
+}
+
+#[test]
+fn test_mail_list_json() {
+    let mut cmd = Command::cargo_bin("pathway").unwrap();
+    cmd.args(["mail", "list", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"action\": \"list-mail-clients\"",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cleanup_removes_leftover_temp_profiles_only_with_yes() {
+    let temp_dir = TempDir::new().unwrap();
+    let leftover = temp_dir.path().join("pathway_profile_abc123");
+    std::fs::create_dir(&leftover).unwrap();
+
+    let mut cmd = Command::cargo_bin("pathway").unwrap();
+    cmd.env("TMPDIR", temp_dir.path())
+        .env("HOME", temp_dir.path())
+        .args(["cleanup", "--older-than", "0", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pathway_profile_abc123"))
+        .stdout(predicate::str::contains("\"dry_run\": true"));
+    assert!(leftover.exists());
+
+    let mut cmd = Command::cargo_bin("pathway").unwrap();
+    cmd.env("TMPDIR", temp_dir.path())
+        .env("HOME", temp_dir.path())
+        .args(["cleanup", "--older-than", "0", "--yes"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Removed"));
+    assert!(!leftover.exists());
+}
+
+#[test]
+fn test_validate_command() {
+    let mut cmd = Command::cargo_bin("pathway").unwrap();
+    cmd.args([
+        "--format",
+        "json",
+        "validate",
+        "--strip-tracking",
+        "example.com/?utm_source=x",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("\"status\": \"valid\""))
+    .stdout(predicate::str::contains(
+        "\"normalized\": \"https://example.com/\"",
+    ));
+
+    let mut cmd = Command::cargo_bin("pathway").unwrap();
+    cmd.args(["--format", "json", "validate", "--file", "-"])
+        .write_stdin("# links\nhttps://example.com\n\njavascript:alert(1)\n")
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("\"valid\": 1"))
+        .stdout(predicate::str::contains("\"invalid\": 1"));
+
+    let mut cmd = Command::cargo_bin("pathway").unwrap();
+    cmd.args(["validate", "--file", "/nonexistent/urls.txt"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_allowed_schemes_from_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = temp_dir.path().join("config.toml");
+    std::fs::write(&config, "[urls]\nallowed_schemes = [\"zoommtg\"]\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("pathway").unwrap();
+    cmd.arg("--config")
+        .arg(&config)
+        .args(["launch", "--no-launch", "zoommtg://zoom.us/join?confno=1"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("scheme: zoommtg"));
+}
+
 #[test]
 fn test_help_commands() {
     let mut cmd = Command::cargo_bin("pathway").unwrap();